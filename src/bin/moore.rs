@@ -73,7 +73,7 @@ fn main() {
             Arg::with_name("inc")
                 .short("I")
                 .value_name("DIR")
-                .help("Add a search path for SystemVerilog includes")
+                .help("Add a search path for SystemVerilog includes and memory files")
                 .multiple(true)
                 .takes_value(true)
                 .number_of_values(1),
@@ -148,7 +148,7 @@ fn main() {
                 .long("format")
                 .help("Output format")
                 .takes_value(true)
-                .possible_values(&["llhd", "mlir", "mlir-native"]),
+                .possible_values(&["llhd", "mlir", "mlir-native", "hw", "verilog"]),
         )
         .arg(
             Arg::with_name("debug-info")
@@ -156,6 +156,11 @@ fn main() {
                 .long("debug-info")
                 .help("Emit location information as part of the output"),
         )
+        .arg(
+            Arg::with_name("four-state")
+                .long("four-state")
+                .help("Preserve X and Z values in the generated code"),
+        )
         .arg(
             Arg::with_name("INPUT")
                 .help("The input files to compile")
@@ -188,6 +193,11 @@ fn main() {
         };
     }
     session.opts.opt_level = matches.value_of("opt-level").unwrap().parse().unwrap();
+    session.opts.four_state = matches.is_present("four-state");
+    session.opts.include_paths = match matches.values_of("inc") {
+        Some(args) => args.map(Into::into).collect(),
+        None => Vec::new(),
+    };
 
     // Invoke the compiler.
     score(&session, &matches);
@@ -519,6 +529,9 @@ fn elaborate_name(
                 svlog::InstVerbosityVisitor::new(ctx.svlog).visit_node_with_id(m, false);
             }
 
+            // Decide what format to use for the output.
+            let fmt = output_format(matches, ctx)?;
+
             // Create an MLIR context and load the dialects we need.
             let mlir_cx = mlir::OwnedContext::new();
             mlir_cx.load_dialect(circt::func::dialect());
@@ -526,8 +539,11 @@ fn elaborate_name(
             mlir_cx.load_dialect(circt::hw::dialect());
             mlir_cx.load_dialect(circt::comb::dialect());
             mlir_cx.load_dialect(circt::llhd::dialect());
+            mlir_cx.load_dialect(circt::ltl::dialect());
             mlir_cx.load_dialect(circt::moore::dialect());
             mlir_cx.load_dialect(circt::seq::dialect());
+            mlir_cx.load_dialect(circt::sv::dialect());
+            mlir_cx.load_dialect(circt::verif::dialect());
             mlir_cx.set_allow_unregistered_dialects(true);
 
             // Attach a custom diagnostic handler to the context such that we
@@ -553,6 +569,44 @@ fn elaborate_name(
                 cg.emit_globals(root)?;
             }
             cg.emit_module(m)?;
+
+            // Verify the module.
+            if !mlir_module.verify() {
+                ctx.sess.emit(DiagBuilder2::bug(
+                    "verification failed for emitted MLIR module",
+                ));
+                // mlir_module.dump();
+                return Err(());
+            }
+
+            // Check that the LLHD output can represent everything.
+            if let OutputFormat::Llhd = fmt {
+                cg.check_llhd()?;
+            }
+
+            // Lower to structural HW modules if requested.
+            if let OutputFormat::Hw | OutputFormat::Verilog = fmt {
+                cg.lower_to_hw()?;
+                if !mlir_module.verify() {
+                    ctx.sess.emit(DiagBuilder2::bug(
+                        "verification failed for structural HW module",
+                    ));
+                    return Err(());
+                }
+            }
+
+            // Lower the registers to always blocks for Verilog output, and
+            // check that the remaining values can be represented.
+            if let OutputFormat::Verilog = fmt {
+                if !circt::seq::lower_to_sv(mlir_module) {
+                    ctx.sess.emit(DiagBuilder2::error(
+                        "unable to lower registers for Verilog output",
+                    ));
+                    return Err(());
+                }
+                cg.check_verilog()?;
+            }
+
             let module = cg.finalize();
             // let mut module = cg.finalize();
             // let pass_ctx = PassContext;
@@ -565,17 +619,7 @@ fn elaborate_name(
             //     llhd::pass::DeadCodeElim::run_on_module(&pass_ctx, &mut module);
             // }
 
-            // Verify the module.
-            if !mlir_module.verify() {
-                ctx.sess.emit(DiagBuilder2::bug(
-                    "verification failed for emitted MLIR module",
-                ));
-                // mlir_module.dump();
-                return Err(());
-            }
-
-            // Decide what format to use for the output.
-            emit_output(matches, ctx, &module, mlir_module)?
+            emit_output(matches, ctx, fmt, &module, mlir_module)?
         }
     }
     Ok(())
@@ -673,19 +717,19 @@ enum OutputFormat {
     Llhd,
     Mlir,
     MlirNative,
+    Hw,
+    Verilog,
 }
 
-fn emit_output(
-    matches: &ArgMatches,
-    ctx: &ScoreContext,
-    module: &llhd::ir::Module,
-    mlir_module: circt::ModuleOp,
-) -> Result<(), ()> {
+/// Determine the output format from the command line arguments.
+fn output_format(matches: &ArgMatches, ctx: &ScoreContext) -> Result<OutputFormat, ()> {
     // Check if the user has provided an explicit output format.
     let fmt = match matches.value_of("output-format") {
         Some("llhd") => Some(OutputFormat::Llhd),
         Some("mlir") => Some(OutputFormat::Mlir),
         Some("mlir-native") => Some(OutputFormat::MlirNative),
+        Some("hw") => Some(OutputFormat::Hw),
+        Some("verilog") => Some(OutputFormat::Verilog),
         Some(x) => {
             ctx.sess.emit(DiagBuilder2::fatal(format!(
                 "unknown output format: `{}`",
@@ -705,6 +749,7 @@ fn emit_output(
         {
             Some("llhd") => Some(OutputFormat::Llhd),
             Some("mlir") => Some(OutputFormat::Mlir),
+            Some("v") => Some(OutputFormat::Verilog),
             _ => None,
         }
     });
@@ -712,7 +757,16 @@ fn emit_output(
     // Otherwise fall back to the LLHD default output format.
     let fmt = fmt.unwrap_or(OutputFormat::Llhd);
     debug!("Using {:?} output format", fmt);
+    Ok(fmt)
+}
 
+fn emit_output(
+    matches: &ArgMatches,
+    ctx: &ScoreContext,
+    fmt: OutputFormat,
+    module: &llhd::ir::Module,
+    mlir_module: circt::ModuleOp,
+) -> Result<(), ()> {
     // Open the output.
     let stdout = std::io::stdout();
     let output: Box<dyn std::io::Write> = match matches.value_of("output") {
@@ -730,7 +784,22 @@ fn emit_output(
     match fmt {
         OutputFormat::Llhd => llhd::assembly::write_module(output, &module),
         OutputFormat::Mlir => llhd::mlir::write_module(output, &module),
-        OutputFormat::MlirNative => mlir_module.print(output, matches.is_present("debug-info")),
+        OutputFormat::MlirNative | OutputFormat::Hw => {
+            mlir_module.print(output, matches.is_present("debug-info"))
+        }
+        OutputFormat::Verilog => {
+            // Restrict the output to constructs that Verilog-2005 tools
+            // understand.
+            mlir_module.set_attr(
+                "circt.loweringOptions",
+                mlir::get_string_attr(mlir_module.context(), "noAlwaysComb,disallowLocalVariables"),
+            );
+            if !circt::sv::export_verilog(mlir_module, output) {
+                ctx.sess
+                    .emit(DiagBuilder2::error("unable to export Verilog output"));
+                return Err(());
+            }
+        }
     };
     Ok(())
 }
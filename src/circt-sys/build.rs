@@ -72,17 +72,26 @@ fn main() {
     // CIRCT/LLVM/MLIR libraries
     let lib_names = [
         "CIRCTCAPIComb",
+        "CIRCTCAPIExportVerilog",
         "CIRCTCAPIHW",
         "CIRCTCAPILLHD",
+        "CIRCTCAPILTL",
         "CIRCTCAPIMoore",
         "CIRCTCAPISV",
         "CIRCTCAPISeq",
+        "CIRCTCAPIVerif",
         "CIRCTComb",
+        "CIRCTExportVerilog",
         "CIRCTHW",
         "CIRCTLLHD",
+        "CIRCTLTL",
         "CIRCTMoore",
         "CIRCTSV",
         "CIRCTSeq",
+        "CIRCTSeqToSV",
+        "CIRCTSeqTransforms",
+        "CIRCTSupport",
+        "CIRCTVerif",
         "LLVMBinaryFormat",
         "LLVMBitstreamReader",
         "LLVMCore",
@@ -108,6 +117,7 @@ fn main() {
         "MLIRSupport",
         "MLIRTransformUtils",
         "MLIRTransforms",
+        "MLIRTranslateLib",
     ];
     for name in &lib_names {
         println!("cargo:rustc-link-lib=static={}", name);
@@ -1,7 +1,10 @@
 #include "wrapper.h"
+#include "circt/Conversion/SeqToSV.h"
 #include "mlir/CAPI/IR.h"
 #include "mlir/CAPI/Support.h"
 #include "mlir/IR/BuiltinTypes.h"
+#include "mlir/Pass/PassManager.h"
+#include "mlir/Transforms/Passes.h"
 
 using namespace llvm;
 using namespace mlir;
@@ -30,3 +33,17 @@ unsigned mlirFileLineColLocGetLine(MlirLocation loc) {
 unsigned mlirFileLineColLocGetColumn(MlirLocation loc) {
   return unwrap(loc).cast<FileLineColLoc>().getColumn();
 }
+
+void mlirValueReplaceAllUsesWith(MlirValue of, MlirValue with) {
+  unwrap(of).replaceAllUsesWith(unwrap(with));
+}
+
+MlirLogicalResult mlirLowerSeqToSV(MlirModule module) {
+  auto op = unwrap(module);
+  PassManager pm(op->getContext());
+  circt::LowerSeqToSVOptions options;
+  options.lowerToAlwaysFF = false;
+  pm.addPass(circt::createLowerSeqToSVPass(options));
+  pm.addPass(createCanonicalizerPass());
+  return wrap(pm.run(op));
+}
@@ -2,8 +2,12 @@
 #include "circt-c/Dialect/Comb.h"
 #include "circt-c/Dialect/HW.h"
 #include "circt-c/Dialect/LLHD.h"
+#include "circt-c/Dialect/LTL.h"
 #include "circt-c/Dialect/Moore.h"
+#include "circt-c/Dialect/SV.h"
 #include "circt-c/Dialect/Seq.h"
+#include "circt-c/Dialect/Verif.h"
+#include "circt-c/ExportVerilog.h"
 #include "mlir-c/AffineExpr.h"
 #include "mlir-c/AffineMap.h"
 #include "mlir-c/BuiltinAttributes.h"
@@ -47,6 +51,23 @@ MLIR_CAPI_EXPORTED MlirStringRef mlirFileLineColLocGetFilename(MlirLocation);
 MLIR_CAPI_EXPORTED unsigned mlirFileLineColLocGetLine(MlirLocation);
 MLIR_CAPI_EXPORTED unsigned mlirFileLineColLocGetColumn(MlirLocation);
 
+//===----------------------------------------------------------------------===//
+// Value API Extensions
+//===----------------------------------------------------------------------===//
+
+/// Replaces all uses of a value with another value.
+MLIR_CAPI_EXPORTED void mlirValueReplaceAllUsesWith(MlirValue of,
+                                                    MlirValue with);
+
+//===----------------------------------------------------------------------===//
+// Pass API Extensions
+//===----------------------------------------------------------------------===//
+
+/// Lowers the `seq` registers in a module to `sv` registers and always blocks
+/// that can be emitted as Verilog, and folds the aggregate values which are
+/// only taken apart again.
+MLIR_CAPI_EXPORTED MlirLogicalResult mlirLowerSeqToSV(MlirModule module);
+
 #ifdef __cplusplus
 }
 #endif
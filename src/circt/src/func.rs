@@ -56,6 +56,23 @@ impl<'a> FunctionBuilder<'a> {
         self
     }
 
+    /// Build a private function declaration without a body.
+    pub fn build_decl(&mut self, builder: &mut Builder) -> FuncOp {
+        builder.build_with(|builder, state| {
+            let arg_types = self.args.iter().map(|(_, ty)| *ty);
+            let result_types = self.results.iter().map(|(_, ty)| *ty);
+            state.add_attribute("sym_name", get_string_attr(builder.cx, self.name));
+            state.add_attribute("sym_visibility", get_string_attr(builder.cx, "private"));
+            state.add_attribute(
+                "function_type",
+                get_type_attr(get_function_type(builder.cx, arg_types, result_types)),
+            );
+            unsafe {
+                state.add_region(mlirRegionCreate());
+            }
+        })
+    }
+
     /// Build a function.
     pub fn build(&mut self, builder: &mut Builder) -> FuncOp {
         builder.build_with(|builder, state| {
@@ -72,6 +72,9 @@ pub fn struct_type_fields(ty: Type) -> impl Iterator<Item = (String, Type)> {
     (0..struct_type_size(ty)).map(move |i| struct_type_field(ty, i))
 }
 
+def_operation!(ModuleOp, "hw.module");
+def_operation!(OutputOp, "hw.output");
+def_operation!(InstanceOp, "hw.instance");
 def_operation_single_result!(ConstantOp, "hw.constant");
 def_operation_single_result!(ArrayCreateOp, "hw.array_create");
 def_operation_single_result!(StructCreateOp, "hw.struct_create");
@@ -82,6 +85,132 @@ def_operation_single_result!(StructExtractOp, "hw.struct_extract");
 def_operation_single_result!(StructInjectOp, "hw.struct_inject");
 def_operation_single_result!(BitcastOp, "hw.bitcast");
 
+impl SingleRegionOp for ModuleOp {}
+impl SingleBlockOp for ModuleOp {}
+
+impl ModuleOp {
+    /// Get the number of input ports.
+    pub fn num_inputs(&self) -> usize {
+        unsafe { mlirBlockGetNumArguments(self.block()) as usize }
+    }
+
+    /// Get an input port by index.
+    pub fn input(&self, index: usize) -> Value {
+        unsafe { Value::from_raw(mlirBlockGetArgument(self.block(), index as _)) }
+    }
+}
+
+pub struct ModuleBuilder<'a> {
+    name: &'a str,
+    inputs: Vec<(&'a str, Type)>,
+    outputs: Vec<(&'a str, Type)>,
+}
+
+impl<'a> ModuleBuilder<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+
+    /// Add an input port.
+    pub fn add_input(&mut self, name: &'a str, ty: Type) -> &mut Self {
+        self.inputs.push((name, ty));
+        self
+    }
+
+    /// Add an output port.
+    pub fn add_output(&mut self, name: &'a str, ty: Type) -> &mut Self {
+        self.outputs.push((name, ty));
+        self
+    }
+
+    /// Build a module. The body must be terminated with an `OutputOp`.
+    pub fn build(&mut self, builder: &mut Builder) -> ModuleOp {
+        builder.build_with(|builder, state| {
+            let input_types = self.inputs.iter().map(|(_, ty)| *ty);
+            let output_types = self.outputs.iter().map(|(_, ty)| *ty);
+            let mlir_types: Vec<MlirType> = input_types.clone().map(|x| x.raw()).collect();
+            let names = |ports: &[(&str, Type)]| {
+                get_array_attr(
+                    builder.cx,
+                    ports
+                        .iter()
+                        .map(|(name, _)| get_string_attr(builder.cx, name)),
+                )
+            };
+
+            state.add_attribute("sym_name", get_string_attr(builder.cx, self.name));
+            state.add_attribute(
+                "function_type",
+                get_type_attr(get_function_type(builder.cx, input_types, output_types)),
+            );
+            state.add_attribute("argNames", names(&self.inputs));
+            state.add_attribute("resultNames", names(&self.outputs));
+            state.add_attribute("parameters", get_array_attr(builder.cx, None));
+            state.add_attribute("comment", get_string_attr(builder.cx, ""));
+
+            unsafe {
+                let region = mlirRegionCreate();
+                let locations = vec![Location::unknown(builder.cx).raw(); mlir_types.len()];
+                mlirRegionAppendOwnedBlock(
+                    region,
+                    mlirBlockCreate(
+                        mlir_types.len() as _,
+                        mlir_types.as_ptr(),
+                        locations.as_ptr(),
+                    ),
+                );
+                state.add_region(region);
+            }
+        })
+    }
+}
+
+impl OutputOp {
+    /// Create a new module terminator yielding the output port values.
+    pub fn new(builder: &mut Builder, values: impl IntoIterator<Item = Value>) -> Self {
+        builder.build_with(|_, state| {
+            for value in values {
+                state.add_operand(value);
+            }
+        })
+    }
+}
+
+impl InstanceOp {
+    /// Create a new instance of a module.
+    ///
+    /// The port names must match the ones of the instantiated module.
+    pub fn new<'a>(
+        builder: &mut Builder,
+        name: &str,
+        module: &str,
+        inputs: impl IntoIterator<Item = (&'a str, Value)>,
+        outputs: impl IntoIterator<Item = (&'a str, Type)>,
+    ) -> Self {
+        builder.build_with(|builder, state| {
+            let mut arg_names = vec![];
+            for (name, value) in inputs {
+                state.add_operand(value);
+                arg_names.push(get_string_attr(builder.cx, name));
+            }
+            let mut result_names = vec![];
+            for (name, ty) in outputs {
+                state.add_result(ty);
+                result_names.push(get_string_attr(builder.cx, name));
+            }
+            state.add_attribute("instanceName", get_string_attr(builder.cx, name));
+            state.add_attribute("moduleName", get_flat_symbol_ref_attr(builder.cx, module));
+            state.add_attribute("argNames", get_array_attr(builder.cx, arg_names));
+            state.add_attribute("resultNames", get_array_attr(builder.cx, result_names));
+            state.add_attribute("parameters", get_array_attr(builder.cx, None));
+        })
+    }
+}
+
 impl ConstantOp {
     /// Create a new constant value.
     pub fn new(builder: &mut Builder, width: usize, value: &BigInt) -> ConstantOp {
@@ -95,9 +95,12 @@ pub mod comb;
 pub mod func;
 pub mod hw;
 pub mod llhd;
+pub mod ltl;
 pub mod mlir;
 pub mod moore;
 pub mod seq;
+pub mod sv;
+pub mod verif;
 
 pub use builtin::*;
 
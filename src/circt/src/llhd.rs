@@ -85,6 +85,12 @@ pub fn get_time_attr(
     })
 }
 
+/// Get the real time component of a time attribute, in the attribute's time
+/// unit.
+pub fn time_attr_time(attr: Attribute) -> u64 {
+    unsafe { llhdTimeAttrGetSeconds(attr.raw()) }
+}
+
 def_operation!(EntityOp, "llhd.entity");
 def_operation!(ProcessOp, "llhd.proc");
 def_operation!(InstanceOp, "llhd.inst");
@@ -244,6 +250,9 @@ impl InstanceOp {
 }
 
 def_operation_single_result!(ConstantTimeOp, "llhd.constant_time");
+def_operation_single_result!(IntToTimeOp, "llhd.int_to_time");
+def_operation_single_result!(TimeToIntOp, "llhd.time_to_int");
+def_operation_single_result!(CurrentTimeOp, "llhd.current_time");
 def_operation_single_result!(SignalOp, "llhd.sig");
 def_operation_single_result!(VariableOp, "llhd.var");
 def_operation!(ConnectOp, "llhd.con");
@@ -287,6 +296,36 @@ impl ConstantTimeOp {
     }
 }
 
+impl IntToTimeOp {
+    /// Convert an `i64` number of femtoseconds to a time value.
+    pub fn new(builder: &mut Builder, value: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(value);
+            state.add_result(get_time_type(builder.cx));
+        })
+    }
+}
+
+impl TimeToIntOp {
+    /// Convert a time value to an `i64` number of femtoseconds, dropping the
+    /// delta and epsilon steps.
+    pub fn new(builder: &mut Builder, value: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(value);
+            state.add_result(get_integer_type(builder.cx, 64));
+        })
+    }
+}
+
+impl CurrentTimeOp {
+    /// Get the current simulation time.
+    pub fn new(builder: &mut Builder) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_result(get_time_type(builder.cx));
+        })
+    }
+}
+
 impl SignalOp {
     /// Create a new signal.
     pub fn new(builder: &mut Builder, name: &str, init: Value) -> Self {
@@ -0,0 +1,211 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+use crate::crate_prelude::*;
+
+pub fn dialect() -> DialectHandle {
+    DialectHandle::from_raw(unsafe { crate::sys::mlirGetDialectHandle__ltl__() })
+}
+
+/// Create a new LTL sequence type.
+pub fn get_sequence_type(cx: Context) -> Type {
+    parse_type(cx, "!ltl.sequence")
+}
+
+/// Create a new LTL property type.
+pub fn get_property_type(cx: Context) -> Type {
+    parse_type(cx, "!ltl.property")
+}
+
+/// Check if a type is an LTL sequence type.
+pub fn is_sequence_type(ty: Type) -> bool {
+    ty == get_sequence_type(ty.context())
+}
+
+/// Check if a type is an LTL property type.
+pub fn is_property_type(ty: Type) -> bool {
+    ty == get_property_type(ty.context())
+}
+
+/// Determine the result type of an operation that accepts booleans, sequences,
+/// and properties. The result is a property if any operand is a property, a
+/// sequence if any operand is a sequence, and a plain `i1` otherwise.
+fn infer_result_type(cx: Context, operands: &[Value]) -> Type {
+    if operands.iter().any(|v| is_property_type(v.ty())) {
+        get_property_type(cx)
+    } else if operands.iter().any(|v| is_sequence_type(v.ty())) {
+        get_sequence_type(cx)
+    } else {
+        get_integer_type(cx, 1)
+    }
+}
+
+/// The edge of a clock that triggers a sequence or property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockEdge {
+    Pos,
+    Neg,
+    Both,
+}
+
+def_operation_single_result!(AndOp, "ltl.and");
+def_operation_single_result!(OrOp, "ltl.or");
+def_operation_single_result!(IntersectOp, "ltl.intersect");
+def_operation_single_result!(NotOp, "ltl.not");
+def_operation_single_result!(ImplicationOp, "ltl.implication");
+def_operation_single_result!(DelayOp, "ltl.delay");
+def_operation_single_result!(ConcatOp, "ltl.concat");
+def_operation_single_result!(RepeatOp, "ltl.repeat");
+def_operation_single_result!(GoToRepeatOp, "ltl.goto_repeat");
+def_operation_single_result!(NonConsecutiveRepeatOp, "ltl.non_consecutive_repeat");
+def_operation_single_result!(ClockOp, "ltl.clock");
+def_operation_single_result!(DisableOp, "ltl.disable");
+
+/// Define a variadic operation whose result type is inferred from its operands.
+macro_rules! impl_variadic_new {
+    ($name:ident) => {
+        impl $name {
+            pub fn new(builder: &mut Builder, inputs: &[Value]) -> Self {
+                builder.build_with(|builder, state| {
+                    for &input in inputs {
+                        state.add_operand(input);
+                    }
+                    state.add_result(infer_result_type(builder.cx, inputs));
+                })
+            }
+        }
+    };
+}
+
+impl_variadic_new!(AndOp);
+impl_variadic_new!(OrOp);
+impl_variadic_new!(IntersectOp);
+
+impl NotOp {
+    /// Create a new property negation.
+    pub fn new(builder: &mut Builder, input: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(input);
+            state.add_result(get_property_type(builder.cx));
+        })
+    }
+}
+
+impl ImplicationOp {
+    /// Create a new overlapping implication `antecedent |-> consequent`.
+    pub fn new(builder: &mut Builder, antecedent: Value, consequent: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(antecedent);
+            state.add_operand(consequent);
+            state.add_result(get_property_type(builder.cx));
+        })
+    }
+}
+
+impl DelayOp {
+    /// Create a new delay of `delay` cycles, optionally extended by up to
+    /// `length` additional cycles. An absent `length` represents an unbounded
+    /// delay.
+    pub fn new(builder: &mut Builder, input: Value, delay: usize, length: Option<usize>) -> Self {
+        builder.build_with(|builder, state| {
+            let attr_ty = get_integer_type(builder.cx, 64);
+            state.add_operand(input);
+            state.add_attribute("delay", get_integer_attr(attr_ty, delay as i64));
+            if let Some(length) = length {
+                state.add_attribute("length", get_integer_attr(attr_ty, length as i64));
+            }
+            state.add_result(get_sequence_type(builder.cx));
+        })
+    }
+}
+
+impl ConcatOp {
+    /// Create a new concatenation of sequences.
+    pub fn new(builder: &mut Builder, inputs: &[Value]) -> Self {
+        builder.build_with(|builder, state| {
+            for &input in inputs {
+                state.add_operand(input);
+            }
+            state.add_result(get_sequence_type(builder.cx));
+        })
+    }
+}
+
+impl RepeatOp {
+    /// Create a new consecutive repetition of `base` times, optionally
+    /// extended by up to `more` additional repetitions. An absent `more`
+    /// represents an unbounded number of repetitions.
+    pub fn new(builder: &mut Builder, input: Value, base: usize, more: Option<usize>) -> Self {
+        builder.build_with(|builder, state| {
+            let attr_ty = get_integer_type(builder.cx, 64);
+            state.add_operand(input);
+            state.add_attribute("base", get_integer_attr(attr_ty, base as i64));
+            if let Some(more) = more {
+                state.add_attribute("more", get_integer_attr(attr_ty, more as i64));
+            }
+            state.add_result(get_sequence_type(builder.cx));
+        })
+    }
+}
+
+impl GoToRepeatOp {
+    /// Create a new goto repetition `input[->base:base+more]`.
+    pub fn new(builder: &mut Builder, input: Value, base: usize, more: usize) -> Self {
+        builder.build_with(|builder, state| {
+            let attr_ty = get_integer_type(builder.cx, 64);
+            state.add_operand(input);
+            state.add_attribute("base", get_integer_attr(attr_ty, base as i64));
+            state.add_attribute("more", get_integer_attr(attr_ty, more as i64));
+            state.add_result(get_sequence_type(builder.cx));
+        })
+    }
+}
+
+impl NonConsecutiveRepeatOp {
+    /// Create a new non-consecutive repetition `input[=base:base+more]`.
+    pub fn new(builder: &mut Builder, input: Value, base: usize, more: usize) -> Self {
+        builder.build_with(|builder, state| {
+            let attr_ty = get_integer_type(builder.cx, 64);
+            state.add_operand(input);
+            state.add_attribute("base", get_integer_attr(attr_ty, base as i64));
+            state.add_attribute("more", get_integer_attr(attr_ty, more as i64));
+            state.add_result(get_sequence_type(builder.cx));
+        })
+    }
+}
+
+impl DisableOp {
+    /// Create a new property that is disabled while `condition` holds. An
+    /// evaluation of the property which is in progress when the condition
+    /// becomes true is aborted and considered to hold.
+    pub fn new(builder: &mut Builder, input: Value, condition: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(input);
+            state.add_operand(condition);
+            state.add_result(get_property_type(builder.cx));
+        })
+    }
+}
+
+impl ClockOp {
+    /// Associate a sequence or property with a clock edge.
+    pub fn new(builder: &mut Builder, input: Value, edge: ClockEdge, clock: Value) -> Self {
+        builder.build_with(|builder, state| {
+            let edge = match edge {
+                ClockEdge::Pos => "posedge",
+                ClockEdge::Neg => "negedge",
+                ClockEdge::Both => "edge",
+            };
+            state.add_operand(input);
+            state.add_operand(clock);
+            state.add_attribute(
+                "edge",
+                parse_attr(builder.cx, &format!("#ltl<clock_edge {}>", edge)),
+            );
+            state.add_result(if is_property_type(input.ty()) {
+                get_property_type(builder.cx)
+            } else {
+                get_sequence_type(builder.cx)
+            });
+        })
+    }
+}
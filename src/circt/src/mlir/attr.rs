@@ -95,3 +95,13 @@ pub fn get_array_attr(cx: Context, elements: impl IntoIterator<Item = Attribute>
         mlirArrayAttrGet(cx.raw(), elements.len() as _, elements.as_ptr())
     })
 }
+
+/// Parse an attribute from its textual representation.
+///
+/// This is useful for dialect attributes which are not exposed through the C
+/// API.
+pub fn parse_attr(cx: Context, attr: &str) -> Attribute {
+    let raw = unsafe { mlirAttributeParseGet(cx.raw(), mlirStringRefCreateFromStr(attr)) };
+    assert!(!raw.ptr.is_null(), "invalid attribute `{}`", attr);
+    Attribute::from_raw(raw)
+}
@@ -75,24 +75,8 @@ pub trait OperationExt: WrapRaw<RawType = MlirOperation> {
 
     /// Print the operation to anything that implements `std::io::Write`.
     fn print<T: std::io::Write>(&self, mut to: T, with_debug_info: bool) {
-        /// Helper callback function that interprets its `user_data` field as a
-        /// reference to a reference to something that implements `Write`. The
-        /// double reference is required to ensure we're not trying to pass a
-        /// fat pointer (e.g. for `T = &dyn Write`) to the C callback.
-        unsafe extern "C" fn callback<T: std::io::Write>(
-            string: MlirStringRef,
-            to: *mut std::ffi::c_void,
-        ) {
-            let to: &mut &mut T = std::mem::transmute(to);
-            to.write(std::slice::from_raw_parts(
-                string.data as *const _,
-                string.length as usize,
-            ))
-            .unwrap();
-        }
-
-        // Print the operation through the above callback, which basically just
-        // forwards the chunks to the Rust-native `Write` implementation.
+        // Print the operation through `print_write_callback`, which basically
+        // just forwards the chunks to the Rust-native `Write` implementation.
         unsafe {
             let flags = mlirOpPrintingFlagsCreate();
             if with_debug_info {
@@ -101,7 +85,7 @@ pub trait OperationExt: WrapRaw<RawType = MlirOperation> {
             mlirOperationPrintWithFlags(
                 self.raw(),
                 flags,
-                Some(callback::<T>),
+                Some(print_write_callback::<T>),
                 (&mut &mut to) as *const _ as *mut _,
             );
             mlirOpPrintingFlagsDestroy(flags);
@@ -128,6 +112,17 @@ pub trait OperationExt: WrapRaw<RawType = MlirOperation> {
         unsafe { mlirOperationGetAttributeByName(self.raw(), mlirStringRefCreateFromStr(name)) }
     }
 
+    /// Set an attribute of the operation.
+    fn set_attr(&self, name: &str, attr: Attribute) {
+        unsafe {
+            mlirOperationSetAttributeByName(
+                self.raw(),
+                mlirStringRefCreateFromStr(name),
+                attr.raw(),
+            )
+        }
+    }
+
     /// Return an attribute of the operation as an `i64`.
     fn get_attr_i64(&self, name: &str) -> Option<i64> {
         let attr = self.attr(name);
@@ -153,10 +148,93 @@ pub trait OperationExt: WrapRaw<RawType = MlirOperation> {
         self.get_attr_usize(name).unwrap()
     }
 
+    /// Return a string attribute of the operation.
+    fn get_attr_string(&self, name: &str) -> Option<String> {
+        let attr = self.attr(name);
+        if attr.ptr.is_null() || !unsafe { mlirAttributeIsAString(attr) } {
+            None
+        } else {
+            unsafe {
+                Some(mlirStringRefToStr(
+                    mlirStringAttrGetValue(attr),
+                    String::from,
+                ))
+            }
+        }
+    }
+
+    /// Return a flat symbol reference attribute of the operation.
+    fn get_attr_symbol(&self, name: &str) -> Option<String> {
+        let attr = self.attr(name);
+        if attr.ptr.is_null() || !unsafe { mlirAttributeIsAFlatSymbolRef(attr) } {
+            None
+        } else {
+            unsafe {
+                Some(mlirStringRefToStr(
+                    mlirFlatSymbolRefAttrGetValue(attr),
+                    String::from,
+                ))
+            }
+        }
+    }
+
+    /// Return the full name of the operation, like `builtin.module`.
+    ///
+    /// Unlike `operation_name`, this works for any operation.
+    fn name(&self) -> String {
+        unsafe { mlirIdentifierToStr(mlirOperationGetName(self.raw()), String::from) }
+    }
+
+    /// Return the location of the operation.
+    fn loc(&self) -> Location {
+        Location::from_raw(unsafe { mlirOperationGetLocation(self.raw()) })
+    }
+
+    /// Get the number of operands of the operation.
+    fn num_operands(&self) -> usize {
+        unsafe { mlirOperationGetNumOperands(self.raw()) as usize }
+    }
+
+    /// Get one of the operands of the operation.
+    fn operand(&self, index: usize) -> Value {
+        Value::from_raw(unsafe { mlirOperationGetOperand(self.raw(), index as _) })
+    }
+
+    /// Get an iterator over the operands of the operation.
+    fn operands(&self) -> Box<dyn Iterator<Item = Value> + '_> {
+        Box::new((0..self.num_operands()).map(move |i| self.operand(i)))
+    }
+
+    /// Get the number of results of the operation.
+    fn num_results(&self) -> usize {
+        unsafe { mlirOperationGetNumResults(self.raw()) as usize }
+    }
+
     /// Get one of the results of the operation.
     fn result(&self, index: usize) -> Value {
         Value::from_raw(unsafe { mlirOperationGetResult(self.raw(), index as _) })
     }
+
+    /// Emit an error diagnostic at the operation's location.
+    fn emit_error(&self, msg: &str) {
+        let msg = std::ffi::CString::new(msg).unwrap();
+        unsafe { mlirEmitError(mlirOperationGetLocation(self.raw()), msg.as_ptr()) }
+    }
+
+    /// Move the operation to the end of a block.
+    fn move_to_end(&self, block: MlirBlock) {
+        unsafe {
+            mlirOperationRemoveFromParent(self.raw());
+            mlirBlockAppendOwnedOperation(block, self.raw());
+        }
+    }
+
+    /// Remove the operation from its block and destroy it.
+    ///
+    /// The results of the operation must no longer be used.
+    fn erase(&self) {
+        unsafe { mlirOperationDestroy(self.raw()) }
+    }
 }
 
 /// An operation that has a single region.
@@ -291,5 +369,46 @@ unsafe extern "C" fn print_formatter_callback(string: MlirStringRef, to: *mut st
     .unwrap();
 }
 
+/// Helper function to feed the output of an MLIR `*Print()` function into an
+/// `std::io::Write`.
+///
+/// Interprets its `user_data` field as a reference to a reference to something
+/// that implements `Write`. The double reference is required to ensure we're
+/// not trying to pass a fat pointer (e.g. for `T = &dyn Write`) to the C
+/// callback.
+pub(crate) unsafe extern "C" fn print_write_callback<T: std::io::Write>(
+    string: MlirStringRef,
+    to: *mut std::ffi::c_void,
+) {
+    let to: &mut &mut T = std::mem::transmute(to);
+    to.write(std::slice::from_raw_parts(
+        string.data as *const _,
+        string.length as usize,
+    ))
+    .unwrap();
+}
+
+/// Get the operations in a block.
+pub fn block_operations(block: MlirBlock) -> Vec<Operation> {
+    let mut ops = vec![];
+    let mut op = unsafe { mlirBlockGetFirstOperation(block) };
+    while !op.ptr.is_null() {
+        ops.push(Operation::from_raw(op));
+        op = unsafe { mlirOperationGetNextInBlock(op) };
+    }
+    ops
+}
+
+/// Get the blocks in a region.
+pub fn region_blocks(region: MlirRegion) -> Vec<MlirBlock> {
+    let mut blocks = vec![];
+    let mut block = unsafe { mlirRegionGetFirstBlock(region) };
+    while !block.ptr.is_null() {
+        blocks.push(block);
+        block = unsafe { mlirBlockGetNextInRegion(block) };
+    }
+    blocks
+}
+
 pub use crate::sys::MlirBlock as Block;
 pub use crate::sys::MlirRegion as Region;
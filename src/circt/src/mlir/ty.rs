@@ -108,3 +108,12 @@ pub fn function_type_inputs(ty: Type) -> impl Iterator<Item = Type> {
 pub fn function_type_results(ty: Type) -> impl Iterator<Item = Type> {
     (0..function_type_num_results(ty)).map(move |i| function_type_result(ty, i))
 }
+
+/// Parse a type from its textual representation.
+///
+/// This is useful for dialect types which are not exposed through the C API.
+pub fn parse_type(cx: Context, ty: &str) -> Type {
+    let raw = unsafe { mlirTypeParseGet(cx.raw(), mlirStringRefCreateFromStr(ty)) };
+    assert!(!raw.ptr.is_null(), "invalid type `{}`", ty);
+    Type::from_raw(raw)
+}
@@ -14,6 +14,23 @@ impl Value {
     pub fn ty(&self) -> Type {
         Type::from_raw(unsafe { mlirValueGetType(self.raw()) })
     }
+
+    /// Return the operation that defines this value, or `None` if it is a
+    /// block argument.
+    pub fn defining_op(&self) -> Option<Operation> {
+        if unsafe { mlirValueIsAOpResult(self.raw()) } {
+            Some(Operation::from_raw(unsafe {
+                mlirOpResultGetOwner(self.raw())
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// Replace all uses of this value with another value.
+    pub fn replace_all_uses_with(&self, other: Value) {
+        unsafe { mlirValueReplaceAllUsesWith(self.raw(), other.raw()) }
+    }
 }
 
 impl WrapRaw for Value {
@@ -1,7 +1,107 @@
 // Copyright (c) 2016-2021 Fabian Schuiki
 
-use crate::mlir::DialectHandle;
+use crate::crate_prelude::*;
 
 pub fn dialect() -> DialectHandle {
     DialectHandle::from_raw(unsafe { crate::sys::mlirGetDialectHandle__seq__() })
 }
+
+/// Create a new clock type.
+pub fn get_clock_type(cx: Context) -> Type {
+    parse_type(cx, "!seq.clock")
+}
+
+def_operation_single_result!(ToClockOp, "seq.to_clock");
+def_operation_single_result!(CompRegOp, "seq.compreg");
+def_operation_single_result!(CompRegClockEnabledOp, "seq.compreg.ce");
+def_operation_single_result!(FirRegOp, "seq.firreg");
+
+impl ToClockOp {
+    /// Convert an `i1` value into a clock.
+    pub fn new(builder: &mut Builder, input: Value) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(input);
+            state.add_result(get_clock_type(builder.cx));
+        })
+    }
+}
+
+impl CompRegOp {
+    /// Create a new register that samples `input` on every rising edge of
+    /// `clock`.
+    pub fn new(builder: &mut Builder, input: Value, clock: Value) -> Self {
+        builder.build_with(|_, state| {
+            state.add_operand(input);
+            state.add_operand(clock);
+            state.add_result(input.ty());
+        })
+    }
+
+    /// Create a new register that samples `input` on every rising edge of
+    /// `clock`, and `reset_value` instead if `reset` is high at that edge.
+    pub fn with_reset(
+        builder: &mut Builder,
+        input: Value,
+        clock: Value,
+        reset: Value,
+        reset_value: Value,
+    ) -> Self {
+        builder.build_with(|_, state| {
+            state.add_operand(input);
+            state.add_operand(clock);
+            state.add_operand(reset);
+            state.add_operand(reset_value);
+            state.add_result(input.ty());
+        })
+    }
+}
+
+impl CompRegClockEnabledOp {
+    /// Create a new register that samples `input` on the rising edges of
+    /// `clock` where `enable` is high.
+    pub fn new(builder: &mut Builder, input: Value, clock: Value, enable: Value) -> Self {
+        builder.build_with(|_, state| {
+            state.add_operand(input);
+            state.add_operand(clock);
+            state.add_operand(enable);
+            state.add_result(input.ty());
+        })
+    }
+}
+
+impl FirRegOp {
+    /// Create a new register called `name` that samples `next` on every rising
+    /// edge of `clock`.
+    ///
+    /// If a `(reset, reset_value, is_async)` triple is given, the register
+    /// assumes `reset_value` while `reset` is high. An asynchronous reset takes
+    /// effect immediately, a synchronous one at the next clock edge.
+    pub fn new(
+        builder: &mut Builder,
+        next: Value,
+        clock: Value,
+        name: &str,
+        reset: Option<(Value, Value, bool)>,
+    ) -> Self {
+        builder.build_with(|builder, state| {
+            state.add_operand(next);
+            state.add_operand(clock);
+            state.add_attribute("name", get_string_attr(builder.cx, name));
+            if let Some((reset, reset_value, is_async)) = reset {
+                state.add_operand(reset);
+                state.add_operand(reset_value);
+                if is_async {
+                    state.add_attribute("isAsync", get_unit_attr(builder.cx));
+                }
+            }
+            state.add_result(next.ty());
+        })
+    }
+}
+
+/// Lower the registers in a module to `sv` registers and always blocks, as
+/// required by `sv::export_verilog`, and fold the structs and arrays which are
+/// only taken apart again.
+pub fn lower_to_sv(module: crate::ModuleOp) -> bool {
+    unsafe { mlirLowerSeqToSV(mlirModuleFromOperation(module.raw())).value != 0 }
+}
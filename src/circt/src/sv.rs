@@ -0,0 +1,25 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+use crate::crate_prelude::*;
+
+pub fn dialect() -> DialectHandle {
+    DialectHandle::from_raw(unsafe { crate::sys::mlirGetDialectHandle__sv__() })
+}
+
+/// Emit a module of `hw`, `comb`, and `sv` operations as Verilog through
+/// CIRCT's ExportVerilog.
+///
+/// The output is written through the same callback as `OperationExt::print`.
+/// Problems with the module are reported as MLIR diagnostics, in which case
+/// `false` is returned.
+pub fn export_verilog<T: std::io::Write>(module: crate::ModuleOp, mut to: T) -> bool {
+    unsafe {
+        mlirExportVerilog(
+            mlirModuleFromOperation(module.raw()),
+            Some(crate::mlir::print_write_callback::<T>),
+            (&mut &mut to) as *const _ as *mut _,
+        )
+        .value
+            != 0
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+use crate::crate_prelude::*;
+
+pub fn dialect() -> DialectHandle {
+    DialectHandle::from_raw(unsafe { crate::sys::mlirGetDialectHandle__verif__() })
+}
+
+def_operation!(AssertOp, "verif.assert");
+def_operation!(AssumeOp, "verif.assume");
+def_operation!(CoverOp, "verif.cover");
+
+/// Define the constructor of a verification operation.
+macro_rules! impl_verif_new {
+    ($name:ident, $doc:expr) => {
+        impl $name {
+            #[doc = $doc]
+            pub fn new(builder: &mut Builder, property: Value, label: Option<&str>) -> Self {
+                builder.build_with(|builder, state| {
+                    state.add_operand(property);
+                    if let Some(label) = label {
+                        state.add_attribute("label", get_string_attr(builder.cx, label));
+                    }
+                })
+            }
+        }
+    };
+}
+
+impl_verif_new!(AssertOp, "Create a new assertion that a property holds.");
+impl_verif_new!(AssumeOp, "Create a new assumption that a property holds.");
+impl_verif_new!(
+    CoverOp,
+    "Create a new check that a property can be covered."
+);
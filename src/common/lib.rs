@@ -75,6 +75,12 @@ pub struct SessionOptions {
     pub verbosity: Verbosity,
     /// The optimization level.
     pub opt_level: usize,
+    /// The directories to search for included files, and for files read at
+    /// compile time such as memory initialization files.
+    pub include_paths: Vec<std::path::PathBuf>,
+    /// Preserve X and Z values in the generated code, instead of mapping all
+    /// four-valued types to two-valued ones.
+    pub four_state: bool,
 }
 
 bitflags! {
@@ -5,9 +5,14 @@
 
 use crate::{
     crate_prelude::*,
-    hir::{AccessedNode, HirNode},
+    format,
+    func_args::FuncArgList,
+    hir::{AccessedNode, HirNode, Visitor},
     port_list::PortList,
+    readmem,
     resolver::InstTarget,
+    runtime::RuntimeFunc,
+    structural,
     ty::UnpackedType,
     value::{Value, ValueKind},
     ParamEnv,
@@ -15,7 +20,7 @@ use crate::{
 use moore_circt::{self as circt, comb::CmpPred, mlir, prelude::*};
 use num::{BigInt, FromPrimitive, One, ToPrimitive, Zero};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     iter::{once, repeat},
     ops::{Deref, DerefMut},
     rc::Rc,
@@ -55,8 +60,31 @@ impl<'gcx, C> CodeGenerator<'gcx, C> {
 
     /// Finalize code generation and return the generated LLHD module.
     pub fn finalize(self) -> llhd::ir::Module {
+        if !self.tables.runtime_strings.is_empty() {
+            let strings = self
+                .tables
+                .runtime_strings
+                .iter()
+                .map(|s| mlir::get_string_attr(self.mcx, s));
+            self.into_mlir
+                .set_attr("moore.strings", mlir::get_array_attr(self.mcx, strings));
+        }
         self.into
     }
+
+    /// Lower the emitted MLIR module to structural HW modules.
+    ///
+    /// Emits diagnostics for the constructs which cannot be represented.
+    pub fn lower_to_hw(&mut self) -> Result<()> {
+        structural::lower_to_hw(self.into_mlir, &self.tables.port_names)
+    }
+
+    /// Check that the structural HW modules can be emitted as Verilog.
+    ///
+    /// Emits diagnostics for the ports which Verilog-2005 cannot represent.
+    pub fn check_verilog(&mut self) -> Result<()> {
+        structural::check_verilog(self.into_mlir, &self.tables.port_names)
+    }
 }
 
 #[derive(Default)]
@@ -65,6 +93,25 @@ struct Tables<'gcx> {
     module_signatures: HashMap<NodeEnvId, (llhd::ir::UnitName, llhd::ir::Signature)>,
     interned_types: HashMap<&'gcx UnpackedType<'gcx>, Result<HybridType>>,
     function_defs: HashMap<NodeEnvId, Result<Rc<EmittedFunction>>>,
+    /// The functions whose body is currently being emitted.
+    functions_in_progress: HashSet<NodeEnvId>,
+    runtime_decls: HashSet<RuntimeFunc>,
+    /// The C functions declared for DPI imports.
+    dpi_decls: HashSet<String>,
+    runtime_strings: Vec<String>,
+    runtime_string_ids: HashMap<String, usize>,
+    /// Initial memory contents loaded by `$readmemh` and `$readmemb`.
+    mem_inits: HashMap<NodeEnvId, Value<'gcx>>,
+    /// The `$readmemh` and `$readmemb` calls that have been applied to a
+    /// memory declaration.
+    mem_init_calls: HashSet<NodeEnvId>,
+    /// The `static` tasks that have been inlined with automatic storage.
+    static_tasks: HashSet<NodeId>,
+    /// The input and output port names of each emitted entity.
+    port_names: structural::PortNames,
+    /// The constructs which the LLHD output cannot represent, together with
+    /// a description of what is missing.
+    llhd_unsupported: Vec<(Span, &'static str)>,
 }
 
 impl<'gcx, C> Deref for CodeGenerator<'gcx, C> {
@@ -82,7 +129,10 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         for file in &ast.files {
             for item in &file.items {
                 match &item.data {
-                    ast::ItemData::SubroutineDecl(decl) => {
+                    // Tasks are inlined into their callers instead.
+                    ast::ItemData::SubroutineDecl(decl)
+                        if decl.prototype.kind == ast::SubroutineKind::Func =>
+                    {
                         self.emit_function(decl.id(), self.default_param_env())?;
                     }
                     _ => (),
@@ -97,6 +147,28 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         self.emit_module_with_env(id, self.default_param_env())
     }
 
+    /// Check that the emitted code can be represented in LLHD.
+    ///
+    /// Emits diagnostics for the constructs which only the MLIR output
+    /// supports.
+    pub fn check_llhd(&mut self) -> Result<()> {
+        for &(span, what) in &self.tables.llhd_unsupported {
+            self.emit(
+                DiagBuilder2::error(format!("unsupported in LLHD output: `{}`", span.extract()))
+                    .span(span)
+                    .add_note(format!(
+                        "LLHD cannot express {}; use `--format=mlir` instead",
+                        what
+                    )),
+            );
+        }
+        if self.tables.llhd_unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
     /// Emit the code for a module and all its dependent modules.
     pub fn emit_module_with_env(
         &mut self,
@@ -141,6 +213,13 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         }
         let entity_op = entity_op.build_entity(&mut mlir_builder);
         mlir_builder.set_insertion_point_to_start(entity_op.block());
+        self.tables.port_names.insert(
+            entity_name.clone(),
+            (
+                ports.inputs.iter().map(|port| port.name.clone()).collect(),
+                ports.outputs.iter().map(|port| port.name.clone()).collect(),
+            ),
+        );
 
         // Create entity.
         let mut ent =
@@ -159,11 +238,25 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             gen.values
                 .insert(port.accnode, (arg, entity_op.input(index)));
         }
+        let mut port_nets = vec![];
         for (index, port) in ports.outputs.iter().enumerate() {
             let arg = gen.builder.output_arg(index);
             gen.builder.set_name(arg, port.name.clone());
-            gen.values
-                .insert(port.accnode, (arg, entity_op.output(index)));
+            let value = (arg, entity_op.output(index));
+            // The output of an inout port only carries the module's
+            // contribution to the net. Reading the port yields the input.
+            match port.kind {
+                ModulePortKind::Port if port.dir == ast::PortDir::Inout => {
+                    port_nets.push((port.port.id, port.ty, Some(value)));
+                }
+                ModulePortKind::Port => {
+                    gen.values.insert(port.accnode, value);
+                    port_nets.push((port.port.id, port.ty, None));
+                }
+                ModulePortKind::IntfSignal { .. } => {
+                    gen.values.insert(port.accnode, value);
+                }
+            }
         }
 
         debug!("  Ports:");
@@ -176,17 +269,22 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             );
         }
 
-        // Emit the actual contents of the entity.
+        // Emit the actual contents of the entity, resolving output and inout
+        // ports driven from multiple places.
+        let port_nets = gen.prepare_nets(env, &hir.block, port_nets)?;
         gen.emit_module_block(id, env, &hir.block, &entity_name)?;
+        gen.emit_net_resolution(port_nets)?;
 
         // Assign default values to undriven output ports.
-        for port in ports.outputs.iter() {
-            let value = gen.values[&port.accnode];
+        for (index, port) in ports.outputs.iter().enumerate() {
+            let value = (gen.builder.output_arg(index), entity_op.output(index));
             let driven = gen
                 .builder
                 .all_insts()
                 .any(|inst| match gen.builder[inst].opcode() {
-                    llhd::ir::Opcode::Drv => gen.builder[inst].args()[0] == value.0,
+                    llhd::ir::Opcode::Drv | llhd::ir::Opcode::Reg => {
+                        gen.builder[inst].args()[0] == value.0
+                    }
                     llhd::ir::Opcode::Inst => gen.builder[inst].output_args().contains(&value.0),
                     _ => false,
                 });
@@ -196,6 +294,18 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             let default_value = gen.emit_const(
                 if let Some(default) = port.default {
                     gen.constant_value_of(default, env)
+                } else if let ModulePortKind::Port = port.kind {
+                    // Undriven nets float, but the contribution of an inout
+                    // port is Z regardless of any pull on the net.
+                    match (port.dir, port.port.kind) {
+                        (ast::PortDir::Inout, _) if gen.is_four_state(port.ty) => {
+                            gen.intern_value(value::make_int_z(port.ty))
+                        }
+                        (_, ast::VarKind::Net { ty, .. }) => {
+                            value::net_default_value(gen.cx, port.ty, ty)
+                        }
+                        _ => gen.type_default_value(port.ty),
+                    }
                 } else {
                     gen.type_default_value(port.ty)
                 },
@@ -263,8 +373,13 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                         llty,
                         mty
                     );
+                    let dir = dirs
+                        .get(&signal.name.value)
+                        .copied()
+                        .unwrap_or(ast::PortDir::Output);
                     let port = ModulePort {
                         port,
+                        dir,
                         ty: signal.ty,
                         mty,
                         name,
@@ -276,12 +391,12 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                             decl_id: signal.decl_id,
                         },
                     };
-                    match dirs.get(&signal.name.value).copied() {
-                        Some(ast::PortDir::Input) | Some(ast::PortDir::Ref) => {
+                    match dir {
+                        ast::PortDir::Input | ast::PortDir::Ref => {
                             sig.add_input(llty);
                             inputs.push(port);
                         }
-                        Some(ast::PortDir::Output) | Some(ast::PortDir::Inout) | None => {
+                        ast::PortDir::Output | ast::PortDir::Inout => {
                             sig.add_output(llty);
                             outputs.push(port);
                         }
@@ -293,6 +408,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 let name = port.name.to_string();
                 let mp = ModulePort {
                     port,
+                    dir: port.dir,
                     ty,
                     mty,
                     name,
@@ -305,10 +421,30 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                         sig.add_input(llty);
                         inputs.push(mp);
                     }
-                    ast::PortDir::Inout | ast::PortDir::Output => {
+                    ast::PortDir::Output => {
                         sig.add_output(llty);
                         outputs.push(mp);
                     }
+                    // Inout ports are split into an input which carries the
+                    // resolved value of the net outside the module, and an
+                    // output which carries the module's own contribution to
+                    // it. The two are combined by the net resolution in the
+                    // parent module.
+                    ast::PortDir::Inout => {
+                        sig.add_input(llty.clone());
+                        sig.add_output(llty);
+                        outputs.push(ModulePort {
+                            port,
+                            dir: port.dir,
+                            ty,
+                            mty,
+                            name: format!("{}.drv", port.name),
+                            accnode: AccessedNode::Regular(port.id),
+                            default: None,
+                            kind: ModulePortKind::Port,
+                        });
+                        inputs.push(mp);
+                    }
                 }
             }
         }
@@ -522,6 +658,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             }
             pg.shadows.insert(id.into(), shadow);
         }
+        pg.alloc_deferred_actions(&[hir.stmt]);
 
         // Emit prologue and determine which basic block to jump back to.
         let head_blk = match hir.kind {
@@ -530,6 +667,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 let check_blk = pg.mk_block(Some("check"));
                 pg.mk_br(body_blk);
                 pg.append_to(check_blk);
+                pg.emit_deferred_actions(env)?;
                 let trigger_on: Vec<_> = inputs
                     .iter()
                     .map(|&id| pg.emitted_value(id).clone())
@@ -579,6 +717,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         // Emit epilogue.
         match hir.kind {
             ast::ProcedureKind::Initial | ast::ProcedureKind::Final => {
+                pg.emit_deferred_actions(env)?;
                 pg.builder.ins().halt();
                 circt::llhd::HaltOp::new(pg.mlir_builder);
             }
@@ -622,12 +761,31 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         }
         let ty = ty.resolve_full();
 
+        // Handle four-valued scalars in four-state mode. See
+        // `emit_four_state_type`.
+        if self.is_four_state(ty) {
+            return Ok(self.emit_four_state_type(ty.get_bit_size().unwrap()));
+        }
+
         // Handle things that coalesce easily to scalars.
         if ty.coalesces_to_llhd_scalar() {
             let bits = ty.get_bit_size().unwrap();
             return Ok((llhd::int_ty(bits), mlir::get_integer_type(self.mcx, bits)));
         }
 
+        // Handle reals, which are represented by their IEEE 754 bit pattern.
+        if let Some(real) = ty.get_real() {
+            let bits = real.bit_size();
+            return Ok((llhd::int_ty(bits), mlir::get_integer_type(self.mcx, bits)));
+        }
+
+        // Handle strings and dynamic arrays, which are represented by a runtime
+        // handle, events (see `mk_event_trigger`), and C handles passed to and
+        // from DPI imports.
+        if ty.is_string() || ty.is_event() || ty.is_dynamic_array() || ty.is_chandle() {
+            return Ok((llhd::int_ty(64), mlir::get_integer_type(self.mcx, 64)));
+        }
+
         // Handle arrays.
         if let Some(dim) = ty.outermost_dim() {
             let size = match dim.get_size() {
@@ -657,6 +815,25 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             ));
         }
 
+        // Handle tagged unions, which are represented as a struct with the tag
+        // as its first field, followed by a field for every member. Packed
+        // unions coalesce to scalars and are handled above.
+        if let Some(union) = ty.get_union() {
+            let (tag_llty, tag_mty) = self.emit_type_both(union.tag_type(self.cx))?;
+            let mut types = vec![tag_llty];
+            let mut mtypes: Vec<(crate::common::name::RcStr, mlir::Type)> =
+                vec![(crate::common::name::RcStr::new("tag"), tag_mty)];
+            for member in &union.members {
+                let (llty, mty) = self.emit_type_both(member.ty)?;
+                types.push(llty);
+                mtypes.push((member.name.value.as_str(), mty));
+            }
+            return Ok((
+                llhd::struct_ty(types),
+                circt::hw::get_struct_type(self.mcx, mtypes),
+            ));
+        }
+
         // Handle packed types.
         if let Some(packed) = ty.get_packed() {
             let packed = packed.resolve_full();
@@ -680,6 +857,19 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         panic!("cannot map `{}` to LLHD", ty);
     }
 
+    /// Map a four-valued scalar to LLHD in four-state mode.
+    ///
+    /// The value is represented as a struct of a value and an unknown bit
+    /// plane. A bit is 0 or 1 if its unknown bit is clear. Otherwise it is Z if
+    /// its value bit is set, and X if it is clear.
+    fn emit_four_state_type(&mut self, width: usize) -> HybridType {
+        let mty = mlir::get_integer_type(self.mcx, width);
+        (
+            llhd::struct_ty(vec![llhd::int_ty(width), llhd::int_ty(width)]),
+            circt::hw::get_struct_type(self.mcx, vec![("value", mty), ("unknown", mty)]),
+        )
+    }
+
     /// Execute the initialization step of a generate loop.
     fn execute_genvar_init(&mut self, id: NodeId, env: ParamEnv) -> Result<ParamEnv> {
         let hir = self.hir_of(id)?;
@@ -763,12 +953,35 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
 
     /// Emit the code for a function or task.
     pub fn emit_function(&mut self, id: NodeId, env: ParamEnv) -> Result<Rc<EmittedFunction>> {
+        let ast = self.ast_for_id(id).as_all().get_subroutine_decl().unwrap();
+
+        // Calls to a function whose body is still being emitted are recursive.
+        // Only automatic functions have per-call storage for their arguments
+        // and locals, so recursion into a static function is an error.
+        if self.tables.functions_in_progress.contains(&id.env(env)) && !ast.is_automatic() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "static function `{}` cannot be called recursively",
+                    ast.prototype.name
+                ))
+                .span(ast.prototype.name.span)
+                .add_note("Declare the function `automatic` to allow recursion."),
+            );
+            self.tables.function_defs.insert(id.env(env), Err(()));
+            return Err(());
+        }
         if let Some(x) = self.tables.function_defs.get(&id.env(env)) {
             return x.clone();
         }
-        let ast = self.ast_for_id(id).as_all().get_subroutine_decl().unwrap();
         info!("Emit function `{}` with {:?}", ast.prototype.name, env);
 
+        // Functions imported through the DPI are only declared.
+        if let Some(dpi) = dpi_import_of(ast) {
+            let x = self.emit_dpi_import(ast, dpi, env);
+            self.tables.function_defs.insert(id.env(env), x.clone());
+            return x;
+        }
+
         // Gather the port details and return type of the function.
         let args = self.canonicalize_func_args(Ref(ast));
         let return_ty = typeck::return_type_of_function(self.cx, &ast.prototype, env);
@@ -805,14 +1018,50 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
             func_op.add_result(None, lowered_return_ty.1);
         }
 
-        // Create the function itself.
+        // Create the function itself. Register it before emitting the body,
+        // such that recursive calls can refer to it.
         let func_op = func_op.build(&mut mlir_builder);
         let mut func = llhd::ir::UnitData::new(
             llhd::ir::UnitKind::Function,
             llhd::ir::UnitName::Local(func_name.clone()),
             sig,
         );
-        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut func);
+        let x = Ok(Rc::new(EmittedFunction {
+            name: func.name().clone(),
+            sig: func.sig().clone(),
+            mlir_symbol: func_name,
+            dpi: false,
+        }));
+        self.tables.function_defs.insert(id.env(env), x.clone());
+        self.tables.functions_in_progress.insert(id.env(env));
+        let result = self.emit_function_body(ast, args, return_ty, &mut func, &func_op, env);
+        self.tables.functions_in_progress.remove(&id.env(env));
+        if result.is_err() {
+            // Remove the partially emitted function from the output.
+            func_op.erase();
+            self.tables.function_defs.insert(id.env(env), Err(()));
+            return Err(());
+        }
+
+        // Add the function to the module and return a handle.
+        self.into.add_unit(func);
+        x
+    }
+
+    /// Emit the body of a function.
+    fn emit_function_body(
+        &mut self,
+        ast: &'gcx ast::SubroutineDecl<'gcx>,
+        args: &'gcx FuncArgList<'gcx>,
+        return_ty: &'gcx UnpackedType<'gcx>,
+        func: &mut llhd::ir::UnitData,
+        func_op: &circt::func::FuncOp,
+        env: ParamEnv,
+    ) -> Result<()> {
+        let lowered_return_ty = self.emit_type_both(return_ty)?;
+        let mut mlir_builder = mlir::Builder::new(self.mcx);
+        mlir_builder.set_loc(span_to_loc(self.mcx, ast.span()));
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(func);
 
         // Create a unit generator that we will use to populate the function
         // with instructions.
@@ -843,6 +1092,15 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 gen.emit_blocking_assign_llhd((value, None), default)?;
             }
         }
+        let stmts: Vec<_> = ast
+            .items
+            .iter()
+            .flat_map(|item| match item {
+                ast::SubroutineItem::Stmt(stmt) => Some(stmt.id()),
+                _ => None,
+            })
+            .collect();
+        gen.alloc_deferred_actions(&stmts);
 
         // Emit the body of the function.
         for item in &ast.items {
@@ -854,6 +1112,7 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
         // If the function body did not provide proper termination, add a
         // default return.
         if !gen.terminated {
+            gen.emit_deferred_actions(env)?;
             if return_ty.is_void() {
                 gen.mk_ret(None);
             } else {
@@ -863,17 +1122,325 @@ impl<'a, 'gcx, C: Context<'gcx>> CodeGenerator<'gcx, &'a C> {
                 gen.mk_ret(return_values);
             }
         }
+        Ok(())
+    }
 
-        // Add the function to the module and return a handle.
-        let x = Ok(Rc::new(EmittedFunction {
-            unit: self.into.add_unit(func),
+    /// Emit the declaration of a function or task imported through the DPI.
+    ///
+    /// Arguments and results are passed in their C representation, following
+    /// the `svdpi.h` ABI of Annex H:
+    ///
+    /// - `byte`, `shortint`, `int`, and `longint` map to `char`, `short`,
+    ///   `int`, and `long long`, passed as an `iN`.
+    /// - `bit` and `logic` scalars map to `svBit` and `svLogic`, passed as an
+    ///   `i8`. A `logic` holds 0, 1, 2, or 3 for 0, 1, Z, and X.
+    /// - `bit` and `logic` packed vectors, including `integer`, map to
+    ///   `const svBitVecVal*` and `const svLogicVecVal*`. The caller passes a
+    ///   pointer to a copy of the value, split into 32 bit chunks starting at
+    ///   the LSB. Each `svLogicVecVal` chunk holds the `aval` bits followed by
+    ///   the `bval` bits.
+    /// - `chandle` maps to `void*`, passed as an `i64`.
+    /// - `string` maps to `const char*`, passed as an `i64`. Strings are
+    ///   converted with `__moore_string_to_c` and `__moore_string_from_c` at
+    ///   the call site.
+    ///
+    /// Output and inout arguments of integer atom and `chandle` type are passed
+    /// as a pointer to their value. Results are restricted to the small values
+    /// of §35.5.5, so packed vectors cannot be returned. Imported tasks have no
+    /// result.
+    fn emit_dpi_import(
+        &mut self,
+        ast: &'gcx ast::SubroutineDecl<'gcx>,
+        dpi: &'gcx ast::DpiDecl<'gcx>,
+        env: ParamEnv,
+    ) -> Result<Rc<EmittedFunction>> {
+        let (spec, cident) = match dpi.data {
+            ast::DpiDeclData::Import { spec, cident, .. } => (spec, cident),
+            _ => unreachable!(),
+        };
+        if &*spec.value.as_str() != "DPI-C" {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "unsupported: DPI specifier \"{}\"; only \"DPI-C\" is supported",
+                    spec.value
+                ))
+                .span(spec.span),
+            );
+            return Err(());
+        }
+        let func_name = cident.unwrap_or(ast.prototype.name).value.to_string();
+
+        // Gather the arguments and return type of the function.
+        let args = self.canonicalize_func_args(Ref(ast));
+        let mut sig = llhd::ir::Signature::new();
+        let mut func_op = circt::func::FunctionBuilder::new(&func_name);
+        let mut failed = false;
+        for arg in &args.args {
+            let ty = self.type_of_func_arg(Ref(arg), env);
+            let repr = match self.dpi_repr(ty, arg.span) {
+                Ok(repr) => repr,
+                Err(()) => {
+                    failed = true;
+                    continue;
+                }
+            };
+            let lty = match (arg.dir, repr) {
+                (ast::SubroutinePortDir::Input, _) => self.emit_dpi_type(repr),
+                (_, DpiRepr::Int(_)) => pointer_ty(self.emit_dpi_type(repr)),
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "unsupported: {} argument of type `{}` in DPI import",
+                            arg.dir, ty
+                        ))
+                        .span(arg.span),
+                    );
+                    failed = true;
+                    continue;
+                }
+            };
+            func_op.add_arg(arg.name.map(|x| x.value.as_str().to_string()), lty.1);
+            sig.add_input(lty.0);
+        }
+        let return_ty = typeck::return_type_of_function(self.cx, &ast.prototype, env);
+        if !return_ty.is_void() {
+            let span = ast
+                .prototype
+                .retty
+                .as_ref()
+                .map(|x| x.span())
+                .unwrap_or(ast.span());
+            match self.dpi_result_repr(return_ty, span) {
+                Ok(repr) => {
+                    let lty = self.emit_dpi_type(repr);
+                    func_op.add_result(None, lty.1);
+                    sig.set_return_type(lty.0);
+                }
+                Err(()) => failed = true,
+            }
+        } else {
+            sig.set_return_type(llhd::void_ty());
+        }
+        if failed {
+            return Err(());
+        }
+
+        // Declare the function, unless another import of the same C function
+        // has already done so.
+        if self.tables.dpi_decls.insert(func_name.clone()) {
+            let mut builder = mlir::Builder::new(self.mcx);
+            builder.set_loc(span_to_loc(self.mcx, dpi.span()));
+            builder.set_insertion_point_to_end(self.into_mlir.block());
+            func_op.build_decl(&mut builder);
+        }
+        Ok(Rc::new(EmittedFunction {
+            name: llhd::ir::UnitName::Global(func_name.clone()),
+            sig,
             mlir_symbol: func_name,
-        }));
-        self.tables.function_defs.insert(id.env(env), x.clone());
-        x
+            dpi: true,
+        }))
+    }
+
+    /// Determine the C representation of a type passed to a DPI import. See
+    /// `emit_dpi_import`.
+    fn dpi_repr(&mut self, ty: &'gcx UnpackedType<'gcx>, span: Span) -> Result<DpiRepr> {
+        if ty.is_string() {
+            return Ok(DpiRepr::String);
+        }
+        if ty.is_chandle() {
+            return Ok(DpiRepr::Int(64));
+        }
+        let sbv = match ty.get_simple_bit_vector() {
+            Some(sbv) if ty.coalesces_to_llhd_scalar() => sbv,
+            _ => {
+                if !ty.is_error() {
+                    self.emit(
+                        DiagBuilder2::error(format!("unsupported: type `{}` in DPI import", ty))
+                            .span(span),
+                    );
+                }
+                return Err(());
+            }
+        };
+        let four_valued = sbv.domain == ty::Domain::FourValued;
+        let scalar = ty
+            .resolve_full()
+            .get_packed()
+            .map(|packed| packed.dims.is_empty())
+            .unwrap_or(false);
+        Ok(
+            match (four_valued, sbv.used_atom, scalar && sbv.size == 1) {
+                (false, true, _) => DpiRepr::Int(sbv.size),
+                (false, false, true) => DpiRepr::Bit,
+                (true, false, true) => DpiRepr::Logic,
+                (false, _, _) => DpiRepr::BitVec(sbv.size),
+                (true, _, _) => DpiRepr::LogicVec(sbv.size),
+            },
+        )
+    }
+
+    /// Determine the C representation of a type returned from a DPI import.
+    ///
+    /// Only small values can be returned. See §35.5.5 "Function result".
+    fn dpi_result_repr(&mut self, ty: &'gcx UnpackedType<'gcx>, span: Span) -> Result<DpiRepr> {
+        match self.dpi_repr(ty, span)? {
+            DpiRepr::BitVec(_) | DpiRepr::LogicVec(_) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "DPI import cannot return a value of type `{}`",
+                        ty
+                    ))
+                    .span(span)
+                    .add_note(
+                        "Only integer atoms, `chandle`, `string`, and single `bit` and \
+                             `logic` values can be returned through the DPI.",
+                    ),
+                );
+                Err(())
+            }
+            repr => Ok(repr),
+        }
+    }
+
+    /// Map a C representation of a DPI argument or result to a type. See
+    /// `emit_dpi_import`.
+    fn emit_dpi_type(&mut self, repr: DpiRepr) -> HybridType {
+        let int_ty = |bits| (llhd::int_ty(bits), mlir::get_integer_type(self.mcx, bits));
+        match repr {
+            DpiRepr::Int(bits) => int_ty(bits),
+            DpiRepr::String => int_ty(64),
+            DpiRepr::Bit | DpiRepr::Logic => int_ty(8),
+            DpiRepr::BitVec(size) => pointer_ty(int_ty(dpi_chunks(size) * 32)),
+            DpiRepr::LogicVec(size) => pointer_ty(int_ty(dpi_chunks(size) * 64)),
+        }
+    }
+
+    /// Add a string to the string table passed to the runtime library.
+    ///
+    /// Returns the index of the string in the table.
+    fn intern_runtime_string(&mut self, value: &str) -> usize {
+        if let Some(&id) = self.tables.runtime_string_ids.get(value) {
+            return id;
+        }
+        let id = self.tables.runtime_strings.len();
+        self.tables.runtime_strings.push(value.to_string());
+        self.tables.runtime_string_ids.insert(value.to_string(), id);
+        id
+    }
+
+    /// Ensure a runtime library function is declared in the MLIR module.
+    fn declare_runtime_func(&mut self, func: RuntimeFunc) {
+        if !self.tables.runtime_decls.insert(func) {
+            return;
+        }
+        let mut builder = mlir::Builder::new(self.mcx);
+        builder.set_insertion_point_to_end(self.into_mlir.block());
+        let mut func_op = circt::func::FunctionBuilder::new(func.name());
+        for &width in func.arg_widths() {
+            func_op.add_arg(None, mlir::get_integer_type(self.mcx, width));
+        }
+        if let Some(width) = func.result_width() {
+            func_op.add_result(None, mlir::get_integer_type(self.mcx, width));
+        }
+        func_op.build_decl(&mut builder);
     }
 }
 
+/// A visitor that collects the `$readmemh` and `$readmemb` calls in a
+/// procedure.
+struct ReadMemCollector<'a, 'gcx, C> {
+    cx: &'a C,
+    calls: Vec<&'gcx hir::Expr<'gcx>>,
+}
+
+impl<'a, 'gcx, C: Context<'gcx>> Visitor<'gcx> for ReadMemCollector<'a, 'gcx, C> {
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>, lvalue: bool) {
+        if let hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem { .. }) = expr.kind {
+            self.calls.push(expr);
+        }
+        hir::walk_expr(self, expr, lvalue);
+    }
+}
+
+/// A visitor that looks for expressions in a procedure which cannot be
+/// emitted as dataflow, such as function calls and system tasks.
+struct ImpureExprFinder<'a, C> {
+    cx: &'a C,
+    found: bool,
+}
+
+impl<'a, 'gcx, C: Context<'gcx>> Visitor<'gcx> for ImpureExprFinder<'a, C> {
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>, lvalue: bool) {
+        match expr.kind {
+            hir::ExprKind::FunctionCall(..)
+            | hir::ExprKind::MethodCall(..)
+            | hir::ExprKind::ArrayNew(..)
+            | hir::ExprKind::Assign { .. }
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Unsupported)
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Rose(..))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(..))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(..))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::Past(..))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::SysTask(..))
+            | hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem { .. }) => self.found = true,
+            _ => hir::walk_expr(self, expr, lvalue),
+        }
+    }
+}
+
+/// A visitor that collects the deferred assertions with action blocks in a
+/// procedure or function.
+struct DeferredActionFinder<'a, C> {
+    cx: &'a C,
+    found: Vec<NodeId>,
+}
+
+impl<'a, 'gcx, C: Context<'gcx>> Visitor<'gcx> for DeferredActionFinder<'a, C> {
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.cx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt) {
+        if let hir::StmtKind::Assert {
+            deferred: Some(_),
+            pass_stmt,
+            fail_stmt,
+            ..
+        } = stmt.kind
+        {
+            if pass_stmt.is_some() || fail_stmt.is_some() {
+                self.found.push(stmt.id);
+            }
+        }
+        hir::walk_stmt(self, stmt);
+    }
+}
+
+/// The state of a deferred assertion with action blocks.
+#[derive(Clone, Copy)]
+struct DeferredAction {
+    /// The assertion statement.
+    stmt: NodeId,
+    /// Whether the assertion has been evaluated since its report last matured.
+    pending: HybridValue,
+    /// The result of the latest evaluation.
+    passed: HybridValue,
+}
+
 /// A name uniquifier.
 #[derive(Default)]
 struct NameUniquifier {
@@ -921,6 +1488,10 @@ struct UnitGenerator<'a, 'gcx, C> {
     /// The shadow variables introduced to handle signals which are both read
     /// and written in a process.
     shadows: HashMap<AccessedNode, HybridValue>,
+    /// The values assigned so far to the variables of a combinational
+    /// procedure emitted as dataflow, which later reads observe instead of
+    /// the signal. See `emit_comb`.
+    dataflow_values: Option<HashMap<NodeId, HybridValue>>,
     /// The emitted signal and instance names.
     unique_names: NameUniquifier,
     /// Whether the last emitted statement was a terminator. This indicates
@@ -934,6 +1505,17 @@ struct UnitGenerator<'a, 'gcx, C> {
     /// A stack of blocks, the last of which will be branched to by a `break`
     /// statement.
     break_stack: Vec<HybridBlock>,
+    /// The clock of the concurrent assertion currently being emitted, used to
+    /// sample past values.
+    assert_clock: Option<mlir::Value>,
+    /// The tasks currently being inlined, together with the block a `return`
+    /// statement in their body branches to. See `emit_task_call`.
+    inlined_tasks: Vec<(NodeId, HybridBlock)>,
+    /// The deferred assertions with action blocks in the current unit. See
+    /// `emit_deferred_actions`.
+    deferred_actions: Vec<DeferredAction>,
+    /// The nets whose drivers are redirected, registered by `prepare_nets`.
+    nets: HashMap<AccessedNode, NetDrivers<'gcx>>,
 }
 
 impl<'a, 'gcx, C> Deref for UnitGenerator<'a, 'gcx, C> {
@@ -966,10 +1548,15 @@ impl<'a, 'gcx, C> UnitGenerator<'a, 'gcx, C> {
             interned_lvalues: Default::default(),
             interned_rvalues: Default::default(),
             shadows: Default::default(),
+            dataflow_values: None,
             unique_names: Default::default(),
             terminated: false,
             break_stack: Default::default(),
             continue_stack: Default::default(),
+            assert_clock: None,
+            inlined_tasks: Default::default(),
+            deferred_actions: Default::default(),
+            nets: Default::default(),
         }
     }
 }
@@ -1008,69 +1595,824 @@ where
         self.interned_rvalues.clear();
     }
 
-    /// Emit the code for the contents of a module.
-    fn emit_module_block(
-        &mut self,
-        id: NodeId,
-        env: ParamEnv,
-        hir: &hir::ModuleBlock,
-        name_prefix: &str,
-    ) -> Result<()> {
-        // Emit declarations.
-        for &decl_id in &hir.decls {
-            let hir = match self.hir_of(decl_id)? {
-                HirNode::VarDecl(x) => x,
-                _ => unreachable!(),
-            };
-            let ty = self.type_of(decl_id, env)?;
-            let value = self.emit_varnet_decl(decl_id, ty, env, hir.init)?;
-            self.builder.set_name(value.0, hir.name.value.into());
-            self.values.insert(decl_id.into(), value);
+    /// Emit a clocked procedure as registers.
+    ///
+    /// Recognizes `always_ff` and `always` procedures triggered by the edge of
+    /// a clock and optionally an asynchronous reset, which only contain
+    /// nonblocking assignments to entire variables, `if`, and `case`
+    /// statements. Each assigned variable becomes a register in the current
+    /// unit. An `if` at the top of the procedure which only assigns constants
+    /// is treated as a synchronous reset, and assignments which only happen
+    /// under some condition as an enable. Returns `false` if the procedure
+    /// does not follow this pattern and needs to be emitted as a process.
+    fn emit_registers(&mut self, hir: &hir::Proc, env: ParamEnv) -> Result<bool> {
+        match hir.kind {
+            ast::ProcedureKind::Always | ast::ProcedureKind::AlwaysFf => (),
+            _ => return Ok(false),
         }
+        let (event_id, body) = match self.hir_of(hir.stmt)? {
+            HirNode::Stmt(x) => match x.kind {
+                hir::StmtKind::Timed {
+                    control: hir::TimingControl::ExplicitEvent(event_id),
+                    stmt,
+                } => (event_id, stmt),
+                _ => return Ok(false),
+            },
+            _ => return Ok(false),
+        };
 
-        // Emit interface instances.
-        for &inst_id in &hir.insts {
-            // Resolve the instantiation details.
-            let inst = match self.hir_of(inst_id)? {
-                HirNode::Inst(x) => x,
-                _ => unreachable!(),
-            };
-            let inst = self.inst_details(Ref(inst), env)?;
-
-            // Compute the array dimensions for the signals.
-            // let mut dims = vec![];
-            let inst_ty = self.type_of_inst(Ref(inst.hir), env);
-            let intf_ty = match inst_ty.resolve_full().core.get_interface() {
-                Some(x) => x,
-                None => continue,
+        // All events must be rising or falling edges of single bits.
+        let events = match self.hir_of(event_id)? {
+            HirNode::EventExpr(x) => &x.events,
+            _ => unreachable!(),
+        };
+        let mut edges = vec![];
+        for event in events {
+            let rising = match event.edge {
+                ast::EdgeIdent::Posedge if event.iff.is_empty() => true,
+                ast::EdgeIdent::Negedge if event.iff.is_empty() => false,
+                _ => return Ok(false),
             };
-            trace!(
-                "Interface instance is of type `{}` ({:?})",
-                inst_ty,
-                intf_ty
-            );
-
-            // Expand the interface declarations.
-            let signals = self.determine_interface_signals(intf_ty, &inst_ty.dims)?;
-            let mut signal_lookup = HashMap::new();
-            for signal in signals {
-                let value =
-                    self.emit_varnet_decl(signal.decl_id, signal.ty, intf_ty.env, signal.default)?;
-                self.builder
-                    .set_name(value.0, format!("{}.{}", inst.hir.name, signal.name));
-                let src = AccessedNode::Intf(inst_id, signal.decl_id);
-                trace!(
-                    "Emitted value for {:?} {}.{}",
-                    src,
-                    inst.hir.name,
-                    signal.name
-                );
-                self.values.insert(src, value);
-                signal_lookup.insert(signal.decl_id, value);
+            let mir = self.mir_rvalue(event.expr, env);
+            if mir.is_error() || mir.ty.get_bit_size() != Some(1) {
+                return Ok(false);
             }
+            edges.push((mir, rising));
+        }
 
-            // Generate the code for the port assignments.
-            let port_list = self.canonicalize_ports(intf_ty.ast);
+        // Function calls and system tasks cannot be emitted as dataflow.
+        let mut impure = ImpureExprFinder {
+            cx: self.cx,
+            found: false,
+        };
+        impure.visit_node_with_id(body, false);
+        if impure.found {
+            return Ok(false);
+        }
+
+        // Determine the clock and the reset. With two edges, the body must be
+        // an `if` that checks for the active level of one of them, which is
+        // then an asynchronous reset. With a single edge, an `if` whose branch
+        // only assigns constants is a synchronous reset.
+        let (clock, reset, body) = match edges.as_slice() {
+            &[clock] => match self.dataflow_if(body)? {
+                Some((cond, main_stmt, else_stmt))
+                    if self.is_dataflow_stmt(main_stmt, env, DataflowKind::Reset)? =>
+                {
+                    (clock, Some((cond, main_stmt, false)), else_stmt)
+                }
+                _ => (clock, None, Some(body)),
+            },
+            &[a, b] => {
+                let (cond, main_stmt, else_stmt) = match self.dataflow_if(body)? {
+                    Some(x) => x,
+                    None => return Ok(false),
+                };
+                let (signal, low) = match rvalue_signal(self.mir_rvalue(cond, env)) {
+                    Some(x) => x,
+                    None => return Ok(false),
+                };
+                let is_reset = |(mir, rising): (&mir::Rvalue, bool)| {
+                    rvalue_signal(mir) == Some((signal, false)) && rising != low
+                };
+                let clock = if is_reset(b) {
+                    a
+                } else if is_reset(a) {
+                    b
+                } else {
+                    return Ok(false);
+                };
+                if !self.is_dataflow_stmt(main_stmt, env, DataflowKind::Reset)? {
+                    return Ok(false);
+                }
+                (clock, Some((cond, main_stmt, true)), else_stmt)
+            }
+            _ => return Ok(false),
+        };
+        if let Some(body) = body {
+            if !self.is_dataflow_stmt(body, env, DataflowKind::Clocked)? {
+                return Ok(false);
+            }
+        }
+
+        // All assigned variables must be signals of the current unit.
+        let acc = self.accessed_nodes(hir.stmt, env)?;
+        let mut targets = vec![];
+        for &id in &acc.written {
+            match id {
+                AccessedNode::Regular(id) if self.values.contains_key(&AccessedNode::from(id)) => {
+                    targets.push(id);
+                }
+                _ => return Ok(false),
+            }
+        }
+        trace!("Emitting {:?} as registers", targets);
+
+        // Emit the clock. Registers in the MLIR output only trigger on the
+        // rising edge, so invert the clock for falling edges.
+        let (clock, rising) = clock;
+        let clock = self.emit_mir_rvalue_known(clock)?;
+        let mode = if rising {
+            llhd::ir::RegMode::Rise
+        } else {
+            llhd::ir::RegMode::Fall
+        };
+        let mlir_clock = if rising {
+            clock.1
+        } else {
+            let one = circt::hw::ConstantOp::new(self.mlir_builder, 1, &BigInt::one());
+            circt::comb::XorOp::new(self.mlir_builder, clock.1, one.into()).into()
+        };
+        let mlir_clock: mlir::Value =
+            circt::seq::ToClockOp::new(self.mlir_builder, mlir_clock).into();
+
+        // Determine the values assigned under reset and on the clock edge.
+        let reset = match reset {
+            Some((cond, stmt, is_async)) => {
+                let cond = self.emit_rvalue_bool(cond, env)?;
+                let mut values = HashMap::new();
+                self.eval_dataflow_stmt(stmt, env, &mut values)?;
+                Some((cond, values, is_async))
+            }
+            None => None,
+        };
+        let mut next = HashMap::new();
+        if let Some(body) = body {
+            self.eval_dataflow_stmt(body, env, &mut next)?;
+        }
+
+        // Emit the registers.
+        let delay = self.mk_const_time(&num::zero(), 1, 0);
+        for id in targets {
+            let signal = self.emitted_value(id);
+            let reset_value = reset
+                .as_ref()
+                .and_then(|(_, values, _)| values.get(&id).copied());
+            let next_value = next.get(&id).copied();
+            if reset_value.is_none() && next_value.is_none() {
+                continue;
+            }
+            let name = match self.hir_of(id)? {
+                HirNode::VarDecl(x) => x.name.to_string(),
+                HirNode::IntPort(x) => x.name.to_string(),
+                _ => "reg".to_string(),
+            };
+
+            // In LLHD, the reset and the clock edge are separate triggers of
+            // the register. The clock edge is ignored during reset.
+            let mut triggers = vec![];
+            let mut gate = next_value.and_then(|v| v.enable).map(|e| e.0);
+            if let Some(&(cond, _, is_async)) = reset.as_ref() {
+                if let Some(reset_value) = reset_value {
+                    triggers.push(llhd::ir::RegTrigger {
+                        data: reset_value.value.0,
+                        mode: if is_async {
+                            llhd::ir::RegMode::High
+                        } else {
+                            mode
+                        },
+                        trigger: if is_async { cond.0 } else { clock.0 },
+                        gate: if is_async { None } else { Some(cond.0) },
+                    });
+                }
+                let not_reset = self.builder.ins().not(cond.0);
+                gate = Some(match gate {
+                    Some(gate) => self.builder.ins().and(gate, not_reset),
+                    None => not_reset,
+                });
+            }
+            if let Some(next_value) = next_value {
+                triggers.push(llhd::ir::RegTrigger {
+                    data: next_value.value.0,
+                    mode,
+                    trigger: clock.0,
+                    gate,
+                });
+            }
+            self.builder.ins().reg(signal.0, triggers);
+
+            // In the MLIR output, asynchronous resets map to `seq.firreg` and
+            // everything else to `seq.compreg`. Enables which the register
+            // cannot represent select between the next and the current value.
+            let hold = |builder: &mut mlir::Builder| -> mlir::Value {
+                circt::llhd::ProbeOp::new(builder, signal.1).into()
+            };
+            let (mut input, enable) = match next_value {
+                Some(v) => (v.value.1, v.enable.map(|e| e.1)),
+                None => (hold(self.mlir_builder), None),
+            };
+            let reg: mlir::Value = match reset.as_ref() {
+                Some(&(cond, _, is_async)) => {
+                    if let Some(enable) = enable {
+                        let current = hold(self.mlir_builder);
+                        input = circt::comb::MuxOp::new(self.mlir_builder, enable, input, current)
+                            .into();
+                    }
+                    match reset_value {
+                        Some(reset_value) if is_async => circt::seq::FirRegOp::new(
+                            self.mlir_builder,
+                            input,
+                            mlir_clock,
+                            &name,
+                            Some((cond.1, reset_value.value.1, true)),
+                        )
+                        .into(),
+                        Some(reset_value) => circt::seq::CompRegOp::with_reset(
+                            self.mlir_builder,
+                            input,
+                            mlir_clock,
+                            cond.1,
+                            reset_value.value.1,
+                        )
+                        .into(),
+                        None => {
+                            let current = hold(self.mlir_builder);
+                            input =
+                                circt::comb::MuxOp::new(self.mlir_builder, cond.1, current, input)
+                                    .into();
+                            if is_async {
+                                circt::seq::FirRegOp::new(
+                                    self.mlir_builder,
+                                    input,
+                                    mlir_clock,
+                                    &name,
+                                    None,
+                                )
+                                .into()
+                            } else {
+                                circt::seq::CompRegOp::new(self.mlir_builder, input, mlir_clock)
+                                    .into()
+                            }
+                        }
+                    }
+                }
+                None => match enable {
+                    Some(enable) => circt::seq::CompRegClockEnabledOp::new(
+                        self.mlir_builder,
+                        input,
+                        mlir_clock,
+                        enable,
+                    )
+                    .into(),
+                    None => circt::seq::CompRegOp::new(self.mlir_builder, input, mlir_clock).into(),
+                },
+            };
+            circt::llhd::DriveOp::new(self.mlir_builder, signal.1, reg, delay.1);
+        }
+        Ok(true)
+    }
+
+    /// Emit a combinational procedure as dataflow.
+    ///
+    /// Recognizes `always_comb` and `always @*` procedures which only contain
+    /// blocking assignments to entire variables, `if`, and `case` statements.
+    /// The value of each assigned variable is computed with multiplexers in
+    /// the current unit and driven onto its signal, like a continuous
+    /// assignment. Returns `false` if the procedure reads a variable before
+    /// assigning it on some path, does not assign a variable on every path, or
+    /// otherwise needs to be emitted as a process.
+    fn emit_comb(&mut self, hir: &hir::Proc, env: ParamEnv) -> Result<bool> {
+        let body = match hir.kind {
+            ast::ProcedureKind::AlwaysComb => hir.stmt,
+            ast::ProcedureKind::Always => match self.hir_of(hir.stmt)? {
+                HirNode::Stmt(x) => match x.kind {
+                    hir::StmtKind::Timed {
+                        control: hir::TimingControl::ImplicitEvent,
+                        stmt,
+                    } => stmt,
+                    _ => return Ok(false),
+                },
+                _ => return Ok(false),
+            },
+            _ => return Ok(false),
+        };
+        if !self.is_dataflow_stmt(body, env, DataflowKind::Comb)? {
+            return Ok(false);
+        }
+        let mut impure = ImpureExprFinder {
+            cx: self.cx,
+            found: false,
+        };
+        impure.visit_node_with_id(body, false);
+        if impure.found {
+            return Ok(false);
+        }
+
+        // All assigned variables must be signals of the current unit which
+        // the procedure only reads after assigning them, and must be assigned
+        // on every path through it. Otherwise the procedure has feedback or
+        // infers a latch.
+        let acc = self.accessed_nodes(hir.stmt, env)?;
+        let assigned = match self.dataflow_assigned(body, env, &acc.written, BTreeSet::new())? {
+            Some(x) => x,
+            None => return Ok(false),
+        };
+        let mut targets = vec![];
+        for &id in &acc.written {
+            match id {
+                AccessedNode::Regular(id)
+                    if self.values.contains_key(&AccessedNode::from(id))
+                        && assigned.contains(&id) =>
+                {
+                    targets.push(id);
+                }
+                _ => return Ok(false),
+            }
+        }
+        if targets.is_empty() {
+            return Ok(false);
+        }
+        trace!("Emitting {:?} as combinational dataflow", targets);
+
+        // Compute the assigned values and drive them like continuous
+        // assignments. Reads of variables assigned earlier in the procedure
+        // observe the assigned value.
+        let mut values = HashMap::new();
+        self.dataflow_values = Some(HashMap::new());
+        let result = self.eval_dataflow_stmt(body, env, &mut values);
+        self.dataflow_values = None;
+        result?;
+        let delay = self.mk_const_time(&num::zero(), 0, 1);
+        for id in targets {
+            let signal = self.emitted_value(id);
+            let value = values[&id].value;
+            self.mk_drv(signal, value, delay);
+        }
+        Ok(true)
+    }
+
+    /// Determine the condition and branches of an `if` statement, looking
+    /// through blocks with a single statement.
+    fn dataflow_if(&mut self, stmt_id: NodeId) -> Result<Option<(NodeId, NodeId, Option<NodeId>)>> {
+        match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => match x.kind {
+                hir::StmtKind::Block(ref ids) if ids.len() == 1 => self.dataflow_if(ids[0]),
+                hir::StmtKind::If {
+                    cond,
+                    main_stmt,
+                    else_stmt,
+                } => Ok(Some((cond, main_stmt, else_stmt))),
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Check whether a statement of a procedure can be emitted as dataflow by
+    /// `eval_dataflow_stmt`.
+    ///
+    /// This is the case for assignments to entire variables, combined by `if`
+    /// and `case` statements. Clocked procedures use nonblocking assignments,
+    /// and combinational ones plain blocking assignments. The branch of a
+    /// reset may only assign constants.
+    fn is_dataflow_stmt(
+        &mut self,
+        stmt_id: NodeId,
+        env: ParamEnv,
+        kind: DataflowKind,
+    ) -> Result<bool> {
+        let hir = match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => return Ok(false),
+        };
+        Ok(match hir.kind {
+            hir::StmtKind::Null => true,
+            hir::StmtKind::Block(ref ids) => {
+                for &id in ids {
+                    if !self.is_dataflow_stmt(id, env, kind)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            hir::StmtKind::Assign {
+                lhs,
+                rhs,
+                kind: assign_kind,
+            } => {
+                match (kind, assign_kind) {
+                    (DataflowKind::Comb, hir::AssignKind::Block(ast::AssignOp::Identity)) => (),
+                    (DataflowKind::Clocked, hir::AssignKind::Nonblock)
+                    | (DataflowKind::Reset, hir::AssignKind::Nonblock) => (),
+                    _ => return Ok(false),
+                }
+                let assign_mir = self.mir_assignment_from_procedural(
+                    stmt_id,
+                    lhs,
+                    rhs,
+                    env,
+                    hir.span,
+                    assign_kind,
+                );
+                self.mir_simplify_assignment(Ref(assign_mir))
+                    .into_iter()
+                    .all(|assign| {
+                        let whole = match assign.lhs.kind {
+                            mir::LvalueKind::Var(_) | mir::LvalueKind::Port(_) => true,
+                            _ => false,
+                        };
+                        !assign.is_error()
+                            && whole
+                            && (kind != DataflowKind::Reset || assign.rhs.is_const())
+                    })
+            }
+            hir::StmtKind::If {
+                main_stmt,
+                else_stmt,
+                ..
+            } if kind != DataflowKind::Reset => {
+                self.is_dataflow_stmt(main_stmt, env, kind)?
+                    && match else_stmt {
+                        Some(else_stmt) => self.is_dataflow_stmt(else_stmt, env, kind)?,
+                        None => true,
+                    }
+            }
+            hir::StmtKind::Case {
+                ref ways, default, ..
+            } if kind != DataflowKind::Reset => {
+                for &(_, stmt) in ways {
+                    if !self.is_dataflow_stmt(stmt, env, kind)? {
+                        return Ok(false);
+                    }
+                }
+                match default {
+                    Some(default) => self.is_dataflow_stmt(default, env, kind)?,
+                    None => true,
+                }
+            }
+            _ => false,
+        })
+    }
+
+    /// Determine the variables which a dataflow statement assigns on every
+    /// path through it, in addition to the `assigned` ones.
+    ///
+    /// Returns `None` if the statement reads one of the `written` variables
+    /// before it is assigned on every path, such that the previous value of
+    /// the variable would be needed.
+    fn dataflow_assigned(
+        &mut self,
+        stmt_id: NodeId,
+        env: ParamEnv,
+        written: &BTreeSet<AccessedNode>,
+        mut assigned: BTreeSet<NodeId>,
+    ) -> Result<Option<BTreeSet<NodeId>>> {
+        let hir = match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Block(ref ids) => {
+                for &id in ids {
+                    assigned = match self.dataflow_assigned(id, env, written, assigned)? {
+                        Some(x) => x,
+                        None => return Ok(None),
+                    };
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                if self.dataflow_reads_unassigned(rhs, env, written, &assigned)? {
+                    return Ok(None);
+                }
+                let assign_mir =
+                    self.mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                for assign in self.mir_simplify_assignment(Ref(assign_mir)) {
+                    match assign.lhs.kind {
+                        mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => {
+                            assigned.insert(id);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                if self.dataflow_reads_unassigned(cond, env, written, &assigned)? {
+                    return Ok(None);
+                }
+                let main =
+                    match self.dataflow_assigned(main_stmt, env, written, assigned.clone())? {
+                        Some(x) => x,
+                        None => return Ok(None),
+                    };
+                if let Some(else_stmt) = else_stmt {
+                    let other = match self.dataflow_assigned(else_stmt, env, written, assigned)? {
+                        Some(x) => x,
+                        None => return Ok(None),
+                    };
+                    assigned = main.intersection(&other).copied().collect();
+                }
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                ..
+            } => {
+                if self.dataflow_reads_unassigned(expr, env, written, &assigned)? {
+                    return Ok(None);
+                }
+                for &way_expr in ways.iter().flat_map(|(exprs, _)| exprs) {
+                    if self.dataflow_reads_unassigned(way_expr, env, written, &assigned)? {
+                        return Ok(None);
+                    }
+                }
+                let mut merged: Option<BTreeSet<NodeId>> = None;
+                for stmt in ways.iter().map(|&(_, stmt)| stmt).chain(default) {
+                    let way = match self.dataflow_assigned(stmt, env, written, assigned.clone())? {
+                        Some(x) => x,
+                        None => return Ok(None),
+                    };
+                    merged = Some(match merged {
+                        Some(merged) => merged.intersection(&way).copied().collect(),
+                        None => way,
+                    });
+                }
+                if default.is_some() {
+                    assigned = merged.unwrap_or(assigned);
+                }
+            }
+            _ => (),
+        }
+        Ok(Some(assigned))
+    }
+
+    /// Check whether an expression of a dataflow statement reads one of the
+    /// `written` variables which is not yet `assigned`. See
+    /// `dataflow_assigned`.
+    fn dataflow_reads_unassigned(
+        &mut self,
+        expr_id: NodeId,
+        env: ParamEnv,
+        written: &BTreeSet<AccessedNode>,
+        assigned: &BTreeSet<NodeId>,
+    ) -> Result<bool> {
+        let acc = self.accessed_nodes(expr_id, env)?;
+        Ok(acc.read.iter().any(|node| {
+            written.contains(node)
+                && match *node {
+                    AccessedNode::Regular(id) => !assigned.contains(&id),
+                    _ => true,
+                }
+        }))
+    }
+
+    /// Emit the values assigned by a statement of a procedure as dataflow.
+    ///
+    /// Updates `values` with the value assigned to each variable, together
+    /// with the condition under which it is assigned. The statement must have
+    /// been accepted by `is_dataflow_stmt`.
+    fn eval_dataflow_stmt(
+        &mut self,
+        stmt_id: NodeId,
+        env: ParamEnv,
+        values: &mut HashMap<NodeId, DataflowValue>,
+    ) -> Result<()> {
+        let hir = match self.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(ref ids) => {
+                for &id in ids {
+                    self.eval_dataflow_stmt(id, env, values)?;
+                }
+            }
+            hir::StmtKind::Assign { lhs, rhs, kind } => {
+                self.sync_dataflow_values(values);
+                let assign_mir =
+                    self.mir_assignment_from_procedural(stmt_id, lhs, rhs, env, hir.span, kind);
+                for assign in self.mir_simplify_assignment(Ref(assign_mir)) {
+                    let id = match assign.lhs.kind {
+                        mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => id,
+                        _ => unreachable!(),
+                    };
+                    let value = self.emit_mir_rvalue(assign.rhs)?;
+                    values.insert(
+                        id,
+                        DataflowValue {
+                            value,
+                            enable: None,
+                        },
+                    );
+                }
+            }
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                self.sync_dataflow_values(values);
+                let cond = self.emit_rvalue_bool(cond, env)?;
+                let mut taken = values.clone();
+                self.eval_dataflow_stmt(main_stmt, env, &mut taken)?;
+                if let Some(else_stmt) = else_stmt {
+                    self.eval_dataflow_stmt(else_stmt, env, values)?;
+                }
+                self.merge_dataflow(cond, taken, values);
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind,
+            } => {
+                self.sync_dataflow_values(values);
+                let expr_ty = self.mir_rvalue(expr, env).ty;
+                let expr = self.emit_rvalue(expr, env)?;
+                let expr_planes = match self.is_four_state(expr_ty) {
+                    true => Some((
+                        self.mk_ext_field_const_offset(expr, 0),
+                        self.mk_ext_field_const_offset(expr, 1),
+                    )),
+                    false => None,
+                };
+                let mut checks = vec![];
+                for &(ref way_exprs, stmt) in ways {
+                    let mut last_check = None;
+                    for &way_expr in way_exprs {
+                        let check = self.emit_case_check(expr, expr_planes, way_expr, kind, env)?;
+                        last_check = Some(match last_check {
+                            Some(last_check) => self.mk_or(last_check, check),
+                            None => check,
+                        });
+                    }
+                    if let Some(last_check) = last_check {
+                        checks.push((last_check, stmt));
+                    }
+                }
+
+                // Earlier items take precedence over later ones, so merge them
+                // starting with the default.
+                let mut merged = values.clone();
+                if let Some(default) = default {
+                    self.eval_dataflow_stmt(default, env, &mut merged)?;
+                }
+                for (check, stmt) in checks.into_iter().rev() {
+                    let mut taken = values.clone();
+                    self.eval_dataflow_stmt(stmt, env, &mut taken)?;
+                    self.merge_dataflow(check, taken, &mut merged);
+                }
+                *values = merged;
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Make the values of the variables assigned on every path so far visible
+    /// to reads in a combinational procedure. See `emit_comb`.
+    fn sync_dataflow_values(&mut self, values: &HashMap<NodeId, DataflowValue>) {
+        if let Some(dataflow_values) = self.dataflow_values.as_mut() {
+            *dataflow_values = values
+                .iter()
+                .filter(|(_, v)| v.enable.is_none())
+                .map(|(&id, v)| (id, v.value))
+                .collect();
+        }
+    }
+
+    /// Merge the values assigned by the two branches of a condition.
+    ///
+    /// `taken` holds the values assigned if `cond` is true, and `values` the
+    /// ones assigned otherwise. The latter is updated with the merged values.
+    fn merge_dataflow(
+        &mut self,
+        cond: HybridValue,
+        taken: HashMap<NodeId, DataflowValue>,
+        values: &mut HashMap<NodeId, DataflowValue>,
+    ) {
+        let mut ids: Vec<NodeId> = taken.keys().chain(values.keys()).copied().collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            let merged = match (taken.get(&id).copied(), values.get(&id).copied()) {
+                (Some(a), Some(b)) if a.same_as(&b) => continue,
+                (Some(a), Some(b)) => {
+                    let value = self.mk_mux(cond, a.value, b.value);
+                    let enable = match (a.enable, b.enable) {
+                        (None, None) => None,
+                        (a_enable, b_enable) => {
+                            let one = self.mk_const_int(1, &BigInt::one());
+                            Some(self.mk_mux(
+                                cond,
+                                a_enable.unwrap_or(one),
+                                b_enable.unwrap_or(one),
+                            ))
+                        }
+                    };
+                    DataflowValue { value, enable }
+                }
+                (Some(a), None) => DataflowValue {
+                    value: a.value,
+                    enable: Some(match a.enable {
+                        Some(enable) => self.mk_and(cond, enable),
+                        None => cond,
+                    }),
+                },
+                (None, Some(b)) => {
+                    let not_cond = self.mk_not(cond);
+                    DataflowValue {
+                        value: b.value,
+                        enable: Some(match b.enable {
+                            Some(enable) => self.mk_and(not_cond, enable),
+                            None => not_cond,
+                        }),
+                    }
+                }
+                (None, None) => unreachable!(),
+            };
+            values.insert(id, merged);
+        }
+    }
+
+    /// Emit the code for the contents of a module.
+    fn emit_module_block(
+        &mut self,
+        id: NodeId,
+        env: ParamEnv,
+        hir: &hir::ModuleBlock,
+        name_prefix: &str,
+    ) -> Result<()> {
+        // Evaluate elaboration system tasks.
+        for &task_id in &hir.elab_tasks {
+            let hir = match self.hir_of(task_id)? {
+                HirNode::ElabTask(x) => x,
+                _ => unreachable!(),
+            };
+            self.emit_elab_task(hir, env)?;
+        }
+
+        // Load memory initialization files.
+        self.emit_mem_inits(env, hir)?;
+
+        // Determine which of the declared nets have multiple drivers.
+        let decl_nets = hir
+            .decls
+            .iter()
+            .map(|&decl_id| Ok((decl_id, self.type_of(decl_id, env)?, None)))
+            .collect::<Result<Vec<_>>>()?;
+        let decl_nets = self.prepare_nets(env, hir, decl_nets)?;
+
+        // Emit declarations.
+        for &decl_id in &hir.decls {
+            let hir = match self.hir_of(decl_id)? {
+                HirNode::VarDecl(x) => x,
+                _ => unreachable!(),
+            };
+            let ty = self.type_of(decl_id, env)?;
+            let value = self.emit_varnet_decl(decl_id, ty, env, hir.init)?;
+            self.builder.set_name(value.0, hir.name.value.into());
+            self.values.insert(decl_id.into(), value);
+        }
+
+        // Emit interface instances.
+        for &inst_id in &hir.insts {
+            // Resolve the instantiation details.
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+
+            // Compute the array dimensions for the signals.
+            // let mut dims = vec![];
+            let inst_ty = self.type_of_inst(Ref(inst.hir), env);
+            let intf_ty = match inst_ty.resolve_full().core.get_interface() {
+                Some(x) => x,
+                None => continue,
+            };
+            trace!(
+                "Interface instance is of type `{}` ({:?})",
+                inst_ty,
+                intf_ty
+            );
+
+            // Expand the interface declarations.
+            let signals = self.determine_interface_signals(intf_ty, &inst_ty.dims)?;
+            let mut signal_lookup = HashMap::new();
+            for signal in signals {
+                let value =
+                    self.emit_varnet_decl(signal.decl_id, signal.ty, intf_ty.env, signal.default)?;
+                self.builder
+                    .set_name(value.0, format!("{}.{}", inst.hir.name, signal.name));
+                let src = AccessedNode::Intf(inst_id, signal.decl_id);
+                trace!(
+                    "Emitted value for {:?} {}.{}",
+                    src,
+                    inst.hir.name,
+                    signal.name
+                );
+                self.values.insert(src, value);
+                signal_lookup.insert(signal.decl_id, value);
+            }
+
+            // Generate the code for the port assignments.
+            let port_list = self.canonicalize_ports(intf_ty.ast);
             let ports = self.determine_module_ports(&port_list.int, intf_ty.env)?;
             let (inputs, outputs) = self.emit_port_connections(
                 port_list,
@@ -1082,184 +2424,1027 @@ where
             trace!("Attaching interface outputs {:?}", outputs);
             trace!("Signal lookup: {:?}", signal_lookup);
 
-            // Actually wire up the ports.
-            let inputs = inputs.into_iter().zip(ports.inputs.iter());
-            let outputs = outputs.into_iter().zip(ports.outputs.iter());
-            for (assigned, port) in inputs.chain(outputs) {
-                trace!(
-                    "Assign `{}` ({:?}) = {:?}",
-                    port.name,
-                    port.port.id,
-                    assigned
+            // Actually wire up the ports. Connecting the input of an inout
+            // port already connects the signal in both directions.
+            let inputs = inputs.into_iter().zip(ports.inputs.iter());
+            let outputs = outputs
+                .into_iter()
+                .zip(ports.outputs.iter())
+                .filter(|(_, port)| port.dir != ast::PortDir::Inout);
+            for (assigned, port) in inputs.chain(outputs) {
+                trace!(
+                    "Assign `{}` ({:?}) = {:?}",
+                    port.name,
+                    port.port.id,
+                    assigned
+                );
+                let sig = signal_lookup[&port.port.id];
+                self.builder.ins().con(sig.0, assigned.0);
+                circt::llhd::ConnectOp::new(self.mlir_builder, sig.1, assigned.1);
+            }
+        }
+
+        // Emit assignments.
+        for &assign_id in &hir.assigns {
+            let hir = match self.hir_of(assign_id)? {
+                HirNode::Assign(x) => x,
+                _ => unreachable!(),
+            };
+
+            // Map the assignment to an MIR node.
+            let assign_mir = self.mir_assignment_from_concurrent(Ref(hir), env);
+            debug!("Concurrent assignment: {:#?}", assign_mir);
+
+            // Simplify the assignment to eliminate concatenations on the
+            // left-hand side.
+            let simplified = self.mir_simplify_assignment(Ref(assign_mir));
+            debug!("Simplified to: {:#?}", simplified);
+
+            // Check for sanity.
+            for &assign in &simplified {
+                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
+                if assign.is_error() {
+                    return Err(());
+                }
+            }
+
+            // Emit the assignments.
+            let delay = self.mk_const_time(&num::zero(), 0, 1);
+            let strength = drive_levels(hir.strength);
+            for &assign in &simplified {
+                let lhs = self.emit_net_driver(assign.lhs, strength)?;
+                let rhs = self.emit_mir_rvalue(assign.rhs)?;
+                self.mk_drv(lhs, rhs, delay);
+            }
+        }
+
+        // Emit pull gates.
+        for &pull_id in &hir.pulls {
+            let hir = match self.hir_of(pull_id)? {
+                HirNode::PullGate(x) => x,
+                _ => unreachable!(),
+            };
+            self.emit_pull_gate(hir, env)?;
+        }
+
+        // Emit concurrent assertions.
+        for &assert_id in &hir.asserts {
+            let hir = match self.hir_of(assert_id)? {
+                HirNode::ConcAssert(x) => x,
+                _ => unreachable!(),
+            };
+            self.emit_conc_assert(hir, env)?;
+        }
+
+        // Emit module instantiations.
+        for &inst_id in &hir.insts {
+            // Resolve the instantiation details.
+            let inst = match self.hir_of(inst_id)? {
+                HirNode::Inst(x) => x,
+                _ => unreachable!(),
+            };
+            let inst = self.inst_details(Ref(inst), env)?;
+            let target_module = match inst.target.kind {
+                InstTarget::Module(x) => self.hir_of_module(x)?,
+                _ => continue,
+            };
+
+            // Emit the instantiated module.
+            let target = self.emit_module_with_env(target_module.id, inst.inner_env)?;
+
+            // Prepare the port assignments.
+            let (inputs, outputs) = self.emit_port_connections(
+                target_module.ports_new,
+                inst.as_ref(),
+                &target.ports.inputs,
+                &target.ports.outputs,
+            )?;
+
+            // Instantiate the module.
+            let ext_unit = self.builder.add_extern(
+                self.into.unit(target.unit).name().clone(),
+                self.into.unit(target.unit).sig().clone(),
+            );
+            if !inst.hir.ast.dims.is_empty() {
+                bug_span!(
+                    inst.hir.ast.span(),
+                    self.cx,
+                    "instance arrays of modules not supported"
+                );
+            }
+            self.builder.ins().inst(
+                ext_unit,
+                inputs.iter().map(|x| x.0).collect(),
+                outputs.iter().map(|x| x.0).collect(),
+            );
+            circt::llhd::InstanceOp::new(
+                self.mlir_builder,
+                &self.unique_names.add(&inst.hir.name.value.to_string()),
+                &target.mlir_symbol,
+                inputs.iter().map(|x| x.1),
+                outputs.iter().map(|x| x.1),
+            );
+        }
+
+        // Emit generate blocks.
+        for &gen_id in &hir.gens {
+            let hir = match self.hir_of(gen_id)? {
+                HirNode::Gen(x) => x,
+                _ => unreachable!(),
+            };
+            #[allow(unreachable_patterns)]
+            match hir.kind {
+                hir::GenKind::If {
+                    cond,
+                    ref main_body,
+                    ref else_body,
+                } => {
+                    let k = self.constant_value_of(cond, env);
+                    if k.is_false() {
+                        if let Some(else_body) = else_body {
+                            self.emit_module_block(id, env, else_body, name_prefix)?;
+                        }
+                    } else {
+                        self.emit_module_block(id, env, main_body, name_prefix)?;
+                    }
+                }
+                hir::GenKind::For {
+                    ref init,
+                    cond,
+                    step,
+                    ref body,
+                } => {
+                    let mut local_env = env;
+                    for &i in init {
+                        local_env = self.execute_genvar_init(i, local_env)?;
+                    }
+                    while self.constant_value_of(cond, local_env).is_true() {
+                        self.emit_module_block(id, local_env, body, name_prefix)?;
+                        local_env = self.execute_genvar_step(step, local_env)?;
+                    }
+                }
+                _ => return self.unimp_msg("code generation for", hir),
+            }
+        }
+
+        // Combine the drivers of the declared nets.
+        self.emit_net_resolution(decl_nets)?;
+
+        // Emit and instantiate procedures.
+        for &proc_id in &hir.procs {
+            // Clocked procedures which follow the usual register patterns do
+            // not need a process.
+            let proc_hir = match self.hir_of(proc_id)? {
+                HirNode::Proc(x) => x,
+                _ => unreachable!(),
+            };
+            if self.emit_registers(proc_hir, env)? {
+                continue;
+            }
+
+            // Combinational procedures without feedback become dataflow.
+            if self.emit_comb(proc_hir, env)? {
+                continue;
+            }
+            let prok = self.emit_procedure(proc_id, env, name_prefix)?;
+            let lookup_value = |&id: &AccessedNode| match self.values.get(&id) {
+                Some(v) => v.clone(),
+                None => {
+                    self.emit(
+                        DiagBuilder2::bug(format!(
+                            "{} used as input/output of {}, but no value has been emitted",
+                            self.hir_of(id.id()).unwrap().desc_full(),
+                            self.hir_of(proc_id).unwrap().desc_full(),
+                        ))
+                        .span(self.span(id.id())),
+                    );
+                    panic!("no value emitted for {:?}", id);
+                }
+            };
+            let inputs: Vec<_> = prok.inputs.iter().map(lookup_value).collect();
+            let outputs: Vec<_> = prok.outputs.iter().map(lookup_value).collect();
+            let ext_unit = self.builder.add_extern(
+                self.into.unit(prok.unit).name().clone(),
+                self.into.unit(prok.unit).sig().clone(),
+            );
+            self.builder.ins().inst(
+                ext_unit,
+                inputs.iter().map(|x| x.0).collect(),
+                outputs.iter().map(|x| x.0).collect(),
+            );
+            circt::llhd::InstanceOp::new(
+                self.mlir_builder,
+                &self.unique_names.add(&format!("{}_inst", prok.mlir_symbol)),
+                &prok.mlir_symbol,
+                inputs.iter().map(|x| x.1),
+                outputs.iter().map(|x| x.1),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Decide how the drivers of a list of nets are combined.
+    ///
+    /// Counts the net declaration assignments, continuous assignments, and
+    /// instance ports which drive each of the nets in a module block and its
+    /// generate blocks. Nets whose drivers must be combined by a resolution
+    /// function, or whose resolved value goes to a separate target signal, are
+    /// registered such that `emit_net_driver` redirects their drivers. Returns
+    /// the registered nets, which must be passed to `emit_net_resolution` once
+    /// the block has been emitted.
+    fn prepare_nets(
+        &mut self,
+        env: ParamEnv,
+        hir: &hir::ModuleBlock,
+        nets: Vec<(NodeId, &'gcx UnpackedType<'gcx>, Option<HybridValue>)>,
+    ) -> Result<Vec<NodeId>> {
+        let mut counts = HashMap::new();
+        self.count_net_drivers(env, hir, &mut counts)?;
+
+        let mut prepared = vec![];
+        for (id, ty, target) in nets {
+            let (kind, name, strength) = match self.hir_of(id)? {
+                HirNode::VarDecl(x) => (x.kind, x.name, x.strength),
+                HirNode::IntPort(x) => (x.kind, x.name, None),
+                _ => continue,
+            };
+            let DriverCount { count, weak, whole } = counts.get(&id).copied().unwrap_or_default();
+
+            // Variables may only have a single continuous driver, regardless
+            // of whether X and Z values are preserved. Drivers of disjoint
+            // parts of a variable are allowed. See §6.5.
+            if !kind.is_net() && count > 1 && whole {
+                self.emit(
+                    DiagBuilder2::error(format!("variable `{}` has {} drivers", name, count))
+                        .span(name.span)
+                        .add_note(
+                            "A variable may only be driven by a single continuous assignment \
+                             or port connection. Declare it as a net, e.g. `wire`, to \
+                             resolve multiple drivers.",
+                        ),
+                );
+                return Err(());
+            }
+            let net_ty = match kind {
+                ast::VarKind::Net { ty, .. } => ty,
+                ast::VarKind::Var if target.is_some() => ast::NetType::Wire,
+                ast::VarKind::Var => continue,
+            };
+            if net_ty == ast::NetType::Uwire && count > 1 {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` has {} drivers", name, count))
+                        .span(name.span)
+                        .add_note("A `uwire` net may only have a single driver."),
+                );
+                return Err(());
+            }
+
+            // The stored charge only matters when it is shared with other
+            // nets, which is not supported. See §28.15.
+            if let Some(ast::NetStrength::Charge(charge)) = strength {
+                self.emit(
+                    DiagBuilder2::warning(format!(
+                        "unsupported: `{}` charge strength of `{}` has no effect",
+                        charge, name
+                    ))
+                    .span(name.span)
+                    .add_note(
+                        "The net retains its value while it is not driven, regardless of its \
+                         charge strength.",
+                    ),
                 );
-                let sig = signal_lookup[&port.port.id];
-                self.builder.ins().con(sig.0, assigned.0);
-                circt::llhd::ConnectOp::new(self.mlir_builder, sig.1, assigned.1);
+            }
+
+            // Drivers with a strength other than `strong`, including pull
+            // gates, may float or be overpowered, which requires resolution.
+            // Without four-state code generation there are no Z values to
+            // resolve otherwise. Multiple drivers then override each other,
+            // and pull and charge storage nets never float.
+            let four_state = self.is_four_state(ty);
+            let floats = match net_ty {
+                ast::NetType::Supply0
+                | ast::NetType::Supply1
+                | ast::NetType::WireAnd
+                | ast::NetType::WireOr
+                | ast::NetType::TriAnd
+                | ast::NetType::TriOr => false,
+                ast::NetType::Tri0 | ast::NetType::Tri1 | ast::NetType::TriReg => count > 0,
+                _ => count > 1,
+            };
+            let resolve = match net_ty {
+                ast::NetType::Supply0 | ast::NetType::Supply1 => true,
+                ast::NetType::WireAnd
+                | ast::NetType::WireOr
+                | ast::NetType::TriAnd
+                | ast::NetType::TriOr => count > 1,
+                _ => (four_state && floats) || weak,
+            };
+            if floats && !resolve {
+                self.emit(
+                    DiagBuilder2::warning(format!(
+                        "`{}` net `{}` is not resolved without `--four-state`",
+                        net_ty, name
+                    ))
+                    .span(name.span)
+                    .add_note(
+                        "Without X and Z values the drivers of the net override each other, \
+                         and the net never floats.",
+                    ),
+                );
+            }
+            if !resolve && target.is_none() {
+                continue;
+            }
+            if resolve && !ty.coalesces_to_llhd_scalar() {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: resolution of `{}` net `{}` of type `{}`",
+                        net_ty, name, ty
+                    ))
+                    .span(name.span),
+                );
+                return Err(());
+            }
+            trace!(
+                "Net `{}` has {} drivers ({})",
+                name,
+                count,
+                if resolve { "resolved" } else { "direct" }
+            );
+            self.nets.insert(
+                id.into(),
+                NetDrivers {
+                    name,
+                    net_ty,
+                    ty,
+                    env,
+                    target,
+                    resolve,
+                    drivers: vec![],
+                },
+            );
+            prepared.push(id);
+        }
+        Ok(prepared)
+    }
+
+    /// Count the drivers of the nets in a module block and its generate
+    /// blocks.
+    ///
+    /// Also records whether any of the drivers of a net has a strength other
+    /// than `strong`, and whether any of them drives the entire net.
+    fn count_net_drivers(
+        &mut self,
+        env: ParamEnv,
+        hir: &hir::ModuleBlock,
+        counts: &mut HashMap<NodeId, DriverCount>,
+    ) -> Result<()> {
+        let mut count = |id, strength: (usize, usize), whole: bool| {
+            let entry = counts.entry(id).or_default();
+            entry.count += 1;
+            entry.weak |= strength != drive_levels(None);
+            entry.whole |= whole;
+        };
+
+        // Net declaration assignments.
+        for &decl_id in &hir.decls {
+            match self.hir_of(decl_id)? {
+                HirNode::VarDecl(x) if x.kind.is_net() && x.init.is_some() => {
+                    count(decl_id, drive_levels(net_drive_strength(x.strength)), true);
+                }
+                _ => (),
             }
         }
 
-        // Emit assignments.
+        // Continuous assignments. Concatenations on the left-hand side may
+        // drive the same net multiple times, which still counts as one driver.
         for &assign_id in &hir.assigns {
             let hir = match self.hir_of(assign_id)? {
                 HirNode::Assign(x) => x,
                 _ => unreachable!(),
             };
-
-            // Map the assignment to an MIR node.
             let assign_mir = self.mir_assignment_from_concurrent(Ref(hir), env);
-            debug!("Concurrent assignment: {:#?}", assign_mir);
-
-            // Simplify the assignment to eliminate concatenations on the
-            // left-hand side.
-            let simplified = self.mir_simplify_assignment(Ref(assign_mir));
-            debug!("Simplified to: {:#?}", simplified);
-
-            // Check for sanity.
-            for &assign in &simplified {
-                assert_type!(assign.rhs.ty, assign.lhs.ty, assign.rhs.span, self.cx);
-                if assign.is_error() {
-                    return Err(());
+            let mut roots = HashMap::new();
+            for assign in self.mir_simplify_assignment(Ref(assign_mir)) {
+                if let Some(id) = lvalue_root(assign.lhs) {
+                    *roots.entry(id).or_insert(false) |= lvalue_is_whole(assign.lhs);
                 }
             }
+            for (id, whole) in roots {
+                count(id, drive_levels(hir.strength), whole);
+            }
+        }
 
-            // Emit the assignments.
-            let delay = self.mk_const_time(&num::zero(), 0, 1);
-            for &assign in &simplified {
-                let lhs = self.emit_mir_lvalue(assign.lhs)?;
-                let rhs = self.emit_mir_rvalue(assign.rhs)?;
-                self.mk_drv(lhs.0, rhs, delay);
+        // Pull gates.
+        for &pull_id in &hir.pulls {
+            let hir = match self.hir_of(pull_id)? {
+                HirNode::PullGate(x) => x,
+                _ => unreachable!(),
+            };
+            for &target in &hir.targets {
+                if let Some(id) = lvalue_root(self.mir_lvalue(target, env)) {
+                    count(id, pull_levels(hir), true);
+                }
             }
         }
 
-        // Emit module instantiations.
+        // Output and inout ports of module instances. Unsupported port
+        // connections are diagnosed when the instance is emitted.
         for &inst_id in &hir.insts {
-            // Resolve the instantiation details.
             let inst = match self.hir_of(inst_id)? {
                 HirNode::Inst(x) => x,
                 _ => unreachable!(),
             };
             let inst = self.inst_details(Ref(inst), env)?;
-            let target_module = match inst.target.kind {
-                InstTarget::Module(x) => self.hir_of_module(x)?,
+            let port_list = match inst.target.kind {
+                InstTarget::Module(x) => self.hir_of_module(x)?.ports_new,
                 _ => continue,
             };
+            for port in &port_list.ext_pos {
+                let mapping = match inst.ports.find(port.id) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let int = match port.exprs.iter().next() {
+                    Some(expr) if port.exprs.len() == 1 && expr.selects.is_empty() => {
+                        &port_list.int[expr.port]
+                    }
+                    _ => continue,
+                };
+                match int.dir {
+                    ast::PortDir::Output | ast::PortDir::Inout => (),
+                    ast::PortDir::Input | ast::PortDir::Ref => continue,
+                }
+                let ty = self.type_of_int_port(Ref(int), inst.inner_env);
+                if ty.resolve_full().core.get_interface().is_some() {
+                    continue;
+                }
+                let mir = self.mir_lvalue(mapping.id(), mapping.env());
+                if let Some(id) = lvalue_root(mir) {
+                    count(id, drive_levels(None), lvalue_is_whole(mir));
+                }
+            }
+        }
 
-            // Emit the instantiated module.
-            let target = self.emit_module_with_env(target_module.id, inst.inner_env)?;
+        // Generate blocks.
+        for &gen_id in &hir.gens {
+            let hir = match self.hir_of(gen_id)? {
+                HirNode::Gen(x) => x,
+                _ => unreachable!(),
+            };
+            #[allow(unreachable_patterns)]
+            match hir.kind {
+                hir::GenKind::If {
+                    cond,
+                    ref main_body,
+                    ref else_body,
+                } => {
+                    if self.constant_value_of(cond, env).is_false() {
+                        if let Some(else_body) = else_body {
+                            self.count_net_drivers(env, else_body, counts)?;
+                        }
+                    } else {
+                        self.count_net_drivers(env, main_body, counts)?;
+                    }
+                }
+                hir::GenKind::For {
+                    ref init,
+                    cond,
+                    step,
+                    ref body,
+                } => {
+                    let mut local_env = env;
+                    for &i in init {
+                        local_env = self.execute_genvar_init(i, local_env)?;
+                    }
+                    while self.constant_value_of(cond, local_env).is_true() {
+                        self.count_net_drivers(local_env, body, counts)?;
+                        local_env = self.execute_genvar_step(step, local_env)?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
 
-            // Prepare the port assignments.
-            let (inputs, outputs) = self.emit_port_connections(
-                target_module.ports_new,
-                inst.as_ref(),
-                &target.ports.inputs,
-                &target.ports.outputs,
-            )?;
+    /// Create an auxiliary signal for an additional driver of a net.
+    ///
+    /// Returns `None` if the drivers of the net are not combined by a
+    /// resolution function. The signal starts out as Z, or as a value which
+    /// does not affect the resolved value if there are no Z values. The
+    /// driver contributes its value with the given `(strength0, strength1)`
+    /// levels.
+    fn mk_net_driver(
+        &mut self,
+        id: AccessedNode,
+        strength: (usize, usize),
+    ) -> Result<Option<HybridValue>> {
+        let (name, net_ty, ty, env) = match self.nets.get(&id) {
+            Some(net) if net.resolve => (net.name, net.net_ty, net.ty, net.env),
+            _ => return Ok(None),
+        };
+        let idle = if self.is_four_state(ty) {
+            value::make_int_z(ty)
+        } else {
+            match net_ty {
+                ast::NetType::WireAnd | ast::NetType::TriAnd => {
+                    value::make_int(ty, (BigInt::one() << ty.get_bit_size().unwrap()) - 1)
+                }
+                _ => value::make_int(ty, BigInt::zero()),
+            }
+        };
+        let idle = self.intern_value(idle);
+        let idle = self.emit_const(idle, env, name.span)?;
+        let driver_name = format!("{}.drv", name);
+        let driver: HybridValue = (
+            self.builder.ins().sig(idle.0),
+            circt::llhd::SignalOp::new(
+                self.mlir_builder,
+                &self.unique_names.add(&driver_name),
+                idle.1,
+            )
+            .into(),
+        );
+        self.builder.set_name(driver.0, driver_name);
+        self.nets
+            .get_mut(&id)
+            .unwrap()
+            .drivers
+            .push((driver, strength));
+        Ok(Some(driver))
+    }
 
-            // Instantiate the module.
-            let ext_unit = self.builder.add_extern(
-                self.into.unit(target.unit).name().clone(),
-                self.into.unit(target.unit).sig().clone(),
-            );
-            if !inst.hir.ast.dims.is_empty() {
-                bug_span!(
-                    inst.hir.ast.span(),
-                    self.cx,
-                    "instance arrays of modules not supported"
+    /// Emit the code for an MIR lvalue which drives a net.
+    ///
+    /// If the net is resolved from multiple drivers, the lvalue targets a new
+    /// auxiliary signal for this driver instead of the net itself.
+    fn emit_net_driver(
+        &mut self,
+        mir: &'gcx mir::Lvalue<'gcx>,
+        strength: (usize, usize),
+    ) -> Result<HybridValue> {
+        let id = match lvalue_root(mir) {
+            Some(id) => AccessedNode::from(id),
+            None => return Ok(self.emit_mir_lvalue(mir)?.0),
+        };
+        let target = match self.mk_net_driver(id, strength)? {
+            Some(driver) => driver,
+            None => match self.nets.get(&id).and_then(|net| net.target) {
+                Some(target) => target,
+                None => return Ok(self.emit_mir_lvalue(mir)?.0),
+            },
+        };
+
+        // Emit the lvalue with the net temporarily replaced by the target.
+        let prev = self.values.insert(id, target);
+        let result = self.emit_mir_lvalue(mir);
+        match prev {
+            Some(prev) => self.values.insert(id, prev),
+            None => self.values.remove(&id),
+        };
+        Ok(result?.0)
+    }
+
+    /// Combine the drivers of nets registered by `prepare_nets`.
+    fn emit_net_resolution(&mut self, nets: Vec<NodeId>) -> Result<()> {
+        for id in nets {
+            let net = self.nets.remove(&AccessedNode::from(id)).unwrap();
+            if !net.resolve {
+                continue;
+            }
+            let target = net.target.unwrap_or_else(|| self.emitted_value(id));
+            let width = net.ty.get_bit_size().unwrap();
+            let four_state = self.is_four_state(net.ty);
+            let zero = self.mk_const_int(width, &BigInt::zero());
+            let ones = self.mk_const_int(width, &((BigInt::one() << width) - 1));
+            let planes = |gen: &mut Self, signal| {
+                let value = gen.mk_prb(signal);
+                if four_state {
+                    (
+                        gen.mk_ext_field_const_offset(value, 0),
+                        gen.mk_ext_field_const_offset(value, 1),
+                    )
+                } else {
+                    (value, zero)
+                }
+            };
+            let mut drivers = vec![];
+            for (driver, strength) in net.drivers {
+                drivers.push((planes(self, driver), strength));
+            }
+
+            // Pull and supply nets behave as if they had an additional
+            // constant driver of the corresponding strength. Charge storage
+            // nets retain their previous value while they are not driven.
+            let mut retain = None;
+            match net.net_ty {
+                ast::NetType::Tri0 => drivers.push(((zero, zero), (5, 0))),
+                ast::NetType::Tri1 => drivers.push(((ones, zero), (0, 5))),
+                ast::NetType::Supply0 => drivers.push(((zero, zero), (7, 0))),
+                ast::NetType::Supply1 => drivers.push(((ones, zero), (0, 7))),
+                ast::NetType::TriReg => retain = Some(planes(self, target)),
+                _ => (),
+            }
+
+            let (value, unknown) = self.mk_net_resolution(net.net_ty, width, &drivers, retain);
+            let value = if four_state {
+                self.mk_four_state(net.ty, value, unknown)?
+            } else {
+                let known = self.mk_not(unknown);
+                self.mk_and(value, known)
+            };
+            let delay = self.mk_const_time(&num::zero(), 0, 1);
+            self.mk_drv(target, value, delay);
+        }
+        Ok(())
+    }
+
+    /// Emit a `pullup` or `pulldown` gate.
+    ///
+    /// Each target is driven with a constant one or zero at the strength of
+    /// the gate.
+    fn emit_pull_gate(&mut self, hir: &hir::PullGate, env: ParamEnv) -> Result<()> {
+        let strength = pull_levels(hir);
+        let delay = self.mk_const_time(&num::zero(), 0, 1);
+        for &target in &hir.targets {
+            let mir = self.mir_lvalue(target, env);
+            if mir.is_error() {
+                return Err(());
+            }
+            let width = match mir.ty.get_bit_size() {
+                Some(w) => w,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` cannot be driven by a {}",
+                            mir.ty,
+                            hir.desc()
+                        ))
+                        .span(mir.span),
+                    );
+                    return Err(());
+                }
+            };
+            let value = if hir.pullup {
+                (BigInt::one() << width) - 1
+            } else {
+                BigInt::zero()
+            };
+            let value = self.intern_value(value::make_int(mir.ty, value));
+            let value = self.emit_const(value, env, hir.span)?;
+            let lhs = self.emit_net_driver(mir, strength)?;
+            self.mk_drv(lhs, value, delay);
+        }
+        Ok(())
+    }
+
+    /// Evaluate the `$readmemh` and `$readmemb` calls in the `initial`
+    /// procedures of a module block.
+    ///
+    /// The loaded contents become the initial value of the memory when it is
+    /// declared in `emit_varnet_decl`. Only memories declared in the same
+    /// block are supported; other memories are reported as errors.
+    fn emit_mem_inits(&mut self, env: ParamEnv, hir: &hir::ModuleBlock) -> Result<()> {
+        for &proc_id in &hir.procs {
+            let prok = match self.hir_of(proc_id)? {
+                HirNode::Proc(x) if x.kind == ast::ProcedureKind::Initial => x,
+                _ => continue,
+            };
+            let mut calls = ReadMemCollector {
+                cx: self.cx,
+                calls: vec![],
+            };
+            calls.visit_node_with_id(prok.stmt, false);
+            for expr in calls.calls {
+                let (hex, file, mem, start, end) = match expr.kind {
+                    hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem {
+                        hex,
+                        file,
+                        mem,
+                        start,
+                        end,
+                    }) => (hex, file, mem, start, end),
+                    _ => unreachable!(),
+                };
+                let decl_id = self.resolve_node(mem, env)?;
+                if !hir.decls.contains(&decl_id) {
+                    let name = if hex { "$readmemh" } else { "$readmemb" };
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "unsupported: `{}` of memory `{}` declared outside of the \
+                             enclosing block",
+                            name,
+                            self.span(mem).extract()
+                        ))
+                        .span(expr.span)
+                        .add_note(format!(
+                            "`{}` is evaluated during elaboration and can only initialize \
+                             memories declared in the same module or generate block",
+                            name
+                        )),
+                    );
+                    return Err(());
+                }
+                let ty = self.type_of(decl_id, env)?;
+                let value = readmem::load_mem(self.cx, expr.span, hex, file, ty, start, end, env)?;
+                self.tables.mem_inits.insert(decl_id.env(env), value);
+                self.tables.mem_init_calls.insert(expr.id.env(env));
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluate an elaboration system task and report its message.
+    fn emit_elab_task(&mut self, hir: &'gcx hir::ElabTask<'gcx>, env: ParamEnv) -> Result<()> {
+        let mir = mir::lower::rvalue::lower_elab_task(self.cx, hir, env)?;
+        let msg = match mir.kind {
+            mir::RvalueKind::SysTask { ref msg, .. } => msg,
+            _ => unreachable!(),
+        };
+
+        // Format the message.
+        let mut text = String::new();
+        for arg in msg {
+            match *arg {
+                mir::FormatArg::Literal(ref literal) => text.push_str(literal),
+                mir::FormatArg::Value(spec, value) => {
+                    if !value.is_const() {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not constant",
+                                value.span.extract()
+                            ))
+                            .span(value.span)
+                            .add_note("Arguments of elaboration system tasks must be constant"),
+                        );
+                        return Err(());
+                    }
+                    if value.ty.is_string() {
+                        let bytes = self.const_mir_rvalue_string(Ref(value))?;
+                        text.push_str(&String::from_utf8_lossy(bytes));
+                        continue;
+                    }
+                    if spec.kind.is_real() {
+                        let value = self.const_mir_rvalue(Ref(value));
+                        match value.get_real() {
+                            Some(v) => text.push_str(&format::format_real(&spec, v)),
+                            None => return Err(()),
+                        }
+                        continue;
+                    }
+                    let sbv = value.ty.simple_bit_vector(self.cx, value.span);
+                    let k = self.const_mir_rvalue_int(Ref(value))?;
+                    text.push_str(&format::format_int(&spec, k, sbv.size, sbv.is_signed()));
+                }
+            }
+        }
+
+        if text.is_empty() {
+            text = format!("`{}` reached during elaboration", hir.span.extract());
+        }
+
+        // Report the message at the appropriate severity. `$fatal` aborts
+        // elaboration.
+        match hir.task {
+            hir::SysTask::Info => self.emit(DiagBuilder2::note(text).span(hir.span)),
+            hir::SysTask::Warning => self.emit(DiagBuilder2::warning(text).span(hir.span)),
+            hir::SysTask::Error => self.emit(DiagBuilder2::error(text).span(hir.span)),
+            hir::SysTask::Fatal => {
+                self.emit(DiagBuilder2::fatal(text).span(hir.span));
+                return Err(());
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Emit a concurrent assertion.
+    ///
+    /// Concurrent assertions are mapped to the CIRCT `verif` and `ltl`
+    /// dialects, such that a formal flow can consume them. LLHD has no notion
+    /// of assertions, so they only show up in the MLIR output.
+    fn emit_conc_assert(&mut self, hir: &hir::ConcAssert, env: ParamEnv) -> Result<()> {
+        // Resolve the clocking event.
+        let clock_hir = match self.hir_of(hir.clock)? {
+            HirNode::EventExpr(x) => x,
+            _ => unreachable!(),
+        };
+        let event = match clock_hir.events.as_slice() {
+            [event] if event.iff.is_empty() => event,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(
+                        "unsupported: assertion clock other than a single edge without `iff`",
+                    )
+                    .span(clock_hir.span),
                 );
+                return Err(());
+            }
+        };
+        let edge = match event.edge {
+            ast::EdgeIdent::Posedge => circt::ltl::ClockEdge::Pos,
+            ast::EdgeIdent::Negedge => circt::ltl::ClockEdge::Neg,
+            ast::EdgeIdent::Edge | ast::EdgeIdent::Implicit => circt::ltl::ClockEdge::Both,
+        };
+        let mut clock = self.emit_rvalue(event.expr, env)?;
+        if self.llhd_type(clock.0) != llhd::int_ty(1) {
+            let zero = self.emit_zero_for_type_both(self.value_type(clock));
+            clock = self.mk_cmp(CmpPred::Neq, clock, zero);
+        }
+
+        // `$past` samples values through registers clocked by the assertion
+        // clock. Registers only trigger on the rising edge, so invert the clock
+        // for falling edges.
+        self.assert_clock = match edge {
+            circt::ltl::ClockEdge::Pos => {
+                Some(circt::seq::ToClockOp::new(self.mlir_builder, clock.1).into())
+            }
+            circt::ltl::ClockEdge::Neg => {
+                let one = circt::hw::ConstantOp::new(self.mlir_builder, 1, &BigInt::one());
+                let inv = circt::comb::XorOp::new(self.mlir_builder, clock.1, one.into());
+                Some(circt::seq::ToClockOp::new(self.mlir_builder, inv.into()).into())
+            }
+            circt::ltl::ClockEdge::Both => None,
+        };
+
+        // Emit the property itself.
+        let prop = self.emit_assert_prop(&hir.prop, env);
+        self.assert_clock = None;
+        let mut prop = prop?;
+
+        // The `disable iff` condition aborts any evaluation of the property
+        // that is in progress, and is checked in every cycle.
+        if let Some(disable) = hir.disable {
+            let disable = self.emit_rvalue_bool(disable, env)?;
+            prop = circt::ltl::DisableOp::new(self.mlir_builder, prop, disable.1).into();
+        }
+
+        // Clock the property and emit the assertion.
+        self.mlir_builder.set_loc(span_to_loc(self.mcx, hir.span));
+        let prop = circt::ltl::ClockOp::new(self.mlir_builder, prop, edge, clock.1).into();
+        let label = hir.label.map(|l| l.value.to_string());
+        match hir.kind {
+            hir::AssertKind::Assert => {
+                circt::verif::AssertOp::new(self.mlir_builder, prop, label.as_deref());
+            }
+            hir::AssertKind::Assume => {
+                circt::verif::AssumeOp::new(self.mlir_builder, prop, label.as_deref());
+            }
+            hir::AssertKind::Cover => {
+                circt::verif::CoverOp::new(self.mlir_builder, prop, label.as_deref());
             }
-            self.builder.ins().inst(
-                ext_unit,
-                inputs.iter().map(|x| x.0).collect(),
-                outputs.iter().map(|x| x.0).collect(),
-            );
-            circt::llhd::InstanceOp::new(
-                self.mlir_builder,
-                &self.unique_names.add(&inst.hir.name.value.to_string()),
-                &target.mlir_symbol,
-                inputs.iter().map(|x| x.1),
-                outputs.iter().map(|x| x.1),
-            );
         }
+        Ok(())
+    }
 
-        // Emit generate blocks.
-        for &gen_id in &hir.gens {
-            let hir = match self.hir_of(gen_id)? {
-                HirNode::Gen(x) => x,
-                _ => unreachable!(),
-            };
-            #[allow(unreachable_patterns)]
-            match hir.kind {
-                hir::GenKind::If {
-                    cond,
-                    ref main_body,
-                    ref else_body,
-                } => {
-                    let k = self.constant_value_of(cond, env);
-                    if k.is_false() {
-                        if let Some(else_body) = else_body {
-                            self.emit_module_block(id, env, else_body, name_prefix)?;
-                        }
-                    } else {
-                        self.emit_module_block(id, env, main_body, name_prefix)?;
-                    }
+    /// Emit a property of a concurrent assertion.
+    fn emit_assert_prop(&mut self, prop: &hir::Prop, env: ParamEnv) -> Result<mlir::Value> {
+        Ok(match *prop {
+            hir::Prop::Seq(ref seq) => self.emit_assert_seq(seq, env)?,
+            hir::Prop::Not(ref arg) => {
+                let arg = self.emit_assert_prop(arg, env)?;
+                circt::ltl::NotOp::new(self.mlir_builder, arg).into()
+            }
+            hir::Prop::And(ref lhs, ref rhs) => {
+                let lhs = self.emit_assert_prop(lhs, env)?;
+                let rhs = self.emit_assert_prop(rhs, env)?;
+                circt::ltl::AndOp::new(self.mlir_builder, &[lhs, rhs]).into()
+            }
+            hir::Prop::Or(ref lhs, ref rhs) => {
+                let lhs = self.emit_assert_prop(lhs, env)?;
+                let rhs = self.emit_assert_prop(rhs, env)?;
+                circt::ltl::OrOp::new(self.mlir_builder, &[lhs, rhs]).into()
+            }
+            hir::Prop::Implication {
+                ref seq,
+                overlap,
+                ref prop,
+            } => {
+                let mut seq = self.emit_assert_seq(seq, env)?;
+                // A non-overlapping implication `s |=> p` is equivalent to
+                // `s ##1 1 |-> p`.
+                if !overlap {
+                    let one = circt::hw::ConstantOp::new(self.mlir_builder, 1, &BigInt::one());
+                    seq = circt::ltl::ConcatOp::new(self.mlir_builder, &[seq, one.into()]).into();
                 }
-                hir::GenKind::For {
-                    ref init,
-                    cond,
-                    step,
-                    ref body,
-                } => {
-                    let mut local_env = env;
-                    for &i in init {
-                        local_env = self.execute_genvar_init(i, local_env)?;
-                    }
-                    while self.constant_value_of(cond, local_env).is_true() {
-                        self.emit_module_block(id, local_env, body, name_prefix)?;
-                        local_env = self.execute_genvar_step(step, local_env)?;
+                let prop = self.emit_assert_prop(prop, env)?;
+                circt::ltl::ImplicationOp::new(self.mlir_builder, seq, prop).into()
+            }
+        })
+    }
+
+    /// Emit a sequence of a concurrent assertion.
+    fn emit_assert_seq(&mut self, seq: &hir::Seq, env: ParamEnv) -> Result<mlir::Value> {
+        Ok(match *seq {
+            hir::Seq::Bool(expr) => self.emit_rvalue_bool(expr, env)?.1,
+            hir::Seq::Delay(ref lhs, range, ref rhs) => {
+                let (delay, length) = self.emit_assert_seq_range(range, env)?;
+                let rhs = self.emit_assert_seq(rhs, env)?;
+                match lhs {
+                    // A sequence concatenation already advances by one cycle,
+                    // so `s ##n t` maps to `s` followed by `t` delayed by one
+                    // cycle less.
+                    Some(lhs) => {
+                        if delay == 0 {
+                            self.emit(
+                                DiagBuilder2::error(
+                                    "unsupported: sequence fusion with zero cycle delay",
+                                )
+                                .span(self.span(match range {
+                                    hir::SeqRange::Exact(x) | hir::SeqRange::Range(x, _) => x,
+                                    _ => unreachable!(),
+                                })),
+                            );
+                            return Err(());
+                        }
+                        let lhs = self.emit_assert_seq(lhs, env)?;
+                        let rhs =
+                            circt::ltl::DelayOp::new(self.mlir_builder, rhs, delay - 1, length);
+                        circt::ltl::ConcatOp::new(self.mlir_builder, &[lhs, rhs.into()]).into()
                     }
+                    None => circt::ltl::DelayOp::new(self.mlir_builder, rhs, delay, length).into(),
                 }
-                _ => return self.unimp_msg("code generation for", hir),
             }
-        }
+            hir::Seq::Repeat(ref arg, range) => {
+                let (base, more) = self.emit_assert_seq_range(range, env)?;
+                let arg = self.emit_assert_seq(arg, env)?;
+                circt::ltl::RepeatOp::new(self.mlir_builder, arg, base, more).into()
+            }
+            hir::Seq::GotoRepeat(arg, count) => {
+                let count = self.emit_assert_seq_bound(count, env)?;
+                let arg = self.emit_rvalue_bool(arg, env)?.1;
+                circt::ltl::GoToRepeatOp::new(self.mlir_builder, arg, count, 0).into()
+            }
+            hir::Seq::NonconsecRepeat(arg, count) => {
+                let count = self.emit_assert_seq_bound(count, env)?;
+                let arg = self.emit_rvalue_bool(arg, env)?.1;
+                circt::ltl::NonConsecutiveRepeatOp::new(self.mlir_builder, arg, count, 0).into()
+            }
+            hir::Seq::And(ref lhs, ref rhs) => {
+                let lhs = self.emit_assert_seq(lhs, env)?;
+                let rhs = self.emit_assert_seq(rhs, env)?;
+                circt::ltl::AndOp::new(self.mlir_builder, &[lhs, rhs]).into()
+            }
+            hir::Seq::Or(ref lhs, ref rhs) => {
+                let lhs = self.emit_assert_seq(lhs, env)?;
+                let rhs = self.emit_assert_seq(rhs, env)?;
+                circt::ltl::OrOp::new(self.mlir_builder, &[lhs, rhs]).into()
+            }
+            hir::Seq::Intersect(ref lhs, ref rhs) => {
+                let lhs = self.emit_assert_seq(lhs, env)?;
+                let rhs = self.emit_assert_seq(rhs, env)?;
+                circt::ltl::IntersectOp::new(self.mlir_builder, &[lhs, rhs]).into()
+            }
+        })
+    }
 
-        // Emit and instantiate procedures.
-        for &proc_id in &hir.procs {
-            let prok = self.emit_procedure(proc_id, env, name_prefix)?;
-            let lookup_value = |&id: &AccessedNode| match self.values.get(&id) {
-                Some(v) => v.clone(),
-                None => {
+    /// Resolve a sequence delay or repetition range to its lower bound and the
+    /// number of additional cycles. The latter is `None` if the range is
+    /// unbounded.
+    fn emit_assert_seq_range(
+        &mut self,
+        range: hir::SeqRange,
+        env: ParamEnv,
+    ) -> Result<(usize, Option<usize>)> {
+        Ok(match range {
+            hir::SeqRange::Exact(n) => (self.emit_assert_seq_bound(n, env)?, Some(0)),
+            hir::SeqRange::Range(lo, None) => (self.emit_assert_seq_bound(lo, env)?, None),
+            hir::SeqRange::Range(lo, Some(hi)) => {
+                let lo_value = self.emit_assert_seq_bound(lo, env)?;
+                let hi_value = self.emit_assert_seq_bound(hi, env)?;
+                if hi_value < lo_value {
                     self.emit(
-                        DiagBuilder2::bug(format!(
-                            "{} used as input/output of {}, but no value has been emitted",
-                            self.hir_of(id.id()).unwrap().desc_full(),
-                            self.hir_of(proc_id).unwrap().desc_full(),
+                        DiagBuilder2::error(format!(
+                            "range upper bound {} is less than lower bound {}",
+                            hi_value, lo_value
                         ))
-                        .span(self.span(id.id())),
+                        .span(self.span(lo))
+                        .add_note("upper bound defined here:")
+                        .span(self.span(hi)),
                     );
-                    panic!("no value emitted for {:?}", id);
+                    return Err(());
                 }
-            };
-            let inputs: Vec<_> = prok.inputs.iter().map(lookup_value).collect();
-            let outputs: Vec<_> = prok.outputs.iter().map(lookup_value).collect();
-            let ext_unit = self.builder.add_extern(
-                self.into.unit(prok.unit).name().clone(),
-                self.into.unit(prok.unit).sig().clone(),
-            );
-            self.builder.ins().inst(
-                ext_unit,
-                inputs.iter().map(|x| x.0).collect(),
-                outputs.iter().map(|x| x.0).collect(),
-            );
-            circt::llhd::InstanceOp::new(
-                self.mlir_builder,
-                &self.unique_names.add(&format!("{}_inst", prok.mlir_symbol)),
-                &prok.mlir_symbol,
-                inputs.iter().map(|x| x.1),
-                outputs.iter().map(|x| x.1),
-            );
-        }
+                (lo_value, Some(hi_value - lo_value))
+            }
+            hir::SeqRange::Star => (0, None),
+            hir::SeqRange::Plus => (1, None),
+        })
+    }
 
-        Ok(())
+    /// Resolve a bound of a sequence delay or repetition range.
+    fn emit_assert_seq_bound(&mut self, id: NodeId, env: ParamEnv) -> Result<usize> {
+        match self.constant_int_value_of(id, env)?.to_usize() {
+            Some(x) => Ok(x),
+            None => {
+                self.emit(
+                    DiagBuilder2::error("sequence range bound must be a non-negative integer")
+                        .span(self.span(id)),
+                );
+                Err(())
+            }
+        }
     }
 
     /// Emit code for the connections made in a port list.
@@ -1343,7 +3528,7 @@ where
                             })
                         }
                     };
-                    self.emit_mir_lvalue(mir).map(|x| x.0)
+                    self.emit_net_driver(mir, drive_levels(None))
                 } else {
                     let mir = self.mir_rvalue(mapping.id(), mapping.env());
                     if mir.is_error() {
@@ -1426,6 +3611,15 @@ where
             return Err(());
         }
         match value.kind {
+            ValueKind::Int(ref k, ref special_bits, ref x_bits) if self.is_four_state(value.ty) => {
+                let size = value.ty.simple_bit_vector(self.cx, span).size;
+                let mask = (BigInt::one() << size) - 1;
+                let unknown = value::bits_to_int(special_bits) & &mask;
+                let z = &unknown ^ (&unknown & value::bits_to_int(x_bits));
+                let v = self.mk_const_int(size, &(k | z));
+                let u = self.mk_const_int(size, &unknown);
+                self.mk_four_state(value.ty, v, u)
+            }
             ValueKind::Int(ref k, ..) => {
                 let size = value.ty.simple_bit_vector(self.cx, span).size;
                 Ok((
@@ -1433,6 +3627,26 @@ where
                     circt::hw::ConstantOp::new(self.mlir_builder, std::cmp::max(size, 1), k).into(),
                 ))
             }
+            ValueKind::Real(bits) => {
+                let (size, bits) = match value.ty.get_real() {
+                    Some(ty::RealType::ShortReal) => {
+                        (32, (f64::from_bits(bits) as f32).to_bits() as u64)
+                    }
+                    _ => (64, bits),
+                };
+                Ok(self.mk_const_int(size, &bits.into()))
+            }
+            ValueKind::String(ref bytes) => {
+                if bytes.is_empty() {
+                    return Ok(self.mk_const_int(64, &BigInt::zero()));
+                }
+                let text = self.intern_runtime_string(&String::from_utf8_lossy(bytes));
+                let text = self.mk_const_int(32, &text.into());
+                Ok(self.emit_runtime_value(RuntimeFunc::StringLiteral, vec![text]))
+            }
+            ValueKind::Event | ValueKind::EmptyArray | ValueKind::Null => {
+                Ok(self.mk_const_int(64, &BigInt::zero()))
+            }
             ValueKind::Time(ref k) => Ok((
                 self.builder
                     .ins()
@@ -1453,7 +3667,7 @@ where
                         self.builder.ins().array(ll_fields),
                         circt::hw::ArrayCreateOp::new(self.mlir_builder, ty.1, mlir_fields).into(),
                     ))
-                } else if let Some(_strukt) = value.ty.get_struct() {
+                } else if value.ty.get_struct().is_some() || value.ty.get_union().is_some() {
                     Ok((
                         self.builder.ins().strukt(ll_fields),
                         circt::hw::StructCreateOp::new(self.mlir_builder, ty.1, mlir_fields).into(),
@@ -1465,6 +3679,16 @@ where
                     );
                 }
             }
+            // Void values, such as the `void` members of tagged unions, are
+            // represented by an empty struct.
+            ValueKind::Void => {
+                let ty = self.emit_type_both(value.ty)?;
+                Ok((
+                    self.builder.ins().strukt(vec![]),
+                    circt::hw::StructCreateOp::new(self.mlir_builder, ty.1, std::iter::empty())
+                        .into(),
+                ))
+            }
             ValueKind::Error => Err(()),
             _ => panic!(
                 "invalid combination of type `{}` and value {:#?}",
@@ -1626,6 +3850,10 @@ where
     ) -> Result<(HybridValue, Mode)> {
         let result = self.emit_mir_rvalue_inner(mir, mode_hint);
         match result {
+            // Void rvalues, such as method calls that modify their target,
+            // are only emitted for their side effects. Their value is
+            // discarded.
+            Ok(_) if mir.ty.is_void() => (),
             Ok((result, actual_mode)) => {
                 let llty_exp = self.emit_type(mir.ty)?;
                 let llty_exp = match actual_mode {
@@ -1672,8 +3900,18 @@ where
                 .map(|v| (v, Mode::Value));
         }
 
+        // Operations on four-state values need to consider the unknown bits.
+        if self.sess().opts.four_state {
+            if let Some(value) = self.emit_four_state_rvalue(mir)? {
+                return Ok((value, Mode::Value));
+            }
+        }
+
         let value: HybridValue = match mir.kind {
             mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) | mir::RvalueKind::Arg(id) => {
+                if let Some(&value) = self.dataflow_values.as_ref().and_then(|v| v.get(&id)) {
+                    return Ok((value, Mode::Value));
+                }
                 let sig = self
                     .shadows
                     .get(&id.into())
@@ -1808,8 +4046,12 @@ where
                 let rhs = self.emit_mir_rvalue(rhs)?;
                 let signed = sign.is_signed();
                 match op {
-                    mir::IntCompOp::Eq => self.mk_cmp(CmpPred::Eq, lhs, rhs),
-                    mir::IntCompOp::Neq => self.mk_cmp(CmpPred::Neq, lhs, rhs),
+                    mir::IntCompOp::Eq | mir::IntCompOp::CaseEq | mir::IntCompOp::WildcardEq => {
+                        self.mk_cmp(CmpPred::Eq, lhs, rhs)
+                    }
+                    mir::IntCompOp::Neq | mir::IntCompOp::CaseNeq | mir::IntCompOp::WildcardNeq => {
+                        self.mk_cmp(CmpPred::Neq, lhs, rhs)
+                    }
                     mir::IntCompOp::Lt if signed => self.mk_cmp(CmpPred::Slt, lhs, rhs),
                     mir::IntCompOp::Leq if signed => self.mk_cmp(CmpPred::Sle, lhs, rhs),
                     mir::IntCompOp::Gt if signed => self.mk_cmp(CmpPred::Sgt, lhs, rhs),
@@ -1847,14 +4089,25 @@ where
                     // The `x**y` operator requires special love, because there
                     // is no direct equivalent for it in LLHD.
                     mir::IntBinaryArithOp::Pow => {
-                        // If the exponent is a constant, we simply unroll.
+                        // If the exponent is a small non-negative constant, we
+                        // simply unroll.
                         if rhs.is_const() {
-                            let count = self.const_mir_rvalue_int(Ref(rhs))?;
-                            let mut value = lhs_ll;
-                            for _ in 1..count.to_usize().unwrap() {
-                                value = self.mk_umul(value, lhs_ll);
+                            let count = self.const_mir_rvalue_int(Ref(rhs))?.to_usize();
+                            match count {
+                                Some(0) => {
+                                    let width = self.llhd_type(lhs_ll.0).len();
+                                    let one = self.mk_const_int(width, &BigInt::one());
+                                    return Ok((one, Mode::Value));
+                                }
+                                Some(count) if count <= 64 => {
+                                    let mut value = lhs_ll;
+                                    for _ in 1..count {
+                                        value = self.mk_umul(value, lhs_ll);
+                                    }
+                                    return Ok((value, Mode::Value));
+                                }
+                                _ => (),
                             }
-                            return Ok((value, Mode::Value));
                         }
 
                         // If the base is a constant power of two, we translate
@@ -1896,12 +4149,14 @@ where
                             }
                         }
 
-                        // Otherwise we just complain.
-                        self.emit(
-                            DiagBuilder2::error("`**` operator on non-constants not supported")
-                                .span(mir.span),
-                        );
-                        return Err(());
+                        // Otherwise we emit a square-and-multiply sequence over
+                        // the bits of the exponent.
+                        self.mk_pow(
+                            lhs_ll,
+                            rhs_ll,
+                            rhs.ty.simple_bit_vector(self.cx, rhs.span),
+                            signed,
+                        )
                     }
                 }
             }
@@ -1955,72 +4210,383 @@ where
                 }
             }
 
-            mir::RvalueKind::Ternary {
-                cond,
-                true_value,
-                false_value,
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } => {
+                let cond = self.emit_mir_rvalue(cond)?;
+                let true_value = self.emit_mir_rvalue(true_value)?;
+                let false_value = self.emit_mir_rvalue(false_value)?;
+                self.mk_mux(cond, true_value, false_value)
+            }
+
+            mir::RvalueKind::Reduction { op, arg } => {
+                let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
+                let arg = self.emit_mir_rvalue(arg)?;
+                let mut value = self.mk_ext_slice_const_offset(arg, 0, 1);
+                for i in 1..width {
+                    let bit = self.mk_ext_slice_const_offset(arg, i, 1);
+                    value = match op {
+                        mir::BinaryBitwiseOp::And => self.mk_and(value, bit),
+                        mir::BinaryBitwiseOp::Or => self.mk_or(value, bit),
+                        mir::BinaryBitwiseOp::Xor => self.mk_xor(value, bit),
+                    };
+                }
+                value
+            }
+
+            mir::RvalueKind::Assignment {
+                lvalue,
+                rvalue,
+                result,
+            } => {
+                self.emit_mir_blocking_assign(lvalue, rvalue)?;
+                self.emit_mir_rvalue(result)?
+            }
+
+            mir::RvalueKind::PackString(value) => {
+                // Assemble the packed vector one 64 bit word at a time.
+                let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+                let value = self.emit_mir_rvalue(value)?;
+                let ty = self.emit_type_both(mir.ty)?;
+                let mut result = self.emit_zero_for_type_both(ty);
+                for i in 0..(width + 63) / 64 {
+                    let offset = i * 64;
+                    let len = std::cmp::min(64, width - offset);
+                    let index = self.mk_const_int(32, &i.into());
+                    let word = self.emit_runtime_value(RuntimeFunc::StringPack, vec![value, index]);
+                    let word = self.mk_ext_slice_const_offset(word, 0, len);
+                    result = self.mk_ins_slice(result, word, offset, len);
+                }
+                result
+            }
+
+            mir::RvalueKind::UnpackString(value) => {
+                // Append the characters one 64 bit word at a time, most
+                // significant word first.
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let value = self.emit_mir_rvalue_known(value)?;
+                let mut result = self.mk_const_int(64, &BigInt::zero());
+                for i in (0..(width + 63) / 64).rev() {
+                    let offset = i * 64;
+                    let len = std::cmp::min(64, width - offset);
+                    let word = self.mk_ext_slice_const_offset(value, offset, len);
+                    let word = self.mk_extend_to_i64(word, len, false);
+                    result = self.emit_runtime_value(RuntimeFunc::StringUnpack, vec![result, word]);
+                }
+                result
+            }
+
+            mir::RvalueKind::StringComp { op, lhs, rhs } => {
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                let op = match op {
+                    mir::StringCompOp::Eq => 0,
+                    mir::StringCompOp::Neq => 1,
+                    mir::StringCompOp::Lt => 2,
+                    mir::StringCompOp::Leq => 3,
+                    mir::StringCompOp::Gt => 4,
+                    mir::StringCompOp::Geq => 5,
+                };
+                let op = self.mk_const_int(32, &BigInt::from(op));
+                self.emit_runtime_value(RuntimeFunc::StringComp, vec![op, lhs, rhs])
+            }
+
+            mir::RvalueKind::StringConcat(ref values) => {
+                let mut result = self.mk_const_int(64, &BigInt::zero());
+                for &value in values {
+                    let value = self.emit_mir_rvalue(value)?;
+                    result =
+                        self.emit_runtime_value(RuntimeFunc::StringConcat, vec![result, value]);
+                }
+                result
+            }
+
+            mir::RvalueKind::StringLen(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                self.emit_runtime_value(RuntimeFunc::StringLen, vec![value])
+            }
+
+            mir::RvalueKind::StringGetc { value, index } => {
+                let value = self.emit_mir_rvalue(value)?;
+                let index = self.emit_mir_rvalue_known(index)?;
+                self.emit_runtime_value(RuntimeFunc::StringGetc, vec![value, index])
+            }
+
+            mir::RvalueKind::StringPutc {
+                value,
+                index,
+                character,
+            } => {
+                let value = self.emit_mir_rvalue(value)?;
+                let index = self.emit_mir_rvalue_known(index)?;
+                let character = self.emit_mir_rvalue_known(character)?;
+                self.emit_runtime_value(RuntimeFunc::StringPutc, vec![value, index, character])
+            }
+
+            mir::RvalueKind::StringSubstr { value, start, stop } => {
+                let value = self.emit_mir_rvalue(value)?;
+                let start = self.emit_mir_rvalue_known(start)?;
+                let stop = self.emit_mir_rvalue_known(stop)?;
+                self.emit_runtime_value(RuntimeFunc::StringSubstr, vec![value, start, stop])
+            }
+
+            mir::RvalueKind::StringToInt { radix, value } => {
+                let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+                let value = self.emit_mir_rvalue(value)?;
+                let radix = self.mk_const_int(32, &radix.into());
+                let result = self.emit_runtime_value(RuntimeFunc::StringToInt, vec![radix, value]);
+                self.mk_ext_slice_const_offset(result, 0, width)
+            }
+
+            mir::RvalueKind::StringFromInt { radix, sign, value } => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let value = self.emit_mir_rvalue_known(value)?;
+                let value = if width > 64 {
+                    self.mk_ext_slice_const_offset(value, 0, 64)
+                } else {
+                    self.mk_extend_to_i64(value, width, sign.is_signed())
+                };
+                let radix = self.mk_const_int(32, &radix.into());
+                let signed = self.mk_const_int(32, &(sign.is_signed() as usize).into());
+                self.emit_runtime_value(RuntimeFunc::StringFromInt, vec![radix, signed, value])
+            }
+
+            mir::RvalueKind::IntToReal { sign, value } => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let value = self.emit_mir_rvalue_known(value)?;
+                let value = if width > 64 {
+                    self.mk_ext_slice_const_offset(value, 0, 64)
+                } else {
+                    self.mk_extend_to_i64(value, width, sign.is_signed())
+                };
+                let signed = self.mk_const_int(32, &(sign.is_signed() as usize).into());
+                let result = self.emit_runtime_value(RuntimeFunc::RealFromInt, vec![signed, value]);
+                self.mk_double_to_real(result, mir.ty)
+            }
+
+            mir::RvalueKind::RealToInt { round, value } => {
+                let value_ty = value.ty;
+                let value = self.emit_mir_rvalue(value)?;
+                let value = self.mk_real_to_double(value, value_ty);
+                let round = self.mk_const_int(32, &(round as usize).into());
+                self.emit_runtime_value(RuntimeFunc::RealToInt, vec![round, value])
+            }
+
+            mir::RvalueKind::ResizeReal(value) => {
+                let value_ty = value.ty;
+                let value = self.emit_mir_rvalue(value)?;
+                let value = self.mk_real_to_double(value, value_ty);
+                self.mk_double_to_real(value, mir.ty)
+            }
+
+            mir::RvalueKind::RealNeg(value) => {
+                // Negation simply flips the sign bit.
+                let width = value.ty.get_bit_size().unwrap();
+                let value = self.emit_mir_rvalue(value)?;
+                let sign = self.mk_const_int(width, &(BigInt::one() << (width - 1)));
+                self.mk_xor(value, sign)
+            }
+
+            mir::RvalueKind::RealArith { op, lhs, rhs } => {
+                let (lhs_ty, rhs_ty) = (lhs.ty, rhs.ty);
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                let lhs = self.mk_real_to_double(lhs, lhs_ty);
+                let rhs = self.mk_real_to_double(rhs, rhs_ty);
+                let op = match op {
+                    mir::RealArithOp::Add => 0,
+                    mir::RealArithOp::Sub => 1,
+                    mir::RealArithOp::Mul => 2,
+                    mir::RealArithOp::Div => 3,
+                    mir::RealArithOp::Pow => 4,
+                };
+                let op = self.mk_const_int(32, &BigInt::from(op));
+                let result = self.emit_runtime_value(RuntimeFunc::RealArith, vec![op, lhs, rhs]);
+                self.mk_double_to_real(result, mir.ty)
+            }
+
+            mir::RvalueKind::RealComp { op, lhs, rhs } => {
+                let (lhs_ty, rhs_ty) = (lhs.ty, rhs.ty);
+                let lhs = self.emit_mir_rvalue(lhs)?;
+                let rhs = self.emit_mir_rvalue(rhs)?;
+                let lhs = self.mk_real_to_double(lhs, lhs_ty);
+                let rhs = self.mk_real_to_double(rhs, rhs_ty);
+                let op = match op {
+                    mir::RealCompOp::Eq => 0,
+                    mir::RealCompOp::Neq => 1,
+                    mir::RealCompOp::Lt => 2,
+                    mir::RealCompOp::Leq => 3,
+                    mir::RealCompOp::Gt => 4,
+                    mir::RealCompOp::Geq => 5,
+                };
+                let op = self.mk_const_int(32, &BigInt::from(op));
+                self.emit_runtime_value(RuntimeFunc::RealComp, vec![op, lhs, rhs])
+            }
+
+            mir::RvalueKind::Past { value, depth } => {
+                let value = self.emit_mir_rvalue(value)?;
+                let clock = match self.assert_clock {
+                    Some(clock) => clock,
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(
+                                "`$past` requires a rising or falling assertion clock edge",
+                            )
+                            .span(mir.span),
+                        );
+                        return Err(());
+                    }
+                };
+                // LLHD has no notion of assertions; only the MLIR value is
+                // ever consumed.
+                let mut past = value.1;
+                for _ in 0..depth {
+                    past = circt::seq::CompRegOp::new(self.mlir_builder, past, clock).into();
+                }
+                (value.0, past)
+            }
+
+            mir::RvalueKind::SysTask {
+                task,
+                diag,
+                ref msg,
+            } => match self.emit_sys_task(mir.span, task, diag, msg)? {
+                Some(value) => value,
+                None => bug_span!(
+                    mir.span,
+                    self.cx,
+                    "system task without result used as a value"
+                ),
+            },
+
+            mir::RvalueKind::EventTriggered(value) => {
+                self.tables
+                    .llhd_unsupported
+                    .push((mir.span, "the simulation time that `.triggered` depends on"));
+                let value = self.emit_mir_rvalue(value)?;
+                let mask = self.mk_const_int(64, &BigInt::from(!1u64));
+                let value = self.mk_and(value, mask);
+                let stamp = self.mk_event_stamp();
+                self.mk_cmp(CmpPred::Eq, value, stamp)
+            }
+
+            mir::RvalueKind::ArrayNew { size, init } => {
+                let size = self.emit_mir_rvalue_known(size)?;
+                let init = self.emit_mir_rvalue(init)?;
+                self.emit_runtime_value(RuntimeFunc::ArrayNew, vec![size, init])
+            }
+
+            mir::RvalueKind::ArraySize(value) => {
+                let value = self.emit_mir_rvalue(value)?;
+                self.emit_runtime_value(RuntimeFunc::ArraySize, vec![value])
+            }
+
+            mir::RvalueKind::ArrayGet { array, index } => {
+                let kind = self.mk_array_kind(array.ty);
+                let index = self.emit_array_word(index)?;
+                let array = self.emit_mir_rvalue(array)?;
+                let word = self.emit_runtime_value(RuntimeFunc::ArrayGet, vec![kind, array, index]);
+                self.mk_array_elem(word, mir.ty, mir.span)?
+            }
+
+            mir::RvalueKind::ArraySet {
+                array,
+                index,
+                value,
             } => {
-                let cond = self.emit_mir_rvalue(cond)?;
-                let true_value = self.emit_mir_rvalue(true_value)?;
-                let false_value = self.emit_mir_rvalue(false_value)?;
-                self.mk_mux(cond, true_value, false_value)
+                let kind = self.mk_array_kind(array.ty);
+                let index = self.emit_array_word(index)?;
+                let value = self.emit_array_word(value)?;
+                let array = self.emit_mir_rvalue(array)?;
+                self.emit_runtime_value(RuntimeFunc::ArraySet, vec![kind, array, index, value])
             }
 
-            mir::RvalueKind::Reduction { op, arg } => {
-                let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
-                let arg = self.emit_mir_rvalue(arg)?;
-                let mut value = self.mk_ext_slice_const_offset(arg, 0, 1);
-                for i in 1..width {
-                    let bit = self.mk_ext_slice_const_offset(arg, i, 1);
-                    value = match op {
-                        mir::BinaryBitwiseOp::And => self.mk_and(value, bit),
-                        mir::BinaryBitwiseOp::Or => self.mk_or(value, bit),
-                        mir::BinaryBitwiseOp::Xor => self.mk_xor(value, bit),
-                    };
-                }
-                value
+            mir::RvalueKind::ArrayDelete { array, index } => {
+                let kind = self.mk_array_kind(array.ty);
+                let index = self.emit_array_word(index)?;
+                let array = self.emit_mir_rvalue(array)?;
+                self.emit_runtime_value(RuntimeFunc::ArrayDelete, vec![kind, array, index])
             }
 
-            mir::RvalueKind::Assignment {
-                lvalue,
-                rvalue,
-                result,
+            mir::RvalueKind::ArrayExists { array, index } => {
+                let kind = self.mk_array_kind(array.ty);
+                let index = self.emit_array_word(index)?;
+                let array = self.emit_mir_rvalue(array)?;
+                let exists =
+                    self.emit_runtime_value(RuntimeFunc::ArrayExists, vec![kind, array, index]);
+                self.mk_bool_to_int(exists, mir.ty)?
+            }
+
+            mir::RvalueKind::QueuePush {
+                front,
+                queue,
+                value,
             } => {
-                self.emit_mir_blocking_assign(lvalue, rvalue)?;
-                self.emit_mir_rvalue(result)?
+                let front = self.mk_const_int(32, &(front as usize).into());
+                let queue = self.emit_mir_rvalue(queue)?;
+                let value = self.emit_array_word(value)?;
+                self.emit_runtime_value(RuntimeFunc::QueuePush, vec![front, queue, value])
             }
 
-            mir::RvalueKind::PackString(value) | mir::RvalueKind::UnpackString(value) => bug_span!(
-                value.span,
-                self.cx,
-                "codegen for string packing/unpacking not implemented"
-            ),
+            mir::RvalueKind::QueuePop { front, queue } => {
+                let front = self.mk_const_int(32, &(front as usize).into());
+                let queue = self.emit_mir_rvalue(queue)?;
+                self.emit_runtime_value(RuntimeFunc::QueuePop, vec![front, queue])
+            }
 
-            mir::RvalueKind::StringComp { .. } => bug_span!(
-                mir.span,
-                self.cx,
-                "runtime string comparisons not implemented"
-            ),
+            mir::RvalueKind::QueuePeek { front, queue } => {
+                let front = self.mk_const_int(32, &(front as usize).into());
+                let queue = self.emit_mir_rvalue(queue)?;
+                let word = self.emit_runtime_value(RuntimeFunc::QueuePeek, vec![front, queue]);
+                self.mk_array_elem(word, mir.ty, mir.span)?
+            }
 
-            mir::RvalueKind::ApplyTimescale(..) => bug_span!(
-                mir.span,
-                self.cx,
-                "runtime int-to-time conversion not implemented"
-            ),
+            mir::RvalueKind::AssocTraverse { op, array, key } => {
+                let word = self.emit_assoc_traverse(op, array, key)?;
+                self.mk_array_elem(word, mir.ty, mir.span)?
+            }
+
+            mir::RvalueKind::AssocTraverseFound { op, array, key } => {
+                // The first and last key exist in any non-empty array. The next
+                // and previous key exist if the traversal moved to a different
+                // key.
+                let found = match op {
+                    mir::AssocTraverseOp::First | mir::AssocTraverseOp::Last => {
+                        let array = self.emit_mir_rvalue(array)?;
+                        let size = self.emit_runtime_value(RuntimeFunc::ArraySize, vec![array]);
+                        let zero = self.mk_const_int(32, &BigInt::zero());
+                        self.mk_cmp(CmpPred::Neq, size, zero)
+                    }
+                    mir::AssocTraverseOp::Next | mir::AssocTraverseOp::Prev => {
+                        let word = self.emit_assoc_traverse(op, array, key)?;
+                        let key = self.emit_array_word(key)?;
+                        self.mk_cmp(CmpPred::Neq, word, key)
+                    }
+                };
+                self.mk_bool_to_int(found, mir.ty)?
+            }
+
+            mir::RvalueKind::ApplyTimescale(value, ref scale) => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let value = self.emit_mir_rvalue_known(value)?;
+                self.mk_int_to_time(value, width, scale)
+            }
 
             mir::RvalueKind::Call { target, ref args } => {
                 // Ensure the function is emitted.
                 let func_env = self.default_param_env();
                 let func = self.emit_function(target.id(), func_env)?;
-                let ext_unit = self.builder.add_extern(
-                    self.into.unit(func.unit).name().clone(),
-                    self.into.unit(func.unit).sig().clone(),
-                );
+                let ext_unit = self.builder.add_extern(func.name.clone(), func.sig.clone());
 
                 // Assemble the arguments passed into the function.
                 let mut input_args = vec![];
                 for arg in args {
                     match arg {
+                        mir::CallArg::Input(rv) if func.dpi => {
+                            input_args.push(self.emit_dpi_arg(rv)?);
+                        }
                         mir::CallArg::Input(rv) => {
                             input_args.push(self.emit_mir_rvalue(rv)?);
                         }
@@ -2046,7 +4612,14 @@ where
                     typeck::return_type_of_function(self.cx, &target.prototype, mir.env);
                 let mut result_tys = vec![];
                 if !return_ty.is_void() {
-                    result_tys.push(self.emit_type_both(return_ty)?.1);
+                    let ty = match func.dpi {
+                        true => {
+                            let repr = self.dpi_result_repr(return_ty, mir.span)?;
+                            self.emit_dpi_type(repr)
+                        }
+                        false => self.emit_type_both(return_ty)?,
+                    };
+                    result_tys.push(ty.1);
                 }
 
                 // Emit the call.
@@ -2059,58 +4632,717 @@ where
 
                 // Perform assignments for arguments that copy back out of the
                 // function.
-                for (arg, &value) in args.iter().zip(input_args.iter()) {
-                    match arg {
-                        mir::CallArg::Output(_, Some(lv)) | mir::CallArg::Inout(_, Some(lv)) => {
-                            let rvid = self.alloc_id(lv.span);
-                            self.set_emitted_value(rvid, value);
-                            let rv = self.arena().alloc_mir_rvalue(mir::Rvalue {
-                                id: rvid,
-                                origin: lv.id,
-                                env: mir.env,
-                                span: lv.span,
-                                ty: lv.ty,
-                                kind: mir::RvalueKind::Var(rvid),
-                                konst: false,
-                            });
-                            let assign = self.arena().alloc_mir_assignment(mir::Assignment {
-                                id: lv.id,
-                                env: mir.env,
-                                span: lv.span,
-                                ty: lv.ty,
-                                lhs: lv,
-                                rhs: rv,
-                            });
-                            let simplified = self.mir_simplify_assignment(Ref(assign));
-                            for assign in simplified {
-                                let lhs = self.emit_mir_lvalue(assign.lhs)?;
-                                let rhs = self.emit_mir_rvalue(assign.rhs)?;
-                                self.emit_blocking_assign_llhd(lhs, rhs)?;
+                self.emit_call_write_back(mir, args, &input_args)?;
+
+                // Unpack the results.
+                let result = if !return_ty.is_void() {
+                    call_op.result(0)
+                } else {
+                    mlir::Value::from_raw(circt::sys::MlirValue {
+                        ptr: std::ptr::null_mut(),
+                    })
+                };
+                let result = (self.builder.ins().call(ext_unit, vec![]), result);
+
+                // Convert results returned from the DPI.
+                if func.dpi && !return_ty.is_void() {
+                    self.emit_dpi_result(result, return_ty, mir.span)?
+                } else {
+                    result
+                }
+            }
+
+            // Propagate tombstones.
+            mir::RvalueKind::Error => return Err(()),
+        };
+
+        // Operations which cannot produce X or Z bits, such as calls into the
+        // runtime library, yield a plain integer for four-valued types.
+        if self.is_four_state(mir.ty) && self.llhd_type(value.0) != self.emit_type(mir.ty)? {
+            let unknown = self.emit_zero_for_type_both(self.value_type(value));
+            return Ok((self.mk_four_state(mir.ty, value, unknown)?, Mode::Value));
+        }
+
+        Ok((value, Mode::Value))
+    }
+
+    /// Assign the output and inout arguments of a call back to the lvalues
+    /// they were passed from, given the variables that hold their values.
+    fn emit_call_write_back(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        args: &[mir::CallArg<'gcx>],
+        values: &[HybridValue],
+    ) -> Result<()> {
+        for (arg, &value) in args.iter().zip(values.iter()) {
+            match arg {
+                mir::CallArg::Output(_, Some(lv)) | mir::CallArg::Inout(_, Some(lv)) => {
+                    let rvid = self.alloc_id(lv.span);
+                    self.set_emitted_value(rvid, value);
+                    let rv = self.arena().alloc_mir_rvalue(mir::Rvalue {
+                        id: rvid,
+                        origin: lv.id,
+                        env: mir.env,
+                        span: lv.span,
+                        ty: lv.ty,
+                        kind: mir::RvalueKind::Var(rvid),
+                        konst: false,
+                    });
+                    let assign = self.arena().alloc_mir_assignment(mir::Assignment {
+                        id: lv.id,
+                        env: mir.env,
+                        span: lv.span,
+                        ty: lv.ty,
+                        lhs: lv,
+                        rhs: rv,
+                    });
+                    let simplified = self.mir_simplify_assignment(Ref(assign));
+                    for assign in simplified {
+                        let lhs = self.emit_mir_lvalue(assign.lhs)?;
+                        let rhs = self.emit_mir_rvalue(assign.rhs)?;
+                        self.emit_blocking_assign_llhd(lhs, rhs)?;
+                    }
+                }
+                mir::CallArg::Output(_, None)
+                | mir::CallArg::Inout(_, None)
+                | mir::CallArg::Input(..)
+                | mir::CallArg::Ref(..) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit a call to a task by inlining the task body into the current unit.
+    ///
+    /// Tasks may contain timing controls such as `@(posedge clk)` or `#10ns`,
+    /// which cannot appear in an LLHD function. Instead, the body is emitted
+    /// into the calling process, with a fresh copy of the task's arguments and
+    /// local variables for every call, as if the task were `automatic`. Output
+    /// and inout arguments are written back when the task returns. The body is
+    /// emitted in the parameter environment of the caller.
+    ///
+    /// Static storage is not supported, so `static` tasks emit a warning.
+    fn emit_task_call(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+        target: &'gcx ast::SubroutineDecl<'gcx>,
+        args: &[mir::CallArg<'gcx>],
+    ) -> Result<()> {
+        // Inlining a task into itself would never terminate.
+        if self.inlined_tasks.iter().any(|&(id, _)| id == target.id()) {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "recursive call to task `{}` cannot be inlined",
+                    target.prototype.name
+                ))
+                .span(mir.span)
+                .add_note("Tasks are inlined into the calling process:")
+                .span(target.prototype.name.span),
+            );
+            return Err(());
+        }
+        if !target.is_automatic() && self.tables.static_tasks.insert(target.id()) {
+            self.emit(
+                DiagBuilder2::warning(format!(
+                    "unsupported: static task `{}` inlined with automatic storage",
+                    target.prototype.name
+                ))
+                .span(target.prototype.name.span)
+                .add_note(
+                    "Arguments and local variables of the task are not shared between \
+                     calls. Declare the task as `automatic` to avoid this warning.",
+                ),
+            );
+        }
+        let env = mir.env;
+        let decl_args = self.canonicalize_func_args(Ref(target));
+
+        // Allocate a variable for each argument, initialized to the value
+        // passed in by the caller.
+        let mut vars = vec![];
+        for (arg, decl) in args.iter().zip(decl_args.args.iter()) {
+            let var = match arg {
+                mir::CallArg::Input(rv) | mir::CallArg::Inout(rv, _) => {
+                    let init = self.emit_mir_rvalue(rv)?;
+                    self.mk_var(init)
+                }
+                mir::CallArg::Output(ty, _) => {
+                    let init = match decl.default {
+                        Some(expr) => self.emit_rvalue(expr.id(), env)?,
+                        None => {
+                            let ty = self.emit_type_both(ty)?;
+                            self.emit_zero_for_type_both(ty)
+                        }
+                    };
+                    self.mk_var(init)
+                }
+                mir::CallArg::Ref(lv) => self.emit_mir_lvalue(lv)?.0,
+            };
+            if let Some(name) = decl.name {
+                self.builder.set_name(var.0, name.value.to_string());
+            }
+            self.set_emitted_value(decl.ast.id(), var);
+            vars.push(var);
+        }
+
+        // Emit the body of the task. Any `return` statement branches to the
+        // exit block, and loops of the caller are not visible to `break` and
+        // `continue` in the task.
+        let exit_blk = self.mk_block(Some("task_exit"));
+        let break_stack = std::mem::take(&mut self.break_stack);
+        let continue_stack = std::mem::take(&mut self.continue_stack);
+        self.inlined_tasks.push((target.id(), exit_blk));
+        let result = target.items.iter().try_for_each(|item| match item {
+            ast::SubroutineItem::Stmt(stmt) => self.emit_stmt(stmt.id(), env),
+            _ => Ok(()),
+        });
+        self.inlined_tasks.pop();
+        self.break_stack = break_stack;
+        self.continue_stack = continue_stack;
+        result?;
+        if !self.terminated {
+            self.mk_br(exit_blk);
+        }
+        self.append_to(exit_blk);
+        self.flush_mir();
+
+        // Copy the output and inout arguments back to the caller.
+        self.emit_call_write_back(mir, args, &vars)
+    }
+
+    /// Emit the code for an MIR rvalue that operates on four-state values.
+    ///
+    /// Returns `None` if neither the rvalue nor its operands carry X and Z
+    /// bits, or if the operation does not depend on how they are represented.
+    /// The regular code generation applies in that case.
+    fn emit_four_state_rvalue(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<Option<HybridValue>> {
+        let four_state = self.is_four_state(mir.ty);
+        let (value, unknown) = match mir.kind {
+            // Casts between four-valued and two-valued types, which map X and
+            // Z bits to 0.
+            mir::RvalueKind::CastValueDomain { value, .. }
+            | mir::RvalueKind::Transmute(value)
+            | mir::RvalueKind::CastSign(_, value) => {
+                if four_state == self.is_four_state(value.ty) {
+                    return Ok(None);
+                }
+                if !four_state && !mir.ty.coalesces_to_llhd_scalar() {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "unsupported: four-state value cast to `{}`",
+                            mir.ty
+                        ))
+                        .span(mir.span),
+                    );
+                    return Err(());
+                }
+                self.emit_four_state_planes(value)?
+            }
+
+            // A value is true if any of its bits is 1, and X if none of its
+            // bits is 1 but some are X or Z.
+            mir::RvalueKind::CastToBool(value) if self.is_four_state(value.ty) => {
+                let (v, u) = self.emit_four_state_planes(value)?;
+                let not_u = self.mk_not(u);
+                let ones = self.mk_and(v, not_u);
+                let any_one = self.mk_any(ones);
+                let any_unknown = self.mk_any(u);
+                let not_one = self.mk_not(any_one);
+                (any_one, self.mk_and(not_one, any_unknown))
+            }
+
+            mir::RvalueKind::Truncate(width, value)
+                if four_state || self.is_four_state(value.ty) =>
+            {
+                let (v, u) = self.emit_four_state_planes(value)?;
+                (
+                    self.mk_ext_slice_const_offset(v, 0, width),
+                    self.mk_ext_slice_const_offset(u, 0, width),
+                )
+            }
+
+            mir::RvalueKind::ZeroExtend(width, value)
+                if four_state || self.is_four_state(value.ty) =>
+            {
+                let from = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let (v, u) = self.emit_four_state_planes(value)?;
+                (
+                    self.mk_zero_extend(v, from, width),
+                    self.mk_zero_extend(u, from, width),
+                )
+            }
+
+            // The value and unknown planes are extended individually, such
+            // that an X or Z sign bit is replicated as well.
+            mir::RvalueKind::SignExtend(width, value)
+                if four_state || self.is_four_state(value.ty) =>
+            {
+                let from = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let (v, u) = self.emit_four_state_planes(value)?;
+                (
+                    self.mk_sign_extend(v, from, width),
+                    self.mk_sign_extend(u, from, width),
+                )
+            }
+
+            mir::RvalueKind::UnaryBitwise { op, arg }
+                if four_state || self.is_four_state(arg.ty) =>
+            {
+                let (v, u) = self.emit_four_state_planes(arg)?;
+                match op {
+                    mir::UnaryBitwiseOp::Not => {
+                        let not_v = self.mk_not(v);
+                        let not_u = self.mk_not(u);
+                        (self.mk_and(not_v, not_u), u)
+                    }
+                }
+            }
+
+            mir::RvalueKind::BinaryBitwise { op, lhs, rhs }
+                if four_state || self.is_four_state(lhs.ty) || self.is_four_state(rhs.ty) =>
+            {
+                let (lv, lu) = self.emit_four_state_planes(lhs)?;
+                let (rv, ru) = self.emit_four_state_planes(rhs)?;
+                let any_u = self.mk_or(lu, ru);
+                match op {
+                    // A 0 in either operand forces the result to 0.
+                    mir::BinaryBitwiseOp::And => {
+                        let lz = self.mk_known_bits(lv, lu, false);
+                        let rz = self.mk_known_bits(rv, ru, false);
+                        let zeros = self.mk_or(lz, rz);
+                        let not_zeros = self.mk_not(zeros);
+                        let u = self.mk_and(not_zeros, any_u);
+                        let v = self.mk_and(lv, rv);
+                        let not_u = self.mk_not(any_u);
+                        (self.mk_and(v, not_u), u)
+                    }
+                    // A 1 in either operand forces the result to 1.
+                    mir::BinaryBitwiseOp::Or => {
+                        let lo = self.mk_known_bits(lv, lu, true);
+                        let ro = self.mk_known_bits(rv, ru, true);
+                        let ones = self.mk_or(lo, ro);
+                        let not_ones = self.mk_not(ones);
+                        (ones, self.mk_and(not_ones, any_u))
+                    }
+                    mir::BinaryBitwiseOp::Xor => {
+                        let v = self.mk_xor(lv, rv);
+                        let not_u = self.mk_not(any_u);
+                        (self.mk_and(v, not_u), any_u)
+                    }
+                }
+            }
+
+            // Arithmetic operators produce an all-X result if any operand bit
+            // is X or Z.
+            mir::RvalueKind::IntUnaryArith { op, arg, .. }
+                if four_state || self.is_four_state(arg.ty) =>
+            {
+                let (v, u) = self.emit_four_state_planes(arg)?;
+                let v = match op {
+                    mir::IntUnaryArithOp::Neg => self.mk_neg(v),
+                };
+                let any_u = self.mk_any(u);
+                self.mk_unknown_if(v, any_u)
+            }
+
+            // Division and modulus by zero produce an all-X result as well.
+            mir::RvalueKind::IntBinaryArith {
+                op, lhs, rhs, sign, ..
+            } if four_state || self.is_four_state(lhs.ty) || self.is_four_state(rhs.ty) => {
+                let (lv, lu) = self.emit_four_state_planes(lhs)?;
+                let (rv, ru) = self.emit_four_state_planes(rhs)?;
+                let lu = self.mk_any(lu);
+                let ru = self.mk_any(ru);
+                let mut any_u = self.mk_or(lu, ru);
+                let signed = sign.is_signed();
+                let v = match op {
+                    mir::IntBinaryArithOp::Add => self.mk_add(lv, rv),
+                    mir::IntBinaryArithOp::Sub => self.mk_sub(lv, rv),
+                    mir::IntBinaryArithOp::Mul if signed => self.mk_smul(lv, rv),
+                    mir::IntBinaryArithOp::Mul => self.mk_umul(lv, rv),
+                    mir::IntBinaryArithOp::Div | mir::IntBinaryArithOp::Mod => {
+                        let zero = self.emit_zero_for_type_both(self.value_type(rv));
+                        let by_zero = self.mk_cmp(CmpPred::Eq, rv, zero);
+                        any_u = self.mk_or(any_u, by_zero);
+                        match op {
+                            mir::IntBinaryArithOp::Div if signed => self.mk_sdiv(lv, rv),
+                            mir::IntBinaryArithOp::Mod if signed => self.mk_smod(lv, rv),
+                            mir::IntBinaryArithOp::Div => self.mk_udiv(lv, rv),
+                            _ => self.mk_umod(lv, rv),
+                        }
+                    }
+                    mir::IntBinaryArithOp::Pow => {
+                        // Zero raised to a negative power is X (§11.4.3).
+                        let rhs_sbv = rhs.ty.simple_bit_vector(self.cx, rhs.span);
+                        if rhs_sbv.is_signed() {
+                            let negative = self.mk_ext_slice_const_offset(rv, rhs_sbv.size - 1, 1);
+                            let zero = self.emit_zero_for_type_both(self.value_type(lv));
+                            let base_zero = self.mk_cmp(CmpPred::Eq, lv, zero);
+                            let undefined = self.mk_and(negative, base_zero);
+                            any_u = self.mk_or(any_u, undefined);
+                        }
+                        self.mk_pow(lv, rv, rhs_sbv, signed)
+                    }
+                };
+                self.mk_unknown_if(v, any_u)
+            }
+
+            mir::RvalueKind::IntComp {
+                op, lhs, rhs, sign, ..
+            } if four_state || self.is_four_state(lhs.ty) || self.is_four_state(rhs.ty) => {
+                let (lv, lu) = self.emit_four_state_planes(lhs)?;
+                let (rv, ru) = self.emit_four_state_planes(rhs)?;
+                let signed = sign.is_signed();
+                let any_u = self.mk_or(lu, ru);
+                let any_u = self.mk_any(any_u);
+                match op {
+                    // Case equality compares X and Z bits exactly, and always
+                    // produces a known result.
+                    mir::IntCompOp::CaseEq | mir::IntCompOp::CaseNeq => {
+                        let pred = match op {
+                            mir::IntCompOp::CaseEq => CmpPred::Eq,
+                            _ => CmpPred::Neq,
+                        };
+                        let value = self.mk_cmp(pred, lv, rv);
+                        let unknown = self.mk_cmp(pred, lu, ru);
+                        let v = match op {
+                            mir::IntCompOp::CaseEq => self.mk_and(value, unknown),
+                            _ => self.mk_or(value, unknown),
+                        };
+                        let u = self.mk_const_int(1, &BigInt::zero());
+                        (v, u)
+                    }
+                    // Wildcard equality ignores the bit positions where the
+                    // right operand is X or Z, and is X if any of the other
+                    // bits of the left operand is X or Z.
+                    mir::IntCompOp::WildcardEq | mir::IntCompOp::WildcardNeq => {
+                        let care = self.mk_not(ru);
+                        let diff = self.mk_xor(lv, rv);
+                        let known = self.mk_not(lu);
+                        let diff = self.mk_and(diff, known);
+                        let diff = self.mk_and(diff, care);
+                        let differs = self.mk_any(diff);
+                        let same = self.mk_not(differs);
+                        let cared_u = self.mk_and(lu, care);
+                        let any_u = self.mk_any(cared_u);
+                        let u = self.mk_and(same, any_u);
+                        let v = match op {
+                            mir::IntCompOp::WildcardEq => {
+                                let not_u = self.mk_not(any_u);
+                                self.mk_and(same, not_u)
+                            }
+                            _ => differs,
+                        };
+                        (v, u)
+                    }
+                    // Logical equality is known to be false if any pair of
+                    // known bits differs, and X otherwise if any bit is X or Z.
+                    mir::IntCompOp::Eq | mir::IntCompOp::Neq => {
+                        let diff = self.mk_xor(lv, rv);
+                        let known = self.mk_or(lu, ru);
+                        let known = self.mk_not(known);
+                        let diff = self.mk_and(diff, known);
+                        let differs = self.mk_any(diff);
+                        let same = self.mk_not(differs);
+                        let u = self.mk_and(same, any_u);
+                        let v = match op {
+                            mir::IntCompOp::Eq => {
+                                let not_u = self.mk_not(any_u);
+                                self.mk_and(same, not_u)
+                            }
+                            _ => differs,
+                        };
+                        (v, u)
+                    }
+                    _ => {
+                        let pred = match op {
+                            mir::IntCompOp::Lt if signed => CmpPred::Slt,
+                            mir::IntCompOp::Leq if signed => CmpPred::Sle,
+                            mir::IntCompOp::Gt if signed => CmpPred::Sgt,
+                            mir::IntCompOp::Geq if signed => CmpPred::Sge,
+                            mir::IntCompOp::Lt => CmpPred::Ult,
+                            mir::IntCompOp::Leq => CmpPred::Ule,
+                            mir::IntCompOp::Gt => CmpPred::Ugt,
+                            _ => CmpPred::Uge,
+                        };
+                        let v = self.mk_cmp(pred, lv, rv);
+                        self.mk_unknown_if(v, any_u)
+                    }
+                }
+            }
+
+            mir::RvalueKind::Concat(ref values)
+                if four_state || values.iter().any(|v| self.is_four_state(v.ty)) =>
+            {
+                let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+                let mut v = self.mk_const_int(width, &BigInt::zero());
+                let mut u = v;
+                let mut offset = 0;
+                for value in values.iter().rev() {
+                    let length = value.ty.simple_bit_vector(self.cx, value.span).size;
+                    let (value_v, value_u) = self.emit_four_state_planes(value)?;
+                    v = self.mk_ins_slice(v, value_v, offset, length);
+                    u = self.mk_ins_slice(u, value_u, offset, length);
+                    offset += length;
+                }
+                (v, u)
+            }
+
+            mir::RvalueKind::Repeat(times, value) if four_state || self.is_four_state(value.ty) => {
+                let width = mir.ty.simple_bit_vector(self.cx, mir.span).size;
+                let length = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let (value_v, value_u) = self.emit_four_state_planes(value)?;
+                let mut v = self.mk_const_int(width, &BigInt::zero());
+                let mut u = v;
+                for i in 0..times {
+                    v = self.mk_ins_slice(v, value_v, i * length, length);
+                    u = self.mk_ins_slice(u, value_u, i * length, length);
+                }
+                (v, u)
+            }
+
+            // Selecting at an X or Z index produces an all-X result.
+            mir::RvalueKind::Index {
+                value,
+                base,
+                length,
+            } if self.is_four_state(value.ty) => {
+                let length = std::cmp::max(1, length);
+                let (v, u) = self.emit_four_state_planes(value)?;
+                let (base, base_u) = self.emit_four_state_planes(base)?;
+                let v = self.mk_ext_slice(v, base, length);
+                let u = self.mk_ext_slice(u, base, length);
+                let base_u = self.mk_any(base_u);
+                let all_u = self.mk_replicate(base_u, length);
+                let v_known = self.mk_not(all_u);
+                (self.mk_and(v, v_known), self.mk_or(u, all_u))
+            }
+
+            // Shifts move the X and Z bits along with the value, and produce
+            // an all-X result if the shift amount is X or Z.
+            mir::RvalueKind::Shift {
+                op,
+                arith,
+                value,
+                amount,
+            } if four_state || self.is_four_state(amount.ty) => {
+                let width = value.ty.simple_bit_vector(self.cx, value.span).size;
+                let (v, u) = self.emit_four_state_planes(value)?;
+                let (amount, amount_u) = self.emit_four_state_planes(amount)?;
+                let mut planes = [v, u];
+                for plane in &mut planes {
+                    let zero = self.mk_const_int(width, &BigInt::zero());
+                    *plane = match op {
+                        mir::ShiftOp::Left => self.mk_moore_shl(*plane, zero, amount, arith),
+                        mir::ShiftOp::Right if arith => {
+                            let sign = self.mk_ext_slice_const_offset(*plane, width - 1, 1);
+                            let hidden = self.mk_replicate(sign, width);
+                            self.mk_moore_shr(*plane, hidden, amount, arith)
+                        }
+                        mir::ShiftOp::Right => self.mk_moore_shr(*plane, zero, amount, arith),
+                    };
+                }
+                let [v, u] = planes;
+                let amount_u = self.mk_any(amount_u);
+                let all_u = self.mk_replicate(amount_u, width);
+                let v_known = self.mk_not(all_u);
+                (self.mk_and(v, v_known), self.mk_or(u, all_u))
+            }
+
+            // An X or Z condition selects the bits on which both values agree,
+            // and X for all others.
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } if four_state => {
+                let (cv, cu) = self.emit_four_state_planes(cond)?;
+                let (tv, tu) = self.emit_four_state_planes(true_value)?;
+                let (fv, fu) = self.emit_four_state_planes(false_value)?;
+                let differ = self.mk_xor(tv, fv);
+                let merged_u = self.mk_or(tu, fu);
+                let merged_u = self.mk_or(merged_u, differ);
+                let merged_known = self.mk_not(merged_u);
+                let merged_v = self.mk_and(tv, merged_known);
+                let v = self.mk_mux(cv, tv, fv);
+                let u = self.mk_mux(cv, tu, fu);
+                (self.mk_mux(cu, merged_v, v), self.mk_mux(cu, merged_u, u))
+            }
+            mir::RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            } if self.is_four_state(cond.ty) => {
+                let cond = self.emit_mir_rvalue_known(cond)?;
+                let true_value = self.emit_mir_rvalue(true_value)?;
+                let false_value = self.emit_mir_rvalue(false_value)?;
+                return Ok(Some(self.mk_mux(cond, true_value, false_value)));
+            }
+
+            mir::RvalueKind::Reduction { op, arg } if self.is_four_state(arg.ty) => {
+                let (v, u) = self.emit_four_state_planes(arg)?;
+                let any_u = self.mk_any(u);
+                match op {
+                    // The result is 0 if any bit is 0, and 1 if any bit is 1,
+                    // respectively. Otherwise any X or Z bit produces X.
+                    mir::BinaryBitwiseOp::And | mir::BinaryBitwiseOp::Or => {
+                        let dominant = op == mir::BinaryBitwiseOp::Or;
+                        let bits = self.mk_known_bits(v, u, dominant);
+                        let any_dominant = self.mk_any(bits);
+                        let no_dominant = self.mk_not(any_dominant);
+                        let u = self.mk_and(no_dominant, any_u);
+                        let v = match op {
+                            mir::BinaryBitwiseOp::Or => any_dominant,
+                            _ => {
+                                let not_u = self.mk_not(any_u);
+                                self.mk_and(no_dominant, not_u)
                             }
+                        };
+                        (v, u)
+                    }
+                    mir::BinaryBitwiseOp::Xor => {
+                        let width = arg.ty.simple_bit_vector(self.cx, arg.span).size;
+                        let mut parity = self.mk_ext_slice_const_offset(v, 0, 1);
+                        for i in 1..width {
+                            let bit = self.mk_ext_slice_const_offset(v, i, 1);
+                            parity = self.mk_xor(parity, bit);
                         }
-                        mir::CallArg::Output(_, None)
-                        | mir::CallArg::Inout(_, None)
-                        | mir::CallArg::Input(..)
-                        | mir::CallArg::Ref(..) => (),
+                        self.mk_unknown_if(parity, any_u)
+                    }
+                }
+            }
+
+            _ => return Ok(None),
+        };
+        if four_state {
+            Ok(Some(self.mk_four_state(mir.ty, value, unknown)?))
+        } else {
+            let known = self.mk_not(unknown);
+            Ok(Some(self.mk_and(value, known)))
+        }
+    }
+
+    /// Convert an argument of a DPI import to its C representation. See
+    /// `emit_dpi_import`.
+    fn emit_dpi_arg(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<HybridValue> {
+        let repr = self.dpi_repr(mir.ty, mir.span)?;
+        Ok(match repr {
+            DpiRepr::Int(_) => self.emit_mir_rvalue_known(mir)?,
+            DpiRepr::String => {
+                let value = self.emit_mir_rvalue(mir)?;
+                self.emit_runtime_value(RuntimeFunc::StringToC, vec![value])
+            }
+            DpiRepr::Bit => {
+                let value = self.emit_mir_rvalue(mir)?;
+                self.mk_zero_extend(value, 1, 8)
+            }
+            DpiRepr::Logic => {
+                let (aval, bval) = self.emit_dpi_logic_planes(mir)?;
+                let value = self.mk_zero_extend(aval, 1, 8);
+                self.mk_ins_slice(value, bval, 1, 1)
+            }
+            DpiRepr::BitVec(size) => {
+                let value = self.emit_mir_rvalue(mir)?;
+                let value = self.mk_zero_extend(value, size, dpi_chunks(size) * 32);
+                self.mk_var(value)
+            }
+            DpiRepr::LogicVec(size) => {
+                let (aval, bval) = self.emit_dpi_logic_planes(mir)?;
+                let chunks = dpi_chunks(size);
+                let mut value = self.mk_const_int(chunks * 64, &BigInt::zero());
+                for chunk in 0..chunks {
+                    let length = std::cmp::min(32, size - chunk * 32);
+                    for (i, &plane) in [aval, bval].iter().enumerate() {
+                        let bits = self.mk_ext_slice_const_offset(plane, chunk * 32, length);
+                        value = self.mk_ins_slice(value, bits, chunk * 64 + i * 32, length);
                     }
                 }
+                self.mk_var(value)
+            }
+        })
+    }
 
-                // Unpack the results.
-                let result = if !return_ty.is_void() {
-                    call_op.result(0)
+    /// Emit a value as its `aval` and `bval` planes, as passed to a DPI import
+    /// through an `svLogic` or `svLogicVecVal`.
+    ///
+    /// X bits have both planes set, and Z bits only `bval`.
+    fn emit_dpi_logic_planes(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<(HybridValue, HybridValue)> {
+        let (value, unknown) = self.emit_four_state_planes(mir)?;
+        Ok((self.mk_xor(value, unknown), unknown))
+    }
+
+    /// Convert the result of a DPI import from its C representation. See
+    /// `emit_dpi_import`.
+    fn emit_dpi_result(
+        &mut self,
+        result: HybridValue,
+        ty: &'gcx UnpackedType<'gcx>,
+        span: Span,
+    ) -> Result<HybridValue> {
+        Ok(match self.dpi_result_repr(ty, span)? {
+            DpiRepr::String => self.emit_runtime_value(RuntimeFunc::StringFromC, vec![result]),
+            DpiRepr::Bit => self.mk_ext_slice_const_offset(result, 0, 1),
+            DpiRepr::Logic => {
+                let aval = self.mk_ext_slice_const_offset(result, 0, 1);
+                let bval = self.mk_ext_slice_const_offset(result, 1, 1);
+                if self.is_four_state(ty) {
+                    let value = self.mk_xor(aval, bval);
+                    self.mk_four_state(ty, value, bval)?
                 } else {
-                    mlir::Value::from_raw(circt::sys::MlirValue {
-                        ptr: std::ptr::null_mut(),
-                    })
-                };
-                (self.builder.ins().call(ext_unit, vec![]), result)
+                    let known = self.mk_not(bval);
+                    self.mk_and(aval, known)
+                }
             }
+            _ => result,
+        })
+    }
 
-            // Propagate tombstones.
-            mir::RvalueKind::Error => return Err(()),
-        };
+    /// Emit the code for an MIR rvalue as separate value and unknown planes.
+    ///
+    /// Values of two-valued types have an unknown plane of all zeros.
+    fn emit_four_state_planes(
+        &mut self,
+        mir: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<(HybridValue, HybridValue)> {
+        if !self.is_four_state(mir.ty) && !mir.ty.coalesces_to_llhd_scalar() {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "unsupported: value of type `{}` used as a four-state value",
+                    mir.ty
+                ))
+                .span(mir.span),
+            );
+            return Err(());
+        }
+        let value = self.emit_mir_rvalue(mir)?;
+        if self.is_four_state(mir.ty) {
+            Ok((
+                self.mk_ext_field_const_offset(value, 0),
+                self.mk_ext_field_const_offset(value, 1),
+            ))
+        } else {
+            let unknown = self.emit_zero_for_type_both(self.value_type(value));
+            Ok((value, unknown))
+        }
+    }
 
-        Ok((value, Mode::Value))
+    /// Emit the code for an MIR rvalue, with its X and Z bits mapped to 0.
+    ///
+    /// Use this where a plain integer is required, for example for conditions
+    /// and indices.
+    fn emit_mir_rvalue_known(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<HybridValue> {
+        if !self.is_four_state(mir.ty) {
+            return self.emit_mir_rvalue(mir);
+        }
+        let (value, unknown) = self.emit_four_state_planes(mir)?;
+        let known = self.mk_not(unknown);
+        Ok(self.mk_and(value, known))
     }
 
     fn emit_prb_or_var(&mut self, sig: HybridValue) -> HybridValue {
@@ -2146,7 +5378,7 @@ where
             "value of type `{}` should be a bool",
             mir.ty
         );
-        self.emit_mir_rvalue(mir)
+        self.emit_mir_rvalue_known(mir)
     }
 
     /// Emit the code for an MIR rvalue interface.
@@ -2209,7 +5441,7 @@ where
         base: &'gcx mir::Rvalue<'gcx>,
         length: usize,
     ) -> Result<HybridValue> {
-        let base = self.emit_mir_rvalue(base)?;
+        let base = self.emit_mir_rvalue_known(base)?;
         if ty.coalesces_to_llhd_scalar() {
             let length = std::cmp::max(1, length);
             Ok(self.mk_ext_slice(value, base, length))
@@ -2401,7 +5633,7 @@ where
         length: usize,
     ) -> Result<(HybridValue, Option<HybridValue>)> {
         let (target_real, target_shadow) = value;
-        let base = self.emit_mir_rvalue(base)?;
+        let base = self.emit_mir_rvalue_known(base)?;
         if ty.coalesces_to_llhd_scalar() {
             let length = std::cmp::max(1, length);
             Ok((
@@ -2442,6 +5674,95 @@ where
         }
     }
 
+    /// Emit the check whether a case expression matches one of the labels of
+    /// a case item.
+    ///
+    /// `expr_planes` carries the value and unknown planes of the expression in
+    /// four-state mode.
+    fn emit_case_check(
+        &mut self,
+        expr: HybridValue,
+        expr_planes: Option<(HybridValue, HybridValue)>,
+        way_expr: NodeId,
+        kind: ast::CaseKind,
+        env: ParamEnv,
+    ) -> Result<HybridValue> {
+        // Determine the constant value of the label.
+        let way_const = self.constant_value_of(way_expr, env);
+        let (way_bits, special_bits, x_bits) = match &way_const.kind {
+            ValueKind::Int(v, s, x) => (v, s, x),
+            _ => panic!("case constant evaluates to non-integer"),
+        };
+
+        // In four-state mode, the X and Z bits of both the
+        // expression and the label are considered.
+        if let Some((expr_value, expr_unknown)) = expr_planes {
+            let way_width = self.llhd_type(expr_value.0).unwrap_int();
+            let mask = (BigInt::one() << way_width) - 1;
+            let unknown = value::bits_to_int(special_bits) & &mask;
+            let z = &unknown ^ (&unknown & value::bits_to_int(x_bits));
+            let way_value = self.mk_const_int(way_width, &((way_bits | z) & mask));
+            let way_unknown = self.mk_const_int(way_width, &unknown);
+
+            // Generate the mask of bits to ignore based on the
+            // case kind.
+            let ignore = match kind {
+                ast::CaseKind::Normal => None,
+                ast::CaseKind::DontCareZ => {
+                    let expr_z = self.mk_and(expr_value, expr_unknown);
+                    let way_z = self.mk_and(way_value, way_unknown);
+                    Some(self.mk_or(expr_z, way_z))
+                }
+                ast::CaseKind::DontCareXZ => Some(self.mk_or(expr_unknown, way_unknown)),
+            };
+
+            // Compare both planes through the mask.
+            let mut planes = [expr_value, expr_unknown, way_value, way_unknown];
+            if let Some(ignore) = ignore {
+                let mask = self.mk_not(ignore);
+                for plane in &mut planes {
+                    *plane = self.mk_and(*plane, mask);
+                }
+            }
+            let value_eq = self.mk_cmp(CmpPred::Eq, planes[0], planes[2]);
+            let unknown_eq = self.mk_cmp(CmpPred::Eq, planes[1], planes[3]);
+            Ok(self.mk_and(value_eq, unknown_eq))
+        } else {
+            let way_expr = self.emit_const(way_const, env, self.span(way_expr))?;
+            let way_width = self.llhd_type(way_expr.0).unwrap_int();
+
+            // Generate the comparison mask based on the case
+            // kind.
+            let mask = match kind {
+                ast::CaseKind::Normal => None,
+                ast::CaseKind::DontCareZ => {
+                    let mut mask = special_bits.clone();
+                    mask.difference(x_bits);
+                    mask.negate();
+                    Some(mask)
+                }
+                ast::CaseKind::DontCareXZ => {
+                    let mut mask = special_bits.clone();
+                    mask.negate();
+                    Some(mask)
+                }
+            };
+            let mask = mask.map(|bits| {
+                let mask = value::bits_to_int(&bits);
+                self.mk_const_int(way_width, &mask)
+            });
+
+            // Filter the comparison values through the mask.
+            let (lhs, rhs) = match mask {
+                Some(mask) => (self.mk_and(expr, mask), self.mk_and(way_expr, mask)),
+                None => (expr, way_expr),
+            };
+
+            // Perform the comparison.
+            Ok(self.mk_cmp(CmpPred::Eq, lhs, rhs))
+        }
+    }
+
     /// Emit the code for a statement, given its HIR.
     fn emit_stmt_regular(&mut self, stmt_id: NodeId, hir: &hir::Stmt, env: ParamEnv) -> Result<()> {
         debug!("Emit stmt `{}`", {
@@ -2513,6 +5834,7 @@ where
             } => {
                 let resume_blk = self.mk_block(None);
                 let duration = self.emit_rvalue(expr_id, env)?;
+                self.emit_deferred_actions(env)?;
                 self.builder
                     .ins()
                     .wait_time(resume_blk.0, duration.0, vec![]);
@@ -2551,6 +5873,7 @@ where
                         trigger_on.push(self.emitted_value(id).clone());
                     }
                 }
+                self.emit_deferred_actions(env)?;
                 self.mk_wait(check_blk, trigger_on, None);
                 self.append_to(check_blk);
                 self.flush_mir(); // ensure we don't reuse earlier expr probe
@@ -2566,7 +5889,9 @@ where
                         event
                     );
                     let now_value = self.emit_rvalue(event.expr, env)?;
-                    let mut trigger = self.emit_event_trigger(event.edge, init_value, now_value)?;
+                    let ty = self.mir_rvalue(event.expr, env).ty;
+                    let mut trigger =
+                        self.emit_event_trigger(event.edge, ty, init_value, now_value)?;
                     for &iff in &event.iff {
                         let iff_value = self.emit_rvalue_bool(iff, env)?;
                         trigger = self.mk_and(trigger, iff_value);
@@ -2606,6 +5931,7 @@ where
                     .iter()
                     .map(|&id| self.emitted_value(id).clone())
                     .collect();
+                self.emit_deferred_actions(env)?;
                 self.mk_wait(trigger_blk, trigger_on, None);
                 self.append_to(trigger_blk);
                 self.flush_mir(); // ensure we don't reuse earlier expr probe
@@ -2614,8 +5940,31 @@ where
                 // Emit the actual statement.
                 self.emit_stmt(stmt, env)?;
             }
+            hir::StmtKind::Expr(expr_id)
+                if self.tables.mem_init_calls.contains(&expr_id.env(env)) =>
+            {
+                // Already applied to the memory declaration.
+            }
             hir::StmtKind::Expr(expr_id) => {
-                self.emit_rvalue(expr_id, env)?;
+                let mir = self.mir_rvalue(expr_id, env);
+                match mir.kind {
+                    mir::RvalueKind::Call { target, ref args }
+                        if target.prototype.kind == ast::SubroutineKind::Task
+                            && !is_dpi_import(target) =>
+                    {
+                        self.emit_task_call(mir, target, args)?;
+                    }
+                    mir::RvalueKind::SysTask {
+                        task,
+                        diag,
+                        ref msg,
+                    } => {
+                        self.emit_sys_task(mir.span, task, diag, msg)?;
+                    }
+                    _ => {
+                        self.emit_mir_rvalue(mir)?;
+                    }
+                }
             }
             hir::StmtKind::If {
                 cond,
@@ -2659,133 +6008,946 @@ where
                 default,
                 kind,
             } => {
+                let expr_ty = self.mir_rvalue(expr, env).ty;
                 let expr = self.emit_rvalue(expr, env)?;
+                let expr_planes = match self.is_four_state(expr_ty) {
+                    true => Some((
+                        self.mk_ext_field_const_offset(expr, 0),
+                        self.mk_ext_field_const_offset(expr, 1),
+                    )),
+                    false => None,
+                };
                 let final_blk = self.mk_block(Some("case_exit"));
                 for &(ref way_exprs, stmt) in ways {
                     let mut last_check = None;
                     for &way_expr in way_exprs {
-                        // Determine the constant value of the label.
-                        let way_const = self.constant_value_of(way_expr, env);
-                        let (_, special_bits, x_bits) = match &way_const.kind {
-                            ValueKind::Int(v, s, x) => (v, s, x),
-                            _ => panic!("case constant evaluates to non-integer"),
-                        };
-                        let way_expr = self.emit_const(way_const, env, self.span(way_expr))?;
-                        let way_width = self.llhd_type(way_expr.0).unwrap_int();
-
-                        // Generate the comparison mask based on the case kind.
-                        let mask = match kind {
-                            ast::CaseKind::Normal => None,
-                            ast::CaseKind::DontCareZ => {
-                                let mut mask = special_bits.clone();
-                                mask.difference(x_bits);
-                                mask.negate();
-                                Some(mask)
-                            }
-                            ast::CaseKind::DontCareXZ => {
-                                let mut mask = special_bits.clone();
-                                mask.negate();
-                                Some(mask)
-                            }
-                        };
-                        let mask = mask.map(|bits| {
-                            let mut mask = BigInt::zero();
-                            for b in &bits {
-                                mask <<= 1;
-                                if b {
-                                    mask |= BigInt::one();
-                                }
-                            }
-                            self.mk_const_int(way_width, &mask)
-                        });
-
-                        // Filter the comparison values through the mask.
-                        let (lhs, rhs) = match mask {
-                            Some(mask) => (self.mk_and(expr, mask), self.mk_and(way_expr, mask)),
-                            None => (expr, way_expr),
-                        };
+                        let check = self.emit_case_check(expr, expr_planes, way_expr, kind, env)?;
 
-                        // Perform the comparison and branch.
-                        let check = self.mk_cmp(CmpPred::Eq, lhs, rhs);
+                        // Combine the checks for all labels of this item.
                         last_check = Some(match last_check {
                             Some(last_check) => self.mk_or(last_check, check),
                             None => check,
                         });
                     }
-                    if let Some(last_check) = last_check {
-                        let taken_blk = self.mk_block(Some("case_body"));
-                        let untaken_blk = self.mk_block(None);
-                        self.mk_cond_br(last_check, taken_blk, untaken_blk);
-                        self.append_to(taken_blk);
-                        self.emit_stmt(stmt, env)?;
-                        self.mk_br(final_blk);
-                        self.append_to(untaken_blk);
+                    if let Some(last_check) = last_check {
+                        let taken_blk = self.mk_block(Some("case_body"));
+                        let untaken_blk = self.mk_block(None);
+                        self.mk_cond_br(last_check, taken_blk, untaken_blk);
+                        self.append_to(taken_blk);
+                        self.emit_stmt(stmt, env)?;
+                        self.mk_br(final_blk);
+                        self.append_to(untaken_blk);
+                    }
+                }
+                if let Some(default) = default {
+                    self.emit_stmt(default, env)?;
+                }
+                self.mk_br(final_blk);
+                self.append_to(final_blk);
+            }
+
+            hir::StmtKind::PatternCase {
+                expr,
+                ref ways,
+                default,
+            } => {
+                self.emit_rvalue(expr, env)?;
+                let final_blk = self.mk_block(Some("case_exit"));
+                for &(pattern, stmt) in ways {
+                    let check =
+                        mir::lower::rvalue::lower_pattern_match(self.cx, expr, pattern, env)?;
+                    let check = self.emit_mir_rvalue_known(check)?;
+                    let taken_blk = self.mk_block(Some("case_body"));
+                    let untaken_blk = self.mk_block(None);
+                    self.mk_cond_br(check, taken_blk, untaken_blk);
+                    self.append_to(taken_blk);
+                    self.emit_stmt(stmt, env)?;
+                    self.mk_br(final_blk);
+                    self.append_to(untaken_blk);
+                }
+                if let Some(default) = default {
+                    self.emit_stmt(default, env)?;
+                }
+                self.mk_br(final_blk);
+                self.append_to(final_blk);
+            }
+
+            hir::StmtKind::Assert {
+                kind,
+                deferred: Some(mode),
+                cond,
+                pass_stmt,
+                fail_stmt,
+            } => {
+                // The runtime holds on to the result of a deferred assertion
+                // until it matures, such that a later evaluation in the same
+                // time step supersedes it. The action blocks run at the next
+                // point where the unit suspends or returns.
+                let cond_value = self.emit_rvalue_bool(cond, env)?;
+                self.emit_assert_deferred(hir, kind, mode, cond, cond_value, fail_stmt.is_some());
+                if pass_stmt.is_some() || fail_stmt.is_some() {
+                    let action = match self.deferred_actions.iter().find(|a| a.stmt == stmt_id) {
+                        Some(&action) => action,
+                        None => {
+                            self.emit(
+                                DiagBuilder2::error(
+                                    "unsupported: action block of deferred assertion in a task",
+                                )
+                                .span(hir.span),
+                            );
+                            return Err(());
+                        }
+                    };
+                    let one = self.mk_const_int(1, &BigInt::one());
+                    self.mk_st(action.passed, cond_value);
+                    self.mk_st(action.pending, one);
+                }
+            }
+
+            hir::StmtKind::Assert {
+                kind,
+                cond,
+                pass_stmt,
+                fail_stmt,
+                ..
+            } => {
+                let pass_blk = self.mk_block(Some("assert_pass"));
+                let fail_blk = self.mk_block(Some("assert_fail"));
+                let cond_value = self.emit_rvalue_bool(cond, env)?;
+                self.mk_cond_br(cond_value, pass_blk, fail_blk);
+                let final_blk = self.mk_block(Some("assert_exit"));
+                self.append_to(pass_blk);
+                if let Some(pass_stmt) = pass_stmt {
+                    self.emit_stmt(pass_stmt, env)?;
+                }
+                self.mk_br(final_blk);
+                self.append_to(fail_blk);
+                match fail_stmt {
+                    Some(fail_stmt) => self.emit_stmt(fail_stmt, env)?,
+                    None if kind != hir::AssertKind::Cover => {
+                        self.emit_assert_failed(hir, kind, cond)
+                    }
+                    None => (),
+                }
+                self.mk_br(final_blk);
+                self.append_to(final_blk);
+            }
+
+            hir::StmtKind::Trigger {
+                target,
+                nonblocking,
+            } => {
+                let ty = self.type_of(target, env)?;
+                if !ty.is_event() {
+                    let span = self.span(target);
+                    self.emit(
+                        DiagBuilder2::error(format!("`{}` is not an event", span.extract()))
+                            .span(span)
+                            .add_note(format!("`{}` has type `{}`", span.extract(), ty)),
+                    );
+                    return Err(());
+                }
+                let lvalue = self.mir_lvalue(target, env);
+                let lvalue = self.emit_mir_lvalue(lvalue)?;
+                let value = self.emit_rvalue(target, env)?;
+                let value = self.mk_event_trigger(value);
+                if nonblocking {
+                    let delay = self.mk_const_time(&num::zero(), 1, 0);
+                    self.mk_drv(lvalue.0, value, delay);
+                } else {
+                    self.emit_blocking_assign_llhd(lvalue, value)?;
+                }
+            }
+
+            hir::StmtKind::Ast(ast) => {
+                self.emit_stmt_ast(ast, env)?;
+            }
+
+            _ => {
+                error!("{:#?}", hir);
+                return self.unimp_msg("code generation for", hir);
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit the code for a statement for which no HIR node exists.
+    fn emit_stmt_ast(&mut self, stmt: &ast::Stmt, env: ParamEnv) -> Result<()> {
+        match &stmt.kind {
+            ast::ReturnStmt(None) => match self.inlined_tasks.last() {
+                Some(&(_, exit_blk)) => self.mk_br(exit_blk),
+                None => {
+                    self.emit_deferred_actions(env)?;
+                    self.mk_ret(None);
+                }
+            },
+            ast::ReturnStmt(Some(expr)) => {
+                let expr = self.emit_rvalue(expr.id(), env)?;
+                self.emit_deferred_actions(env)?;
+                self.mk_ret(Some(expr));
+            }
+            ast::BreakStmt => match self.break_stack.last() {
+                Some(&block) => {
+                    self.mk_br(block);
+                }
+                None => {
+                    self.emit(
+                        DiagBuilder2::error("break statement outside of loop")
+                            .span(stmt.human_span()),
+                    );
+                    return Err(());
+                }
+            },
+            ast::ContinueStmt => match self.continue_stack.last() {
+                Some(&block) => {
+                    self.mk_br(block);
+                }
+                None => {
+                    self.emit(
+                        DiagBuilder2::error("continue statement outside of loop")
+                            .span(stmt.human_span()),
+                    );
+                    return Err(());
+                }
+            },
+            _ => {
+                error!("{:#?}", stmt);
+                bug_span!(
+                    stmt.span(),
+                    self.cx,
+                    "code generation for {} not implemented",
+                    stmt.format_indefinite()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit the default fail action of an assertion.
+    fn emit_assert_failed(&mut self, hir: &hir::Stmt, kind: hir::AssertKind, cond: NodeId) {
+        let kind = match kind {
+            hir::AssertKind::Assert => 0,
+            hir::AssertKind::Assume => 1,
+            hir::AssertKind::Cover => unreachable!("cover statements cannot fail"),
+        };
+        let msg = match hir.label {
+            Some(label) => label.value.to_string(),
+            None => self.span(cond).extract(),
+        };
+        let loc = hir.span.begin();
+        let msg = self.intern_runtime_string(&msg);
+        let file = self.intern_runtime_string(&loc.source.get_path());
+        let args = vec![
+            self.mk_const_int(32, &kind.into()),
+            self.mk_const_int(32, &msg.into()),
+            self.mk_const_int(32, &file.into()),
+            self.mk_const_int(32, &loc.human_line().into()),
+            self.mk_const_int(32, &loc.human_column().into()),
+        ];
+        self.emit_runtime_call(RuntimeFunc::AssertFailed, args);
+    }
+
+    /// Emit the evaluation of a deferred assertion.
+    fn emit_assert_deferred(
+        &mut self,
+        hir: &hir::Stmt,
+        kind: hir::AssertKind,
+        mode: hir::DeferredMode,
+        cond: NodeId,
+        passed: HybridValue,
+        has_fail: bool,
+    ) {
+        let mode = match mode {
+            hir::DeferredMode::Observed => 0,
+            hir::DeferredMode::Final => 1,
+        };
+        let kind = match kind {
+            hir::AssertKind::Assert => 0,
+            hir::AssertKind::Assume => 1,
+            hir::AssertKind::Cover => 2,
+        };
+        let msg = match hir.label {
+            Some(label) => label.value.to_string(),
+            None => self.span(cond).extract(),
+        };
+        let loc = hir.span.begin();
+        let msg = self.intern_runtime_string(&msg);
+        let file = self.intern_runtime_string(&loc.source.get_path());
+        let args = vec![
+            self.mk_const_int(32, &mode.into()),
+            self.mk_const_int(32, &kind.into()),
+            passed,
+            self.mk_const_int(1, &(has_fail as usize).into()),
+            self.mk_const_int(32, &msg.into()),
+            self.mk_const_int(32, &file.into()),
+            self.mk_const_int(32, &loc.human_line().into()),
+            self.mk_const_int(32, &loc.human_column().into()),
+        ];
+        self.emit_runtime_call(RuntimeFunc::AssertDeferred, args);
+    }
+
+    /// Allocate the state of the deferred assertions with action blocks in a
+    /// procedure or function body.
+    ///
+    /// Must be called in the entry block, such that the state is available at
+    /// every point where the unit suspends.
+    fn alloc_deferred_actions(&mut self, stmts: &[NodeId]) {
+        let mut finder = DeferredActionFinder {
+            cx: self.cx,
+            found: vec![],
+        };
+        for &stmt in stmts {
+            finder.visit_node_with_id(stmt, false);
+        }
+        for stmt in finder.found {
+            let zero = self.mk_const_int(1, &BigInt::zero());
+            let pending = self.mk_var(zero);
+            let passed = self.mk_var(zero);
+            self.deferred_actions.push(DeferredAction {
+                stmt,
+                pending,
+                passed,
+            });
+        }
+    }
+
+    /// Run the action blocks of the deferred assertions whose report matures.
+    ///
+    /// The report of a deferred assertion matures once the unit that
+    /// evaluated it suspends, halts, or returns. Only the latest evaluation
+    /// since the last maturity is acted upon; earlier ones are flushed.
+    fn emit_deferred_actions(&mut self, env: ParamEnv) -> Result<()> {
+        // Take the actions out of the unit, such that a suspension point in
+        // an action block does not run the actions again.
+        let actions = std::mem::take(&mut self.deferred_actions);
+        let result = actions
+            .iter()
+            .try_for_each(|&action| self.emit_deferred_action(action, env));
+        self.deferred_actions = actions;
+        result
+    }
+
+    /// Run the action block of a deferred assertion if its report is pending.
+    fn emit_deferred_action(&mut self, action: DeferredAction, env: ParamEnv) -> Result<()> {
+        let (pass_stmt, fail_stmt) = match self.hir_of(action.stmt)? {
+            HirNode::Stmt(hir::Stmt {
+                kind:
+                    hir::StmtKind::Assert {
+                        pass_stmt,
+                        fail_stmt,
+                        ..
+                    },
+                ..
+            }) => (*pass_stmt, *fail_stmt),
+            _ => unreachable!(),
+        };
+        let run_blk = self.mk_block(Some("assert_mature"));
+        let pass_blk = self.mk_block(Some("assert_pass"));
+        let fail_blk = self.mk_block(Some("assert_fail"));
+        let done_blk = self.mk_block(Some("assert_done"));
+        let pending = self.mk_ld(action.pending);
+        self.mk_cond_br(pending, run_blk, done_blk);
+        self.append_to(run_blk);
+        let zero = self.mk_const_int(1, &BigInt::zero());
+        self.mk_st(action.pending, zero);
+        let passed = self.mk_ld(action.passed);
+        self.mk_cond_br(passed, pass_blk, fail_blk);
+        for &(blk, stmt) in &[(pass_blk, pass_stmt), (fail_blk, fail_stmt)] {
+            self.append_to(blk);
+            if let Some(stmt) = stmt {
+                self.emit_stmt(stmt, env)?;
+            }
+            self.mk_br(done_blk);
+        }
+        self.append_to(done_blk);
+        Ok(())
+    }
+
+    /// Emit a call to a function of the runtime library.
+    ///
+    /// Returns the result of the call, or `None` if the function has no
+    /// result.
+    fn emit_runtime_call(
+        &mut self,
+        func: RuntimeFunc,
+        args: Vec<HybridValue>,
+    ) -> Option<HybridValue> {
+        self.declare_runtime_func(func);
+
+        // Declare the function in the LLHD unit.
+        let mut sig = llhd::ir::Signature::new();
+        for &width in func.arg_widths() {
+            sig.add_input(llhd::int_ty(width));
+        }
+        sig.set_return_type(match func.result_width() {
+            Some(width) => llhd::int_ty(width),
+            None => llhd::void_ty(),
+        });
+        let ext_unit = self
+            .builder
+            .add_extern(llhd::ir::UnitName::Global(func.name().to_string()), sig);
+
+        // Emit the call.
+        let (args_llhd, args_mlir): (Vec<_>, Vec<_>) = args.into_iter().unzip();
+        let result_ty = func
+            .result_width()
+            .map(|width| mlir::get_integer_type(self.mcx, width));
+        let value = self.builder.ins().call(ext_unit, args_llhd);
+        let call_op =
+            circt::func::CallOp::new(self.mlir_builder, func.name(), args_mlir, result_ty);
+        func.result_width().map(|_| (value, call_op.result(0)))
+    }
+
+    /// Emit a call to a function of the runtime library that has a result.
+    fn emit_runtime_value(&mut self, func: RuntimeFunc, args: Vec<HybridValue>) -> HybridValue {
+        match self.emit_runtime_call(func, args) {
+            Some(value) => value,
+            None => panic!("runtime function `{}` has no result", func.name()),
+        }
+    }
+
+    /// Emit the runtime calls for a display, simulation control, or severity
+    /// task.
+    ///
+    /// Returns the formatted message for `$sformatf`, and `None` for all other
+    /// tasks.
+    fn emit_sys_task(
+        &mut self,
+        span: Span,
+        task: hir::SysTask,
+        diag: usize,
+        msg: &[mir::FormatArg<'gcx>],
+    ) -> Result<Option<HybridValue>> {
+        // Evaluate all arguments before passing anything to the runtime. The
+        // runtime collects the pieces of a message in a single buffer, which
+        // an argument such as a nested `$sformatf` would otherwise clobber.
+        let mut values = vec![];
+        for arg in msg {
+            values.push(match *arg {
+                mir::FormatArg::Literal(..) => (None, None),
+                mir::FormatArg::Value(_, value) if value.ty.is_string() => {
+                    (Some(self.emit_mir_rvalue(value)?), None)
+                }
+                mir::FormatArg::Value(spec, value) if spec.kind.is_real() => {
+                    let ty = value.ty;
+                    let value = self.emit_mir_rvalue(value)?;
+                    (Some(self.mk_real_to_double(value, ty)), None)
+                }
+                // Four-state values keep their unknown plane, such that the
+                // runtime can print X and Z bits.
+                mir::FormatArg::Value(_, value) if self.is_four_state(value.ty) => {
+                    let (value, unknown) = self.emit_four_state_planes(value)?;
+                    (Some(value), Some(unknown))
+                }
+                mir::FormatArg::Value(_, value) => (Some(self.emit_mir_rvalue(value)?), None),
+            });
+        }
+
+        // Pass the message to the runtime piece by piece.
+        for (arg, (evaluated, unknown)) in msg.iter().zip(values) {
+            match *arg {
+                mir::FormatArg::Literal(ref text) => {
+                    let text = self.intern_runtime_string(text);
+                    let text = self.mk_const_int(32, &text.into());
+                    self.emit_runtime_call(RuntimeFunc::FormatLiteral, vec![text]);
+                }
+                mir::FormatArg::Value(spec, value) if value.ty.is_string() => {
+                    let value = evaluated.unwrap();
+                    let flags = (spec.left_align as usize) << 1;
+                    let width = match spec.width {
+                        Some(width) => BigInt::from(width),
+                        None => BigInt::from(u32::max_value()),
+                    };
+                    let args = vec![
+                        self.mk_const_int(32, &flags.into()),
+                        self.mk_const_int(32, &width),
+                        value,
+                    ];
+                    self.emit_runtime_call(RuntimeFunc::FormatString, args);
+                }
+                mir::FormatArg::Value(spec, _) if spec.kind.is_real() => {
+                    let value = evaluated.unwrap();
+                    let flags = (spec.left_align as usize) << 1;
+                    let width = match spec.width {
+                        Some(width) => BigInt::from(width),
+                        None => BigInt::from(u32::max_value()),
+                    };
+                    let precision = match spec.precision {
+                        Some(precision) => BigInt::from(precision),
+                        None => BigInt::from(u32::max_value()),
+                    };
+                    let args = vec![
+                        self.mk_const_int(32, &(spec.kind.letter() as u32).into()),
+                        self.mk_const_int(32, &flags.into()),
+                        self.mk_const_int(32, &width),
+                        self.mk_const_int(32, &precision),
+                        value,
+                    ];
+                    self.emit_runtime_call(RuntimeFunc::FormatReal, args);
+                }
+                mir::FormatArg::Value(spec, value) => {
+                    let sbv = value.ty.simple_bit_vector(self.cx, value.span);
+                    let signed = sbv.is_signed();
+                    let value = evaluated.unwrap();
+                    let num_words = (sbv.size + 63) / 64;
+
+                    // Push the unknown plane of four-state values, most
+                    // significant word first.
+                    if let Some(unknown) = unknown {
+                        for i in (0..num_words).rev() {
+                            let offset = i * 64;
+                            let len = std::cmp::min(64, sbv.size - offset);
+                            let word = self.mk_ext_slice_const_offset(unknown, offset, len);
+                            let word = self.mk_extend_to_i64(word, len, false);
+                            self.emit_runtime_call(RuntimeFunc::FormatUnknown, vec![word]);
+                        }
+                    }
+
+                    // Push the upper words of wide values, most significant
+                    // word first.
+                    for i in (1..num_words).rev() {
+                        let offset = i * 64;
+                        let len = std::cmp::min(64, sbv.size - offset);
+                        let word = self.mk_ext_slice_const_offset(value, offset, len);
+                        let word = self.mk_extend_to_i64(word, len, signed && i == num_words - 1);
+                        self.emit_runtime_call(RuntimeFunc::FormatWord, vec![word]);
                     }
+
+                    // Pass the least significant word along with the spec.
+                    let len = std::cmp::min(64, sbv.size);
+                    let word = self.mk_ext_slice_const_offset(value, 0, len);
+                    let word = self.mk_extend_to_i64(word, len, signed && num_words <= 1);
+                    let flags = (signed as usize) | ((spec.left_align as usize) << 1);
+                    let width = match spec.width {
+                        Some(width) => BigInt::from(width),
+                        None => BigInt::from(u32::max_value()),
+                    };
+                    let args = vec![
+                        self.mk_const_int(32, &(spec.kind.letter() as u32).into()),
+                        self.mk_const_int(32, &flags.into()),
+                        self.mk_const_int(32, &width),
+                        self.mk_const_int(32, &sbv.size.into()),
+                        word,
+                    ];
+                    self.emit_runtime_call(RuntimeFunc::FormatInt, args);
                 }
-                if let Some(default) = default {
-                    self.emit_stmt(default, env)?;
+            }
+        }
+
+        // Emit the message.
+        if task.is_print() {
+            let kind = match task {
+                hir::SysTask::Display => 0,
+                hir::SysTask::Write => 1,
+                hir::SysTask::Strobe => 2,
+                hir::SysTask::Info => 3,
+                hir::SysTask::Warning => 4,
+                hir::SysTask::Error => 5,
+                hir::SysTask::Fatal => 6,
+                hir::SysTask::Finish | hir::SysTask::Stop | hir::SysTask::SFormatF => {
+                    unreachable!()
                 }
-                self.mk_br(final_blk);
-                self.append_to(final_blk);
+            };
+            let loc = span.begin();
+            let file = self.intern_runtime_string(&loc.source.get_path());
+            let args = vec![
+                self.mk_const_int(32, &BigInt::from(kind)),
+                self.mk_const_int(32, &file.into()),
+                self.mk_const_int(32, &loc.human_line().into()),
+                self.mk_const_int(32, &loc.human_column().into()),
+            ];
+            self.emit_runtime_call(RuntimeFunc::Print, args);
+        }
+
+        // Terminate or suspend the simulation.
+        let control = match task {
+            hir::SysTask::Finish | hir::SysTask::Fatal => Some(RuntimeFunc::Finish),
+            hir::SysTask::Stop => Some(RuntimeFunc::Stop),
+            _ => None,
+        };
+        if let Some(func) = control {
+            let diag = self.mk_const_int(32, &diag.into());
+            self.emit_runtime_call(func, vec![diag]);
+        }
+
+        // Return the message as a string.
+        if task == hir::SysTask::SFormatF {
+            return Ok(Some(
+                self.emit_runtime_value(RuntimeFunc::StringFormat, vec![]),
+            ));
+        }
+        Ok(None)
+    }
+
+    /// Compute the new state of an event when it is triggered.
+    ///
+    /// Events are represented as an `i64`. Bit 0 toggles whenever the event is
+    /// triggered, such that processes waiting for the event observe a change.
+    /// The upper bits hold the simulation time of the last trigger plus one,
+    /// which allows `e.triggered` to check whether the event was triggered in
+    /// the current time step. An event that was never triggered is 0.
+    fn mk_event_trigger(&mut self, value: HybridValue) -> HybridValue {
+        let one = self.mk_const_int(64, &BigInt::one());
+        let toggle = self.mk_not(value);
+        let toggle = self.mk_and(toggle, one);
+        let stamp = self.mk_event_stamp();
+        self.mk_or(stamp, toggle)
+    }
+
+    /// Compute the upper bits of an event triggered in the current time step.
+    ///
+    /// The stamp is derived from the physical simulation time, which all delta
+    /// steps of a time step share. An event therefore remains `triggered` for
+    /// the rest of the time step, and is reset as soon as time advances,
+    /// without any process having to clear it.
+    fn mk_event_stamp(&mut self) -> HybridValue {
+        // LLHD has no way to read the simulation time, so all triggers appear
+        // to happen at time zero there. This still toggles bit 0 for waiting
+        // processes, but breaks `.triggered`, which `check_llhd` rejects.
+        let now = circt::llhd::CurrentTimeOp::new(self.mlir_builder).into();
+        let now = (
+            self.builder.ins().const_int((64, BigInt::zero())),
+            circt::llhd::TimeToIntOp::new(self.mlir_builder, now).into(),
+        );
+        let one = self.mk_const_int(64, &BigInt::one());
+        let now = self.mk_add(now, one);
+        let two = self.mk_const_int(64, &BigInt::from(2));
+        self.mk_umul(now, two)
+    }
+
+    /// Compute `base ** exp` for integers.
+    ///
+    /// Multiplies the result by `base**(2**i)` for every set bit `i` of the
+    /// exponent. Negative exponents yield 0, unless the base is 1 or -1, as
+    /// required by §11.4.3. The X produced by `0 ** y` with a negative `y`
+    /// maps to 0; the caller marks it as unknown in four-state mode.
+    fn mk_pow(
+        &mut self,
+        base: HybridValue,
+        exp: HybridValue,
+        exp_sbv: ty::SbvType,
+        signed: bool,
+    ) -> HybridValue {
+        let width = self.llhd_type(base.0).len();
+        let one = self.mk_const_int(width, &BigInt::one());
+        let mut result = one;
+        let mut power = base;
+        for i in 0..exp_sbv.size {
+            let bit = self.mk_ext_slice_const_offset(exp, i, 1);
+            let product = self.mk_umul(result, power);
+            result = self.mk_mux(bit, product, result);
+            if i + 1 < exp_sbv.size {
+                power = self.mk_umul(power, power);
             }
+        }
 
-            hir::StmtKind::Ast(ast) => {
-                self.emit_stmt_ast(ast, env)?;
+        // Handle negative exponents. Since `(-1)**y` only depends on whether
+        // `y` is odd, the result above is already correct for a base of 1 or
+        // -1.
+        if exp_sbv.is_signed() {
+            let negative = self.mk_ext_slice_const_offset(exp, exp_sbv.size - 1, 1);
+            let mut keep = self.mk_cmp(CmpPred::Eq, base, one);
+            if signed {
+                let ones = self.mk_const_int(width, &((BigInt::one() << width) - 1));
+                let is_minus_one = self.mk_cmp(CmpPred::Eq, base, ones);
+                keep = self.mk_or(keep, is_minus_one);
             }
+            let zero = self.mk_const_int(width, &BigInt::zero());
+            let clamped = self.mk_mux(keep, result, zero);
+            result = self.mk_mux(negative, clamped, result);
+        }
+        result
+    }
+
+    /// Emit a call to `__moore_assoc_traverse`.
+    fn emit_assoc_traverse(
+        &mut self,
+        op: mir::AssocTraverseOp,
+        array: &'gcx mir::Rvalue<'gcx>,
+        key: &'gcx mir::Rvalue<'gcx>,
+    ) -> Result<HybridValue> {
+        let op = match op {
+            mir::AssocTraverseOp::First => 0,
+            mir::AssocTraverseOp::Last => 1,
+            mir::AssocTraverseOp::Next => 2,
+            mir::AssocTraverseOp::Prev => 3,
+        };
+        let op = self.mk_const_int(32, &BigInt::from(op));
+        let kind = self.mk_array_kind(array.ty);
+        let key = self.emit_array_word(key)?;
+        let array = self.emit_mir_rvalue(array)?;
+        Ok(self.emit_runtime_value(RuntimeFunc::AssocTraverse, vec![op, kind, array, key]))
+    }
 
+    /// Emit the `kind` argument of the runtime's array functions for an array
+    /// of the given type.
+    fn mk_array_kind(&mut self, ty: &'gcx UnpackedType<'gcx>) -> HybridValue {
+        let kind = match ty.get_dynamic_dim() {
+            Some(ty::UnpackedDim::Queue(_)) => 1,
+            Some(ty::UnpackedDim::Assoc(Some(key))) if key.is_string() => 3,
+            Some(ty::UnpackedDim::Assoc(_)) => 2,
+            _ => 0,
+        };
+        self.mk_const_int(32, &BigInt::from(kind))
+    }
+
+    /// Determine the width of the values of type `ty` that are passed to the
+    /// runtime's array functions as `i64` words.
+    fn array_word_width(&mut self, ty: &'gcx UnpackedType<'gcx>, span: Span) -> Result<usize> {
+        match *self.emit_type(ty)? {
+            llhd::IntType(w) if w <= 64 => Ok(w),
             _ => {
-                error!("{:#?}", hir);
-                return self.unimp_msg("code generation for", hir);
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: dynamic array elements or keys of type `{}`",
+                        ty
+                    ))
+                    .span(span)
+                    .add_note("Only types that fit into 64 bits are supported"),
+                );
+                Err(())
             }
         }
-        Ok(())
     }
 
-    /// Emit the code for a statement for which no HIR node exists.
-    fn emit_stmt_ast(&mut self, stmt: &ast::Stmt, env: ParamEnv) -> Result<()> {
-        match &stmt.kind {
-            ast::ReturnStmt(None) => {
-                self.mk_ret(None);
-            }
-            ast::ReturnStmt(Some(expr)) => {
-                let expr = self.emit_rvalue(expr.id(), env)?;
-                self.mk_ret(Some(expr));
-            }
-            ast::BreakStmt => match self.break_stack.last() {
-                Some(&block) => {
-                    self.mk_br(block);
+    /// Emit an MIR rvalue as an `i64` word for the runtime's array functions.
+    fn emit_array_word(&mut self, mir: &'gcx mir::Rvalue<'gcx>) -> Result<HybridValue> {
+        let width = self.array_word_width(mir.ty, mir.span)?;
+        let signed = mir.ty.get_simple_bit_vector().map(|sbv| sbv.is_signed());
+        let value = self.emit_mir_rvalue_known(mir)?;
+        Ok(self.mk_extend_to_i64(value, width, signed.unwrap_or(false)))
+    }
+
+    /// Convert an `i64` word returned by the runtime's array functions to a
+    /// value of type `ty`.
+    fn mk_array_elem(
+        &mut self,
+        word: HybridValue,
+        ty: &'gcx UnpackedType<'gcx>,
+        span: Span,
+    ) -> Result<HybridValue> {
+        let width = self.array_word_width(ty, span)?;
+        if width == 64 {
+            Ok(word)
+        } else {
+            Ok(self.mk_ext_slice_const_offset(word, 0, width))
+        }
+    }
+
+    /// Zero-extend a single bit to an integer of type `ty`, like `int`.
+    fn mk_bool_to_int(
+        &mut self,
+        value: HybridValue,
+        ty: &'gcx UnpackedType<'gcx>,
+    ) -> Result<HybridValue> {
+        let ty = self.emit_type_both(ty)?;
+        let zero = self.emit_zero_for_type_both(ty);
+        Ok(self.mk_ins_slice(zero, value, 0, 1))
+    }
+
+    /// Zero- or sign-extend an integer of at most 64 bits to 64 bits.
+    fn mk_extend_to_i64(&mut self, value: HybridValue, width: usize, signed: bool) -> HybridValue {
+        if width == 64 {
+            return value;
+        }
+        let llty = (llhd::int_ty(64), mlir::get_integer_type(self.mcx, 64));
+        let zeros = self.emit_zero_for_type_both(llty);
+        let base = if signed {
+            let sign = self.mk_ext_slice_const_offset(value, width - 1, 1);
+            let ones = self.mk_not(zeros);
+            self.mk_mux(sign, ones, zeros)
+        } else {
+            zeros
+        };
+        self.mk_ins_slice(base, value, 0, width)
+    }
+
+    /// Zero-extend an integer from `from` to `to` bits.
+    fn mk_zero_extend(&mut self, value: HybridValue, from: usize, to: usize) -> HybridValue {
+        let zeros = self.mk_const_int(to, &BigInt::zero());
+        self.mk_ins_slice(zeros, value, 0, from)
+    }
+
+    /// Sign-extend an integer from `from` to `to` bits.
+    fn mk_sign_extend(&mut self, value: HybridValue, from: usize, to: usize) -> HybridValue {
+        let sign = self.mk_ext_slice_const_offset(value, from - 1, 1);
+        let base = self.mk_replicate(sign, to);
+        self.mk_ins_slice(base, value, 0, from)
+    }
+
+    /// Replicate a single bit `width` times.
+    fn mk_replicate(&mut self, bit: HybridValue, width: usize) -> HybridValue {
+        let zeros = self.mk_const_int(width, &BigInt::zero());
+        let ones = self.mk_not(zeros);
+        self.mk_mux(bit, ones, zeros)
+    }
+
+    /// Check whether any bit of an integer is set.
+    fn mk_any(&mut self, value: HybridValue) -> HybridValue {
+        let zero = self.emit_zero_for_type_both(self.value_type(value));
+        self.mk_cmp(CmpPred::Neq, value, zero)
+    }
+
+    /// Assemble a four-state value of type `ty` from its value and unknown
+    /// planes. See `emit_four_state_type`.
+    fn mk_four_state(
+        &mut self,
+        ty: &'gcx UnpackedType<'gcx>,
+        value: HybridValue,
+        unknown: HybridValue,
+    ) -> Result<HybridValue> {
+        let ty = self.emit_type_both(ty)?;
+        Ok(self.mk_struct(ty, &[value, unknown]))
+    }
+
+    /// Combine the drivers of a net according to the resolution function of
+    /// its net type.
+    ///
+    /// Each driver is given as its value and unknown bit planes, together with
+    /// the `(strength0, strength1)` levels it drives with. A bit driven at a
+    /// stronger level overpowers all weaker drivers of that bit, and drivers of
+    /// equal strength are combined by the resolution function. Bits which are
+    /// not driven at all become Z, or assume the `retain` value for charge
+    /// storage nets. Returns the planes of the resolved value.
+    fn mk_net_resolution(
+        &mut self,
+        net_ty: ast::NetType,
+        width: usize,
+        drivers: &[((HybridValue, HybridValue), (usize, usize))],
+        retain: Option<(HybridValue, HybridValue)>,
+    ) -> (HybridValue, HybridValue) {
+        let zero = self.mk_const_int(width, &BigInt::zero());
+
+        // Determine the bits each driver drives to 0, 1, and X. An X has the
+        // larger of the two strengths.
+        let mut driven = vec![];
+        for &((value, unknown), (s0, s1)) in drivers {
+            let not_value = self.mk_not(value);
+            let known = self.mk_not(unknown);
+            let is0 = self.mk_and(known, not_value);
+            let is1 = self.mk_and(known, value);
+            let isx = self.mk_and(unknown, not_value);
+            driven.push([(is0, s0), (is1, s1), (isx, std::cmp::max(s0, s1))]);
+        }
+        let mut levels: Vec<usize> = drivers
+            .iter()
+            .flat_map(|&(_, (s0, s1))| vec![s0, s1])
+            .filter(|&level| level > 0)
+            .collect();
+        levels.sort();
+        levels.dedup();
+
+        // Resolve the bits one strength level at a time, starting with the
+        // strongest.
+        let (mut one, mut x, mut decided) = (zero, zero, zero);
+        for &level in levels.iter().rev() {
+            let (mut any0, mut any1, mut anyx) = (zero, zero, zero);
+            for &[(is0, s0), (is1, s1), (isx, sx)] in &driven {
+                if s0 == level {
+                    any0 = self.mk_or(any0, is0);
                 }
-                None => {
-                    self.emit(
-                        DiagBuilder2::error("break statement outside of loop")
-                            .span(stmt.human_span()),
-                    );
-                    return Err(());
+                if s1 == level {
+                    any1 = self.mk_or(any1, is1);
                 }
-            },
-            ast::ContinueStmt => match self.continue_stack.last() {
-                Some(&block) => {
-                    self.mk_br(block);
+                if sx == level {
+                    anyx = self.mk_or(anyx, isx);
                 }
-                None => {
-                    self.emit(
-                        DiagBuilder2::error("continue statement outside of loop")
-                            .span(stmt.human_span()),
-                    );
-                    return Err(());
+            }
+
+            // Determine the bits which resolve to 1 and X at this level.
+            // Wired-AND and wired-OR nets let a 0 or 1 win over everything
+            // else, respectively. All other nets resolve conflicting values to
+            // X.
+            let (level_one, level_x) = match net_ty {
+                ast::NetType::WireAnd | ast::NetType::TriAnd => {
+                    let not0 = self.mk_not(any0);
+                    let notx = self.mk_not(anyx);
+                    let one = self.mk_and(any1, notx);
+                    (self.mk_and(one, not0), self.mk_and(anyx, not0))
                 }
-            },
-            _ => {
-                error!("{:#?}", stmt);
-                bug_span!(
-                    stmt.span(),
-                    self.cx,
-                    "code generation for {} not implemented",
-                    stmt.format_indefinite()
-                );
+                ast::NetType::WireOr | ast::NetType::TriOr => {
+                    let not1 = self.mk_not(any1);
+                    (any1, self.mk_and(anyx, not1))
+                }
+                _ => {
+                    let conflict = self.mk_and(any0, any1);
+                    let x = self.mk_or(anyx, conflict);
+                    let notx = self.mk_not(x);
+                    (self.mk_and(any1, notx), x)
+                }
+            };
+
+            // Only bits not already decided at a stronger level take on the
+            // value of this level.
+            let level_driven = self.mk_or(any0, any1);
+            let level_driven = self.mk_or(level_driven, anyx);
+            let undecided = self.mk_not(decided);
+            let new = self.mk_and(level_driven, undecided);
+            let level_one = self.mk_and(level_one, new);
+            let level_x = self.mk_and(level_x, new);
+            one = self.mk_or(one, level_one);
+            x = self.mk_or(x, level_x);
+            decided = self.mk_or(decided, level_driven);
+        }
+
+        // Bits which no driver drives to a value are Z, unless the net
+        // retains its previous value.
+        let z = self.mk_not(decided);
+        match retain {
+            Some((value, unknown)) => {
+                let value = self.mk_and(value, z);
+                let unknown = self.mk_and(unknown, z);
+                (self.mk_or(one, value), self.mk_or(x, unknown))
             }
+            None => (self.mk_or(one, z), self.mk_or(x, z)),
+        }
+    }
+
+    /// Determine the bits of a four-state value which are a known 1 or 0.
+    fn mk_known_bits(
+        &mut self,
+        value: HybridValue,
+        unknown: HybridValue,
+        one: bool,
+    ) -> HybridValue {
+        let known = self.mk_not(unknown);
+        let value = if one { value } else { self.mk_not(value) };
+        self.mk_and(value, known)
+    }
+
+    /// Make all bits of an integer X if a condition holds, and return the
+    /// resulting value and unknown planes.
+    fn mk_unknown_if(
+        &mut self,
+        value: HybridValue,
+        cond: HybridValue,
+    ) -> (HybridValue, HybridValue) {
+        let width = self.llhd_type(value.0).unwrap_int();
+        let unknown = self.mk_replicate(cond, width);
+        let known = self.mk_not(unknown);
+        (self.mk_and(value, known), unknown)
+    }
+
+    /// Widen a real value to the bit pattern of a double, as expected by the
+    /// runtime library.
+    fn mk_real_to_double(&mut self, value: HybridValue, ty: &UnpackedType) -> HybridValue {
+        match ty.get_real() {
+            Some(ty::RealType::ShortReal) => {
+                self.emit_runtime_value(RuntimeFunc::RealFromShortReal, vec![value])
+            }
+            _ => value,
+        }
+    }
+
+    /// Narrow the bit pattern of a double returned by the runtime library to a
+    /// real value of the given type.
+    fn mk_double_to_real(&mut self, value: HybridValue, ty: &UnpackedType) -> HybridValue {
+        match ty.get_real() {
+            Some(ty::RealType::ShortReal) => {
+                self.emit_runtime_value(RuntimeFunc::RealToShortReal, vec![value])
+            }
+            _ => value,
         }
-        Ok(())
     }
 
     /// Emit the code for a loop statement.
@@ -2802,7 +6964,8 @@ where
             hir::LoopKind::Forever => None,
             hir::LoopKind::Repeat(count) => {
                 let ty = self.type_of(count, env)?;
-                let count = self.emit_rvalue(count, env)?;
+                let count = self.mir_rvalue(count, env);
+                let count = self.emit_mir_rvalue_known(count)?;
                 let var = self.mk_var(count);
                 self.builder.set_name(var.0, "loop_count".to_string());
                 Some((var, ty))
@@ -2821,10 +6984,9 @@ where
         let enter_cond = match kind {
             hir::LoopKind::Forever => None,
             hir::LoopKind::Repeat(_) => {
-                let (repeat_var, ty) = repeat_var.clone().unwrap();
-                let lty = self.emit_type_both(ty)?;
+                let (repeat_var, _) = repeat_var.clone().unwrap();
                 let value = self.mk_ld(repeat_var);
-                let zero = self.emit_zero_for_type_both(lty);
+                let zero = self.emit_zero_for_type_both(self.value_type(value));
                 Some(self.mk_cmp(CmpPred::Neq, value, zero))
             }
             hir::LoopKind::While(cond) => Some(self.emit_rvalue_bool(cond, env)?),
@@ -2894,21 +7056,99 @@ where
         Ok(())
     }
 
+    /// Extract the bit of a value whose transitions count as edges.
+    ///
+    /// Only the LSB of a vector is considered when detecting edges, as
+    /// required by §9.4.2.
+    fn mk_edge_bit(&mut self, value: HybridValue, width: usize) -> HybridValue {
+        if width > 1 {
+            self.mk_ext_slice_const_offset(value, 0, 1)
+        } else {
+            value
+        }
+    }
+
+    /// Emit the code to check if a certain edge occurred between two
+    /// four-state values of the given `width`.
+    ///
+    /// A posedge is a transition of the LSB from 0 to anything else, or from X
+    /// or Z to 1. A negedge is a transition of the LSB from 1 to anything
+    /// else, or from X or Z to 0.
+    fn emit_four_state_event_trigger(
+        &mut self,
+        edge: ast::EdgeIdent,
+        width: usize,
+        prev: HybridValue,
+        now: HybridValue,
+    ) -> HybridValue {
+        let changed = self.mk_cmp(CmpPred::Neq, prev, now);
+        if edge == ast::EdgeIdent::Implicit {
+            self.builder.set_name(changed.0, "impledge".to_string());
+            return changed;
+        }
+        let mut levels = vec![];
+        for &value in &[prev, now] {
+            let v = self.mk_ext_field_const_offset(value, 0);
+            let v = self.mk_edge_bit(v, width);
+            let u = self.mk_ext_field_const_offset(value, 1);
+            let u = self.mk_edge_bit(u, width);
+            let any_u = self.mk_any(u);
+            let known = self.mk_not(any_u);
+            let any_v = self.mk_any(v);
+            let no_v = self.mk_not(any_v);
+            let zero = self.mk_and(known, no_v);
+            let one = self.mk_and(known, any_v);
+            levels.push((zero, one, any_u));
+        }
+        let (prev_zero, prev_one, prev_unknown) = levels[0];
+        let (now_zero, now_one, _) = levels[1];
+        let not_now_zero = self.mk_not(now_zero);
+        let not_now_one = self.mk_not(now_one);
+        let from_zero = self.mk_and(prev_zero, not_now_zero);
+        let to_one = self.mk_and(prev_unknown, now_one);
+        let posedge = self.mk_or(from_zero, to_one);
+        let from_one = self.mk_and(prev_one, not_now_one);
+        let to_zero = self.mk_and(prev_unknown, now_zero);
+        let negedge = self.mk_or(from_one, to_zero);
+        let value = match edge {
+            ast::EdgeIdent::Posedge => posedge,
+            ast::EdgeIdent::Negedge => negedge,
+            _ => self.mk_or(posedge, negedge),
+        };
+        self.builder.set_name(value.0, "edge".to_string());
+        value
+    }
+
     /// Emit the code to check if a certain edge occurred between two values.
     fn emit_event_trigger(
         &mut self,
         edge: ast::EdgeIdent,
+        value_ty: &'gcx UnpackedType<'gcx>,
         prev: HybridValue,
         now: HybridValue,
     ) -> Result<HybridValue> {
-        let ty = self.value_type(now);
+        if self.is_four_state(value_ty) {
+            let width = value_ty.get_bit_size().unwrap();
+            return Ok(self.emit_four_state_event_trigger(edge, width, prev, now));
+        }
+
+        // Only the LSB of a vector matters for posedges and negedges.
+        let (prev_bit, now_bit) = match value_ty.get_bit_size() {
+            Some(width)
+                if edge != ast::EdgeIdent::Implicit && value_ty.coalesces_to_llhd_scalar() =>
+            {
+                (self.mk_edge_bit(prev, width), self.mk_edge_bit(now, width))
+            }
+            _ => (prev, now),
+        };
+        let ty = self.value_type(now_bit);
 
         // Check if a posedge happened.
         let posedge = match edge {
             ast::EdgeIdent::Posedge | ast::EdgeIdent::Edge => {
                 let zero = self.emit_zero_for_type_both(ty.clone());
-                let prev_eq_0 = self.mk_cmp(CmpPred::Eq, prev, zero);
-                let now_neq_0 = self.mk_cmp(CmpPred::Neq, now, zero);
+                let prev_eq_0 = self.mk_cmp(CmpPred::Eq, prev_bit, zero);
+                let now_neq_0 = self.mk_cmp(CmpPred::Neq, now_bit, zero);
                 let value = self.mk_and(prev_eq_0, now_neq_0);
                 self.builder.set_name(value.0, "posedge".to_string());
                 Some(value)
@@ -2920,8 +7160,8 @@ where
         let negedge = match edge {
             ast::EdgeIdent::Negedge | ast::EdgeIdent::Edge => {
                 let zero = self.emit_zero_for_type_both(ty.clone());
-                let prev_neq_0 = self.mk_cmp(CmpPred::Neq, prev, zero);
-                let now_eq_0 = self.mk_cmp(CmpPred::Eq, now, zero);
+                let prev_neq_0 = self.mk_cmp(CmpPred::Neq, prev_bit, zero);
+                let now_eq_0 = self.mk_cmp(CmpPred::Eq, now_bit, zero);
                 let value = self.mk_and(prev_neq_0, now_eq_0);
                 self.builder.set_name(value.0, "negedge".to_string());
                 Some(value)
@@ -2953,9 +7193,22 @@ where
         lvalue: &'gcx mir::Lvalue<'gcx>,
         rvalue: &'gcx mir::Rvalue<'gcx>,
     ) -> Result<()> {
-        let lv = self.emit_mir_lvalue(lvalue)?;
-        let rv = self.emit_mir_rvalue(rvalue)?;
-        self.emit_blocking_assign_llhd(lv, rv)
+        // Simplify the assignment to eliminate accesses into dynamic arrays on
+        // the left-hand side.
+        let assign = self.arena().alloc_mir_assignment(mir::Assignment {
+            id: lvalue.id,
+            env: lvalue.env,
+            span: lvalue.span,
+            ty: lvalue.ty,
+            lhs: lvalue,
+            rhs: rvalue,
+        });
+        for assign in self.mir_simplify_assignment(Ref(assign)) {
+            let lv = self.emit_mir_lvalue(assign.lhs)?;
+            let rv = self.emit_mir_rvalue(assign.rhs)?;
+            self.emit_blocking_assign_llhd(lv, rv)?;
+        }
+        Ok(())
     }
 
     /// Emit a blocking assignment to a variable or signal.
@@ -2997,21 +7250,22 @@ where
         default: Option<NodeId>,
     ) -> Result<HybridValue> {
         // Check if this is a variable or a net declaration.
-        let (is_var, name) = match self.hir_of(decl_id)? {
-            HirNode::VarDecl(x) => (x.kind.is_var(), x.name.value.as_str()),
-            HirNode::IntPort(x) => (x.kind.is_var(), x.name.value.as_str()),
+        let (kind, name, strength) = match self.hir_of(decl_id)? {
+            HirNode::VarDecl(x) => (x.kind, x.name.value.as_str(), x.strength),
+            HirNode::IntPort(x) => (x.kind, x.name.value.as_str(), None),
             x => unreachable!("emit_varnet_decl on HIR {:?}", x),
         };
 
         // Differentiate between variable and net declarations, which have
         // slightly different semantics regarding their initial value.
-        if is_var {
+        if kind.is_var() {
             // For variables we require that the initial value is a
             // constant.
             let init = self.emit_const(
-                match default {
-                    Some(expr) => self.constant_value_of(expr, env),
-                    None => self.type_default_value(ty),
+                match (self.tables.mem_inits.get(&decl_id.env(env)), default) {
+                    (Some(&value), _) => value,
+                    (None, Some(expr)) => self.constant_value_of(expr, env),
+                    (None, None) => self.type_default_value(ty),
                 },
                 env,
                 self.span(default.unwrap_or(decl_id)),
@@ -3028,7 +7282,15 @@ where
         } else {
             // For nets we simply emit the initial value as a signal, then
             // short-circuit it with the net declaration.
-            let zero = self.emit_const(self.type_default_value(ty), env, self.span(decl_id))?;
+            let net_ty = match kind {
+                ast::VarKind::Net { ty, .. } => ty,
+                ast::VarKind::Var => unreachable!(),
+            };
+            let zero = self.emit_const(
+                value::net_default_value(self.cx, ty, net_ty),
+                env,
+                self.span(decl_id),
+            )?;
             let net = (
                 self.builder.ins().sig(zero.0),
                 circt::llhd::SignalOp::new(
@@ -3040,8 +7302,10 @@ where
             );
             if let Some(default) = default {
                 let init = self.emit_rvalue_mode(default, env, Mode::Signal)?;
-                self.builder.ins().con(net.0, init.0);
-                circt::llhd::ConnectOp::new(self.mlir_builder, net.1, init.1);
+                let strength = drive_levels(net_drive_strength(strength));
+                let target = self.mk_net_driver(decl_id.into(), strength)?.unwrap_or(net);
+                self.builder.ins().con(target.0, init.0);
+                circt::llhd::ConnectOp::new(self.mlir_builder, target.1, init.1);
             }
             Ok(net)
         }
@@ -3520,6 +7784,53 @@ where
         )
     }
 
+    /// Convert an unsigned integer to a time value, where each unit of the
+    /// integer corresponds to `scale` seconds.
+    ///
+    /// In MLIR, the integer is multiplied by the scale in femtoseconds and
+    /// converted with `llhd.int_to_time`. LLHD cannot multiply time values, so
+    /// the time is assembled as a sum of the scaled powers of two selected by
+    /// the bits of the integer.
+    fn mk_int_to_time(
+        &mut self,
+        value: HybridValue,
+        width: usize,
+        scale: &num::BigRational,
+    ) -> HybridValue {
+        // LLHD
+        let zero = self
+            .builder
+            .ins()
+            .const_time(llhd::value::TimeValue::new(num::zero(), 0, 0));
+        let mut llhd_time = zero;
+        for i in 0..width {
+            let bit = self.builder.ins().ext_slice(value.0, i, 1);
+            let weight = scale * num::BigRational::from(BigInt::one() << i);
+            let weight = self
+                .builder
+                .ins()
+                .const_time(llhd::value::TimeValue::new(weight, 0, 0));
+            let array = self.builder.ins().array(vec![zero, weight]);
+            let term = self.builder.ins().mux(array, bit);
+            llhd_time = self.builder.ins().add(llhd_time, term);
+        }
+
+        // MLIR
+        let value = if width > 64 {
+            self.mk_ext_slice_const_offset(value, 0, 64)
+        } else {
+            self.mk_extend_to_i64(value, width, false)
+        };
+        let femtos = (scale * num::BigRational::from(BigInt::from(1_000_000_000_000_000u64)))
+            .round()
+            .to_integer();
+        let femtos = self.mk_const_int(64, &femtos);
+        let femtos = self.mk_umul(value, femtos);
+        let mlir_time = circt::llhd::IntToTimeOp::new(self.mlir_builder, femtos.1).into();
+
+        (llhd_time, mlir_time)
+    }
+
     fn mk_cmp(&mut self, pred: CmpPred, mut lhs: HybridValue, mut rhs: HybridValue) -> HybridValue {
         if (pred == CmpPred::Eq || pred == CmpPred::Neq) && !mlir::is_integer_type(lhs.1.ty()) {
             let ty = mlir::get_integer_type(self.mcx, circt::hw::bit_width(lhs.1.ty()).unwrap());
@@ -3697,12 +8008,76 @@ pub struct EmittedProcedure {
     outputs: Vec<AccessedNode>,
 }
 
+/// The kind of statement checked by `is_dataflow_stmt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DataflowKind {
+    /// The body of a clocked procedure.
+    Clocked,
+    /// The reset branch of a clocked procedure.
+    Reset,
+    /// The body of a combinational procedure.
+    Comb,
+}
+
+/// The value a procedure assigns to a variable, as determined by
+/// `eval_dataflow_stmt`.
+#[derive(Debug, Clone, Copy)]
+struct DataflowValue {
+    /// The assigned value.
+    value: HybridValue,
+    /// The condition under which the value is assigned. The variable keeps its
+    /// current value otherwise.
+    enable: Option<HybridValue>,
+}
+
+impl DataflowValue {
+    /// Check whether two values are the result of the same assignment.
+    fn same_as(&self, other: &Self) -> bool {
+        self.value.0 == other.value.0 && self.enable.map(|e| e.0) == other.enable.map(|e| e.0)
+    }
+}
+
 /// Result of emitting a function.
 pub struct EmittedFunction {
-    /// The emitted LLHD unit.
-    unit: llhd::ir::UnitId,
+    /// The name of the emitted LLHD unit.
+    name: llhd::ir::UnitName,
+    /// The signature of the emitted LLHD unit.
+    sig: llhd::ir::Signature,
     /// The emitted MLIR symbol name.
     mlir_symbol: String,
+    /// Whether this is a DPI import that expects its arguments and result in
+    /// their C representation. See `emit_dpi_import`.
+    dpi: bool,
+}
+
+/// The C representation of an argument or result of a DPI import. See
+/// `emit_dpi_import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DpiRepr {
+    /// An integer atom or `chandle` of the given width, passed by value.
+    Int(usize),
+    /// A `string`, passed as a `const char*`.
+    String,
+    /// A `bit` scalar, passed as an `svBit`.
+    Bit,
+    /// A `logic` scalar, passed as an `svLogic`.
+    Logic,
+    /// A `bit` vector of the given width, passed as an `svBitVecVal*`.
+    BitVec(usize),
+    /// A `logic` vector of the given width, passed as an `svLogicVecVal*`.
+    LogicVec(usize),
+}
+
+/// The continuous drivers of a net or variable. See `count_net_drivers`.
+#[derive(Debug, Default, Clone, Copy)]
+struct DriverCount {
+    /// The number of drivers.
+    count: usize,
+    /// Whether any of the drivers has a strength other than `strong`.
+    weak: bool,
+    /// Whether any of the drivers drives the entire net, rather than a part of
+    /// it.
+    whole: bool,
 }
 
 /// A module's port interface.
@@ -3722,6 +8097,9 @@ pub struct ModulePort<'a> {
     /// The original port that generated this port. One `IntPort`s may spawn
     /// multiple module ports, e.g. in an interface.
     pub port: &'a port_list::IntPort<'a>,
+    /// The direction of the port. Inout ports appear once as an input and
+    /// once as an output.
+    pub dir: ast::PortDir,
     /// The type of the port.
     pub ty: &'a UnpackedType<'a>,
     /// The lowered MLIR type.
@@ -3749,6 +8127,113 @@ pub enum ModulePortKind<'a> {
     },
 }
 
+/// The drivers of a net declared in a module.
+#[derive(Debug)]
+struct NetDrivers<'a> {
+    /// The name of the net.
+    name: Spanned<Name>,
+    /// The net type, which determines the resolution function.
+    net_ty: ast::NetType,
+    /// The type of the net.
+    ty: &'a UnpackedType<'a>,
+    /// The environment the net is declared in.
+    env: ParamEnv,
+    /// The signal the resolved value is driven onto, if not the net itself.
+    target: Option<HybridValue>,
+    /// Whether the drivers are combined by a resolution function. Otherwise
+    /// they drive the target directly.
+    resolve: bool,
+    /// The auxiliary signals which hold the value of each driver, together
+    /// with the `(strength0, strength1)` levels of the driver.
+    drivers: Vec<(HybridValue, (usize, usize))>,
+}
+
+/// Determine the `(strength0, strength1)` levels of a driver.
+///
+/// Drivers without an explicit strength drive with `strong` strength.
+fn drive_levels(strength: Option<(ast::DriveStrength, ast::DriveStrength)>) -> (usize, usize) {
+    match strength {
+        Some((a, b)) if a.is_strength0() => (a.level(), b.level()),
+        Some((a, b)) => (b.level(), a.level()),
+        None => (6, 6),
+    }
+}
+
+/// Determine the drive strength of a net declaration assignment.
+fn net_drive_strength(
+    strength: Option<ast::NetStrength>,
+) -> Option<(ast::DriveStrength, ast::DriveStrength)> {
+    match strength {
+        Some(ast::NetStrength::Drive(a, b)) => Some((a, b)),
+        _ => None,
+    }
+}
+
+/// Determine the `(strength0, strength1)` levels of a pull gate.
+fn pull_levels(gate: &hir::PullGate) -> (usize, usize) {
+    if gate.pullup {
+        (0, gate.strength.level())
+    } else {
+        (gate.strength.level(), 0)
+    }
+}
+
+/// Determine the net or variable an MIR lvalue drives.
+fn lvalue_root(mir: &mir::Lvalue) -> Option<NodeId> {
+    match mir.kind {
+        mir::LvalueKind::Transmute(value)
+        | mir::LvalueKind::Index { value, .. }
+        | mir::LvalueKind::Member { value, .. } => lvalue_root(value),
+        mir::LvalueKind::Var(id) | mir::LvalueKind::Port(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Check whether an lvalue refers to an entire variable or port, rather than a
+/// part of it.
+fn lvalue_is_whole(mir: &mir::Lvalue) -> bool {
+    match mir.kind {
+        mir::LvalueKind::Transmute(value) => lvalue_is_whole(value),
+        mir::LvalueKind::Var(_) | mir::LvalueKind::Port(_) => true,
+        _ => false,
+    }
+}
+
+/// Determine the variable an rvalue tests, and whether it is tested for being
+/// low.
+///
+/// Recognizes conditions like `rst`, `!rst_n`, and `rst_n == 0`.
+fn rvalue_signal(mir: &mir::Rvalue) -> Option<(NodeId, bool)> {
+    match mir.kind {
+        mir::RvalueKind::CastValueDomain { value, .. }
+        | mir::RvalueKind::Transmute(value)
+        | mir::RvalueKind::CastSign(_, value)
+        | mir::RvalueKind::CastToBool(value)
+        | mir::RvalueKind::ZeroExtend(_, value) => rvalue_signal(value),
+        mir::RvalueKind::UnaryBitwise {
+            op: mir::UnaryBitwiseOp::Not,
+            arg,
+        } => rvalue_signal(arg).map(|(id, low)| (id, !low)),
+        mir::RvalueKind::IntComp { op, lhs, rhs, .. } => {
+            let zero = match rhs.kind {
+                mir::RvalueKind::Const(value) if value.is_false() => true,
+                mir::RvalueKind::Const(value) if value.is_true() => false,
+                _ => return None,
+            };
+            let low = match op {
+                mir::IntCompOp::Eq | mir::IntCompOp::CaseEq | mir::IntCompOp::WildcardEq => zero,
+                mir::IntCompOp::Neq | mir::IntCompOp::CaseNeq | mir::IntCompOp::WildcardNeq => {
+                    !zero
+                }
+                _ => return None,
+            };
+            rvalue_signal(lhs).map(|(id, inner)| (id, inner != low))
+        }
+        mir::RvalueKind::Var(id) | mir::RvalueKind::Port(id) => Some((id, false)),
+        _ => None,
+    }
+}
+
 /// An signal within an interface.
 #[derive(Debug)]
 pub struct IntfSignal<'a> {
@@ -3777,6 +8262,16 @@ fn signal_ty(ty: HybridType) -> HybridType {
     (llhd::signal_ty(ty.0), circt::llhd::get_signal_type(ty.1))
 }
 
+/// Get the DPI declaration that imports a function or task, if any.
+fn dpi_import_of<'a>(decl: &'a ast::SubroutineDecl<'a>) -> Option<&'a ast::DpiDecl<'a>> {
+    decl.get_parent().and_then(|x| x.as_all().get_dpi_decl())
+}
+
+/// Check whether a function or task is imported through the DPI.
+fn is_dpi_import<'a>(decl: &'a ast::SubroutineDecl<'a>) -> bool {
+    dpi_import_of(decl).is_some()
+}
+
 /// Make a type a pointer type.
 ///
 /// This is a convenience function that processes old LLHD types and the newer
@@ -3784,3 +8279,9 @@ fn signal_ty(ty: HybridType) -> HybridType {
 fn pointer_ty(ty: HybridType) -> HybridType {
     (llhd::pointer_ty(ty.0), circt::llhd::get_pointer_type(ty.1))
 }
+
+/// Determine the number of 32 bit chunks of an `svBitVecVal` or
+/// `svLogicVecVal` array holding `size` bits.
+fn dpi_chunks(size: usize) -> usize {
+    std::cmp::max(1, (size + 31) / 32)
+}
@@ -402,6 +402,17 @@ pub trait Context<'gcx>: DiagEmitter + QueryDatabase<'gcx> + ty::HasTypeStorage<
         &self.gcx().tables
     }
 
+    /// Check whether values of a type carry their X and Z bits in the
+    /// generated code.
+    ///
+    /// This is the case for four-valued types that map to a scalar, if
+    /// four-state code generation has been enabled in the session options.
+    fn is_four_state(&self, ty: &ty::UnpackedType<'gcx>) -> bool {
+        self.sess().opts.four_state
+            && ty.coalesces_to_llhd_scalar()
+            && ty.domain() == ty::Domain::FourValued
+    }
+
     /// Emit an internal compiler error that a node is not implemented.
     fn unimp<T: HasSpan + HasDesc, R>(&self, node: &T) -> Result<R> {
         self.emit(
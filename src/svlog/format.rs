@@ -0,0 +1,357 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Format strings of the display and severity system tasks.
+//!
+//! Format strings such as the one in `$display("x = %0d", x)` are parsed at
+//! compile time into a sequence of literal text and format specifiers. The
+//! code generator then passes the literal text and the formatted values to the
+//! runtime library piece by piece, such that the runtime never has to parse a
+//! format string itself. See [`crate::runtime`] for the calling convention.
+
+use num::{BigInt, One, Signed};
+use std::fmt;
+
+/// A piece of a parsed format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatItem {
+    /// Literal text to be printed as-is.
+    Literal(String),
+    /// A format specifier that consumes an argument, or `%m`.
+    Spec(FormatSpec),
+}
+
+/// A format specifier such as `%0d` or `%-8h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    /// The kind of formatting to apply.
+    pub kind: FormatKind,
+    /// The explicit field width, if any. A width of zero requests the minimal
+    /// width, as in `%0d`.
+    pub width: Option<usize>,
+    /// The number of digits after the decimal point of a real value, as in
+    /// `%.3f`, if any.
+    pub precision: Option<usize>,
+    /// Whether the value is left-aligned within the field, as in `%-4d`.
+    pub left_align: bool,
+    /// The byte offset of the `%` within the format string.
+    pub offset: usize,
+    /// The length of the specifier in bytes, including the `%`.
+    pub len: usize,
+}
+
+/// The different kinds of format specifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatKind {
+    /// `%b`, binary.
+    Binary,
+    /// `%o`, octal.
+    Octal,
+    /// `%d`, decimal.
+    Decimal,
+    /// `%h` or `%x`, hexadecimal.
+    Hex,
+    /// `%c`, a single character.
+    Char,
+    /// `%s`, a string.
+    String,
+    /// `%t`, a time value.
+    Time,
+    /// `%e`, real in exponential notation.
+    Exp,
+    /// `%f`, real in decimal notation.
+    Float,
+    /// `%g`, real in the shorter of exponential and decimal notation.
+    General,
+    /// `%m`, the hierarchical name of the enclosing scope. Consumes no
+    /// argument.
+    HierName,
+}
+
+impl FormatKind {
+    /// Get the character which identifies this kind of format specifier.
+    ///
+    /// This is also the code that is passed to the runtime library.
+    pub fn letter(self) -> char {
+        match self {
+            FormatKind::Binary => 'b',
+            FormatKind::Octal => 'o',
+            FormatKind::Decimal => 'd',
+            FormatKind::Hex => 'h',
+            FormatKind::Char => 'c',
+            FormatKind::String => 's',
+            FormatKind::Time => 't',
+            FormatKind::Exp => 'e',
+            FormatKind::Float => 'f',
+            FormatKind::General => 'g',
+            FormatKind::HierName => 'm',
+        }
+    }
+
+    /// Check whether this specifier consumes an argument.
+    pub fn takes_arg(self) -> bool {
+        self != FormatKind::HierName
+    }
+
+    /// Check whether this specifier expects a real-valued argument.
+    pub fn is_real(self) -> bool {
+        match self {
+            FormatKind::Exp | FormatKind::Float | FormatKind::General => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "%")?;
+        if self.left_align {
+            write!(f, "-")?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        write!(f, "{}", self.kind.letter())
+    }
+}
+
+/// An error encountered while parsing a format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    /// The byte offset of the offending specifier within the format string.
+    pub offset: usize,
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// Parse a format string into literal text and format specifiers.
+///
+/// Escaped percent signs `%%` are folded into the surrounding literal text.
+pub fn parse_format(input: &str) -> Result<Vec<FormatItem>, FormatError> {
+    let mut items = vec![];
+    let mut literal = String::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c != '%' {
+            literal.push(c);
+            continue;
+        }
+
+        // Parse the flags and the field width.
+        let mut left_align = false;
+        let mut width: Option<usize> = None;
+        if let Some(&(_, '-')) = chars.peek() {
+            left_align = true;
+            chars.next();
+        }
+        while let Some(&(_, d)) = chars.peek() {
+            match d.to_digit(10) {
+                Some(d) => {
+                    width = Some(width.unwrap_or(0) * 10 + d as usize);
+                    chars.next();
+                }
+                None => break,
+            }
+        }
+        let mut precision: Option<usize> = None;
+        if let Some(&(_, '.')) = chars.peek() {
+            chars.next();
+            precision = Some(0);
+            while let Some(&(_, d)) = chars.peek() {
+                match d.to_digit(10) {
+                    Some(d) => {
+                        precision = Some(precision.unwrap_or(0) * 10 + d as usize);
+                        chars.next();
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        // Parse the specifier letter.
+        let (end, letter) = match chars.next() {
+            Some(x) => x,
+            None => {
+                return Err(FormatError {
+                    offset,
+                    message: "incomplete format specifier at end of string".to_string(),
+                })
+            }
+        };
+        let kind = match letter.to_ascii_lowercase() {
+            '%' if !left_align && width.is_none() && precision.is_none() => {
+                literal.push('%');
+                continue;
+            }
+            'b' => FormatKind::Binary,
+            'o' => FormatKind::Octal,
+            'd' => FormatKind::Decimal,
+            'h' | 'x' => FormatKind::Hex,
+            'c' => FormatKind::Char,
+            's' => FormatKind::String,
+            't' => FormatKind::Time,
+            'e' => FormatKind::Exp,
+            'f' => FormatKind::Float,
+            'g' => FormatKind::General,
+            'm' => FormatKind::HierName,
+            _ => {
+                return Err(FormatError {
+                    offset,
+                    message: format!(
+                        "unknown format specifier `{}`",
+                        &input[offset..end + letter.len_utf8()]
+                    ),
+                })
+            }
+        };
+        if precision.is_some() && !kind.is_real() {
+            return Err(FormatError {
+                offset,
+                message: format!(
+                    "format specifier `{}` cannot have a precision",
+                    &input[offset..end + letter.len_utf8()]
+                ),
+            });
+        }
+        if !literal.is_empty() {
+            items.push(FormatItem::Literal(std::mem::take(&mut literal)));
+        }
+        items.push(FormatItem::Spec(FormatSpec {
+            kind,
+            width,
+            precision,
+            left_align,
+            offset,
+            len: end + letter.len_utf8() - offset,
+        }));
+    }
+    if !literal.is_empty() {
+        items.push(FormatItem::Literal(literal));
+    }
+    Ok(items)
+}
+
+/// Format an integer value of `size` bits according to a format specifier.
+///
+/// This is used to evaluate format strings at compile time, for example in
+/// elaboration system tasks. Without an explicit field width, values are
+/// padded to the width required by the largest value of their type, as
+/// mandated by the standard. A width of zero suppresses the padding.
+pub fn format_int(spec: &FormatSpec, value: &BigInt, size: usize, signed: bool) -> String {
+    // Reinterpret the value as unsigned for the non-decimal formats.
+    let unsigned = if value.is_negative() {
+        value + (BigInt::one() << size)
+    } else {
+        value.clone()
+    };
+    let max: BigInt = (BigInt::one() << size) - 1;
+    let (text, natural_width, pad) = match spec.kind {
+        FormatKind::Binary => (unsigned.to_str_radix(2), max.to_str_radix(2).len(), '0'),
+        FormatKind::Octal => (unsigned.to_str_radix(8), max.to_str_radix(8).len(), '0'),
+        FormatKind::Hex => (unsigned.to_str_radix(16), max.to_str_radix(16).len(), '0'),
+        FormatKind::Decimal => {
+            let width = if signed {
+                (BigInt::one() << (size - 1)).to_str_radix(10).len() + 1
+            } else {
+                max.to_str_radix(10).len()
+            };
+            (value.to_string(), width, ' ')
+        }
+        FormatKind::Char => {
+            let byte = (&unsigned & BigInt::from(0xffu8)).to_bytes_be().1[0];
+            ((byte as char).to_string(), 1, ' ')
+        }
+        FormatKind::String => {
+            let bytes = unsigned.to_bytes_be().1;
+            let text: String = bytes
+                .into_iter()
+                .filter(|&b| b != 0)
+                .map(|b| b as char)
+                .collect();
+            (text, (size + 7) / 8, ' ')
+        }
+        _ => (value.to_string(), 0, ' '),
+    };
+    let width = spec.width.unwrap_or(natural_width);
+    if text.len() >= width {
+        text
+    } else if spec.left_align {
+        format!("{}{}", text, " ".repeat(width - text.len()))
+    } else {
+        let pad: String = std::iter::repeat(pad).take(width - text.len()).collect();
+        format!("{}{}", pad, text)
+    }
+}
+
+/// Format a real value according to a `%e`, `%f`, or `%g` format specifier.
+///
+/// This follows the conversions of the C `printf` function, which the
+/// standard refers to. Without an explicit precision, six digits are printed
+/// after the decimal point.
+pub fn format_real(spec: &FormatSpec, value: f64) -> String {
+    let precision = spec.precision.unwrap_or(6);
+    let text = if !value.is_finite() {
+        match value {
+            v if v.is_nan() => "nan".to_string(),
+            v if v > 0.0 => "inf".to_string(),
+            _ => "-inf".to_string(),
+        }
+    } else {
+        match spec.kind {
+            FormatKind::Exp => format_exp(value, precision),
+            FormatKind::General => {
+                // Use the exponential notation for very small or large
+                // values, and drop trailing zeros in either case.
+                let precision = std::cmp::max(precision, 1);
+                let exp = exponent_of(value, precision - 1);
+                let text = if exp < -4 || exp >= precision as i32 {
+                    format_exp(value, precision - 1)
+                } else {
+                    format!("{:.*}", (precision as i32 - 1 - exp) as usize, value)
+                };
+                strip_fraction_zeros(text)
+            }
+            _ => format!("{:.*}", precision, value),
+        }
+    };
+    let width = spec.width.unwrap_or(0);
+    if text.len() >= width {
+        text
+    } else if spec.left_align {
+        format!("{}{}", text, " ".repeat(width - text.len()))
+    } else {
+        format!("{}{}", " ".repeat(width - text.len()), text)
+    }
+}
+
+/// Format a finite real value in the exponential notation of `%e`, such as
+/// `1.500000e+00`.
+fn format_exp(value: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let (mantissa, exp) = text.split_at(text.find('e').unwrap());
+    let exp: i32 = exp[1..].parse().unwrap();
+    let sign = if exp < 0 { '-' } else { '+' };
+    format!("{}e{}{:02}", mantissa, sign, exp.abs())
+}
+
+/// Determine the decimal exponent of a finite real value after rounding it to
+/// `precision` digits after the decimal point in exponential notation.
+fn exponent_of(value: f64, precision: usize) -> i32 {
+    let text = format!("{:.*e}", precision, value);
+    text[text.find('e').unwrap() + 1..].parse().unwrap()
+}
+
+/// Remove trailing zeros after the decimal point, as done by `%g`.
+fn strip_fraction_zeros(text: String) -> String {
+    let (mantissa, exp) = match text.find('e') {
+        Some(i) => text.split_at(i),
+        None => (text.as_str(), ""),
+    };
+    if !mantissa.contains('.') {
+        return text;
+    }
+    let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", mantissa, exp)
+}
@@ -157,6 +157,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
                     .as_ref()
                     .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
                 kind: ast::VarKind::Var,
+                strength: None,
             };
             Ok(HirNode::VarDecl(cx.arena().alloc_hir(hir)))
         }
@@ -175,6 +176,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
                     ty: decl.net_type,
                     kind: decl.kind,
                 },
+                strength: decl.strength,
             };
             Ok(HirNode::VarDecl(cx.arena().alloc_hir(hir)))
         }
@@ -264,12 +266,13 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
             };
             Ok(HirNode::Typedef(cx.arena().alloc_hir(hir)))
         }
-        AstNode::ContAssign(_, lhs, rhs) => {
+        AstNode::ContAssign(assign, lhs, rhs) => {
             let hir = hir::Assign {
                 id: node_id,
                 span: Span::union(lhs.span(), rhs.span()),
                 lhs: cx.map_ast_with_parent(AstNode::Expr(lhs), node_id),
                 rhs: cx.map_ast_with_parent(AstNode::Expr(rhs), node_id),
+                strength: assign.strength,
             };
             Ok(HirNode::Assign(cx.arena().alloc_hir(hir)))
         }
@@ -284,6 +287,7 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
                     .as_ref()
                     .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), ty)),
                 kind: ast::VarKind::Var,
+                strength: None,
             };
             Ok(HirNode::VarDecl(cx.arena().alloc_hir(hir)))
         }
@@ -306,6 +310,14 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
         AstNode::CallArg(x) => Ok(HirNode::CallArg(x)),
         AstNode::Any(ast) => match ast.as_all() {
             ast::AllNode::SubroutinePort(x) => Ok(HirNode::SubroutinePort(x)),
+            ast::AllNode::ElabSystemTask(x) => lower_elab_task(cx, node_id, x),
+            ast::AllNode::PullGate(x) => lower_pull_gate(cx, node_id, x),
+            ast::AllNode::WithIterator(x) => lower_with_iterator(cx, node_id, x),
+            ast::AllNode::PatternVar(x) => lower_pattern_var(cx, node_id, x),
+            ast::AllNode::Item(x) => match x.data {
+                ast::ItemData::Assertion(ref assert) => lower_conc_assert(cx, node_id, assert),
+                _ => bug_span!(x.span(), cx, "lowering of {} to hir not implemented", x),
+            },
             _ => {
                 error!("{:#?}", ast);
                 bug_span!(ast.span(), cx, "lowering of {} to hir not implemented", ast);
@@ -323,6 +335,91 @@ pub(crate) fn hir_of<'a>(cx: &impl Context<'a>, node_id: NodeId) -> Result<HirNo
     }
 }
 
+/// Lower the iterator of an array method's `with` clause to HIR.
+fn lower_with_iterator<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    ast: &'a ast::WithIterator<'a>,
+) -> Result<HirNode<'a>> {
+    // The iterator runs over the target of the method call it belongs to.
+    let call_id = ast.get_parent().expect("iterator must have a parent").id();
+    let array = match cx.hir_of(call_id)? {
+        HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::MethodCall(target, ..),
+            ..
+        }) => *target,
+        x => bug_span!(ast.span(), cx, "iterator parent is {:?}", x),
+    };
+    let hir = hir::WithIterator {
+        id: node_id,
+        name: ast.name,
+        span: ast.span(),
+        array,
+    };
+    Ok(HirNode::WithIterator(cx.arena().alloc_hir(hir)))
+}
+
+/// Lower a pattern variable to HIR.
+fn lower_pattern_var<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    ast: &'a ast::PatternVar<'a>,
+) -> Result<HirNode<'a>> {
+    // The variable binds the member of a `tagged` pattern, which may itself
+    // be nested in further `tagged` patterns. The outermost one must be the
+    // pattern of a `case ... matches` item.
+    let binding = ast
+        .get_parent()
+        .expect("pattern variable must have a parent");
+    let mut path = vec![];
+    let mut parent = binding.get_parent();
+    while let Some(node) = parent {
+        match node.as_all() {
+            ast::AllNode::Expr(x) => match x.data {
+                ast::TaggedExpr { member, .. } => path.push(member),
+                _ => break,
+            },
+            _ => break,
+        }
+        parent = node.get_parent();
+    }
+    path.reverse();
+    let in_item = match parent.map(|x| x.as_all()) {
+        Some(ast::AllNode::CasePattern(_)) => true,
+        _ => false,
+    };
+    let case = parent.and_then(|x| x.get_parent());
+    let scrutinee = match case.map(|x| x.as_all()) {
+        Some(ast::AllNode::Stmt(x)) => match x.kind {
+            ast::CaseStmt { ref expr, .. } => Some(expr.id()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let scrutinee = match scrutinee {
+        Some(scrutinee) if in_item && !path.is_empty() => scrutinee,
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "unsupported: pattern variable `{}` outside of a `tagged` pattern of a `case \
+                     ... matches` item",
+                    ast.name
+                ))
+                .span(ast.span()),
+            );
+            return Err(());
+        }
+    };
+    let hir = hir::PatternVar {
+        id: node_id,
+        name: ast.name,
+        span: ast.span(),
+        scrutinee,
+        path,
+    };
+    Ok(HirNode::PatternVar(cx.arena().alloc_hir(hir)))
+}
+
 /// Lower a module to HIR.
 #[moore_derive::query]
 pub(crate) fn hir_of_module<'a>(
@@ -413,6 +510,9 @@ fn lower_module_block<'gcx>(
         gens: Vec::new(),
         params: Vec::new(),
         assigns: Vec::new(),
+        asserts: Vec::new(),
+        elab_tasks: Vec::new(),
+        pulls: Vec::new(),
         last_rib: parent_rib,
     };
     lower_module_block_into(cx, items, allow_ports, allow_modports, &mut block)?;
@@ -547,11 +647,37 @@ fn lower_module_block_into<'gcx>(
                 let id = cx.map_ast_with_parent(AstNode::SubroutineDecl(decl), into.last_rib);
                 into.last_rib = id;
             }
-            ast::ItemData::Assertion(ref assert) => {
-                cx.emit(
-                    DiagBuilder2::warning("unsupported: concurrent assertion; ignored")
-                        .span(assert.span),
-                );
+            ast::ItemData::Assertion(ref assert) => match assert.data {
+                ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssertProperty(..))
+                | ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssumeProperty(..))
+                | ast::AssertionData::Concurrent(ast::ConcurrentAssertion::CoverProperty(..)) => {
+                    let id = cx.map_ast_with_parent(AstNode::Any(item), into.last_rib);
+                    into.asserts.push(id);
+                }
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::warning("unsupported: assertion; ignored").span(assert.span),
+                    );
+                }
+            },
+            ast::ItemData::ElabSystemTask(ref task) => match &*task.name.value.as_str() {
+                "info" | "warning" | "error" | "fatal" => {
+                    let id = cx.map_ast_with_parent(AstNode::Any(task), into.last_rib);
+                    into.elab_tasks.push(id);
+                }
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::warning(format!(
+                            "unsupported: elaboration system task `${}`; ignored",
+                            task.name
+                        ))
+                        .span(task.span),
+                    );
+                }
+            },
+            ast::ItemData::PullGate(ref gate) => {
+                let id = cx.map_ast_with_parent(AstNode::Any(gate), into.last_rib);
+                into.pulls.push(id);
             }
 
             // The remaining items don't need an HIR representation.
@@ -581,6 +707,9 @@ fn lower_type<'gcx>(
         ast::LongIntType => hir::TypeKind::Builtin(hir::BuiltinType::LongInt),
         ast::StringType => hir::TypeKind::Builtin(hir::BuiltinType::String),
         ast::TimeType => hir::TypeKind::Builtin(hir::BuiltinType::Time),
+        ast::ShortRealType => hir::TypeKind::Builtin(hir::BuiltinType::ShortReal),
+        ast::RealType => hir::TypeKind::Builtin(hir::BuiltinType::Real),
+        ast::RealtimeType => hir::TypeKind::Builtin(hir::BuiltinType::RealTime),
         ast::NamedType(name) => hir::TypeKind::Named(name),
         ast::StructType(ref def) => {
             let mut fields = vec![];
@@ -659,9 +788,6 @@ fn lower_type<'gcx>(
         | ast::MailboxType
         | ast::ImplicitSignedType
         | ast::ImplicitUnsignedType
-        | ast::ShortRealType
-        | ast::RealType
-        | ast::RealtimeType
         | ast::SpecializedType(..)
         | ast::ForwardType { .. }
         | ast::ScopedType { .. } => {
@@ -739,6 +865,17 @@ fn lower_expr_inner<'gcx>(
                 return Err(());
             }
         },
+        ast::LiteralExpr(Lit::Number(int, Some(frac))) => {
+            lower_real_literal(cx, expr.span, format!("{}.{}", int, frac))?
+        }
+        ast::LiteralExpr(Lit::Real(int, frac, exp)) => lower_real_literal(
+            cx,
+            expr.span,
+            match frac {
+                Some(frac) => format!("{}.{}e{}", int, frac, exp),
+                None => format!("{}e{}", int, exp),
+            },
+        )?,
         ast::LiteralExpr(Lit::UnbasedUnsized(c)) => hir::ExprKind::UnsizedConst(c),
 
         ast::LiteralExpr(Lit::BasedInteger(maybe_size, signed, base, value)) => {
@@ -917,12 +1054,10 @@ fn lower_expr_inner<'gcx>(
                 Op::Pow => hir::BinaryOp::Pow,
                 Op::LogicEq => hir::BinaryOp::Eq,
                 Op::LogicNeq => hir::BinaryOp::Neq,
-                // TODO: Make these separate operators.
-                Op::CaseEq => hir::BinaryOp::Eq,
-                Op::CaseNeq => hir::BinaryOp::Neq,
-                // TODO: Make these separate operators.
-                Op::WildcardEq => hir::BinaryOp::Eq,
-                Op::WildcardNeq => hir::BinaryOp::Neq,
+                Op::CaseEq => hir::BinaryOp::CaseEq,
+                Op::CaseNeq => hir::BinaryOp::CaseNeq,
+                Op::WildcardEq => hir::BinaryOp::WildcardEq,
+                Op::WildcardNeq => hir::BinaryOp::WildcardNeq,
                 Op::Lt => hir::BinaryOp::Lt,
                 Op::Leq => hir::BinaryOp::Leq,
                 Op::Gt => hir::BinaryOp::Gt,
@@ -976,6 +1111,48 @@ fn lower_expr_inner<'gcx>(
             hir::ExprKind::Index(indexee, mode)
         }
         ast::CallExpr(ref callee, ref args) => lower_call(cx, expr, callee, args)?,
+        ast::WithExpr {
+            ref call,
+            ref iterator,
+            expr: ref with,
+        } => {
+            let kind = match call.data {
+                ast::CallExpr(ref callee, ref args) => lower_call(cx, expr, callee, args)?,
+                ast::MemberExpr {
+                    expr: ref target,
+                    name,
+                } => hir::ExprKind::MethodCall(
+                    cx.map_ast_with_parent(AstNode::Expr(target), node_id),
+                    name,
+                    vec![],
+                    None,
+                ),
+                _ => hir::ExprKind::Ast(call),
+            };
+            match kind {
+                hir::ExprKind::MethodCall(target, name, args, None) => {
+                    let with = cx.map_ast_with_parent(AstNode::Expr(with), node_id);
+                    hir::ExprKind::MethodCall(target, name, args, Some((iterator.id(), with)))
+                }
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "`with` clause requires an array method call, but `{}` is not one",
+                            call.span().extract()
+                        ))
+                        .span(call.span()),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        ast::TaggedExpr { member, ref expr } => hir::ExprKind::Tagged(
+            member,
+            expr.as_ref()
+                .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+        ),
+        ast::PatternVarExpr(ref var) => hir::ExprKind::PatternVar(var.id()),
+        ast::WildcardPatternExpr => hir::ExprKind::WildcardPattern,
         ast::TernaryExpr {
             ref cond,
             ref true_expr,
@@ -1096,6 +1273,68 @@ fn lower_expr_inner<'gcx>(
                 .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id))
                 .collect(),
         ),
+        ast::StreamConcatExpr {
+            dir,
+            ref slice,
+            ref exprs,
+        } => {
+            let slice = match slice {
+                Some(ast::StreamConcatSlice::Type(ty)) => Some(hir::StreamSlice::Type(
+                    cx.map_ast_with_parent(AstNode::Type(ty), node_id),
+                )),
+                Some(ast::StreamConcatSlice::Expr(expr)) => match expr.data {
+                    // Catch the corner case where a slice type looks like an
+                    // expression.
+                    ast::IdentExpr(n) => {
+                        let binding = cx.resolve_upwards_or_error(n, node_id)?;
+                        match cx.hir_of(binding)? {
+                            HirNode::TypeParam(..) | HirNode::Typedef(..) => {
+                                let ty = cx.arena().alloc_ast_type(ast::Type::new(
+                                    expr.span,
+                                    ast::TypeData {
+                                        kind: ast::TypeKind::new(expr.span, ast::NamedType(n)),
+                                        sign: ast::TypeSign::None,
+                                        dims: vec![],
+                                    },
+                                ));
+                                ty.link_attach(expr.as_ref(), expr.order());
+                                Some(hir::StreamSlice::Type(
+                                    cx.map_ast_with_parent(AstNode::Type(ty), node_id),
+                                ))
+                            }
+                            _ => Some(hir::StreamSlice::Expr(
+                                cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+                            )),
+                        }
+                    }
+                    _ => Some(hir::StreamSlice::Expr(
+                        cx.map_ast_with_parent(AstNode::Expr(expr), node_id),
+                    )),
+                },
+                None => None,
+            };
+            let mut failed = false;
+            for stream_expr in exprs {
+                if let Some(ref range) = stream_expr.range {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: `with [...]` in streaming concatenation")
+                            .span(range.span()),
+                    );
+                    failed = true;
+                }
+            }
+            if failed {
+                return Err(());
+            }
+            hir::ExprKind::Stream(
+                dir,
+                slice,
+                exprs
+                    .iter()
+                    .map(|x| cx.map_ast_with_parent(AstNode::Expr(&x.expr), node_id))
+                    .collect(),
+            )
+        }
         ast::CastExpr(ref ty, ref expr) => {
             // Catch the corner case where a size cast looks like a type cast.
             if let ast::NamedType(n) = ty.kind.data {
@@ -1161,6 +1400,11 @@ fn lower_expr_inner<'gcx>(
                 .collect(),
         ),
         ast::BitsExpr { ref arg, .. } => hir::ExprKind::Builtin(hir::BuiltinCall::Bits(arg)),
+        ast::ArrayNewExpr(ref size, ref init) => hir::ExprKind::ArrayNew(
+            cx.map_ast_with_parent(AstNode::Expr(size), node_id),
+            init.as_ref()
+                .map(|init| cx.map_ast_with_parent(AstNode::Expr(init), node_id)),
+        ),
         ast::AssignExpr {
             op,
             ref lhs,
@@ -1335,6 +1579,7 @@ fn lower_stmt_inner<'a>(
                             .collect(),
                         cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id),
                     )),
+                    ast::CaseItem::Pattern(..) => unreachable!("pattern item in regular case"),
                 }
             }
             hir::StmtKind::Case {
@@ -1344,17 +1589,396 @@ fn lower_stmt_inner<'a>(
                 kind,
             }
         }
-        ast::AssertionStmt { .. } => {
+        ast::CaseStmt {
+            ref expr,
+            mode: ast::CaseMode::Pattern,
+            ref items,
+            ..
+        } => {
+            let expr = cx.map_ast_with_parent(AstNode::Expr(expr), node_id);
+            let mut ways = vec![];
+            let mut default = None;
+            for item in items {
+                match *item {
+                    ast::CaseItem::Default(ref stmt) => {
+                        if default.is_none() {
+                            default = Some(cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id));
+                        } else {
+                            cx.emit(
+                                DiagBuilder2::error("multiple default cases")
+                                    .span(stmt.human_span()),
+                            );
+                        }
+                    }
+                    ast::CaseItem::Pattern(ref item) => ways.push((
+                        cx.map_ast_with_parent(AstNode::Expr(&item.pattern), node_id),
+                        cx.map_ast_with_parent(AstNode::Stmt(&item.stmt), node_id),
+                    )),
+                    ast::CaseItem::Expr(..) => unreachable!("expression item in pattern case"),
+                }
+            }
+            hir::StmtKind::PatternCase {
+                expr,
+                ways,
+                default,
+            }
+        }
+        ast::AssertionStmt(ref assertion) => {
+            let (deferred, blocking) = match assertion.data {
+                ast::AssertionData::Immediate(ref a) => (None, a),
+                ast::AssertionData::Deferred(ast::AssertionDeferred::Observed, ref a) => {
+                    (Some(hir::DeferredMode::Observed), a)
+                }
+                ast::AssertionData::Deferred(ast::AssertionDeferred::Final, ref a) => {
+                    (Some(hir::DeferredMode::Final), a)
+                }
+                ast::AssertionData::Concurrent(..) => {
+                    cx.emit(
+                        DiagBuilder2::warning("unsupported: concurrent assertion; ignored")
+                            .span(stmt.human_span()),
+                    );
+                    return Ok(hir::StmtKind::Null);
+                }
+            };
+            let map_stmt = |stmt| cx.map_ast_with_parent(AstNode::Stmt(stmt), node_id);
+            let (kind, cond, pass_stmt, fail_stmt) = match *blocking {
+                ast::BlockingAssertion::Assert(ref cond, ref action) => {
+                    let (pass, fail) = lower_assertion_action_block(action);
+                    (hir::AssertKind::Assert, cond, pass, fail)
+                }
+                ast::BlockingAssertion::Assume(ref cond, ref action) => {
+                    let (pass, fail) = lower_assertion_action_block(action);
+                    (hir::AssertKind::Assume, cond, pass, fail)
+                }
+                ast::BlockingAssertion::Cover(ref cond, ref stmt) => {
+                    let pass = match stmt.kind {
+                        ast::NullStmt => None,
+                        _ => Some(stmt),
+                    };
+                    (hir::AssertKind::Cover, cond, pass, None)
+                }
+            };
+
+            hir::StmtKind::Assert {
+                kind,
+                deferred,
+                cond: cx.map_ast_with_parent(AstNode::Expr(cond), node_id),
+                pass_stmt: pass_stmt.map(map_stmt),
+                fail_stmt: fail_stmt.map(map_stmt),
+            }
+        }
+        ast::EventTriggerStmt {
+            nonblocking,
+            ref target,
+        } => hir::StmtKind::Trigger {
+            target: cx.map_ast_with_parent(AstNode::Expr(target), node_id),
+            nonblocking,
+        },
+        _ => hir::StmtKind::Ast(stmt),
+    })
+}
+
+/// Split an assertion action block into its pass and fail statements.
+///
+/// Null statements are dropped, such that `assert (x);` yields neither a pass
+/// nor a fail statement.
+fn lower_assertion_action_block<'a>(
+    action: &'a ast::AssertionActionBlock<'a>,
+) -> (Option<&'a ast::Stmt<'a>>, Option<&'a ast::Stmt<'a>>) {
+    let non_null = |stmt: &'a ast::Stmt<'a>| match stmt.kind {
+        ast::NullStmt => None,
+        _ => Some(stmt),
+    };
+    match *action {
+        ast::AssertionActionBlock::Positive(ref pass) => (non_null(pass), None),
+        ast::AssertionActionBlock::Negative(ref fail) => (None, Some(fail)),
+        ast::AssertionActionBlock::Both(ref pass, ref fail) => (non_null(pass), Some(fail)),
+    }
+}
+
+/// Lower an elaboration system task to HIR.
+fn lower_elab_task<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    ast: &'gcx ast::ElabSystemTask<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    let task = match &*ast.name.value.as_str() {
+        "info" => hir::SysTask::Info,
+        "warning" => hir::SysTask::Warning,
+        "error" => hir::SysTask::Error,
+        "fatal" => hir::SysTask::Fatal,
+        _ => unreachable!("unsupported elaboration system task `${}`", ast.name),
+    };
+    for arg in &ast.args {
+        cx.map_ast_with_parent(AstNode::CallArg(arg), node_id);
+    }
+    let hir = hir::ElabTask {
+        id: node_id,
+        span: ast.span,
+        task,
+        args: &ast.args,
+    };
+    Ok(HirNode::ElabTask(cx.arena().alloc_hir(hir)))
+}
+
+fn lower_pull_gate<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    ast: &'gcx ast::PullGate<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    let hir = hir::PullGate {
+        id: node_id,
+        span: ast.span,
+        pullup: ast.pullup,
+        strength: ast.strength.unwrap_or(if ast.pullup {
+            ast::DriveStrength::Pull1
+        } else {
+            ast::DriveStrength::Pull0
+        }),
+        targets: ast
+            .instances
+            .iter()
+            .map(|(_, expr)| cx.map_ast_with_parent(AstNode::Expr(expr), node_id))
+            .collect(),
+    };
+    Ok(HirNode::PullGate(cx.arena().alloc_hir(hir)))
+}
+
+/// Lower a concurrent assertion to HIR.
+fn lower_conc_assert<'gcx>(
+    cx: &impl Context<'gcx>,
+    node_id: NodeId,
+    ast: &'gcx ast::Assertion<'gcx>,
+) -> Result<HirNode<'gcx>> {
+    // Determine the kind of assertion. Action blocks have no equivalent in the
+    // verification dialects and are rejected.
+    let (kind, spec, has_action) = match ast.data {
+        ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssertProperty(
+            ref spec,
+            ref action,
+        )) => {
+            let (pass, fail) = lower_assertion_action_block(action);
+            let has_action = pass.is_some() || fail.is_some();
+            (hir::AssertKind::Assert, spec, has_action)
+        }
+        ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssumeProperty(
+            ref spec,
+            ref action,
+        )) => {
+            let (pass, fail) = lower_assertion_action_block(action);
+            let has_action = pass.is_some() || fail.is_some();
+            (hir::AssertKind::Assume, spec, has_action)
+        }
+        ast::AssertionData::Concurrent(ast::ConcurrentAssertion::CoverProperty(
+            ref spec,
+            ref stmt,
+        )) => {
+            let has_action = match stmt.kind {
+                ast::NullStmt => false,
+                _ => true,
+            };
+            (hir::AssertKind::Cover, spec, has_action)
+        }
+        _ => bug_span!(ast.span, cx, "not a concurrent assertion property"),
+    };
+    if has_action {
+        cx.emit(
+            DiagBuilder2::error("unsupported: action block of concurrent assertion")
+                .span(ast.span)
+                .add_note(
+                    "Concurrent assertions are emitted as `verif` operations, which cannot run \
+                     pass or fail actions",
+                ),
+        );
+        return Err(());
+    }
+
+    // Determine the clocking event, which may either be given before the
+    // property or as its outermost operator.
+    let (clock, prop) = match (&spec.clock, &spec.prop.data) {
+        (Some(clock), _) => (clock, &spec.prop),
+        (None, ast::PropExprData::Clocked(clock, prop)) => (clock, prop.as_ref()),
+        (None, _) => {
             cx.emit(
-                DiagBuilder2::warning("unsupported: immediate assertion; ignored")
-                    .span(stmt.human_span()),
+                DiagBuilder2::error("concurrent assertion has no clocking event")
+                    .span(ast.span)
+                    .add_note("Default clocking is not supported; specify `@(...)` explicitly."),
             );
-            hir::StmtKind::Null
+            return Err(());
+        }
+    };
+
+    let hir = hir::ConcAssert {
+        id: node_id,
+        span: ast.span,
+        label: ast.label.map(|(name, span)| Spanned::new(name, span)),
+        kind,
+        clock: cx.map_ast_with_parent(AstNode::EventExpr(clock), node_id),
+        disable: spec
+            .disable
+            .as_ref()
+            .map(|expr| cx.map_ast_with_parent(AstNode::Expr(expr), node_id)),
+        prop: lower_prop(cx, node_id, prop)?,
+    };
+    Ok(HirNode::ConcAssert(cx.arena().alloc_hir(hir)))
+}
+
+/// Lower a property expression to HIR.
+fn lower_prop<'gcx>(
+    cx: &impl Context<'gcx>,
+    parent: NodeId,
+    prop: &'gcx ast::PropExpr<'gcx>,
+) -> Result<hir::Prop> {
+    Ok(match prop.data {
+        // Strong and weak sequences are treated the same.
+        ast::PropExprData::SeqOp(_, ref seq) => hir::Prop::Seq(lower_seq(cx, parent, seq)?),
+        ast::PropExprData::SeqBinOp(op, _, ref seq, ref rhs) => {
+            let overlap = match op {
+                ast::PropSeqBinOp::ImplOverlap => true,
+                ast::PropSeqBinOp::ImplNonoverlap => false,
+                ast::PropSeqBinOp::FollowOverlap | ast::PropSeqBinOp::FollowNonoverlap => {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: followed-by operator").span(prop.span),
+                    );
+                    return Err(());
+                }
+            };
+            hir::Prop::Implication {
+                seq: lower_seq(cx, parent, seq)?,
+                overlap,
+                prop: Box::new(lower_prop(cx, parent, rhs)?),
+            }
+        }
+        ast::PropExprData::Not(ref arg) => hir::Prop::Not(Box::new(lower_prop(cx, parent, arg)?)),
+        ast::PropExprData::BinOp(op, ref lhs, ref rhs) => {
+            let lhs = Box::new(lower_prop(cx, parent, lhs)?);
+            let rhs = Box::new(lower_prop(cx, parent, rhs)?);
+            match op {
+                ast::PropBinOp::And => hir::Prop::And(lhs, rhs),
+                ast::PropBinOp::Or => hir::Prop::Or(lhs, rhs),
+                // `p implies q` is equivalent to `not p or q`.
+                ast::PropBinOp::Impl => hir::Prop::Or(Box::new(hir::Prop::Not(lhs)), rhs),
+                _ => {
+                    cx.emit(DiagBuilder2::error("unsupported: property operator").span(prop.span));
+                    return Err(());
+                }
+            }
+        }
+        ast::PropExprData::Clocked(..) => {
+            cx.emit(
+                DiagBuilder2::error("unsupported: clocking event within a property")
+                    .span(prop.span),
+            );
+            return Err(());
         }
-        _ => hir::StmtKind::Ast(stmt),
     })
 }
 
+/// Lower a sequence expression to HIR.
+fn lower_seq<'gcx>(
+    cx: &impl Context<'gcx>,
+    parent: NodeId,
+    seq: &'gcx ast::SeqExpr<'gcx>,
+) -> Result<hir::Seq> {
+    let map_expr = |expr| cx.map_ast_with_parent(AstNode::Expr(expr), parent);
+    let map_seq = |seq| lower_seq(cx, parent, seq).map(Box::new);
+    Ok(match seq.data {
+        ast::SeqExprData::Expr(ref expr, ref rep) => {
+            lower_seq_rep(cx, parent, hir::Seq::Bool(map_expr(expr)), rep, seq.span)?
+        }
+        ast::SeqExprData::Paren(ref inner, ref rep) => {
+            lower_seq_rep(cx, parent, lower_seq(cx, parent, inner)?, rep, seq.span)?
+        }
+        ast::SeqExprData::Delay(ref lhs, ref delay, ref rhs) => {
+            let lhs = match lhs {
+                Some(lhs) => Some(map_seq(lhs)?),
+                None => None,
+            };
+            let range = match *delay {
+                ast::SeqDelay::Fixed(ref n) => hir::SeqRange::Exact(map_expr(n)),
+                ast::SeqDelay::Range(ref lo, ref hi) => {
+                    hir::SeqRange::Range(map_expr(lo), hi.as_ref().map(map_expr))
+                }
+                ast::SeqDelay::Star => hir::SeqRange::Star,
+                ast::SeqDelay::Plus => hir::SeqRange::Plus,
+            };
+            hir::Seq::Delay(lhs, range, map_seq(rhs)?)
+        }
+        ast::SeqExprData::BinOp(op, ref lhs, ref rhs) => {
+            let lhs = map_seq(lhs)?;
+            let rhs = map_seq(rhs)?;
+            match op {
+                ast::SeqBinOp::And => hir::Seq::And(lhs, rhs),
+                ast::SeqBinOp::Or => hir::Seq::Or(lhs, rhs),
+                ast::SeqBinOp::Intersect => hir::Seq::Intersect(lhs, rhs),
+                ast::SeqBinOp::Within => {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: `within` sequence operator")
+                            .span(seq.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        // `b throughout s` is equivalent to `b[*] intersect s`.
+        ast::SeqExprData::Throughout(ref expr, ref rhs) => hir::Seq::Intersect(
+            Box::new(hir::Seq::Repeat(
+                Box::new(hir::Seq::Bool(map_expr(expr))),
+                hir::SeqRange::Star,
+            )),
+            map_seq(rhs)?,
+        ),
+        ast::SeqExprData::Clocked(..) => {
+            cx.emit(
+                DiagBuilder2::error("unsupported: clocking event within a sequence").span(seq.span),
+            );
+            return Err(());
+        }
+    })
+}
+
+/// Apply an optional repetition to a lowered sequence.
+fn lower_seq_rep<'gcx>(
+    cx: &impl Context<'gcx>,
+    parent: NodeId,
+    seq: hir::Seq,
+    rep: &'gcx Option<ast::SeqRep<'gcx>>,
+    span: Span,
+) -> Result<hir::Seq> {
+    let map_expr = |expr| cx.map_ast_with_parent(AstNode::Expr(expr), parent);
+    let rep = match rep {
+        Some(rep) => rep,
+        None => return Ok(seq),
+    };
+    let range = match *rep {
+        ast::SeqRep::Consec(ref n) => hir::SeqRange::Exact(map_expr(n)),
+        ast::SeqRep::ConsecRange(ref lo, ref hi) => {
+            hir::SeqRange::Range(map_expr(lo), hi.as_ref().map(map_expr))
+        }
+        ast::SeqRep::ConsecStar => hir::SeqRange::Star,
+        ast::SeqRep::ConsecPlus => hir::SeqRange::Plus,
+        ast::SeqRep::Nonconsec(ref n) | ast::SeqRep::Goto(ref n) => {
+            let arg = match seq {
+                hir::Seq::Bool(arg) => arg,
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(
+                            "goto and non-consecutive repetition require a boolean expression",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            };
+            return Ok(match *rep {
+                ast::SeqRep::Goto(..) => hir::Seq::GotoRepeat(arg, map_expr(n)),
+                _ => hir::Seq::NonconsecRepeat(arg, map_expr(n)),
+            });
+        }
+    };
+    Ok(hir::Seq::Repeat(Box::new(seq), range))
+}
+
 /// Parse a fixed point number into a [`BigRational`].
 ///
 /// The fractional part of the number is optional, such that this function may
@@ -1389,6 +2013,25 @@ fn parse_fixed_point_number<'gcx>(
     }
 }
 
+/// Lower a real number literal, given in Rust's floating point syntax.
+fn lower_real_literal<'gcx>(
+    cx: &impl Context<'gcx>,
+    span: Span,
+    value: String,
+) -> Result<hir::ExprKind<'gcx>> {
+    match value.parse::<f64>() {
+        Ok(v) => Ok(hir::ExprKind::RealConst(v.to_bits())),
+        Err(e) => {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` is not a valid real literal", value))
+                    .span(span)
+                    .add_note(format!("{}", e)),
+            );
+            Err(())
+        }
+    }
+}
+
 fn lower_event_expr<'gcx>(
     cx: &impl Context<'gcx>,
     expr: &'gcx ast::EventExpr<'gcx>,
@@ -1669,6 +2312,93 @@ fn lower_call<'a>(
                     Err(())
                 }
             };
+            let map_past = || match args {
+                [ast::CallArg {
+                    data:
+                        ast::CallArgData {
+                            expr: Some(ref arg),
+                            ..
+                        },
+                    ..
+                }] => Ok(hir::BuiltinCall::Past(
+                    cx.map_ast_with_parent(AstNode::Expr(arg), expr.id()),
+                    None,
+                )),
+                [ast::CallArg {
+                    data:
+                        ast::CallArgData {
+                            expr: Some(ref arg),
+                            ..
+                        },
+                    ..
+                }, ast::CallArg {
+                    data:
+                        ast::CallArgData {
+                            expr: Some(ref depth),
+                            ..
+                        },
+                    ..
+                }] => Ok(hir::BuiltinCall::Past(
+                    cx.map_ast_with_parent(AstNode::Expr(arg), expr.id()),
+                    Some(cx.map_ast_with_parent(AstNode::Expr(depth), expr.id())),
+                )),
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(format!("`{}` takes one or two arguments", ident))
+                            .span(expr.human_span()),
+                    );
+                    Err(())
+                }
+            };
+            let map_readmem = |hex| {
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|arg| {
+                        arg.expr
+                            .as_ref()
+                            .map(|arg| cx.map_ast_with_parent(AstNode::Expr(arg), expr.id()))
+                    })
+                    .collect();
+                match args.as_slice() {
+                    &[Some(file), Some(mem)] => Ok(hir::BuiltinCall::ReadMem {
+                        hex,
+                        file,
+                        mem,
+                        start: None,
+                        end: None,
+                    }),
+                    &[Some(file), Some(mem), start] => Ok(hir::BuiltinCall::ReadMem {
+                        hex,
+                        file,
+                        mem,
+                        start,
+                        end: None,
+                    }),
+                    &[Some(file), Some(mem), start, end] => Ok(hir::BuiltinCall::ReadMem {
+                        hex,
+                        file,
+                        mem,
+                        start,
+                        end,
+                    }),
+                    _ => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` takes a file name, a memory, and an optional address range",
+                                ident
+                            ))
+                            .span(expr.human_span()),
+                        );
+                        Err(())
+                    }
+                }
+            };
+            let map_sys_task = |task| {
+                for arg in args {
+                    cx.map_ast_with_parent(AstNode::CallArg(arg), expr.id());
+                }
+                hir::BuiltinCall::SysTask(task, args)
+            };
             hir::ExprKind::Builtin(match &*ident.value.as_str() {
                 "clog2" => hir::BuiltinCall::Clog2(map_unary_id()?),
                 "signed" => hir::BuiltinCall::Signed(map_unary_id()?),
@@ -1683,6 +2413,47 @@ fn lower_call<'a>(
                 "high" => map_array_dim(hir::ArrayDim::High)?,
                 "increment" => map_array_dim(hir::ArrayDim::Increment)?,
                 "size" => map_array_dim(hir::ArrayDim::Size)?,
+                "rose" => hir::BuiltinCall::Rose(map_unary_id()?),
+                "fell" => hir::BuiltinCall::Fell(map_unary_id()?),
+                "stable" => hir::BuiltinCall::Stable(map_unary_id()?),
+                "rtoi" => hir::BuiltinCall::RealConv(hir::RealConv::RealToInt, map_unary_id()?),
+                "itor" => hir::BuiltinCall::RealConv(hir::RealConv::IntToReal, map_unary_id()?),
+                "realtobits" => {
+                    hir::BuiltinCall::RealConv(hir::RealConv::RealToBits, map_unary_id()?)
+                }
+                "bitstoreal" => {
+                    hir::BuiltinCall::RealConv(hir::RealConv::BitsToReal, map_unary_id()?)
+                }
+                "shortrealtobits" => {
+                    hir::BuiltinCall::RealConv(hir::RealConv::ShortRealToBits, map_unary_id()?)
+                }
+                "bitstoshortreal" => {
+                    hir::BuiltinCall::RealConv(hir::RealConv::BitsToShortReal, map_unary_id()?)
+                }
+                "past" => map_past()?,
+                "readmemh" => map_readmem(true)?,
+                "readmemb" => map_readmem(false)?,
+                "display" => map_sys_task(hir::SysTask::Display),
+                "write" => map_sys_task(hir::SysTask::Write),
+                "monitor" => {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: system task `$monitor`")
+                            .span(expr.human_span())
+                            .add_note(
+                                "Printing again whenever an argument changes is not supported; \
+                                 use `$strobe` in a procedure sensitive to the arguments instead",
+                            ),
+                    );
+                    return Err(());
+                }
+                "strobe" => map_sys_task(hir::SysTask::Strobe),
+                "finish" => map_sys_task(hir::SysTask::Finish),
+                "stop" => map_sys_task(hir::SysTask::Stop),
+                "info" => map_sys_task(hir::SysTask::Info),
+                "warning" => map_sys_task(hir::SysTask::Warning),
+                "error" => map_sys_task(hir::SysTask::Error),
+                "fatal" => map_sys_task(hir::SysTask::Fatal),
+                "sformatf" => map_sys_task(hir::SysTask::SFormatF),
                 _ => {
                     cx.emit(
                         DiagBuilder2::warning(format!(
@@ -1734,6 +2505,31 @@ fn lower_call<'a>(
             // Package the call up.
             hir::ExprKind::FunctionCall(target, args)
         }
+        ast::MemberExpr {
+            expr: ref target,
+            name,
+        } => {
+            let target = cx.map_ast_with_parent(AstNode::Expr(target), expr.id());
+            let mut arg_ids = vec![];
+            for arg in args {
+                match arg.expr {
+                    Some(ref arg) => {
+                        arg_ids.push(cx.map_ast_with_parent(AstNode::Expr(arg), expr.id()))
+                    }
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "arguments of method `{}` cannot be omitted",
+                                name
+                            ))
+                            .span(arg.span()),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+            hir::ExprKind::MethodCall(target, name, arg_ids, None)
+        }
         _ => {
             error!("{:#?}", callee);
             cx.emit(
@@ -37,8 +37,13 @@ make_arenas!(
         genvar_decls: GenvarDecl,
         typedefs: Typedef,
         assigns: Assign,
+        conc_asserts: ConcAssert,
+        elab_tasks: ElabTask<'hir>,
+        pull_gates: PullGate,
         packages: Package,
         enum_variants: EnumVariant,
+        with_iterators: WithIterator,
+        pattern_vars: PatternVar,
     }
 );
 
@@ -57,6 +62,7 @@ pub(crate) fn accessed_nodes<'a>(
             read: Default::default(),
             written: Default::default(),
         },
+        tasks: vec![],
     };
     k.visit_node_with_id(node_id, false);
     Ok(Arc::new(k.table))
@@ -106,6 +112,8 @@ struct AccessTableCollector<'a, C> {
     cx: &'a C,
     env: ParamEnv,
     table: AccessTable,
+    /// The tasks whose body is currently being visited.
+    tasks: Vec<NodeId>,
 }
 
 impl<'a, 'gcx: 'a, C> Visitor<'gcx> for AccessTableCollector<'a, C>
@@ -166,6 +174,21 @@ where
                 }
                 true
             }
+            // Tasks are inlined into the calling process, such that the nodes
+            // accessed by their body are accessed by the caller.
+            mir::RvalueKind::Call { target, .. }
+                if target.prototype.kind == ast::SubroutineKind::Task
+                    && !self.tasks.contains(&target.id()) =>
+            {
+                self.tasks.push(target.id());
+                for item in &target.items {
+                    if let ast::SubroutineItem::Stmt(stmt) = item {
+                        self.visit_node_with_id(stmt.id(), false);
+                    }
+                }
+                self.tasks.pop();
+                true
+            }
             _ => true,
         }
     }
@@ -177,5 +200,9 @@ where
 {
     fn is_binding_interesting(&self, binding: NodeId) -> bool {
         !self.cx.is_parent_of(self.table.node_id, binding)
+            && !self
+                .tasks
+                .iter()
+                .any(|&id| self.cx.is_parent_of(id, binding))
     }
 }
@@ -31,8 +31,13 @@ pub enum HirNode<'a> {
     GenvarDecl(&'a GenvarDecl),
     Typedef(&'a Typedef),
     Assign(&'a Assign),
+    ConcAssert(&'a ConcAssert),
+    ElabTask(&'a ElabTask<'a>),
+    PullGate(&'a PullGate),
     Package(&'a Package),
     EnumVariant(&'a EnumVariant),
+    WithIterator(&'a WithIterator),
+    PatternVar(&'a PatternVar),
     SubroutinePort(&'a ast::SubroutinePort<'a>),
     CallArg(&'a ast::CallArg<'a>),
 }
@@ -58,8 +63,13 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::GenvarDecl(x) => x.span(),
             HirNode::Typedef(x) => x.span(),
             HirNode::Assign(x) => x.span(),
+            HirNode::ConcAssert(x) => x.span(),
+            HirNode::ElabTask(x) => x.span(),
+            HirNode::PullGate(x) => x.span(),
             HirNode::Package(x) => x.span(),
             HirNode::EnumVariant(x) => x.span(),
+            HirNode::WithIterator(x) => x.span(),
+            HirNode::PatternVar(x) => x.span(),
             HirNode::SubroutinePort(x) => x.span(),
             HirNode::CallArg(x) => x.span(),
         }
@@ -85,8 +95,13 @@ impl<'hir> HasSpan for HirNode<'hir> {
             HirNode::GenvarDecl(x) => x.human_span(),
             HirNode::Typedef(x) => x.human_span(),
             HirNode::Assign(x) => x.human_span(),
+            HirNode::ConcAssert(x) => x.human_span(),
+            HirNode::ElabTask(x) => x.human_span(),
+            HirNode::PullGate(x) => x.human_span(),
             HirNode::Package(x) => x.human_span(),
             HirNode::EnumVariant(x) => x.human_span(),
+            HirNode::WithIterator(x) => x.human_span(),
+            HirNode::PatternVar(x) => x.human_span(),
             HirNode::SubroutinePort(x) => x.human_span(),
             HirNode::CallArg(x) => x.human_span(),
         }
@@ -114,8 +129,13 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::GenvarDecl(x) => x.desc(),
             HirNode::Typedef(x) => x.desc(),
             HirNode::Assign(x) => x.desc(),
+            HirNode::ConcAssert(x) => x.desc(),
+            HirNode::ElabTask(x) => x.desc(),
+            HirNode::PullGate(x) => x.desc(),
             HirNode::Package(x) => x.desc(),
             HirNode::EnumVariant(x) => x.desc(),
+            HirNode::WithIterator(x) => x.desc(),
+            HirNode::PatternVar(x) => x.desc(),
             HirNode::SubroutinePort(..) => "subroutine port",
             HirNode::CallArg(..) => "call argument",
         }
@@ -141,8 +161,13 @@ impl<'hir> HasDesc for HirNode<'hir> {
             HirNode::GenvarDecl(x) => x.desc_full(),
             HirNode::Typedef(x) => x.desc_full(),
             HirNode::Assign(x) => x.desc_full(),
+            HirNode::ConcAssert(x) => x.desc_full(),
+            HirNode::ElabTask(x) => x.desc_full(),
+            HirNode::PullGate(x) => x.desc_full(),
             HirNode::Package(x) => x.desc_full(),
             HirNode::EnumVariant(x) => x.desc_full(),
+            HirNode::WithIterator(x) => x.desc_full(),
+            HirNode::PatternVar(x) => x.desc_full(),
             HirNode::SubroutinePort(x) => x.to_string(),
             HirNode::CallArg(x) => x.to_string(),
         }
@@ -207,6 +232,12 @@ pub struct ModuleBlock {
     pub params: Vec<NodeId>,
     /// The continuous assignments in the module.
     pub assigns: Vec<NodeId>,
+    /// The concurrent assertions in the module.
+    pub asserts: Vec<NodeId>,
+    /// The elaboration system tasks in the module.
+    pub elab_tasks: Vec<NodeId>,
+    /// The `pullup` and `pulldown` gates in the module.
+    pub pulls: Vec<NodeId>,
     /// The bottom of the name scope tree.
     pub last_rib: NodeId,
 }
@@ -545,6 +576,9 @@ impl HasDesc for TypeKind {
             TypeKind::Builtin(BuiltinType::Int) => "int type",
             TypeKind::Builtin(BuiltinType::Integer) => "integer type",
             TypeKind::Builtin(BuiltinType::LongInt) => "long int type",
+            TypeKind::Builtin(BuiltinType::ShortReal) => "short real type",
+            TypeKind::Builtin(BuiltinType::Real) => "real type",
+            TypeKind::Builtin(BuiltinType::RealTime) => "real time type",
             TypeKind::Struct(_) => "struct type",
             TypeKind::PackedArray(..) => "packed array type",
             _ => "type",
@@ -572,6 +606,9 @@ pub enum BuiltinType {
     LongInt,
     Time,
     String,
+    ShortReal,
+    Real,
+    RealTime,
 }
 
 /// An expression.
@@ -607,6 +644,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { .. } => "integer constant",
             ExprKind::TimeConst(_) => "time constant",
+            ExprKind::RealConst(_) => "real constant",
             ExprKind::Ident(_) => "identifier",
             _ => "expression",
         }
@@ -617,6 +655,7 @@ impl HasDesc for Expr<'_> {
         match self.kind {
             ExprKind::IntConst { value: ref k, .. } => format!("{} `{}`", self.desc(), k),
             ExprKind::TimeConst(ref k) => format!("{} `{}`", self.desc(), k),
+            ExprKind::RealConst(k) => format!("{} `{}`", self.desc(), f64::from_bits(k)),
             ExprKind::Ident(n) => format!("`{}`", n.value),
             ExprKind::PositionalPattern(..) => format!("positional pattern"),
             ExprKind::NamedPattern(..) => format!("named pattern"),
@@ -644,6 +683,11 @@ pub enum ExprKind<'a> {
     UnsizedConst(char),
     /// A time constant literal.
     TimeConst(BigRational),
+    /// A real constant literal such as `1.5` or `2e-3`.
+    ///
+    /// Holds the bit pattern of the IEEE 754 double precision value, such that
+    /// the expression remains `Eq`.
+    RealConst(u64),
     /// A string constant literal.
     StringConst(Spanned<Name>),
     /// An identifier.
@@ -670,6 +714,8 @@ pub enum ExprKind<'a> {
     RepeatPattern(NodeId, Vec<NodeId>),
     /// A concatenation such as `{a,b}` or `{4{a,b}}`.
     Concat(Option<NodeId>, Vec<NodeId>),
+    /// A streaming concatenation such as `{>>{a,b}}` or `{<<8{a,b}}`.
+    Stream(ast::StreamDir, Option<StreamSlice>, Vec<NodeId>),
     /// A cast `(ty, expr)` such as `foo'(bar)`.
     Cast(NodeId, NodeId),
     /// A sign cast such as `unsigned'(foo)`.
@@ -680,6 +726,21 @@ pub enum ExprKind<'a> {
     Inside(NodeId, Vec<Spanned<InsideRange>>),
     /// A function call such as `foo(a, b, c)`.
     FunctionCall(&'a ast::SubroutineDecl<'a>, &'a [ast::CallArg<'a>]),
+    /// A method call such as `a.len()`.
+    ///
+    /// The method is resolved during type checking, based on the type of the
+    /// target. Array methods may carry a `with` clause, given as the iterator
+    /// and the expression, such as `a.sum() with (item * 2)`.
+    MethodCall(NodeId, Spanned<Name>, Vec<NodeId>, Option<(NodeId, NodeId)>),
+    /// A dynamic array allocation `new[size]` or `new[size](init)`.
+    ArrayNew(NodeId, Option<NodeId>),
+    /// A tagged union expression such as `tagged Valid 42`, or a pattern such
+    /// as `tagged Valid .v`.
+    Tagged(Spanned<Name>, Option<NodeId>),
+    /// A pattern variable such as the `.v` in `tagged Valid .v`.
+    PatternVar(NodeId),
+    /// A wildcard pattern such as the `.*` in `tagged Valid .*`.
+    WildcardPattern,
     /// An assignment.
     Assign {
         op: ast::AssignOp,
@@ -763,6 +824,14 @@ pub enum BinaryOp {
     Eq,
     /// The inequality operator `x != y`.
     Neq,
+    /// The case equality operator `x === y`.
+    CaseEq,
+    /// The case inequality operator `x !== y`.
+    CaseNeq,
+    /// The wildcard equality operator `x ==? y`.
+    WildcardEq,
+    /// The wildcard inequality operator `x !=? y`.
+    WildcardNeq,
     /// The less-than operator `x < y`.
     Lt,
     /// The less-than-or-equal operator `x <= y`.
@@ -808,6 +877,10 @@ impl HasDesc for BinaryOp {
             BinaryOp::Pow => "`**` operator",
             BinaryOp::Eq => "`==` operator",
             BinaryOp::Neq => "`!=` operator",
+            BinaryOp::CaseEq => "`===` operator",
+            BinaryOp::CaseNeq => "`!==` operator",
+            BinaryOp::WildcardEq => "`==?` operator",
+            BinaryOp::WildcardNeq => "`!=?` operator",
             BinaryOp::Lt => "`<` operator",
             BinaryOp::Leq => "`<=` operator",
             BinaryOp::Gt => "`>` operator",
@@ -860,6 +933,500 @@ pub enum BuiltinCall<'a> {
     IsUnknown(&'a ast::Expr<'a>),
     /// A call to one of the array dimension functions.
     ArrayDim(ArrayDim, &'a ast::Expr<'a>, Option<&'a ast::Expr<'a>>),
+    /// A call to the sampled value function `$rose(x)`.
+    Rose(NodeId),
+    /// A call to the sampled value function `$fell(x)`.
+    Fell(NodeId),
+    /// A call to the sampled value function `$stable(x)`.
+    Stable(NodeId),
+    /// A call to the sampled value function `$past(x)` or `$past(x, n)`.
+    Past(NodeId, Option<NodeId>),
+    /// A call to one of the display, simulation control, or severity tasks.
+    SysTask(SysTask, &'a [ast::CallArg<'a>]),
+    /// A call to one of the real conversion functions, such as `$rtoi(x)`.
+    RealConv(RealConv, NodeId),
+    /// A call to `$readmemh` or `$readmemb`.
+    ReadMem {
+        /// Whether the file holds hexadecimal (`$readmemh`) or binary
+        /// (`$readmemb`) words.
+        hex: bool,
+        /// The file name.
+        file: NodeId,
+        /// The memory to be initialized.
+        mem: NodeId,
+        /// The optional start address.
+        start: Option<NodeId>,
+        /// The optional end address.
+        end: Option<NodeId>,
+    },
+}
+
+/// The conversion functions between real and integral values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RealConv {
+    /// `$rtoi(x)`, which truncates a real to an `integer`.
+    RealToInt,
+    /// `$itor(x)`, which converts an integral value to a real.
+    IntToReal,
+    /// `$realtobits(x)`, which returns the 64 bits of a real.
+    RealToBits,
+    /// `$bitstoreal(x)`, which interprets 64 bits as a real.
+    BitsToReal,
+    /// `$shortrealtobits(x)`, which returns the 32 bits of a shortreal.
+    ShortRealToBits,
+    /// `$bitstoshortreal(x)`, which interprets 32 bits as a shortreal.
+    BitsToShortReal,
+}
+
+/// The built-in methods that can be called on a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// A method of the `string` type.
+    String(StringMethod),
+    /// A method of the `event` type.
+    Event(EventMethod),
+    /// A method of dynamic arrays, queues, and associative arrays.
+    Array(ArrayMethod),
+    /// A method of an enum type.
+    Enum(EnumMethod),
+    /// An array manipulation method of a fixed-size unpacked array.
+    ArrayManip(ArrayManipMethod),
+}
+
+impl Method {
+    /// Get the number of arguments the method takes.
+    pub fn num_args(self) -> usize {
+        match self {
+            Method::String(method) => method.num_args(),
+            Method::Event(_) => 0,
+            Method::Array(method) => method.num_args(),
+            Method::Enum(method) => method.num_args(),
+            Method::ArrayManip(_) => 0,
+        }
+    }
+
+    /// Check whether the method may be followed by a `with` clause.
+    pub fn allows_with(self) -> bool {
+        match self {
+            Method::ArrayManip(method) => method.allows_with(),
+            _ => false,
+        }
+    }
+
+    /// Check whether the method must be followed by a `with` clause.
+    pub fn requires_with(self) -> bool {
+        match self {
+            Method::ArrayManip(method) => method.requires_with(),
+            _ => false,
+        }
+    }
+}
+
+/// The built-in methods of the `string` type.
+///
+/// See §6.16 "String data type".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringMethod {
+    /// `s.len()`, which returns the number of characters.
+    Len,
+    /// `s.putc(i, c)`, which replaces the character at index `i`.
+    Putc,
+    /// `s.getc(i)`, which returns the character at index `i`.
+    Getc,
+    /// `s.substr(i, j)`, which returns the characters `i` through `j`.
+    Substr,
+    /// `s.atoi()`, `s.atohex()`, `s.atooct()`, or `s.atobin()`, which parse
+    /// the string as an integer in the given radix.
+    AtoI(u32),
+    /// `s.itoa(i)`, `s.hextoa(i)`, `s.octtoa(i)`, or `s.bintoa(i)`, which
+    /// replace the string with the representation of an integer in the given
+    /// radix.
+    ItoA(u32),
+}
+
+impl StringMethod {
+    /// Look up a string method by name.
+    pub fn from_name(name: Name) -> Option<Self> {
+        Some(match &*name.as_str() {
+            "len" => StringMethod::Len,
+            "putc" => StringMethod::Putc,
+            "getc" => StringMethod::Getc,
+            "substr" => StringMethod::Substr,
+            "atoi" => StringMethod::AtoI(10),
+            "atohex" => StringMethod::AtoI(16),
+            "atooct" => StringMethod::AtoI(8),
+            "atobin" => StringMethod::AtoI(2),
+            "itoa" => StringMethod::ItoA(10),
+            "hextoa" => StringMethod::ItoA(16),
+            "octtoa" => StringMethod::ItoA(8),
+            "bintoa" => StringMethod::ItoA(2),
+            _ => return None,
+        })
+    }
+
+    /// Get the number of arguments the method takes.
+    pub fn num_args(self) -> usize {
+        match self {
+            StringMethod::Len | StringMethod::AtoI(_) => 0,
+            StringMethod::Getc | StringMethod::ItoA(_) => 1,
+            StringMethod::Putc | StringMethod::Substr => 2,
+        }
+    }
+
+    /// Check whether the method modifies the string it is called on.
+    pub fn modifies_target(self) -> bool {
+        match self {
+            StringMethod::Putc | StringMethod::ItoA(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The built-in methods of the `event` type.
+///
+/// See §15.5.3 "Persistent trigger: triggered built-in method".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventMethod {
+    /// `e.triggered`, which checks whether the event has been triggered in
+    /// the current time step.
+    Triggered,
+}
+
+impl EventMethod {
+    /// Look up an event method by name.
+    pub fn from_name(name: Name) -> Option<Self> {
+        match &*name.as_str() {
+            "triggered" => Some(EventMethod::Triggered),
+            _ => None,
+        }
+    }
+}
+
+/// The built-in methods of dynamic arrays, queues, and associative arrays.
+///
+/// See §7.5.1 "new[ ]", §7.5.2 "Size()", §7.5.3 "Delete()", §7.9 "Associative
+/// array methods", and §7.10.2 "Queue methods".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArrayMethod {
+    /// `a.size()` or `a.num()`, which returns the number of elements.
+    Size,
+    /// `a.delete()`, which removes all elements.
+    Delete,
+    /// `a.delete(i)`, which removes the element at index or key `i`.
+    DeleteIndex,
+    /// `a.exists(k)`, which checks whether an element with key `k` exists.
+    Exists,
+    /// `q.push_front(x)`, which inserts `x` at the front of a queue.
+    PushFront,
+    /// `q.push_back(x)`, which inserts `x` at the back of a queue.
+    PushBack,
+    /// `q.pop_front()`, which removes and returns the first element.
+    PopFront,
+    /// `q.pop_back()`, which removes and returns the last element.
+    PopBack,
+    /// `a.first(k)`, which assigns the smallest key to `k`.
+    First,
+    /// `a.last(k)`, which assigns the largest key to `k`.
+    Last,
+    /// `a.next(k)`, which assigns the next larger key to `k`.
+    Next,
+    /// `a.prev(k)`, which assigns the next smaller key to `k`.
+    Prev,
+}
+
+impl ArrayMethod {
+    /// Look up an array method by name.
+    ///
+    /// `delete` refers to `DeleteIndex` if it is called with an argument.
+    pub fn from_name(name: Name, num_args: usize) -> Option<Self> {
+        Some(match &*name.as_str() {
+            "size" | "num" => ArrayMethod::Size,
+            "delete" if num_args > 0 => ArrayMethod::DeleteIndex,
+            "delete" => ArrayMethod::Delete,
+            "exists" => ArrayMethod::Exists,
+            "push_front" => ArrayMethod::PushFront,
+            "push_back" => ArrayMethod::PushBack,
+            "pop_front" => ArrayMethod::PopFront,
+            "pop_back" => ArrayMethod::PopBack,
+            "first" => ArrayMethod::First,
+            "last" => ArrayMethod::Last,
+            "next" => ArrayMethod::Next,
+            "prev" => ArrayMethod::Prev,
+            _ => return None,
+        })
+    }
+
+    /// Check whether the method is available on arrays with dimension `dim`.
+    pub fn applies_to(self, dim: ty::UnpackedDim) -> bool {
+        match (self, dim) {
+            (ArrayMethod::Size, _) | (ArrayMethod::Delete, _) => true,
+            (ArrayMethod::DeleteIndex, ty::UnpackedDim::Queue(_))
+            | (ArrayMethod::DeleteIndex, ty::UnpackedDim::Assoc(_)) => true,
+            (ArrayMethod::PushFront, ty::UnpackedDim::Queue(_))
+            | (ArrayMethod::PushBack, ty::UnpackedDim::Queue(_))
+            | (ArrayMethod::PopFront, ty::UnpackedDim::Queue(_))
+            | (ArrayMethod::PopBack, ty::UnpackedDim::Queue(_)) => true,
+            (ArrayMethod::Exists, ty::UnpackedDim::Assoc(_))
+            | (ArrayMethod::First, ty::UnpackedDim::Assoc(_))
+            | (ArrayMethod::Last, ty::UnpackedDim::Assoc(_))
+            | (ArrayMethod::Next, ty::UnpackedDim::Assoc(_))
+            | (ArrayMethod::Prev, ty::UnpackedDim::Assoc(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Get the number of arguments the method takes.
+    pub fn num_args(self) -> usize {
+        match self {
+            ArrayMethod::Size
+            | ArrayMethod::Delete
+            | ArrayMethod::PopFront
+            | ArrayMethod::PopBack => 0,
+            _ => 1,
+        }
+    }
+
+    /// Check whether the method modifies the array it is called on.
+    pub fn modifies_target(self) -> bool {
+        match self {
+            ArrayMethod::Delete
+            | ArrayMethod::DeleteIndex
+            | ArrayMethod::PushFront
+            | ArrayMethod::PushBack
+            | ArrayMethod::PopFront
+            | ArrayMethod::PopBack => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether the method assigns to its argument, like `a.next(k)`.
+    pub fn modifies_args(self) -> bool {
+        match self {
+            ArrayMethod::First | ArrayMethod::Last | ArrayMethod::Next | ArrayMethod::Prev => true,
+            _ => false,
+        }
+    }
+}
+
+/// The array manipulation methods of fixed-size unpacked arrays.
+///
+/// See §7.12 "Array manipulation methods".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArrayManipMethod {
+    /// `a.sum()`, which adds up the elements.
+    Sum,
+    /// `a.product()`, which multiplies the elements.
+    Product,
+    /// `a.and()`, which computes the bitwise AND of the elements.
+    And,
+    /// `a.or()`, which computes the bitwise OR of the elements.
+    Or,
+    /// `a.xor()`, which computes the bitwise XOR of the elements.
+    Xor,
+    /// `a.min()`, which returns a queue with the smallest element.
+    Min,
+    /// `a.max()`, which returns a queue with the largest element.
+    Max,
+    /// `a.unique()`, which returns a queue with one of each distinct element.
+    Unique,
+    /// `a.unique_index()`, which returns the indices of `a.unique()`.
+    UniqueIndex,
+    /// `a.find() with (...)`, which returns all matching elements.
+    Find,
+    /// `a.find_index() with (...)`, which returns the indices of all matching
+    /// elements.
+    FindIndex,
+    /// `a.find_first() with (...)`, which returns the first matching element.
+    FindFirst,
+    /// `a.find_first_index() with (...)`, which returns the index of the
+    /// first matching element.
+    FindFirstIndex,
+    /// `a.find_last() with (...)`, which returns the last matching element.
+    FindLast,
+    /// `a.find_last_index() with (...)`, which returns the index of the last
+    /// matching element.
+    FindLastIndex,
+    /// `a.reverse()`, which reverses the order of the elements.
+    Reverse,
+    /// `a.sort()`, which sorts the elements in ascending order.
+    Sort,
+    /// `a.rsort()`, which sorts the elements in descending order.
+    Rsort,
+}
+
+impl ArrayManipMethod {
+    /// Look up an array manipulation method by name.
+    pub fn from_name(name: Name) -> Option<Self> {
+        Some(match &*name.as_str() {
+            "sum" => ArrayManipMethod::Sum,
+            "product" => ArrayManipMethod::Product,
+            "and" => ArrayManipMethod::And,
+            "or" => ArrayManipMethod::Or,
+            "xor" => ArrayManipMethod::Xor,
+            "min" => ArrayManipMethod::Min,
+            "max" => ArrayManipMethod::Max,
+            "unique" => ArrayManipMethod::Unique,
+            "unique_index" => ArrayManipMethod::UniqueIndex,
+            "find" => ArrayManipMethod::Find,
+            "find_index" => ArrayManipMethod::FindIndex,
+            "find_first" => ArrayManipMethod::FindFirst,
+            "find_first_index" => ArrayManipMethod::FindFirstIndex,
+            "find_last" => ArrayManipMethod::FindLast,
+            "find_last_index" => ArrayManipMethod::FindLastIndex,
+            "reverse" => ArrayManipMethod::Reverse,
+            "sort" => ArrayManipMethod::Sort,
+            "rsort" => ArrayManipMethod::Rsort,
+            _ => return None,
+        })
+    }
+
+    /// Check whether the method reduces the array to a single value.
+    pub fn is_reduction(self) -> bool {
+        match self {
+            ArrayManipMethod::Sum
+            | ArrayManipMethod::Product
+            | ArrayManipMethod::And
+            | ArrayManipMethod::Or
+            | ArrayManipMethod::Xor => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether the method returns the indices of elements rather than
+    /// the elements themselves.
+    pub fn returns_indices(self) -> bool {
+        match self {
+            ArrayManipMethod::UniqueIndex
+            | ArrayManipMethod::FindIndex
+            | ArrayManipMethod::FindFirstIndex
+            | ArrayManipMethod::FindLastIndex => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether the method may be followed by a `with` clause.
+    pub fn allows_with(self) -> bool {
+        self != ArrayManipMethod::Reverse
+    }
+
+    /// Check whether the method must be followed by a `with` clause, which
+    /// selects the elements to locate.
+    pub fn requires_with(self) -> bool {
+        match self {
+            ArrayManipMethod::Find
+            | ArrayManipMethod::FindIndex
+            | ArrayManipMethod::FindFirst
+            | ArrayManipMethod::FindFirstIndex
+            | ArrayManipMethod::FindLast
+            | ArrayManipMethod::FindLastIndex => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether the method modifies the array it is called on.
+    pub fn modifies_target(self) -> bool {
+        match self {
+            ArrayManipMethod::Reverse | ArrayManipMethod::Sort | ArrayManipMethod::Rsort => true,
+            _ => false,
+        }
+    }
+}
+
+/// The built-in methods of enum types.
+///
+/// See §6.19.5 "Enumerated type methods".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnumMethod {
+    /// `e.first()`, which returns the first member of the enum.
+    First,
+    /// `e.last()`, which returns the last member of the enum.
+    Last,
+    /// `e.num()`, which returns the number of members in the enum.
+    Num,
+    /// `e.next()` or `e.next(N)`, which returns the Nth member after the
+    /// current value. The flag indicates whether `N` is given explicitly.
+    Next(bool),
+    /// `e.prev()` or `e.prev(N)`, which returns the Nth member before the
+    /// current value. The flag indicates whether `N` is given explicitly.
+    Prev(bool),
+    /// `e.name()`, which returns the name of the current value as a string.
+    Name,
+}
+
+impl EnumMethod {
+    /// Look up an enum method by name.
+    ///
+    /// `next` and `prev` take an optional step count.
+    pub fn from_name(name: Name, num_args: usize) -> Option<Self> {
+        Some(match &*name.as_str() {
+            "first" => EnumMethod::First,
+            "last" => EnumMethod::Last,
+            "num" => EnumMethod::Num,
+            "next" => EnumMethod::Next(num_args > 0),
+            "prev" => EnumMethod::Prev(num_args > 0),
+            "name" => EnumMethod::Name,
+            _ => return None,
+        })
+    }
+
+    /// Get the number of arguments the method takes.
+    pub fn num_args(self) -> usize {
+        match self {
+            EnumMethod::Next(true) | EnumMethod::Prev(true) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// The system tasks which are lowered to calls into the runtime library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SysTask {
+    /// The `$display` task.
+    Display,
+    /// The `$write` task.
+    Write,
+    /// The `$strobe` task.
+    Strobe,
+    /// The `$finish` task.
+    Finish,
+    /// The `$stop` task.
+    Stop,
+    /// The `$info` severity task.
+    Info,
+    /// The `$warning` severity task.
+    Warning,
+    /// The `$error` severity task.
+    Error,
+    /// The `$fatal` severity task.
+    Fatal,
+    /// The `$sformatf` function.
+    SFormatF,
+}
+
+impl SysTask {
+    /// Check whether this task prints a message.
+    pub fn is_print(self) -> bool {
+        match self {
+            SysTask::Finish | SysTask::Stop | SysTask::SFormatF => false,
+            _ => true,
+        }
+    }
+
+    /// Check whether this task assembles a message from a format string.
+    pub fn has_message(self) -> bool {
+        match self {
+            SysTask::Finish | SysTask::Stop => false,
+            _ => true,
+        }
+    }
+
+    /// Check whether this is one of the severity tasks.
+    pub fn is_severity(self) -> bool {
+        match self {
+            SysTask::Info | SysTask::Warning | SysTask::Error | SysTask::Fatal => true,
+            _ => false,
+        }
+    }
 }
 
 /// The different builtin array dimension function calls that are supported.
@@ -891,6 +1458,8 @@ pub struct VarDecl {
     pub init: Option<NodeId>,
     /// Variable or net-specific data
     pub kind: ast::VarKind,
+    /// Drive or charge strength of a net
+    pub strength: Option<ast::NetStrength>,
 }
 
 impl HasSpan for VarDecl {
@@ -973,6 +1542,8 @@ impl HasDesc for Stmt<'_> {
             StmtKind::Null => "null statement",
             StmtKind::Block(_) => "block",
             StmtKind::Assign { .. } => "assign statement",
+            StmtKind::Assert { .. } => "assertion",
+            StmtKind::Trigger { .. } => "event trigger",
             _ => "statement",
         }
     }
@@ -1024,6 +1595,31 @@ pub enum StmtKind<'a> {
         default: Option<NodeId>,
         kind: ast::CaseKind,
     },
+    /// A pattern matching case statement, like `case (x) matches ...`.
+    PatternCase {
+        expr: NodeId,
+        ways: Vec<(NodeId, NodeId)>,
+        default: Option<NodeId>,
+    },
+    /// An immediate or deferred assertion.
+    ///
+    /// ```text
+    /// assert [#0|final] (<cond>) [<pass_stmt>] [else <fail_stmt>]
+    /// ```
+    Assert {
+        kind: AssertKind,
+        deferred: Option<DeferredMode>,
+        cond: NodeId,
+        pass_stmt: Option<NodeId>,
+        fail_stmt: Option<NodeId>,
+    },
+    /// An event trigger statement.
+    ///
+    /// ```text
+    /// -> <target>
+    /// ->> <target>
+    /// ```
+    Trigger { target: NodeId, nonblocking: bool },
     /// A statement in the AST that requires no representational change.
     Ast(&'a ast::Stmt<'a>),
 }
@@ -1039,6 +1635,26 @@ pub enum AssignKind {
     NonblockDelay(NodeId),
 }
 
+/// The different forms an assertion can take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssertKind {
+    /// An `assert` statement.
+    Assert,
+    /// An `assume` statement.
+    Assume,
+    /// A `cover` statement.
+    Cover,
+}
+
+/// The point at which a deferred assertion reports its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeferredMode {
+    /// An `assert #0`, reported in the observed region.
+    Observed,
+    /// An `assert final`, reported at the end of the time step.
+    Final,
+}
+
 /// The different forms a loop can take.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoopKind {
@@ -1199,6 +1815,8 @@ pub struct Assign {
     pub span: Span,
     pub lhs: NodeId,
     pub rhs: NodeId,
+    /// The `(strength0, strength1)` drive strength of the assignment.
+    pub strength: Option<(ast::DriveStrength, ast::DriveStrength)>,
 }
 
 impl HasSpan for Assign {
@@ -1213,6 +1831,189 @@ impl HasDesc for Assign {
     }
 }
 
+/// A concurrent assertion.
+///
+/// For example `assert property (@(posedge clk) disable iff (rst) a |-> b)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcAssert {
+    pub id: NodeId,
+    pub span: Span,
+    /// The optional label of the assertion.
+    pub label: Option<Spanned<Name>>,
+    /// Whether this is an `assert`, `assume`, or `cover property`.
+    pub kind: AssertKind,
+    /// The clocking event, lowered as an `EventExpr`.
+    pub clock: NodeId,
+    /// The `disable iff` condition.
+    pub disable: Option<NodeId>,
+    /// The property being checked.
+    pub prop: Prop,
+}
+
+impl HasSpan for ConcAssert {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl HasDesc for ConcAssert {
+    fn desc(&self) -> &'static str {
+        "concurrent assertion"
+    }
+}
+
+/// An elaboration system task.
+///
+/// For example `$error("unsupported width %0d", W)` in a generate block. These
+/// are evaluated when the enclosing scope is elaborated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElabTask<'a> {
+    pub id: NodeId,
+    pub span: Span,
+    /// Which of the severity tasks this is.
+    pub task: SysTask,
+    /// The task arguments.
+    pub args: &'a [ast::CallArg<'a>],
+}
+
+impl HasSpan for ElabTask<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl HasDesc for ElabTask<'_> {
+    fn desc(&self) -> &'static str {
+        "elaboration system task"
+    }
+}
+
+/// A `pullup` or `pulldown` gate.
+///
+/// For example `pullup (weak1) (a), (b);`. Each target net is driven with a
+/// constant one or zero at the given strength.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullGate {
+    pub id: NodeId,
+    pub span: Span,
+    /// Whether this is a `pullup` or `pulldown`.
+    pub pullup: bool,
+    /// The strength with which the targets are driven.
+    pub strength: ast::DriveStrength,
+    /// The nets driven by the gate.
+    pub targets: Vec<NodeId>,
+}
+
+impl HasSpan for PullGate {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl HasDesc for PullGate {
+    fn desc(&self) -> &'static str {
+        if self.pullup {
+            "pullup"
+        } else {
+            "pulldown"
+        }
+    }
+}
+
+/// A property expression in a concurrent assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prop {
+    /// A sequence that must match.
+    Seq(Seq),
+    /// The negation `not p`.
+    Not(Box<Prop>),
+    /// The conjunction `p and q`.
+    And(Box<Prop>, Box<Prop>),
+    /// The disjunction `p or q`.
+    Or(Box<Prop>, Box<Prop>),
+    /// The implication `s |-> p` if `overlap` is set, or `s |=> p` otherwise.
+    Implication {
+        seq: Seq,
+        overlap: bool,
+        prop: Box<Prop>,
+    },
+}
+
+impl Prop {
+    /// Check whether an expression is one of the boolean operands of the
+    /// property.
+    pub fn has_bool_operand(&self, id: NodeId) -> bool {
+        match *self {
+            Prop::Seq(ref seq) => seq.has_bool_operand(id),
+            Prop::Not(ref prop) => prop.has_bool_operand(id),
+            Prop::And(ref lhs, ref rhs) | Prop::Or(ref lhs, ref rhs) => {
+                lhs.has_bool_operand(id) || rhs.has_bool_operand(id)
+            }
+            Prop::Implication {
+                ref seq, ref prop, ..
+            } => seq.has_bool_operand(id) || prop.has_bool_operand(id),
+        }
+    }
+}
+
+/// A sequence expression in a concurrent assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Seq {
+    /// A boolean expression which holds for one cycle.
+    Bool(NodeId),
+    /// A cycle delay `s ##[range] t`, or `##[range] t` without a left-hand
+    /// side.
+    Delay(Option<Box<Seq>>, SeqRange, Box<Seq>),
+    /// A consecutive repetition `s [*range]`.
+    Repeat(Box<Seq>, SeqRange),
+    /// A goto repetition `b [-> n]`.
+    GotoRepeat(NodeId, NodeId),
+    /// A non-consecutive repetition `b [= n]`.
+    NonconsecRepeat(NodeId, NodeId),
+    /// The sequence conjunction `s and t`.
+    And(Box<Seq>, Box<Seq>),
+    /// The sequence disjunction `s or t`.
+    Or(Box<Seq>, Box<Seq>),
+    /// The sequence intersection `s intersect t`.
+    Intersect(Box<Seq>, Box<Seq>),
+}
+
+impl Seq {
+    /// Check whether an expression is one of the boolean operands of the
+    /// sequence.
+    pub fn has_bool_operand(&self, id: NodeId) -> bool {
+        match *self {
+            Seq::Bool(x) | Seq::GotoRepeat(x, _) | Seq::NonconsecRepeat(x, _) => x == id,
+            Seq::Delay(ref lhs, _, ref rhs) => {
+                lhs.as_ref()
+                    .map(|x| x.has_bool_operand(id))
+                    .unwrap_or(false)
+                    || rhs.has_bool_operand(id)
+            }
+            Seq::Repeat(ref seq, _) => seq.has_bool_operand(id),
+            Seq::And(ref lhs, ref rhs)
+            | Seq::Or(ref lhs, ref rhs)
+            | Seq::Intersect(ref lhs, ref rhs) => {
+                lhs.has_bool_operand(id) || rhs.has_bool_operand(id)
+            }
+        }
+    }
+}
+
+/// The number of cycles of a sequence delay, or the number of repetitions of
+/// a sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqRange {
+    /// Exactly `n`.
+    Exact(NodeId),
+    /// Between `m` and `n`, or at least `m` if the upper bound is `$`.
+    Range(NodeId, Option<NodeId>),
+    /// Zero or more, as in `[*]`.
+    Star,
+    /// One or more, as in `[+]`.
+    Plus,
+}
+
 /// A package.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Package {
@@ -1283,6 +2084,69 @@ impl HasDesc for EnumVariant {
     }
 }
 
+/// The iterator of an array method's `with` clause.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithIterator {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The array being iterated over.
+    pub array: NodeId,
+}
+
+impl HasSpan for WithIterator {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for WithIterator {
+    fn desc(&self) -> &'static str {
+        "iterator"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("iterator `{}`", self.name.value)
+    }
+}
+
+/// A variable bound by a pattern in a `case ... matches` item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternVar {
+    pub id: NodeId,
+    pub name: Spanned<Name>,
+    pub span: Span,
+    /// The value being matched by the case statement.
+    pub scrutinee: NodeId,
+    /// The tagged union members the variable binds to, from the outermost
+    /// pattern inwards.
+    pub path: Vec<Spanned<Name>>,
+}
+
+impl HasSpan for PatternVar {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn human_span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl HasDesc for PatternVar {
+    fn desc(&self) -> &'static str {
+        "pattern variable"
+    }
+
+    fn desc_full(&self) -> String {
+        format!("pattern variable `{}`", self.name.value)
+    }
+}
+
 /// A named pattern mapping.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PatternMapping {
@@ -1294,6 +2158,15 @@ pub enum PatternMapping {
     Default,
 }
 
+/// The slice size of a streaming concatenation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamSlice {
+    /// A slice size given as a constant expression, e.g. `{<<8{...}}`.
+    Expr(NodeId),
+    /// A slice size given as a type, e.g. `{<<byte{...}}`.
+    Type(NodeId),
+}
+
 /// Single values or value ranges admissible in `inside` sets.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InsideRange {
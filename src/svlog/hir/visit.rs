@@ -37,6 +37,9 @@ pub trait Visitor<'a>: Sized {
             HirNode::Typedef(x) => self.visit_typedef(x),
             HirNode::VarDecl(x) => self.visit_var_decl(x),
             HirNode::Assign(x) => self.visit_assign(x),
+            HirNode::ConcAssert(x) => self.visit_conc_assert(x),
+            HirNode::ElabTask(x) => self.visit_elab_task(x),
+            HirNode::PullGate(x) => self.visit_pull_gate(x),
             HirNode::IntPort(x) => self.visit_int_port(x),
             HirNode::ExtPort(x) => self.visit_ext_port(x),
             HirNode::Inst(x) => self.visit_inst(x),
@@ -89,6 +92,18 @@ pub trait Visitor<'a>: Sized {
         walk_assign(self, assign);
     }
 
+    fn visit_conc_assert(&mut self, assert: &'a ConcAssert) {
+        walk_conc_assert(self, assert);
+    }
+
+    fn visit_elab_task(&mut self, task: &'a ElabTask<'a>) {
+        walk_elab_task(self, task);
+    }
+
+    fn visit_pull_gate(&mut self, gate: &'a PullGate) {
+        walk_pull_gate(self, gate);
+    }
+
     fn visit_int_port(&mut self, int_port: &'a IntPort) {
         walk_int_port(self, int_port);
     }
@@ -140,6 +155,15 @@ pub fn walk_module_block<'a>(visitor: &mut impl Visitor<'a>, blk: &'a ModuleBloc
     for &id in &blk.assigns {
         visitor.visit_node_with_id(id, false);
     }
+    for &id in &blk.asserts {
+        visitor.visit_node_with_id(id, false);
+    }
+    for &id in &blk.elab_tasks {
+        visitor.visit_node_with_id(id, false);
+    }
+    for &id in &blk.pulls {
+        visitor.visit_node_with_id(id, false);
+    }
 }
 
 /// Walk the contents of a procedure.
@@ -213,6 +237,35 @@ pub fn walk_stmt<'a>(visitor: &mut impl Visitor<'a>, stmt: &'a Stmt) {
                 visitor.visit_node_with_id(default, false);
             }
         }
+        StmtKind::PatternCase {
+            expr,
+            ref ways,
+            default,
+        } => {
+            visitor.visit_node_with_id(expr, false);
+            for &(pattern, stmt) in ways {
+                visitor.visit_node_with_id(pattern, false);
+                visitor.visit_node_with_id(stmt, false);
+            }
+            if let Some(default) = default {
+                visitor.visit_node_with_id(default, false);
+            }
+        }
+        StmtKind::Assert {
+            cond,
+            pass_stmt,
+            fail_stmt,
+            ..
+        } => {
+            visitor.visit_node_with_id(cond, false);
+            if let Some(pass_stmt) = pass_stmt {
+                visitor.visit_node_with_id(pass_stmt, false);
+            }
+            if let Some(fail_stmt) = fail_stmt {
+                visitor.visit_node_with_id(fail_stmt, false);
+            }
+        }
+        StmtKind::Trigger { target, .. } => visitor.visit_node_with_id(target, true),
         StmtKind::Ast(ast) => visitor.visit_node_with_id(ast.id(), false),
     }
 }
@@ -224,7 +277,10 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         | ExprKind::IntConst { .. }
         | ExprKind::UnsizedConst(_)
         | ExprKind::TimeConst(_)
-        | ExprKind::StringConst(_) => (),
+        | ExprKind::RealConst(_)
+        | ExprKind::StringConst(_)
+        | ExprKind::PatternVar(_)
+        | ExprKind::WildcardPattern => (),
         ExprKind::Ident(x) => {
             visitor.visit_ident(x);
         }
@@ -240,6 +296,37 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         ExprKind::Field(expr, _) => {
             visitor.visit_node_with_id(expr, lvalue);
         }
+        ExprKind::MethodCall(target, name, ref args, with) => {
+            let array_method = ArrayMethod::from_name(name.value, args.len());
+            let modifies = StringMethod::from_name(name.value)
+                .map(|method| method.modifies_target())
+                .or_else(|| array_method.map(|method| method.modifies_target()))
+                .or_else(|| {
+                    ArrayManipMethod::from_name(name.value).map(|method| method.modifies_target())
+                })
+                .unwrap_or(false);
+            let modifies_args = array_method
+                .map(|method| method.modifies_args())
+                .unwrap_or(false);
+            visitor.visit_node_with_id(target, lvalue || modifies);
+            for &arg in args {
+                visitor.visit_node_with_id(arg, modifies_args);
+            }
+            if let Some((_, with)) = with {
+                visitor.visit_node_with_id(with, false);
+            }
+        }
+        ExprKind::ArrayNew(size, init) => {
+            visitor.visit_node_with_id(size, false);
+            if let Some(init) = init {
+                visitor.visit_node_with_id(init, false);
+            }
+        }
+        ExprKind::Tagged(_, expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_node_with_id(expr, false);
+            }
+        }
         ExprKind::Index(expr, mode) => {
             visitor.visit_node_with_id(expr, lvalue);
             match mode {
@@ -252,8 +339,18 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         }
         ExprKind::Builtin(BuiltinCall::Clog2(arg))
         | ExprKind::Builtin(BuiltinCall::Signed(arg))
-        | ExprKind::Builtin(BuiltinCall::Unsigned(arg)) => {
+        | ExprKind::Builtin(BuiltinCall::Unsigned(arg))
+        | ExprKind::Builtin(BuiltinCall::Rose(arg))
+        | ExprKind::Builtin(BuiltinCall::Fell(arg))
+        | ExprKind::Builtin(BuiltinCall::Stable(arg))
+        | ExprKind::Builtin(BuiltinCall::RealConv(_, arg)) => {
+            visitor.visit_node_with_id(arg, false);
+        }
+        ExprKind::Builtin(BuiltinCall::Past(arg, depth)) => {
             visitor.visit_node_with_id(arg, false);
+            if let Some(depth) = depth {
+                visitor.visit_node_with_id(depth, false);
+            }
         }
         ExprKind::Builtin(BuiltinCall::CountOnes(arg))
         | ExprKind::Builtin(BuiltinCall::OneHot(arg))
@@ -270,6 +367,26 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
         ExprKind::Builtin(BuiltinCall::Bits(arg)) => {
             visitor.visit_node_with_id(arg.id(), false);
         }
+        ExprKind::Builtin(BuiltinCall::ReadMem {
+            file,
+            mem,
+            start,
+            end,
+            ..
+        }) => {
+            visitor.visit_node_with_id(file, false);
+            visitor.visit_node_with_id(mem, true);
+            for &addr in start.iter().chain(end.iter()) {
+                visitor.visit_node_with_id(addr, false);
+            }
+        }
+        ExprKind::Builtin(BuiltinCall::SysTask(_, args)) => {
+            for arg in args {
+                if let Some(ref expr) = arg.expr {
+                    visitor.visit_node_with_id(expr.id(), false);
+                }
+            }
+        }
         ExprKind::Ternary(cond, true_expr, false_expr) => {
             visitor.visit_node_with_id(cond, false);
             visitor.visit_node_with_id(true_expr, lvalue);
@@ -307,6 +424,17 @@ pub fn walk_expr<'a>(visitor: &mut impl Visitor<'a>, expr: &'a Expr, lvalue: boo
                 visitor.visit_node_with_id(expr, lvalue);
             }
         }
+        ExprKind::Stream(_, slice, ref exprs) => {
+            match slice {
+                Some(StreamSlice::Expr(id)) | Some(StreamSlice::Type(id)) => {
+                    visitor.visit_node_with_id(id, false)
+                }
+                None => (),
+            }
+            for &expr in exprs {
+                visitor.visit_node_with_id(expr, lvalue);
+            }
+        }
         ExprKind::Cast(ty, expr) => {
             visitor.visit_node_with_id(ty, false);
             visitor.visit_node_with_id(expr, false);
@@ -388,6 +516,91 @@ pub fn walk_assign<'a>(visitor: &mut impl Visitor<'a>, assign: &'a Assign) {
     visitor.visit_node_with_id(assign.rhs, false);
 }
 
+/// Walk the contents of a concurrent assertion.
+pub fn walk_conc_assert<'a>(visitor: &mut impl Visitor<'a>, assert: &'a ConcAssert) {
+    visitor.visit_node_with_id(assert.clock, false);
+    if let Some(disable) = assert.disable {
+        visitor.visit_node_with_id(disable, false);
+    }
+    walk_prop(visitor, &assert.prop);
+}
+
+/// Walk the contents of an elaboration system task.
+pub fn walk_elab_task<'a>(visitor: &mut impl Visitor<'a>, task: &'a ElabTask<'a>) {
+    for arg in task.args {
+        if let Some(ref expr) = arg.expr {
+            visitor.visit_node_with_id(expr.id(), false);
+        }
+    }
+}
+
+/// Walk the contents of a pull gate.
+pub fn walk_pull_gate<'a>(visitor: &mut impl Visitor<'a>, gate: &'a PullGate) {
+    for &id in &gate.targets {
+        visitor.visit_node_with_id(id, true);
+    }
+}
+
+/// Walk the contents of a property expression.
+pub fn walk_prop<'a>(visitor: &mut impl Visitor<'a>, prop: &'a Prop) {
+    match *prop {
+        Prop::Seq(ref seq) => walk_seq(visitor, seq),
+        Prop::Not(ref arg) => walk_prop(visitor, arg),
+        Prop::And(ref lhs, ref rhs) | Prop::Or(ref lhs, ref rhs) => {
+            walk_prop(visitor, lhs);
+            walk_prop(visitor, rhs);
+        }
+        Prop::Implication {
+            ref seq, ref prop, ..
+        } => {
+            walk_seq(visitor, seq);
+            walk_prop(visitor, prop);
+        }
+    }
+}
+
+/// Walk the contents of a sequence expression.
+pub fn walk_seq<'a>(visitor: &mut impl Visitor<'a>, seq: &'a Seq) {
+    match *seq {
+        Seq::Bool(arg) => visitor.visit_node_with_id(arg, false),
+        Seq::Delay(ref lhs, ref range, ref rhs) => {
+            if let Some(lhs) = lhs {
+                walk_seq(visitor, lhs);
+            }
+            walk_seq_range(visitor, range);
+            walk_seq(visitor, rhs);
+        }
+        Seq::Repeat(ref arg, ref range) => {
+            walk_seq(visitor, arg);
+            walk_seq_range(visitor, range);
+        }
+        Seq::GotoRepeat(arg, count) | Seq::NonconsecRepeat(arg, count) => {
+            visitor.visit_node_with_id(arg, false);
+            visitor.visit_node_with_id(count, false);
+        }
+        Seq::And(ref lhs, ref rhs)
+        | Seq::Or(ref lhs, ref rhs)
+        | Seq::Intersect(ref lhs, ref rhs) => {
+            walk_seq(visitor, lhs);
+            walk_seq(visitor, rhs);
+        }
+    }
+}
+
+/// Walk the bounds of a sequence delay or repetition.
+pub fn walk_seq_range<'a>(visitor: &mut impl Visitor<'a>, range: &'a SeqRange) {
+    match *range {
+        SeqRange::Exact(n) => visitor.visit_node_with_id(n, false),
+        SeqRange::Range(lo, hi) => {
+            visitor.visit_node_with_id(lo, false);
+            if let Some(hi) = hi {
+                visitor.visit_node_with_id(hi, false);
+            }
+        }
+        SeqRange::Star | SeqRange::Plus => (),
+    }
+}
+
 /// Walk the contents of an internal port.
 pub fn walk_int_port<'a>(visitor: &mut impl Visitor<'a>, int_port: &'a IntPort) {
     if let Some(data) = &int_port.data {
@@ -111,6 +111,8 @@ pub mod call_mapping;
 mod codegen;
 mod context;
 #[warn(missing_docs)]
+pub mod format;
+#[warn(missing_docs)]
 pub mod func_args;
 pub mod hir;
 mod inst_details;
@@ -121,9 +123,13 @@ pub mod pattern_mapping;
 #[warn(missing_docs)]
 pub mod port_list;
 mod port_mapping;
+mod readmem;
 pub mod resolver;
 pub mod rst;
 #[warn(missing_docs)]
+pub mod runtime;
+mod structural;
+#[warn(missing_docs)]
 pub mod ty;
 pub mod typeck;
 pub mod value;
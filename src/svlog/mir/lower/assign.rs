@@ -4,14 +4,17 @@
 
 use crate::crate_prelude::*;
 use crate::{
+    hir::HirNode,
     mir::{
         assign::*,
         lower,
         lvalue::{Lvalue, LvalueKind},
-        rvalue::{BinaryBitwiseOp, IntBinaryArithOp, Rvalue, RvalueKind, ShiftOp},
+        rvalue::{BinaryBitwiseOp, IntBinaryArithOp, Rvalue, RvalueKind, ShiftOp, UnaryBitwiseOp},
     },
+    ty::SbvType,
     ParamEnv,
 };
+use num::{BigInt, ToPrimitive};
 
 /// Lower a procedural assign statement.
 #[moore_derive::query]
@@ -26,6 +29,7 @@ pub(crate) fn mir_assignment_from_procedural<'a>(
 ) -> &'a mir::Assignment<'a> {
     let lhs_mir_lv = cx.mir_lvalue(lhs, env);
     let rhs_mir = cx.mir_rvalue(rhs, env);
+    let rhs_mir = unpack_stream_source(cx, lhs, lhs_mir_lv, rhs_mir, env);
 
     let value = match kind {
         // `a = b`
@@ -117,6 +121,7 @@ pub(crate) fn mir_assignment_from_concurrent<'a>(
 ) -> &'a mir::Assignment<'a> {
     let lhs_mir = cx.mir_lvalue(assign.lhs, env);
     let rhs_mir = cx.mir_rvalue(assign.rhs, env);
+    let rhs_mir = unpack_stream_source(cx, assign.lhs, lhs_mir, rhs_mir, env);
     cx.arena().alloc_mir_assignment(Assignment {
         id: assign.id,
         env,
@@ -127,6 +132,50 @@ pub(crate) fn mir_assignment_from_concurrent<'a>(
     })
 }
 
+/// Select the bits of a source that a streaming concatenation unpacks.
+///
+/// A stream on the left-hand side of an assignment consumes the leftmost bits
+/// of a source that is wider than the stream. The remaining bits on the right
+/// are ignored. See §11.4.14.3 "Streaming concatenation as an assignment
+/// target (unpack)".
+fn unpack_stream_source<'a>(
+    cx: &impl Context<'a>,
+    lhs: NodeId,
+    lhs_mir: &'a Lvalue<'a>,
+    rhs_mir: &'a Rvalue<'a>,
+    env: ParamEnv,
+) -> &'a Rvalue<'a> {
+    if lhs_mir.is_error() || rhs_mir.is_error() {
+        return rhs_mir;
+    }
+    match cx.hir_of(lhs) {
+        Ok(HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Stream(..),
+            ..
+        })) => (),
+        _ => return rhs_mir,
+    }
+    let need = lhs_mir.ty.get_bit_size().unwrap();
+    let have = rhs_mir.ty.get_bit_size().unwrap();
+    if have <= need {
+        return rhs_mir;
+    }
+    let builder = lower::rvalue::Builder {
+        cx,
+        span: rhs_mir.span,
+        expr: rhs_mir.origin,
+        env,
+    };
+    builder.build(
+        lhs_mir.ty,
+        RvalueKind::Index {
+            value: rhs_mir,
+            base: builder.constant_u32((have - need) as u32),
+            length: need,
+        },
+    )
+}
+
 /// Simplify an MIR assignment to potentially multiple simple MIR assignments.
 ///
 /// This eliminates assignments to compound `Lvalue` objects, for example
@@ -199,6 +248,30 @@ fn simplify<'a>(
                 base += sbvt.size;
             }
         }
+        // Assignments to an element of a dynamic array, queue, or associative
+        // array are mapped to an assignment of the updated array as a whole.
+        LvalueKind::Index { value, base, .. } if value.ty.is_dynamic_array() => {
+            let builder = lower::rvalue::Builder {
+                cx,
+                span: rhs.span,
+                expr: rhs.id,
+                env: rhs.env,
+            };
+            let array = cx.mir_rvalue(value.origin, value.env);
+            let updated = builder.build(
+                value.ty,
+                RvalueKind::ArraySet {
+                    array,
+                    index: base,
+                    value: rhs,
+                },
+            );
+            let mut a = root.clone();
+            a.lhs = value;
+            a.rhs = updated;
+            let a = cx.arena().alloc_mir_assignment(a);
+            simplify(cx, a, a.lhs, a.rhs, into);
+        }
         LvalueKind::Index {
             value,
             base,
@@ -260,6 +333,39 @@ fn simplify<'a>(
                     shift += sbvt.size;
                 }
             }
+            // In four-state mode, the value and unknown bits of a part of a
+            // four-valued value cannot be addressed as a single signal. Such
+            // assignments are mapped to an assignment of the updated value
+            // as a whole.
+            _ if cx.is_four_state(value.ty) => {
+                let builder = lower::rvalue::Builder {
+                    cx,
+                    span: rhs.span,
+                    expr: rhs.id,
+                    env: rhs.env,
+                };
+                let width = value.ty.simple_bit_vector(cx, value.span).size;
+                let length = std::cmp::max(1, length);
+                let current = cx.mir_rvalue(value.origin, value.env);
+                let offset = match base.is_const() {
+                    true => cx
+                        .const_mir_rvalue_int(Ref(base))
+                        .ok()
+                        .and_then(|x| x.to_usize()),
+                    false => None,
+                };
+                let updated = match offset {
+                    Some(offset) if offset + length <= width => {
+                        splice_part(&builder, current, rhs, offset, length, width)
+                    }
+                    _ => mask_part(&builder, current, rhs, base, length, width),
+                };
+                let mut a = root.clone();
+                a.lhs = value;
+                a.rhs = updated;
+                let a = cx.arena().alloc_mir_assignment(a);
+                simplify(cx, a, a.lhs, a.rhs, into);
+            }
             _ => {
                 into.push(root);
             }
@@ -269,3 +375,89 @@ fn simplify<'a>(
         }
     }
 }
+
+/// Replace the bits `offset..offset+length` of a value with `part`, by
+/// concatenating the part with the surrounding bits of the value.
+///
+/// This preserves the X and Z bits of the value and the part exactly.
+fn splice_part<'a>(
+    builder: &lower::rvalue::Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+    part: &'a Rvalue<'a>,
+    offset: usize,
+    length: usize,
+    width: usize,
+) -> &'a Rvalue<'a> {
+    let domain = value.ty.domain();
+    let slice = |offset: usize, length: usize| {
+        builder.build(
+            SbvType::new(domain, ty::Sign::Unsigned, length).to_unpacked(builder.cx),
+            RvalueKind::Index {
+                value,
+                base: builder.constant_u32(offset as u32),
+                length,
+            },
+        )
+    };
+    let mut parts = vec![];
+    if offset + length < width {
+        parts.push(slice(offset + length, width - offset - length));
+    }
+    parts.push(part);
+    if offset > 0 {
+        parts.push(slice(0, offset));
+    }
+    builder.build(value.ty, RvalueKind::Concat(parts))
+}
+
+/// Replace the bits `base..base+length` of a value with `part`, as
+/// `(value & ~(mask << base)) | (part << base)`.
+///
+/// Z bits in the part and the rest of the value turn into X, since the
+/// bitwise operators do not preserve them.
+fn mask_part<'a>(
+    builder: &lower::rvalue::Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+    part: &'a Rvalue<'a>,
+    base: &'a Rvalue<'a>,
+    length: usize,
+    width: usize,
+) -> &'a Rvalue<'a> {
+    let ty = value.ty;
+    let shift = |value| {
+        builder.build(
+            ty,
+            RvalueKind::Shift {
+                op: ShiftOp::Left,
+                arith: false,
+                value,
+                amount: base,
+            },
+        )
+    };
+    let mask = builder.constant(value::make_int(ty, (BigInt::from(1) << length) - 1));
+    let mask = builder.build(
+        ty,
+        RvalueKind::UnaryBitwise {
+            op: UnaryBitwiseOp::Not,
+            arg: shift(mask),
+        },
+    );
+    let cleared = builder.build(
+        ty,
+        RvalueKind::BinaryBitwise {
+            op: BinaryBitwiseOp::And,
+            lhs: value,
+            rhs: mask,
+        },
+    );
+    let part = builder.build(ty, RvalueKind::ZeroExtend(width, part));
+    builder.build(
+        ty,
+        RvalueKind::BinaryBitwise {
+            op: BinaryBitwiseOp::Or,
+            lhs: cleared,
+            rhs: shift(part),
+        },
+    )
+}
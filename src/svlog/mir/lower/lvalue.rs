@@ -160,6 +160,17 @@ fn lower_expr_inner<'a>(
 
             // Lower the indexee and make sure it can be indexed into.
             let target = cx.mir_lvalue(target, env);
+            if target.ty.is_dynamic_array() {
+                if let hir::IndexMode::Many(..) = mode {
+                    cx.emit(
+                        DiagBuilder2::error(
+                            "unsupported: slices of dynamic arrays, queues, or associative arrays",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            }
             assert_span!(
                 target.ty.dims().next().is_some(),
                 target.span,
@@ -204,10 +215,110 @@ fn lower_expr_inner<'a>(
                 }
             } else {
                 let (field, _) = cx.resolve_field_access(expr_id, env)?;
-                return Ok(builder.build(ty, LvalueKind::Member { value, field }));
+                return match value.ty.get_union() {
+                    // Members of packed unions reinterpret the union's bits.
+                    Some(_) if value.ty.get_packed().is_some() => {
+                        if !ty.coalesces_to_llhd_scalar() {
+                            cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "unsupported: assignment to packed union member of type `{}`",
+                                    ty
+                                ))
+                                .span(span),
+                            );
+                            return Err(());
+                        }
+                        let bits = pack_simple_bit_vector(builder, value);
+                        Ok(builder.build(ty, LvalueKind::Transmute(bits)))
+                    }
+                    // Members of tagged unions can only change together with
+                    // the tag. See §7.3.2 "Tagged unions".
+                    Some(_) => {
+                        cx.emit(
+                            DiagBuilder2::error(format!(
+                                "cannot assign to `{}` directly",
+                                span.extract()
+                            ))
+                            .span(span)
+                            .add_note(
+                                "Members of a tagged union can only be changed by assigning a \
+                                 `tagged` expression to the union.",
+                            ),
+                        );
+                        Err(())
+                    }
+                    None => Ok(builder.build(ty, LvalueKind::Member { value, field })),
+                };
             }
         }
 
+        hir::ExprKind::Stream(dir, slice, ref exprs) => {
+            // Lower the operands, which have been cast to their SBVT.
+            let values = exprs
+                .iter()
+                .map(|&expr| {
+                    let value = builder.cx.mir_lvalue(expr, env);
+                    if value.is_error() {
+                        return Err(());
+                    }
+                    assert_span!(
+                        value.ty.coalesces_to_llhd_scalar(),
+                        value.span,
+                        builder.cx,
+                        "type `{}` does not coalesce to LLHD scalar",
+                        value.ty
+                    );
+                    Ok(value)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let widths: Vec<_> = values
+                .iter()
+                .map(|value| value.ty.get_bit_size().unwrap())
+                .collect();
+
+            // Assign the bits of the stream to the operands according to the
+            // slice size.
+            let slice = lower::rvalue::stream_slice_size(cx, slice, env)?;
+            let rvalue_builder = lower::rvalue::Builder {
+                cx,
+                span: builder.span,
+                expr: builder.expr,
+                env,
+            };
+            let segment = |(index, base, length): (usize, usize, usize)| {
+                let value = values[index];
+                if base == 0 && length == widths[index] {
+                    return value;
+                }
+                let part_ty =
+                    SbvType::new(value.ty.domain(), ty::Sign::Unsigned, length).to_unpacked(cx);
+                builder.build(
+                    part_ty,
+                    LvalueKind::Index {
+                        value,
+                        base: rvalue_builder.constant_u32(base as u32),
+                        length,
+                    },
+                )
+            };
+            let parts = lower::rvalue::stream_segments(&widths, slice, dir)
+                .into_iter()
+                .map(|segments| {
+                    // Slices which span multiple operands become a nested
+                    // concatenation.
+                    if segments.len() == 1 {
+                        return segment(segments[0]);
+                    }
+                    let segments: Vec<_> = segments.into_iter().map(segment).collect();
+                    let domain = lower::rvalue::stream_slice_domain(segments.iter().map(|v| v.ty));
+                    let length = segments.iter().map(|v| v.ty.get_bit_size().unwrap()).sum();
+                    let slice_ty = SbvType::new(domain, ty::Sign::Unsigned, length).to_unpacked(cx);
+                    builder.build(slice_ty, LvalueKind::Concat(segments))
+                })
+                .collect();
+            return Ok(builder.build(ty, LvalueKind::Concat(parts)));
+        }
+
         hir::ExprKind::Concat(repeat, ref exprs) => {
             // Compute the SBVT for each expression and lower it to MIR,
             // implicitly casting to the SBVT.
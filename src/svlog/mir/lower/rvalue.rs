@@ -7,12 +7,14 @@ use crate::{
     call_mapping::CallArgSource,
     hir::HirNode,
     mir::rvalue::*,
+    pattern_mapping::find_tagged_member,
     syntax::ast::BasicNode,
     ty::{SbvType, UnpackedType},
     typeck::{CastOp, CastType},
     value::{self, ValueData, ValueKind},
-    ParamEnv,
+    ParamEnv, ParamEnvBinding,
 };
+use bit_vec::BitVec;
 use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
 use std::{cmp::max, collections::HashMap};
 
@@ -155,12 +157,27 @@ fn lower_expr_inner<'gcx>(
         ))),
         hir::ExprKind::UnsizedConst('0') => Ok(builder.constant(value::make_int(ty, num::zero()))),
         hir::ExprKind::UnsizedConst('1') => Ok(builder.constant(value::make_int(ty, num::one()))),
+        hir::ExprKind::UnsizedConst('x') if cx.is_four_state(ty) => {
+            Ok(builder.constant(value::make_int_x(ty)))
+        }
+        hir::ExprKind::UnsizedConst('z') if cx.is_four_state(ty) => {
+            let width = ty.get_bit_size().unwrap();
+            Ok(builder.constant(value::make_int_special(
+                ty,
+                num::zero(),
+                BitVec::from_elem(width, true),
+                BitVec::from_elem(width, false),
+            )))
+        }
         hir::ExprKind::UnsizedConst('x') => Ok(builder.constant(value::make_int(ty, num::one()))),
         hir::ExprKind::UnsizedConst('z') => Ok(builder.constant(value::make_int(ty, num::one()))),
         hir::ExprKind::UnsizedConst(c) => {
             bug_span!(span, cx, "unsized const with weird '{}' char", c)
         }
         hir::ExprKind::TimeConst(ref k) => Ok(builder.constant(value::make_time(k.clone()))),
+        hir::ExprKind::RealConst(bits) => {
+            Ok(builder.constant(value::make_real(ty, f64::from_bits(bits))))
+        }
         hir::ExprKind::StringConst(string) => Ok(builder.constant(value::make_int(
             // TODO: This could use `value::make_string` to build a string
             // value, and then resort to the conversion function there to map
@@ -226,9 +243,90 @@ fn lower_expr_inner<'gcx>(
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_)) => {
             bug_span!(span, cx, "unsupported system function {:?}", hir.kind)
         }
-        hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_)) => {
-            // Since we currently don't emit logic types, this is always zero.
-            Ok(builder.constant(value::make_int(ty, num::zero())))
+        hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(arg)) => {
+            let arg = cx.mir_rvalue(arg, env);
+            if arg.is_error() {
+                return Err(());
+            }
+
+            // Constant arguments can be checked for X and Z bits directly.
+            if arg.is_const() {
+                let unknown = match cx.const_mir_rvalue(arg.into()).kind {
+                    ValueKind::Int(_, ref special_bits, _) => special_bits.any(),
+                    _ => false,
+                };
+                return Ok(builder.constant(value::make_int(ty, (unknown as usize).into())));
+            }
+
+            // Without X and Z bits in the generated code, the argument is
+            // never unknown.
+            if !cx.is_four_state(arg.ty) {
+                return Ok(builder.constant(value::make_int(ty, num::zero())));
+            }
+
+            // Otherwise the XOR reduction of the argument is X if any of its
+            // bits is X or Z.
+            let logic = UnpackedType::make_logic();
+            let reduced = builder.build(
+                logic,
+                RvalueKind::Reduction {
+                    op: BinaryBitwiseOp::Xor,
+                    arg,
+                },
+            );
+            let x = builder.constant(value::make_int_x(logic));
+            Ok(make_int_comparison(
+                builder,
+                ty,
+                logic,
+                IntCompOp::CaseEq,
+                reduced,
+                x,
+            ))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, arg)) => {
+            Ok(lower_real_conv(builder, ty, conv, arg))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::SysTask(task, args)) => {
+            lower_sys_task(builder, ty, hir.ast, task, args)
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem { hex, .. }) => {
+            // Memory initializations are performed during elaboration, when
+            // the memory is declared. See `readmem::load_mem`.
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` is only supported as a statement in an `initial` procedure in the \
+                     scope that declares the memory",
+                    if hex { "$readmemh" } else { "$readmemb" }
+                ))
+                .span(span),
+            );
+            Err(())
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, depth)) => {
+            let depth = match depth {
+                Some(depth) => match cx.constant_int_value_of(depth, env)?.to_usize() {
+                    Some(d) if d > 0 => d,
+                    _ => {
+                        cx.emit(
+                            DiagBuilder2::error("`$past` depth must be a positive integer")
+                                .span(cx.span(depth)),
+                        );
+                        return Err(());
+                    }
+                },
+                None => 1,
+            };
+            let value = cx.mir_rvalue(arg, env);
+            if value.is_error() {
+                return Ok(builder.error());
+            }
+            Ok(builder.build(ty, RvalueKind::Past { value, depth }))
+        }
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rose(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(arg))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(arg)) => {
+            Ok(lower_sampled_value_change(builder, ty, &hir.kind, arg))
         }
         hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(func, arg, dim)) => {
             // Decide which dimension to inspect.
@@ -305,6 +403,52 @@ fn lower_expr_inner<'gcx>(
                     Ok(builder.build(ty, RvalueKind::Const(k)))
                 }
                 HirNode::SubroutinePort(port) => Ok(builder.build(ty, RvalueKind::Arg(port.id))),
+                HirNode::PatternVar(var) => {
+                    // Pattern variables refer to the matched member of the
+                    // scrutinee of the enclosing `case ... matches`. Nested
+                    // patterns each select a member of their enclosing one.
+                    let mut value = builder.cx.mir_rvalue(var.scrutinee, env);
+                    if value.is_error() {
+                        return Err(());
+                    }
+                    for &name in &var.path {
+                        let (index, member) = find_tagged_member(cx, value.ty, name, var.span)?;
+                        value = builder.build(
+                            member.ty,
+                            RvalueKind::Member {
+                                value,
+                                field: index + 1,
+                            },
+                        );
+                    }
+                    Ok(value)
+                }
+                HirNode::WithIterator(it) => {
+                    // The iterator is bound to the index of the current
+                    // element while the `with` clause is being unrolled.
+                    let index = match builder.cx.param_env_data(env).find_value(it.id) {
+                        Some(ParamEnvBinding::Direct(index)) => index,
+                        _ => {
+                            builder.cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "iterator `{}` can only be used in its `with` clause",
+                                    it.name
+                                ))
+                                .span(span),
+                            );
+                            return Err(());
+                        }
+                    };
+                    let value = builder.cx.mir_rvalue(it.array, env);
+                    Ok(builder.build(
+                        ty,
+                        RvalueKind::Index {
+                            value,
+                            base: builder.build(index.ty, RvalueKind::Const(index)),
+                            length: 0,
+                        },
+                    ))
+                }
                 x => {
                     builder.cx.emit(
                         DiagBuilder2::error(format!(
@@ -341,6 +485,45 @@ fn lower_expr_inner<'gcx>(
         | hir::ExprKind::RepeatPattern(..)
         | hir::ExprKind::NamedPattern(..) => Ok(lower_pattern(&builder, hir, ty)),
 
+        hir::ExprKind::Tagged(..) => lower_tagged(&builder, hir, ty),
+
+        hir::ExprKind::PatternVar(..) | hir::ExprKind::WildcardPattern => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` can only be used as a pattern",
+                    span.extract()
+                ))
+                .span(span)
+                .add_note("Patterns are only allowed in `case ... matches` items."),
+            );
+            Err(())
+        }
+
+        hir::ExprKind::Concat(repeat, ref exprs) if ty.is_string() => {
+            let values: Vec<_> = exprs
+                .iter()
+                .map(|&expr| builder.cx.mir_rvalue(expr, env))
+                .collect();
+            let count = match repeat {
+                Some(repeat) => match builder.cx.constant_int_value_of(repeat, env)?.to_usize() {
+                    Some(count) => count,
+                    None => {
+                        builder.cx.emit(
+                            DiagBuilder2::error(format!(
+                                "replication count `{}` must be a non-negative integer",
+                                builder.cx.span(repeat).extract()
+                            ))
+                            .span(builder.cx.span(repeat)),
+                        );
+                        return Ok(builder.error());
+                    }
+                },
+                None => 1,
+            };
+            let values = (0..count).flat_map(|_| values.iter().cloned()).collect();
+            Ok(builder.build(ty, RvalueKind::StringConcat(values)))
+        }
+
         hir::ExprKind::Concat(repeat, ref exprs) => {
             // Compute the SBVT for each expression and lower it to MIR,
             // implicitly casting to the SBVT.
@@ -383,6 +566,92 @@ fn lower_expr_inner<'gcx>(
             Ok(repeat)
         }
 
+        hir::ExprKind::Stream(dir, slice, ref exprs) => {
+            // Lower the operands, which have been cast to their SBVT.
+            let values = exprs
+                .iter()
+                .map(|&expr| {
+                    let value = builder.cx.mir_rvalue(expr, env);
+                    if value.is_error() {
+                        return Err(());
+                    }
+                    assert_span!(value.ty.coalesces_to_llhd_scalar(), value.span, builder.cx);
+                    Ok(value)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let widths: Vec<_> = values
+                .iter()
+                .map(|value| value.ty.get_bit_size().unwrap())
+                .collect();
+
+            // Reorder the bits of the operands according to the slice size.
+            let slice = stream_slice_size(cx, slice, env)?;
+            let segment = |(index, base, length): (usize, usize, usize)| {
+                let value = values[index];
+                if base == 0 && length == widths[index] {
+                    return value;
+                }
+                let part_ty =
+                    SbvType::new(value.ty.domain(), ty::Sign::Unsigned, length).to_unpacked(cx);
+                builder.build(
+                    part_ty,
+                    RvalueKind::Index {
+                        value,
+                        base: builder.constant_u32(base as u32),
+                        length,
+                    },
+                )
+            };
+            let mut parts: Vec<_> = stream_segments(&widths, slice, dir)
+                .into_iter()
+                .map(|segments| {
+                    // Slices which span multiple operands become a nested
+                    // concatenation.
+                    if segments.len() == 1 {
+                        return segment(segments[0]);
+                    }
+                    let segments: Vec<_> = segments.into_iter().map(segment).collect();
+                    let domain = stream_slice_domain(segments.iter().map(|v| v.ty));
+                    let length = segments.iter().map(|v| v.ty.get_bit_size().unwrap()).sum();
+                    let slice_ty = SbvType::new(domain, ty::Sign::Unsigned, length).to_unpacked(cx);
+                    builder.build(slice_ty, RvalueKind::Concat(segments))
+                })
+                .collect();
+
+            // Zero-fill the stream on the right if it is assigned to a wider
+            // target.
+            let size: usize = widths.iter().sum();
+            let target_size = ty.get_bit_size().unwrap();
+            if target_size > size {
+                let fill_ty = SbvType::new(ty.domain(), ty::Sign::Unsigned, target_size - size)
+                    .to_unpacked(cx);
+                parts.push(builder.constant(value::make_int(fill_ty, Zero::zero())));
+            }
+            Ok(builder.build(ty, RvalueKind::Concat(parts)))
+        }
+
+        hir::ExprKind::Index(target, mode)
+            if cx.need_self_determined_type(target, env).is_dynamic_array() =>
+        {
+            let index = match mode {
+                hir::IndexMode::One(index) => cx.mir_rvalue(index, env),
+                hir::IndexMode::Many(..) => {
+                    cx.emit(
+                        DiagBuilder2::error(
+                            "unsupported: slices of dynamic arrays, queues, or associative arrays",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            };
+            let array = cx.mir_rvalue(target, env);
+            if array.is_error() || index.is_error() {
+                return Err(());
+            }
+            Ok(builder.build(ty, RvalueKind::ArrayGet { array, index }))
+        }
+
         hir::ExprKind::Index(target, mode) => {
             let (base, length) = compute_indexing(cx, builder.expr, env, mode)?;
 
@@ -415,6 +684,12 @@ fn lower_expr_inner<'gcx>(
             ))
         }
 
+        hir::ExprKind::Field(target, _)
+            if cx.need_self_determined_type(target, env).has_methods() =>
+        {
+            lower_method_call(builder, ty, target, &[], None)
+        }
+
         hir::ExprKind::Field(target, name) => {
             let target_ty = cx.self_determined_type(target, env);
             let value = cx.mir_rvalue(target, env);
@@ -428,7 +703,22 @@ fn lower_expr_inner<'gcx>(
                 }
             } else {
                 let (field, _) = cx.resolve_field_access(expr_id, env)?;
-                Ok(builder.build(ty, RvalueKind::Member { value, field }))
+                match value.ty.get_union() {
+                    // Members of packed unions reinterpret the union's bits.
+                    Some(_) if value.ty.get_packed().is_some() => {
+                        let bits = pack_simple_bit_vector(builder, value);
+                        Ok(unpack_simple_bit_vector(builder, bits, ty))
+                    }
+                    // Members of tagged unions follow after the tag.
+                    Some(_) => Ok(builder.build(
+                        ty,
+                        RvalueKind::Member {
+                            value,
+                            field: field + 1,
+                        },
+                    )),
+                    None => Ok(builder.build(ty, RvalueKind::Member { value, field })),
+                }
             }
         }
 
@@ -505,18 +795,311 @@ fn lower_expr_inner<'gcx>(
             Ok(check)
         }
 
+        hir::ExprKind::MethodCall(target, _, ref args, with) => {
+            lower_method_call(builder, ty, target, args, with)
+        }
+
         hir::ExprKind::FunctionCall(target, args) => {
             Ok(lower_function_call(&builder, ty, target, args))
         }
 
         hir::ExprKind::Assign { op, lhs, rhs } => Ok(lower_assign(&builder, ty, op, lhs, rhs)),
 
+        hir::ExprKind::ArrayNew(size, init) => {
+            match ty.get_dynamic_dim() {
+                Some(ty::UnpackedDim::Unsized) => (),
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "`new[]` cannot allocate a value of type `{}`",
+                            ty
+                        ))
+                        .span(span)
+                        .add_note("Only dynamic arrays such as `int a[]` can be allocated"),
+                    );
+                    return Err(());
+                }
+            }
+            let size = cx.mir_rvalue(size, env);
+            let init = match init {
+                Some(init) => cx.mir_rvalue(init, env),
+                None => builder.constant(ValueData {
+                    ty,
+                    kind: ValueKind::EmptyArray,
+                }),
+            };
+            if size.is_error() || init.is_error() {
+                return Err(());
+            }
+            Ok(builder.build(ty, RvalueKind::ArrayNew { size, init }))
+        }
+
         hir::ExprKind::Ast(expr) => {
             bug_span!(span, cx, "unsupported raw AST expr {:#?}", expr)
         }
     }
 }
 
+/// Lower a `tagged` expression.
+///
+/// Tagged unions are represented as a struct with the tag as first field,
+/// followed by the union members. The selected member is set to the given
+/// value, all other members are set to their default value.
+fn lower_tagged<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    expr: &'a hir::Expr<'a>,
+    ty: &'a UnpackedType<'a>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let map = cx.map_tagged(Ref(expr), builder.env)?;
+    assert_type!(ty, map.ty, builder.span, cx);
+    let union = ty.get_union().unwrap();
+
+    // Construct the tag and the members.
+    let tag_ty = union.tag_type(cx);
+    let mut values = vec![builder.constant(value::make_int(tag_ty, map.index.into()))];
+    for (index, member) in union.members.iter().enumerate() {
+        let value = match map.value {
+            Some(value) if index == map.index => {
+                let value = cx.mir_rvalue(value.id, builder.env);
+                assert_type!(value.ty, member.ty, value.span, cx);
+                value
+            }
+            _ => builder.build(
+                member.ty,
+                RvalueKind::Const(cx.type_default_value(member.ty)),
+            ),
+        };
+        values.push(value);
+    }
+    Ok(builder.build(ty, RvalueKind::ConstructStruct(values)))
+}
+
+/// Lower the check whether an expression matches a `case ... matches` pattern.
+///
+/// Produces a single bit that is set if the value of `scrutinee` matches
+/// `pattern`. Pattern variables and wildcards match anything, `tagged`
+/// patterns check the tag and recursively match the member, and all other
+/// expressions are compared for equality.
+pub fn lower_pattern_match<'a>(
+    cx: &impl Context<'a>,
+    scrutinee: NodeId,
+    pattern: NodeId,
+    env: ParamEnv,
+) -> Result<&'a Rvalue<'a>> {
+    let builder = Builder {
+        cx,
+        span: cx.span(pattern),
+        expr: pattern,
+        env,
+    };
+    let value = cx.mir_rvalue(scrutinee, env);
+    if value.is_error() {
+        return Err(());
+    }
+    lower_pattern_match_value(&builder, value, pattern)
+}
+
+/// Lower the check whether a value matches a pattern.
+fn lower_pattern_match_value<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+    pattern: NodeId,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let builder = builder.with(pattern);
+    let bit_ty = UnpackedType::make_logic();
+    let hir = match cx.hir_of(pattern)? {
+        HirNode::Expr(x) => x,
+        x => unreachable!("pattern should be an expression, got {:?}", x),
+    };
+    match hir.kind {
+        hir::ExprKind::PatternVar(..) | hir::ExprKind::WildcardPattern => {
+            Ok(builder.constant(value::make_int(bit_ty, One::one())))
+        }
+        hir::ExprKind::Tagged(name, inner) => {
+            let (index, member) = find_tagged_member(cx, value.ty, name, hir.span)?;
+            let tag_ty = value.ty.get_union().unwrap().tag_type(cx);
+            let tag = builder.build(tag_ty, RvalueKind::Member { value, field: 0 });
+            let expected = builder.constant(value::make_int(tag_ty, index.into()));
+            let check = make_int_comparison(&builder, bit_ty, tag_ty, IntCompOp::Eq, tag, expected);
+            match inner {
+                Some(inner) => {
+                    let member_value = builder.build(
+                        member.ty,
+                        RvalueKind::Member {
+                            value,
+                            field: index + 1,
+                        },
+                    );
+                    let inner_check = lower_pattern_match_value(&builder, member_value, inner)?;
+                    Ok(make_binary_bitwise(
+                        &builder,
+                        bit_ty,
+                        BinaryBitwiseOp::And,
+                        false,
+                        check,
+                        inner_check,
+                    ))
+                }
+                None => Ok(check),
+            }
+        }
+        _ => {
+            let expected = cx.mir_rvalue(pattern, builder.env);
+            if expected.is_error() {
+                return Err(());
+            }
+            if value.ty.get_simple_bit_vector().is_none() {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot match value of type `{}` against an expression",
+                        value.ty
+                    ))
+                    .span(hir.span)
+                    .add_note("Only integral values can be compared in a pattern."),
+                );
+                return Err(());
+            }
+            Ok(make_int_comparison(
+                &builder,
+                bit_ty,
+                value.ty,
+                IntCompOp::Eq,
+                value,
+                expected,
+            ))
+        }
+    }
+}
+
+/// Determine the slice size of a streaming concatenation.
+///
+/// The slice size defaults to a single bit if omitted.
+pub(crate) fn stream_slice_size<'a>(
+    cx: &impl Context<'a>,
+    slice: Option<hir::StreamSlice>,
+    env: ParamEnv,
+) -> Result<usize> {
+    let (size, span) = match slice {
+        None => return Ok(1),
+        Some(hir::StreamSlice::Type(ty)) => {
+            let span = cx.span(ty);
+            let ty = cx.packed_type_from_ast(
+                Ref(cx.ast_for_id(ty).as_all().get_type().unwrap()),
+                env,
+                None,
+            );
+            if ty.is_error() {
+                return Err(());
+            }
+            match ty.get_bit_size() {
+                Some(size) => (Some(size), span),
+                None => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "slice type `{}` of streaming concatenation has no fixed size",
+                            ty
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Some(hir::StreamSlice::Expr(expr)) => (
+            cx.constant_int_value_of(expr, env)?.to_usize(),
+            cx.span(expr),
+        ),
+    };
+    match size {
+        Some(size) if size > 0 => Ok(size),
+        _ => {
+            cx.emit(
+                DiagBuilder2::error("slice size of streaming concatenation must be positive")
+                    .span(span),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Determine the domain of a slice of a streaming concatenation which spans
+/// operands of the given types.
+pub(crate) fn stream_slice_domain<'a>(
+    mut tys: impl Iterator<Item = &'a UnpackedType<'a>>,
+) -> ty::Domain {
+    if tys.any(|ty| ty.domain() == ty::Domain::FourValued) {
+        ty::Domain::FourValued
+    } else {
+        ty::Domain::TwoValued
+    }
+}
+
+/// Determine how the operands of a streaming concatenation are reordered.
+///
+/// The operands of the given `widths` are concatenated into a bit stream. A
+/// `{<<...}` stream is cut into slices of the given size starting at the LSB,
+/// such that only the most significant slice may be shorter, and the order of
+/// the slices is reversed. A `{>>...}` stream is kept as it is, with every
+/// operand forming a slice of its own. Returns the slices in order from the
+/// MSB to the LSB of the resulting bit stream, each as a list of `(operand,
+/// base, length)` triples which select the bits `[base +: length]` of an
+/// operand, again from MSB to LSB. See §11.4.14 "Streaming operators
+/// (pack/unpack)".
+pub(crate) fn stream_segments(
+    widths: &[usize],
+    slice: usize,
+    dir: ast::StreamDir,
+) -> Vec<Vec<(usize, usize, usize)>> {
+    if dir == ast::StreamDir::In {
+        return widths
+            .iter()
+            .enumerate()
+            .map(|(index, &width)| vec![(index, 0, width)])
+            .collect();
+    }
+
+    // Determine the offset of each operand from the LSB of the stream.
+    let total: usize = widths.iter().sum();
+    let mut offset = total;
+    let offsets: Vec<_> = widths
+        .iter()
+        .map(|&width| {
+            offset -= width;
+            offset
+        })
+        .collect();
+
+    // Cut the stream into `(lsb, length)` slices, starting at the LSB. The
+    // reversal of the slices places the least significant one at the MSB of
+    // the result, such that this is also the order of the result.
+    let mut ranges = vec![];
+    let mut lo = 0;
+    while lo < total {
+        let length = std::cmp::min(slice, total - lo);
+        ranges.push((lo, length));
+        lo += length;
+    }
+
+    // Split the slices at the operand boundaries.
+    ranges
+        .into_iter()
+        .map(|(lo, length)| {
+            let hi = lo + length;
+            let mut segments = vec![];
+            for (index, (&width, &offset)) in widths.iter().zip(offsets.iter()).enumerate() {
+                let seg_lo = max(lo, offset);
+                let seg_hi = std::cmp::min(hi, offset + width);
+                if seg_lo < seg_hi {
+                    segments.push((index, seg_lo - offset, seg_hi - seg_lo));
+                }
+            }
+            segments
+        })
+        .collect()
+}
+
 /// Compute the base and length of an indexing operation.
 ///
 /// Determine the index of the LSB and the width of the selection. Note that
@@ -668,6 +1251,17 @@ fn lower_cast<'gcx>(
     for &(op, to) in &to.casts {
         debug!("- {:?} from `{}` to `{}`", op, value.ty, to);
         match op {
+            CastOp::Bool if value.ty.is_real() => {
+                let zero = builder.constant(value::make_real(value.ty, 0.0));
+                value = builder.build(
+                    to,
+                    RvalueKind::RealComp {
+                        op: RealCompOp::Neq,
+                        lhs: value,
+                        rhs: zero,
+                    },
+                );
+            }
             CastOp::Bool => {
                 assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
                 value = builder.build(to, RvalueKind::CastToBool(value));
@@ -721,6 +1315,27 @@ fn lower_cast<'gcx>(
                 assert_span!(value.ty.is_simple_bit_vector(), value.span, builder.cx);
                 value = builder.build(to, RvalueKind::UnpackString(value));
             }
+            CastOp::IntToReal => {
+                assert_span!(to.is_real(), value.span, builder.cx);
+                let sbvt = value.ty.simple_bit_vector(builder.cx, value.span);
+                value = builder.build(
+                    to,
+                    RvalueKind::IntToReal {
+                        sign: sbvt.sign,
+                        value,
+                    },
+                );
+            }
+            CastOp::RealToInt => {
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                assert_span!(to.is_simple_bit_vector(), value.span, builder.cx);
+                value = builder.build(to, RvalueKind::RealToInt { round: true, value });
+            }
+            CastOp::ResizeReal => {
+                assert_span!(value.ty.is_real(), value.span, builder.cx);
+                assert_span!(to.is_real(), value.span, builder.cx);
+                value = builder.build(to, RvalueKind::ResizeReal(value));
+            }
         }
         if !value.ty.is_identical(to) {
             error!(
@@ -1026,6 +1641,9 @@ fn lower_unary<'gcx>(
 ) -> &'gcx Rvalue<'gcx> {
     // Determine the category of the operation.
     match op {
+        hir::UnaryOp::Pos | hir::UnaryOp::Neg if ty.is_real() => {
+            lower_real_unary_arith(builder, ty, op, arg)
+        }
         hir::UnaryOp::Pos | hir::UnaryOp::Neg => lower_int_unary_arith(builder, ty, op, arg),
         hir::UnaryOp::BitNot => lower_unary_bitwise(builder, ty, op, arg),
         hir::UnaryOp::LogicNot => lower_unary_logic(builder, ty, op, arg),
@@ -1051,6 +1669,15 @@ fn lower_binary<'gcx>(
     rhs: NodeId,
 ) -> &'gcx Rvalue<'gcx> {
     match op {
+        hir::BinaryOp::Add
+        | hir::BinaryOp::Sub
+        | hir::BinaryOp::Mul
+        | hir::BinaryOp::Div
+        | hir::BinaryOp::Pow
+            if ty.is_real() =>
+        {
+            lower_real_binary_arith(builder, ty, op, lhs, rhs)
+        }
         hir::BinaryOp::Add
         | hir::BinaryOp::Sub
         | hir::BinaryOp::Mul
@@ -1059,6 +1686,10 @@ fn lower_binary<'gcx>(
         | hir::BinaryOp::Pow => lower_int_binary_arith(builder, ty, op, lhs, rhs),
         hir::BinaryOp::Eq
         | hir::BinaryOp::Neq
+        | hir::BinaryOp::CaseEq
+        | hir::BinaryOp::CaseNeq
+        | hir::BinaryOp::WildcardEq
+        | hir::BinaryOp::WildcardNeq
         | hir::BinaryOp::Lt
         | hir::BinaryOp::Leq
         | hir::BinaryOp::Gt
@@ -1066,6 +1697,8 @@ fn lower_binary<'gcx>(
             let op_ty = builder.cx.need_operation_type(builder.expr, builder.env);
             if op_ty.is_string() {
                 lower_string_comparison(builder, ty, op_ty, op, lhs, rhs)
+            } else if op_ty.is_real() {
+                lower_real_comparison(builder, ty, op_ty, op, lhs, rhs)
             } else {
                 lower_int_comparison(builder, ty, op_ty, op, lhs, rhs)
             }
@@ -1210,6 +1843,10 @@ fn lower_int_comparison<'a>(
     let op = match op {
         hir::BinaryOp::Eq => IntCompOp::Eq,
         hir::BinaryOp::Neq => IntCompOp::Neq,
+        hir::BinaryOp::CaseEq => IntCompOp::CaseEq,
+        hir::BinaryOp::CaseNeq => IntCompOp::CaseNeq,
+        hir::BinaryOp::WildcardEq => IntCompOp::WildcardEq,
+        hir::BinaryOp::WildcardNeq => IntCompOp::WildcardNeq,
         hir::BinaryOp::Lt => IntCompOp::Lt,
         hir::BinaryOp::Leq => IntCompOp::Leq,
         hir::BinaryOp::Gt => IntCompOp::Gt,
@@ -1271,8 +1908,14 @@ fn lower_string_comparison<'a>(
 
     // Determine the operation.
     let op = match op {
-        hir::BinaryOp::Eq => StringCompOp::Eq,
-        hir::BinaryOp::Neq => StringCompOp::Neq,
+        hir::BinaryOp::Eq | hir::BinaryOp::CaseEq | hir::BinaryOp::WildcardEq => StringCompOp::Eq,
+        hir::BinaryOp::Neq | hir::BinaryOp::CaseNeq | hir::BinaryOp::WildcardNeq => {
+            StringCompOp::Neq
+        }
+        hir::BinaryOp::Lt => StringCompOp::Lt,
+        hir::BinaryOp::Leq => StringCompOp::Leq,
+        hir::BinaryOp::Gt => StringCompOp::Gt,
+        hir::BinaryOp::Geq => StringCompOp::Geq,
         _ => bug_span!(
             builder.span,
             builder.cx,
@@ -1289,49 +1932,207 @@ fn lower_string_comparison<'a>(
     builder.build(result_ty, RvalueKind::StringComp { op, lhs, rhs })
 }
 
-/// Map an integer shift operator to MIR.
-fn lower_shift<'a>(
+/// Map a real unary arithmetic operator to MIR.
+fn lower_real_unary_arith<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: hir::UnaryOp,
+    arg: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operand.
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    if arg.is_error() {
+        return builder.error();
+    }
+    assert_type!(arg.ty, result_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    match op {
+        hir::UnaryOp::Pos => arg,
+        hir::UnaryOp::Neg => builder.build(result_ty, RvalueKind::RealNeg(arg)),
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real unary arithmetic operator",
+            op
+        ),
+    }
+}
+
+/// Map a real binary arithmetic operator to MIR.
+fn lower_real_binary_arith<'a>(
     builder: &Builder<'_, impl Context<'a>>,
     result_ty: &'a UnpackedType<'a>,
     op: hir::BinaryOp,
-    value: NodeId,
-    amount: NodeId,
+    lhs: NodeId,
+    rhs: NodeId,
 ) -> &'a Rvalue<'a> {
     // Lower the operands.
-    let value = builder.cx.mir_rvalue(value, builder.env);
-    let amount = builder.cx.mir_rvalue(amount, builder.env);
-    if value.is_error() || amount.is_error() {
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() {
         return builder.error();
     }
 
     // Determine the operation.
-    let (op, arith) = match op {
-        hir::BinaryOp::LogicShL => (ShiftOp::Left, false),
-        hir::BinaryOp::LogicShR => (ShiftOp::Right, false),
-        hir::BinaryOp::ArithShL => (ShiftOp::Left, true),
-        hir::BinaryOp::ArithShR => (ShiftOp::Right, true),
+    let op = match op {
+        hir::BinaryOp::Add => RealArithOp::Add,
+        hir::BinaryOp::Sub => RealArithOp::Sub,
+        hir::BinaryOp::Mul => RealArithOp::Mul,
+        hir::BinaryOp::Div => RealArithOp::Div,
+        hir::BinaryOp::Pow => RealArithOp::Pow,
         _ => bug_span!(
             builder.span,
             builder.cx,
-            "{:?} is not an integer shift operator",
+            "{:?} is not a real binary arithmetic operator",
             op
         ),
     };
 
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, result_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, result_ty, builder.span, builder.cx);
+
     // Assemble the node.
-    make_shift(builder, result_ty, op, arith, value, amount)
+    builder.build(result_ty, RvalueKind::RealArith { op, lhs, rhs })
 }
 
-/// Map an integer shift operator to MIR.
-pub fn make_shift<'a>(
+/// Map a real comparison operator to MIR.
+fn lower_real_comparison<'a>(
     builder: &Builder<'_, impl Context<'a>>,
     result_ty: &'a UnpackedType<'a>,
-    op: ShiftOp,
-    arith: bool,
-    value: &'a Rvalue<'a>,
-    amount: &'a Rvalue<'a>,
+    op_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    lhs: NodeId,
+    rhs: NodeId,
 ) -> &'a Rvalue<'a> {
-    // Check that the operands are of the right type.
+    // Lower the operands.
+    let lhs = builder.cx.mir_rvalue(lhs, builder.env);
+    let rhs = builder.cx.mir_rvalue(rhs, builder.env);
+    if lhs.is_error() || rhs.is_error() || op_ty.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let op = match op {
+        hir::BinaryOp::Eq | hir::BinaryOp::CaseEq | hir::BinaryOp::WildcardEq => RealCompOp::Eq,
+        hir::BinaryOp::Neq | hir::BinaryOp::CaseNeq | hir::BinaryOp::WildcardNeq => RealCompOp::Neq,
+        hir::BinaryOp::Lt => RealCompOp::Lt,
+        hir::BinaryOp::Leq => RealCompOp::Leq,
+        hir::BinaryOp::Gt => RealCompOp::Gt,
+        hir::BinaryOp::Geq => RealCompOp::Geq,
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not a real binary comparison operator",
+            op
+        ),
+    };
+
+    // Check that the operands are of the right type.
+    assert_type!(lhs.ty, op_ty, builder.span, builder.cx);
+    assert_type!(rhs.ty, op_ty, builder.span, builder.cx);
+
+    // Assemble the node.
+    builder.build(result_ty, RvalueKind::RealComp { op, lhs, rhs })
+}
+
+/// Map a real conversion function to MIR.
+fn lower_real_conv<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    conv: hir::RealConv,
+    arg: NodeId,
+) -> &'a Rvalue<'a> {
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    if arg.is_error() {
+        return builder.error();
+    }
+    match conv {
+        // `$rtoi` truncates towards zero, and then converts to an `integer`.
+        hir::RealConv::RealToInt => {
+            let wide =
+                SbvType::new(ty::Domain::TwoValued, ty::Sign::Signed, 64).to_unpacked(builder.cx);
+            let value = builder.build(
+                wide,
+                RvalueKind::RealToInt {
+                    round: false,
+                    value: arg,
+                },
+            );
+            let size = ty.simple_bit_vector(builder.cx, builder.span).size;
+            builder.build(ty, RvalueKind::Truncate(size, value))
+        }
+        // `$itor` operates on a self-determined argument, which may already be
+        // a real.
+        hir::RealConv::IntToReal if arg.ty.is_real() => {
+            if arg.ty.is_identical(ty) {
+                arg
+            } else {
+                builder.build(ty, RvalueKind::ResizeReal(arg))
+            }
+        }
+        hir::RealConv::IntToReal => {
+            let sbvt = arg.ty.simple_bit_vector(builder.cx, arg.span);
+            builder.build(
+                ty,
+                RvalueKind::IntToReal {
+                    sign: sbvt.sign,
+                    value: arg,
+                },
+            )
+        }
+        // The bit conversions reinterpret the IEEE 754 representation.
+        hir::RealConv::RealToBits
+        | hir::RealConv::BitsToReal
+        | hir::RealConv::ShortRealToBits
+        | hir::RealConv::BitsToShortReal => builder.build(ty, RvalueKind::Transmute(arg)),
+    }
+}
+
+/// Map an integer shift operator to MIR.
+fn lower_shift<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: hir::BinaryOp,
+    value: NodeId,
+    amount: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operands.
+    let value = builder.cx.mir_rvalue(value, builder.env);
+    let amount = builder.cx.mir_rvalue(amount, builder.env);
+    if value.is_error() || amount.is_error() {
+        return builder.error();
+    }
+
+    // Determine the operation.
+    let (op, arith) = match op {
+        hir::BinaryOp::LogicShL => (ShiftOp::Left, false),
+        hir::BinaryOp::LogicShR => (ShiftOp::Right, false),
+        hir::BinaryOp::ArithShL => (ShiftOp::Left, true),
+        hir::BinaryOp::ArithShR => (ShiftOp::Right, true),
+        _ => bug_span!(
+            builder.span,
+            builder.cx,
+            "{:?} is not an integer shift operator",
+            op
+        ),
+    };
+
+    // Assemble the node.
+    make_shift(builder, result_ty, op, arith, value, amount)
+}
+
+/// Map an integer shift operator to MIR.
+pub fn make_shift<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    op: ShiftOp,
+    arith: bool,
+    value: &'a Rvalue<'a>,
+    amount: &'a Rvalue<'a>,
+) -> &'a Rvalue<'a> {
+    // Check that the operands are of the right type.
     let sbvt = result_ty.simple_bit_vector(builder.cx, builder.span);
     assert_type!(value.ty, result_ty, value.span, builder.cx);
     assert_span!(
@@ -1572,6 +2373,350 @@ fn lower_reduction<'a>(
     }
 }
 
+/// Map a display, simulation control, or severity task to MIR.
+///
+/// String literal arguments are parsed as format strings, and each format
+/// specifier consumes one of the subsequent arguments. Arguments which are not
+/// consumed by a format specifier are printed in decimal.
+fn lower_sys_task<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    ast: &'a dyn ast::AnyNode<'a>,
+    task: hir::SysTask,
+    args: &'a [ast::CallArg<'a>],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let name = match task {
+        hir::SysTask::Display => "$display",
+        hir::SysTask::Write => "$write",
+        hir::SysTask::Strobe => "$strobe",
+        hir::SysTask::Finish => "$finish",
+        hir::SysTask::Stop => "$stop",
+        hir::SysTask::Info => "$info",
+        hir::SysTask::Warning => "$warning",
+        hir::SysTask::Error => "$error",
+        hir::SysTask::Fatal => "$fatal",
+        hir::SysTask::SFormatF => "$sformatf",
+    };
+
+    // Determine the finish number. `$fatal` only has one if its first argument
+    // is not already the message.
+    let mut args = args.iter().peekable();
+    let has_diag = match task {
+        hir::SysTask::Finish | hir::SysTask::Stop => true,
+        hir::SysTask::Fatal => match args.peek().cloned().and_then(|arg| arg.expr.as_ref()) {
+            Some(expr) => match expr.data {
+                ast::LiteralExpr(crate::syntax::token::Lit::Str(..)) => false,
+                _ => true,
+            },
+            None => false,
+        },
+        _ => false,
+    };
+    let diag = match args.peek().cloned().and_then(|arg| arg.expr.as_ref()) {
+        Some(expr) if has_diag => {
+            args.next();
+            match cx.constant_int_value_of(expr.id(), builder.env)?.to_usize() {
+                Some(diag) if diag <= 2 => diag,
+                _ => {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "finish number of `{}` must be 0, 1, or 2",
+                            name
+                        ))
+                        .span(expr.span()),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        _ => 1,
+    };
+    if !task.has_message() {
+        if let Some(arg) = args.next() {
+            cx.emit(
+                DiagBuilder2::error(format!("`{}` takes at most one argument", name))
+                    .span(arg.span()),
+            );
+            return Err(());
+        }
+    }
+
+    // Assemble the message.
+    let mut msg = vec![];
+    while let Some(arg) = args.next() {
+        // Empty arguments print a single space.
+        let expr = match arg.expr {
+            Some(ref expr) => expr,
+            None => {
+                msg.push(FormatArg::Literal(" ".to_string()));
+                continue;
+            }
+        };
+
+        // Arguments other than string literals are printed in decimal.
+        let format = match expr.data {
+            ast::LiteralExpr(crate::syntax::token::Lit::Str(value)) => value,
+            _ => {
+                let spec = crate::format::FormatSpec {
+                    kind: crate::format::FormatKind::Decimal,
+                    width: None,
+                    precision: None,
+                    left_align: false,
+                    offset: 0,
+                    len: 0,
+                };
+                let value = lower_format_value(builder, spec, expr)?;
+                msg.push(FormatArg::Value(spec, value));
+                continue;
+            }
+        };
+
+        // Parse the format string and consume arguments for each specifier.
+        let items = match crate::format::parse_format(&*format.as_str()) {
+            Ok(items) => items,
+            Err(err) => {
+                cx.emit(DiagBuilder2::error(err.message).span(expr.span()));
+                return Err(());
+            }
+        };
+        for item in items {
+            match item {
+                crate::format::FormatItem::Literal(text) => msg.push(FormatArg::Literal(text)),
+                crate::format::FormatItem::Spec(spec) if !spec.kind.takes_arg() => {
+                    msg.push(FormatArg::Literal(scope_name(ast)));
+                }
+                crate::format::FormatItem::Spec(spec) => {
+                    let value = match args.next().and_then(|arg| arg.expr.as_ref()) {
+                        Some(value) => value,
+                        None => {
+                            cx.emit(
+                                DiagBuilder2::error(format!(
+                                    "format specifier `{}` has no corresponding argument",
+                                    spec
+                                ))
+                                .span(expr.span()),
+                            );
+                            return Err(());
+                        }
+                    };
+                    let value = lower_format_value(builder, spec, value)?;
+                    msg.push(FormatArg::Value(spec, value));
+                }
+            }
+        }
+    }
+
+    Ok(builder.build(ty, RvalueKind::SysTask { task, diag, msg }))
+}
+
+/// Lower an elaboration system task to MIR.
+///
+/// The message is assembled in the same way as for system tasks executed at
+/// runtime. Since elaboration system tasks are evaluated during elaboration,
+/// all printed values must be constant.
+pub fn lower_elab_task<'a>(
+    cx: &impl Context<'a>,
+    hir: &'a hir::ElabTask<'a>,
+    env: ParamEnv,
+) -> Result<&'a Rvalue<'a>> {
+    let builder = Builder {
+        cx,
+        span: hir.span,
+        expr: hir.id,
+        env,
+    };
+    lower_sys_task(
+        &builder,
+        UnpackedType::make_void(),
+        cx.ast_for_id(hir.id),
+        hir.task,
+        hir.args,
+    )
+}
+
+/// Lower a value printed by a system task, and check that its type matches the
+/// format specifier.
+fn lower_format_value<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    spec: crate::format::FormatSpec,
+    expr: &'a ast::Expr<'a>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let value = cx.mir_rvalue(expr.id(), builder.env);
+    if value.is_error() {
+        return Err(());
+    }
+    if spec.kind.is_real() {
+        if value.ty.is_real() {
+            return Ok(value);
+        }
+        if let Some(sbvt) = value.ty.get_simple_bit_vector() {
+            // Integral values passed to a real format are converted first.
+            let builder = builder.with(expr.id());
+            let value = if value.ty.is_simple_bit_vector() {
+                value
+            } else {
+                pack_simple_bit_vector(&builder, value)
+            };
+            return Ok(builder.build(
+                UnpackedType::make_real(ty::RealType::Real),
+                RvalueKind::IntToReal {
+                    sign: sbvt.sign,
+                    value,
+                },
+            ));
+        }
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "format specifier `{}` expects a real value, but argument has type `{}`",
+                spec, value.ty
+            ))
+            .span(expr.span()),
+        );
+        return Err(());
+    }
+    if value.ty.is_simple_bit_vector() {
+        return Ok(value);
+    }
+    if value.ty.is_string() && spec.kind == crate::format::FormatKind::String {
+        return Ok(value);
+    }
+    if value.ty.get_simple_bit_vector().is_some() {
+        return Ok(pack_simple_bit_vector(&builder.with(expr.id()), value));
+    }
+    if value.ty.is_real() {
+        // Real values passed to an integral format are rounded (§21.2.1.3).
+        let ty = SbvType::new(ty::Domain::TwoValued, ty::Sign::Signed, 64).to_unpacked(cx);
+        return Ok(builder
+            .with(expr.id())
+            .build(ty, RvalueKind::RealToInt { round: true, value }));
+    }
+    let mut d = DiagBuilder2::error(format!(
+        "format specifier `{}` expects an integral value, but argument has type `{}`",
+        spec, value.ty
+    ))
+    .span(expr.span());
+    if value.ty.is_string() {
+        d = d.add_note("Use `%s` to print strings.");
+    }
+    cx.emit(d);
+    Err(())
+}
+
+/// Determine the name printed by the `%m` format specifier.
+///
+/// Instance paths are not known during MIR lowering. Instead, this is the
+/// name of the enclosing module, interface, or package, followed by the name
+/// of the enclosing function or task, if any.
+fn scope_name<'a>(node: &'a dyn ast::AnyNode<'a>) -> String {
+    let mut names = vec![];
+    let mut next = Some(node);
+    while let Some(node) = next {
+        match node.as_all() {
+            ast::AllNode::Module(x) => names.push(x.name.value),
+            ast::AllNode::Interface(x) => names.push(x.name.value),
+            ast::AllNode::Package(x) => names.push(x.name.value),
+            ast::AllNode::SubroutineDecl(x) => names.push(x.prototype.name.value),
+            _ => (),
+        }
+        next = node.get_parent();
+    }
+    if names.is_empty() {
+        return "$unit".to_string();
+    }
+    names
+        .iter()
+        .rev()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Map a `$rose`, `$fell`, or `$stable` call to MIR.
+///
+/// These are expressed in terms of the argument's value in the previous clock
+/// tick of the surrounding assertion. `$rose` and `$fell` only consider the
+/// least significant bit of the argument.
+fn lower_sampled_value_change<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    result_ty: &'a UnpackedType<'a>,
+    kind: &hir::ExprKind,
+    arg: NodeId,
+) -> &'a Rvalue<'a> {
+    // Lower the operand.
+    let arg = builder.cx.mir_rvalue(arg, builder.env);
+    if arg.is_error() {
+        return builder.error();
+    }
+    if !arg.ty.is_simple_bit_vector() {
+        builder.cx.emit(
+            DiagBuilder2::error(format!(
+                "argument of type `{}` cannot be sampled for changes",
+                arg.ty
+            ))
+            .span(arg.span),
+        );
+        return builder.error();
+    }
+    let sbvt = arg.ty.simple_bit_vector(builder.cx, arg.span);
+
+    // `$stable` compares the entire value against its past value.
+    if let hir::ExprKind::Builtin(hir::BuiltinCall::Stable(_)) = kind {
+        let past = builder.build(
+            arg.ty,
+            RvalueKind::Past {
+                value: arg,
+                depth: 1,
+            },
+        );
+        return make_int_comparison(builder, result_ty, arg.ty, IntCompOp::Eq, arg, past);
+    }
+
+    // Isolate the least significant bit.
+    let lsb_ty = SbvType::new(sbvt.domain, ty::Sign::Unsigned, 1).to_unpacked(builder.cx);
+    let lsb = if sbvt.size == 1 {
+        builder.build(lsb_ty, RvalueKind::Transmute(arg))
+    } else {
+        builder.build(lsb_ty, RvalueKind::Truncate(1, arg))
+    };
+    let past = builder.build(
+        lsb_ty,
+        RvalueKind::Past {
+            value: lsb,
+            depth: 1,
+        },
+    );
+    let not = |arg| {
+        builder.build(
+            lsb_ty,
+            RvalueKind::UnaryBitwise {
+                op: UnaryBitwiseOp::Not,
+                arg,
+            },
+        )
+    };
+    let (lhs, rhs) = match kind {
+        hir::ExprKind::Builtin(hir::BuiltinCall::Rose(_)) => (lsb, not(past)),
+        _ => (not(lsb), past),
+    };
+    let value = make_binary_bitwise(builder, lsb_ty, BinaryBitwiseOp::And, false, lhs, rhs);
+
+    // Map to the result domain.
+    if result_ty.domain() != sbvt.domain {
+        builder.build(
+            result_ty,
+            RvalueKind::CastValueDomain {
+                from: sbvt.domain,
+                to: result_ty.domain(),
+                value,
+            },
+        )
+    } else {
+        builder.build(result_ty, RvalueKind::Transmute(value))
+    }
+}
+
 /// Map an increment/decrement operator to MIR.
 fn lower_int_incdec<'gcx>(
     builder: &Builder<'_, impl Context<'gcx>>,
@@ -1586,8 +2731,23 @@ fn lower_int_incdec<'gcx>(
     }
 
     // Compute the new value, depending on the operand type.
-    let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
-    let new = if sbvt.size == 1 {
+    let new = if lv.ty.is_real() {
+        // Real values add/subtract one.
+        let op = match op {
+            hir::UnaryOp::PreInc | hir::UnaryOp::PostInc => RealArithOp::Add,
+            hir::UnaryOp::PreDec | hir::UnaryOp::PostDec => RealArithOp::Sub,
+            _ => unreachable!(),
+        };
+        let one = builder.constant(value::make_real(lv.ty, 1.0));
+        builder.build(
+            lv.ty,
+            RvalueKind::RealArith {
+                op,
+                lhs: rv,
+                rhs: one,
+            },
+        )
+    } else if lv.ty.simple_bit_vector(builder.cx, builder.span).size == 1 {
         // Single bit values simply toggle the bit.
         builder.build(
             lv.ty,
@@ -1607,6 +2767,7 @@ fn lower_int_incdec<'gcx>(
             lv.ty,
             RvalueKind::Const(builder.cx.intern_value(value::make_int(lv.ty, One::one()))),
         );
+        let sbvt = lv.ty.simple_bit_vector(builder.cx, builder.span);
         builder.build(
             lv.ty,
             RvalueKind::IntBinaryArith {
@@ -1679,6 +2840,720 @@ fn lower_assign<'a>(
     )
 }
 
+/// Lower a call to a built-in method.
+fn lower_method_call<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    target: NodeId,
+    args: &[NodeId],
+    with: Option<(NodeId, NodeId)>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let method = cx.resolve_method_call(builder.expr, builder.env)?;
+    let value = cx.mir_rvalue(target, builder.env);
+    let arg_ids = args;
+    let args: Vec<_> = args
+        .iter()
+        .map(|&arg| cx.mir_rvalue(arg, builder.env))
+        .collect();
+    if value.is_error() || args.iter().any(|arg| arg.is_error()) {
+        return Err(());
+    }
+    let string = UnpackedType::make_string();
+
+    // Compute the result of the method, or the new value of the target for
+    // methods that modify it.
+    let (kind, modified) = match method {
+        hir::Method::String(hir::StringMethod::Len) => (RvalueKind::StringLen(value), false),
+        hir::Method::String(hir::StringMethod::Getc) => (
+            RvalueKind::StringGetc {
+                value,
+                index: args[0],
+            },
+            false,
+        ),
+        hir::Method::String(hir::StringMethod::Substr) => (
+            RvalueKind::StringSubstr {
+                value,
+                start: args[0],
+                stop: args[1],
+            },
+            false,
+        ),
+        hir::Method::String(hir::StringMethod::AtoI(radix)) => {
+            (RvalueKind::StringToInt { radix, value }, false)
+        }
+        hir::Method::String(hir::StringMethod::Putc) => (
+            RvalueKind::StringPutc {
+                value,
+                index: args[0],
+                character: args[1],
+            },
+            true,
+        ),
+        hir::Method::String(hir::StringMethod::ItoA(radix)) => {
+            let sign = args[0].ty.simple_bit_vector(cx, args[0].span).sign;
+            (
+                RvalueKind::StringFromInt {
+                    radix,
+                    sign,
+                    value: args[0],
+                },
+                true,
+            )
+        }
+        hir::Method::Event(hir::EventMethod::Triggered) => {
+            (RvalueKind::EventTriggered(value), false)
+        }
+        hir::Method::Array(method) => {
+            return lower_array_method_call(builder, ty, method, target, value, arg_ids, &args)
+        }
+        hir::Method::Enum(method) => {
+            return lower_enum_method_call(builder, ty, method, value, arg_ids)
+        }
+        hir::Method::ArrayManip(method) => {
+            return lower_array_manip_call(builder, ty, method, target, value, with)
+        }
+    };
+    if !modified {
+        return Ok(builder.build(ty, kind));
+    }
+
+    // Assign the new value back to the target.
+    let lvalue = cx.mir_lvalue(target, builder.env);
+    if lvalue.is_error() {
+        return Err(());
+    }
+    let rvalue = builder.build(string, kind);
+    Ok(builder.build(
+        ty,
+        RvalueKind::Assignment {
+            lvalue,
+            rvalue,
+            result: rvalue,
+        },
+    ))
+}
+
+/// Lower a call to a built-in method of a dynamic array, queue, or associative
+/// array.
+fn lower_array_method_call<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: hir::ArrayMethod,
+    target: NodeId,
+    value: &'a Rvalue<'a>,
+    arg_ids: &[NodeId],
+    args: &[&'a Rvalue<'a>],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+
+    // Compute the new value of the array, and the result of the method if it
+    // differs from that value.
+    let (rvalue, result) = match method {
+        hir::ArrayMethod::Size => return Ok(builder.build(ty, RvalueKind::ArraySize(value))),
+        hir::ArrayMethod::Exists => {
+            return Ok(builder.build(
+                ty,
+                RvalueKind::ArrayExists {
+                    array: value,
+                    index: args[0],
+                },
+            ))
+        }
+        hir::ArrayMethod::First
+        | hir::ArrayMethod::Last
+        | hir::ArrayMethod::Next
+        | hir::ArrayMethod::Prev => {
+            // The traversal methods assign the key they find to their argument
+            // instead of modifying the array.
+            let op = match method {
+                hir::ArrayMethod::First => AssocTraverseOp::First,
+                hir::ArrayMethod::Last => AssocTraverseOp::Last,
+                hir::ArrayMethod::Next => AssocTraverseOp::Next,
+                _ => AssocTraverseOp::Prev,
+            };
+            let key = args[0];
+            let lvalue = cx.mir_lvalue(arg_ids[0], builder.env);
+            if lvalue.is_error() {
+                return Err(());
+            }
+            let rvalue = builder.build(
+                key.ty,
+                RvalueKind::AssocTraverse {
+                    op,
+                    array: value,
+                    key,
+                },
+            );
+            let result = builder.build(
+                ty,
+                RvalueKind::AssocTraverseFound {
+                    op,
+                    array: value,
+                    key,
+                },
+            );
+            return Ok(builder.build(
+                ty,
+                RvalueKind::Assignment {
+                    lvalue,
+                    rvalue,
+                    result,
+                },
+            ));
+        }
+        hir::ArrayMethod::Delete => (
+            builder.constant(ValueData {
+                ty: value.ty,
+                kind: ValueKind::EmptyArray,
+            }),
+            None,
+        ),
+        hir::ArrayMethod::DeleteIndex => (
+            builder.build(
+                value.ty,
+                RvalueKind::ArrayDelete {
+                    array: value,
+                    index: args[0],
+                },
+            ),
+            None,
+        ),
+        hir::ArrayMethod::PushFront | hir::ArrayMethod::PushBack => (
+            builder.build(
+                value.ty,
+                RvalueKind::QueuePush {
+                    front: method == hir::ArrayMethod::PushFront,
+                    queue: value,
+                    value: args[0],
+                },
+            ),
+            None,
+        ),
+        hir::ArrayMethod::PopFront | hir::ArrayMethod::PopBack => {
+            let front = method == hir::ArrayMethod::PopFront;
+            (
+                builder.build(
+                    value.ty,
+                    RvalueKind::QueuePop {
+                        front,
+                        queue: value,
+                    },
+                ),
+                Some(builder.build(
+                    ty,
+                    RvalueKind::QueuePeek {
+                        front,
+                        queue: value,
+                    },
+                )),
+            )
+        }
+    };
+
+    // Assign the new value back to the array.
+    let lvalue = cx.mir_lvalue(target, builder.env);
+    if lvalue.is_error() {
+        return Err(());
+    }
+    Ok(builder.build(
+        ty,
+        RvalueKind::Assignment {
+            lvalue,
+            rvalue,
+            result: result.unwrap_or(rvalue),
+        },
+    ))
+}
+
+/// Lower a call to a built-in method of an enum.
+///
+/// The methods are expanded into a chain of comparisons against the values of
+/// the enum's members, such that they fold to a constant if the target is
+/// constant.
+fn lower_enum_method_call<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: hir::EnumMethod,
+    value: &'a Rvalue<'a>,
+    arg_ids: &[NodeId],
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let enm = match value.ty.get_enum() {
+        Some(enm) => enm,
+        None => bug_span!(builder.span, cx, "enum method called on `{}`", value.ty),
+    };
+
+    // Determine the value of each member.
+    let members = enm
+        .variants
+        .iter()
+        .map(|(name, ast)| Ok((name.value, cx.constant_int_value_of(ast.id(), builder.env)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let num = members.len();
+    let make_member =
+        |index: usize| builder.constant(value::make_int(ty, members[index].1.clone()));
+
+    // Methods that do not depend on the current value are simple constants.
+    let step = match method {
+        hir::EnumMethod::First => return Ok(make_member(0)),
+        hir::EnumMethod::Last => return Ok(make_member(num - 1)),
+        hir::EnumMethod::Num => return Ok(builder.constant(value::make_int(ty, num.into()))),
+        hir::EnumMethod::Next(true) | hir::EnumMethod::Prev(true) => {
+            let step = cx.mir_rvalue(arg_ids[0], builder.env);
+            if step.is_error() {
+                return Err(());
+            }
+            if !step.is_const() {
+                let values: Vec<_> = members.iter().map(|&(_, value)| value).collect();
+                return Ok(lower_enum_step_dynamic(
+                    builder, ty, method, value, step, &values,
+                ));
+            }
+            (cx.constant_int_value_of(arg_ids[0], builder.env)? % num)
+                .to_usize()
+                .unwrap()
+        }
+        _ => 1 % num,
+    };
+
+    // Build the chain of comparisons, such that the first matching member
+    // determines the result. Values that are not a member of the enum produce
+    // the default value of the enum, or an empty string for `name()`.
+    let bit = SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 1).to_unpacked(cx);
+    let mut result = builder.build(ty, RvalueKind::Const(cx.type_default_value(ty)));
+    for (index, (_, member)) in members.iter().enumerate().rev() {
+        let true_value = match method {
+            hir::EnumMethod::Next(_) => make_member((index + step) % num),
+            hir::EnumMethod::Prev(_) => make_member((index + num - step) % num),
+            hir::EnumMethod::Name => builder.constant(value::make_string(
+                ty,
+                members[index].0.as_str().as_bytes().to_vec(),
+            )),
+            _ => unreachable!(),
+        };
+        let cond = make_int_comparison(
+            builder,
+            bit,
+            value.ty,
+            IntCompOp::Eq,
+            value,
+            builder.constant(value::make_int(value.ty, (*member).clone())),
+        );
+        result = builder.build(
+            ty,
+            RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value: result,
+            },
+        );
+    }
+    Ok(result)
+}
+
+/// Lower a call to `next(N)` or `prev(N)` of an enum, where `N` is not
+/// constant.
+///
+/// The position of the current value among the members is determined through
+/// a chain of comparisons, and then advanced by `N` modulo the number of
+/// members. The result is read from a table of the member values at that
+/// position. Values that are not a member of the enum map to one past the last
+/// member, where the table holds the default value of the enum.
+fn lower_enum_step_dynamic<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: hir::EnumMethod,
+    value: &'a Rvalue<'a>,
+    step: &'a Rvalue<'a>,
+    members: &[&'a BigInt],
+) -> &'a Rvalue<'a> {
+    let cx = builder.cx;
+    let num = members.len();
+    let bit = SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 1).to_unpacked(cx);
+    let index_ty = step.ty;
+
+    // Determine the position of the current value.
+    let count = builder.constant_u32(num as u32);
+    let mut index = count;
+    for (i, &member) in members.iter().enumerate().rev() {
+        let cond = make_int_comparison(
+            builder,
+            bit,
+            value.ty,
+            IntCompOp::Eq,
+            value,
+            builder.constant(value::make_int(value.ty, member.clone())),
+        );
+        index = builder.build(
+            index_ty,
+            RvalueKind::Ternary {
+                cond,
+                true_value: builder.constant_u32(i as u32),
+                false_value: index,
+            },
+        );
+    }
+
+    // Advance the position, wrapping around at the end of the enum.
+    let step = make_int_binary_arith(builder, index_ty, IntBinaryArithOp::Mod, step, count);
+    let advanced = match method {
+        hir::EnumMethod::Next(_) => {
+            make_int_binary_arith(builder, index_ty, IntBinaryArithOp::Add, index, step)
+        }
+        _ => {
+            let index =
+                make_int_binary_arith(builder, index_ty, IntBinaryArithOp::Add, index, count);
+            make_int_binary_arith(builder, index_ty, IntBinaryArithOp::Sub, index, step)
+        }
+    };
+    let advanced = make_int_binary_arith(builder, index_ty, IntBinaryArithOp::Mod, advanced, count);
+    let is_member = make_int_comparison(builder, bit, index_ty, IntCompOp::Lt, index, count);
+    let position = builder.build(
+        index_ty,
+        RvalueKind::Ternary {
+            cond: is_member,
+            true_value: advanced,
+            false_value: count,
+        },
+    );
+
+    // Look up the member value in the table.
+    let mut table: Vec<_> = members
+        .iter()
+        .map(|&member| cx.intern_value(value::make_int(ty, member.clone())))
+        .collect();
+    table.push(cx.type_default_value(ty));
+    let table_ty =
+        UnpackedType::make_dims(cx, ty.core.clone(), vec![ty::UnpackedDim::Array(num + 1)]);
+    let table = builder.constant(value::make_array(table_ty, table));
+    builder.build(
+        ty,
+        RvalueKind::Index {
+            value: table,
+            base: position,
+            length: 0,
+        },
+    )
+}
+
+/// Lower a call to an array manipulation method of a fixed-size array.
+///
+/// The size of the array is known statically, so the methods are unrolled into
+/// one operation per element. The `with` clause is lowered once for every
+/// element, with the iterator bound to that element's index.
+fn lower_array_manip_call<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    ty: &'a UnpackedType<'a>,
+    method: hir::ArrayManipMethod,
+    target: NodeId,
+    value: &'a Rvalue<'a>,
+    with: Option<(NodeId, NodeId)>,
+) -> Result<&'a Rvalue<'a>> {
+    let cx = builder.cx;
+    let dim = value.ty.unpacked_dims().next();
+    let (size, offset) = match dim {
+        Some(ty::UnpackedDim::Array(size)) => (size, 0),
+        Some(ty::UnpackedDim::Range(r)) => (r.size, r.offset),
+        _ => bug_span!(builder.span, cx, "array method called on `{}`", value.ty),
+    };
+    let elem_ty = value.ty.pop_dim(cx).unwrap();
+    let bit = SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 1).to_unpacked(cx);
+
+    // Gather the elements of the array, and the value the method operates on
+    // for each of them. The latter is the result of the `with` clause if one
+    // is present, or the element itself otherwise.
+    let elems: Vec<_> = (0..size)
+        .map(|i| {
+            builder.build(
+                elem_ty,
+                RvalueKind::Index {
+                    value,
+                    base: builder.constant_u32(i as u32),
+                    length: 0,
+                },
+            )
+        })
+        .collect();
+    let keys: Vec<_> = match with {
+        Some((iterator, expr)) => (0..size)
+            .map(|i| {
+                let index_ty =
+                    SbvType::new(ty::Domain::TwoValued, ty::Sign::Unsigned, 32).to_unpacked(cx);
+                let index = cx.intern_value(value::make_int(index_ty, i.into()));
+                let mut env_data = cx.param_env_data(builder.env).clone();
+                env_data.set_value(iterator, index);
+                cx.mir_rvalue(expr, cx.intern_param_env(env_data))
+            })
+            .collect(),
+        None => elems.clone(),
+    };
+    if keys.iter().any(|key| key.is_error()) {
+        return Err(());
+    }
+
+    // The `with` clause of the locator methods is a condition (§7.12.1).
+    let keys = if method.requires_with() {
+        keys.into_iter()
+            .map(|key| lower_locator_condition(builder, key))
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        keys
+    };
+    let key_ty = keys.first().map(|key| key.ty).unwrap_or(elem_ty);
+    let compares_keys = !method.requires_with() && method != hir::ArrayManipMethod::Reverse;
+    if compares_keys && key_ty.get_simple_bit_vector().is_none() {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "unsupported: array method on elements of type `{}`",
+                key_ty
+            ))
+            .span(builder.span),
+        );
+        return Err(());
+    }
+
+    // Locator methods return either the element or its index.
+    let make_result = |i: usize| {
+        if method.returns_indices() {
+            let index_ty = ty.pop_dim(cx).unwrap();
+            builder.constant(value::make_int(index_ty, (offset + i as isize).into()))
+        } else {
+            elems[i]
+        }
+    };
+    let make_queue = |items: &mut dyn Iterator<Item = (Option<&'a Rvalue<'a>>, usize)>| {
+        let mut queue = builder.constant(ValueData {
+            ty,
+            kind: ValueKind::EmptyArray,
+        });
+        for (cond, i) in items {
+            let pushed = builder.build(
+                ty,
+                RvalueKind::QueuePush {
+                    front: false,
+                    queue,
+                    value: make_result(i),
+                },
+            );
+            queue = match cond {
+                Some(cond) => builder.build(
+                    ty,
+                    RvalueKind::Ternary {
+                        cond,
+                        true_value: pushed,
+                        false_value: queue,
+                    },
+                ),
+                None => pushed,
+            };
+        }
+        queue
+    };
+    let make_ternary = |ty, cond, true_value, false_value| {
+        builder.build(
+            ty,
+            RvalueKind::Ternary {
+                cond,
+                true_value,
+                false_value,
+            },
+        )
+    };
+
+    let result = match method {
+        // Reductions combine all values with the corresponding operator.
+        hir::ArrayManipMethod::Sum
+        | hir::ArrayManipMethod::Product
+        | hir::ArrayManipMethod::And
+        | hir::ArrayManipMethod::Or
+        | hir::ArrayManipMethod::Xor => {
+            keys.iter().skip(1).fold(keys[0], |acc, &key| match method {
+                hir::ArrayManipMethod::Sum => {
+                    make_int_binary_arith(builder, ty, IntBinaryArithOp::Add, acc, key)
+                }
+                hir::ArrayManipMethod::Product => {
+                    make_int_binary_arith(builder, ty, IntBinaryArithOp::Mul, acc, key)
+                }
+                hir::ArrayManipMethod::And => {
+                    make_binary_bitwise(builder, ty, BinaryBitwiseOp::And, false, acc, key)
+                }
+                hir::ArrayManipMethod::Or => {
+                    make_binary_bitwise(builder, ty, BinaryBitwiseOp::Or, false, acc, key)
+                }
+                _ => make_binary_bitwise(builder, ty, BinaryBitwiseOp::Xor, false, acc, key),
+            })
+        }
+
+        // The extrema keep track of the best value seen so far, and the first
+        // element that produced it.
+        hir::ArrayManipMethod::Min | hir::ArrayManipMethod::Max => {
+            let op = match method {
+                hir::ArrayManipMethod::Min => IntCompOp::Lt,
+                _ => IntCompOp::Gt,
+            };
+            let mut best_key = keys[0];
+            let mut best_elem = elems[0];
+            for i in 1..size {
+                let better = make_int_comparison(builder, bit, key_ty, op, keys[i], best_key);
+                best_key = make_ternary(key_ty, better, keys[i], best_key);
+                best_elem = make_ternary(elem_ty, better, elems[i], best_elem);
+            }
+            builder.build(
+                ty,
+                RvalueKind::QueuePush {
+                    front: false,
+                    queue: builder.constant(ValueData {
+                        ty,
+                        kind: ValueKind::EmptyArray,
+                    }),
+                    value: best_elem,
+                },
+            )
+        }
+
+        // Unique elements are those whose value differs from all the
+        // preceding ones.
+        hir::ArrayManipMethod::Unique | hir::ArrayManipMethod::UniqueIndex => {
+            make_queue(&mut (0..size).map(|i| {
+                let cond = (0..i)
+                    .map(|j| {
+                        make_int_comparison(builder, bit, key_ty, IntCompOp::Neq, keys[i], keys[j])
+                    })
+                    .fold(None, |acc, neq| match acc {
+                        Some(acc) => Some(make_binary_bitwise(
+                            builder,
+                            bit,
+                            BinaryBitwiseOp::And,
+                            false,
+                            acc,
+                            neq,
+                        )),
+                        None => Some(neq),
+                    });
+                (cond, i)
+            }))
+        }
+
+        // Locators collect all elements for which the `with` clause holds.
+        hir::ArrayManipMethod::Find | hir::ArrayManipMethod::FindIndex => {
+            make_queue(&mut (0..size).map(|i| (Some(keys[i]), i)))
+        }
+
+        // The first and last locators pick the earliest or latest element for
+        // which the `with` clause holds.
+        hir::ArrayManipMethod::FindFirst
+        | hir::ArrayManipMethod::FindFirstIndex
+        | hir::ArrayManipMethod::FindLast
+        | hir::ArrayManipMethod::FindLastIndex => {
+            let first = match method {
+                hir::ArrayManipMethod::FindFirst | hir::ArrayManipMethod::FindFirstIndex => true,
+                _ => false,
+            };
+            let order: Box<dyn Iterator<Item = usize>> = if first {
+                Box::new((0..size).rev())
+            } else {
+                Box::new(0..size)
+            };
+            let mut queue = builder.constant(ValueData {
+                ty,
+                kind: ValueKind::EmptyArray,
+            });
+            for i in order {
+                let found = make_queue(&mut std::iter::once((None, i)));
+                queue = make_ternary(ty, keys[i], found, queue);
+            }
+            queue
+        }
+
+        // Reversing and sorting rearrange the elements and assign them back
+        // to the array. Sorting uses an odd-even transposition network, where
+        // each stage swaps neighbouring elements that are out of order.
+        hir::ArrayManipMethod::Reverse
+        | hir::ArrayManipMethod::Sort
+        | hir::ArrayManipMethod::Rsort => {
+            let mut elems = elems;
+            if method == hir::ArrayManipMethod::Reverse {
+                elems.reverse();
+            } else {
+                let op = match method {
+                    hir::ArrayManipMethod::Sort => IntCompOp::Gt,
+                    _ => IntCompOp::Lt,
+                };
+                let mut keys = keys;
+                for stage in 0..size {
+                    for j in (stage % 2..size.saturating_sub(1)).step_by(2) {
+                        let swap =
+                            make_int_comparison(builder, bit, key_ty, op, keys[j], keys[j + 1]);
+                        let (ka, kb) = (keys[j], keys[j + 1]);
+                        let (ea, eb) = (elems[j], elems[j + 1]);
+                        keys[j] = make_ternary(key_ty, swap, kb, ka);
+                        keys[j + 1] = make_ternary(key_ty, swap, ka, kb);
+                        elems[j] = make_ternary(elem_ty, swap, eb, ea);
+                        elems[j + 1] = make_ternary(elem_ty, swap, ea, eb);
+                    }
+                }
+            }
+            let lvalue = cx.mir_lvalue(target, builder.env);
+            if lvalue.is_error() {
+                return Err(());
+            }
+            let rvalue = builder.build(
+                value.ty,
+                RvalueKind::ConstructArray(elems.into_iter().enumerate().collect()),
+            );
+            return Ok(builder.build(
+                ty,
+                RvalueKind::Assignment {
+                    lvalue,
+                    rvalue,
+                    result: rvalue,
+                },
+            ));
+        }
+    };
+    Ok(result)
+}
+
+/// Cast the value of the `with` clause of a locator method to a boolean.
+fn lower_locator_condition<'a>(
+    builder: &Builder<'_, impl Context<'a>>,
+    value: &'a Rvalue<'a>,
+) -> Result<&'a Rvalue<'a>> {
+    let bool_ty = UnpackedType::make_logic();
+    if value.ty.is_simple_bit_vector() && value.ty.get_bit_size() == Some(1) {
+        return Ok(value);
+    }
+    if value.ty.is_real() {
+        let zero = builder.constant(value::make_real(value.ty, 0.0));
+        return Ok(builder.build(
+            bool_ty,
+            RvalueKind::RealComp {
+                op: RealCompOp::Neq,
+                lhs: value,
+                rhs: zero,
+            },
+        ));
+    }
+    if value.ty.get_simple_bit_vector().is_some() {
+        let value = if value.ty.is_simple_bit_vector() {
+            value
+        } else {
+            pack_simple_bit_vector(builder, value)
+        };
+        return Ok(builder.build(bool_ty, RvalueKind::CastToBool(value)));
+    }
+    builder.cx.emit(
+        DiagBuilder2::error(format!(
+            "`with` clause of a locator method must be a condition, but has type `{}`",
+            value.ty
+        ))
+        .span(value.span),
+    );
+    Err(())
+}
+
 /// Lower a function call.
 fn lower_function_call<'a>(
     builder: &Builder<'_, impl Context<'a>>,
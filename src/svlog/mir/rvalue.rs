@@ -235,6 +235,56 @@ impl<'a> Print for Rvalue<'a> {
             RvalueKind::Reduction { op, arg } => {
                 write!(inner, "Reduce({:?}, {})", op, ctx.print(outer, arg))?
             }
+            RvalueKind::IntToReal { sign, value } => {
+                write!(inner, "IntToReal({}, {})", sign, ctx.print(outer, value))?
+            }
+            RvalueKind::RealToInt { round, value } => write!(
+                inner,
+                "RealToInt({}, {})",
+                if round { "round" } else { "trunc" },
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::ResizeReal(arg) => write!(inner, "ResizeReal({})", ctx.print(outer, arg))?,
+            RvalueKind::RealNeg(arg) => write!(inner, "RealNeg({})", ctx.print(outer, arg))?,
+            RvalueKind::RealArith { op, lhs, rhs } => write!(
+                inner,
+                "RealArith {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
+            RvalueKind::RealComp { op, lhs, rhs } => write!(
+                inner,
+                "RealComp {} {:?} {}",
+                ctx.print(outer, lhs),
+                op,
+                ctx.print(outer, rhs)
+            )?,
+            RvalueKind::SysTask {
+                task,
+                diag,
+                ref msg,
+            } => {
+                write!(inner, "SysTask {:?} {} [", task, diag)?;
+                for (i, arg) in msg.iter().enumerate() {
+                    if i > 0 {
+                        write!(inner, ", ")?;
+                    }
+                    match arg {
+                        FormatArg::Literal(s) => write!(inner, "{:?}", s)?,
+                        FormatArg::Value(spec, value) => {
+                            write!(inner, "{} {}", spec, ctx.print(outer, value))?
+                        }
+                    }
+                }
+                write!(inner, "]")?
+            }
+            RvalueKind::Past { value, depth } => {
+                write!(inner, "Past({}, {})", ctx.print(outer, value), depth)?
+            }
+            RvalueKind::EventTriggered(value) => {
+                write!(inner, "EventTriggered({})", ctx.print(outer, value))?
+            }
             RvalueKind::Assignment {
                 lvalue,
                 rvalue,
@@ -257,6 +307,119 @@ impl<'a> Print for Rvalue<'a> {
                 op,
                 ctx.print(outer, rhs)
             )?,
+            RvalueKind::StringConcat(ref values) => write!(
+                inner,
+                "StringConcat({})",
+                ctx.print_comma_separated(outer, values)
+            )?,
+            RvalueKind::StringLen(arg) => write!(inner, "StringLen({})", ctx.print(outer, arg))?,
+            RvalueKind::StringGetc { value, index } => write!(
+                inner,
+                "StringGetc({}, {})",
+                ctx.print(outer, value),
+                ctx.print(outer, index)
+            )?,
+            RvalueKind::StringPutc {
+                value,
+                index,
+                character,
+            } => write!(
+                inner,
+                "StringPutc({}, {}, {})",
+                ctx.print(outer, value),
+                ctx.print(outer, index),
+                ctx.print(outer, character)
+            )?,
+            RvalueKind::StringSubstr { value, start, stop } => write!(
+                inner,
+                "StringSubstr({}, {}, {})",
+                ctx.print(outer, value),
+                ctx.print(outer, start),
+                ctx.print(outer, stop)
+            )?,
+            RvalueKind::StringToInt { radix, value } => {
+                write!(inner, "StringToInt({}, {})", radix, ctx.print(outer, value))?
+            }
+            RvalueKind::StringFromInt { radix, sign, value } => write!(
+                inner,
+                "StringFromInt({}, {}, {})",
+                radix,
+                sign,
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::ArrayNew { size, init } => write!(
+                inner,
+                "ArrayNew({}, {})",
+                ctx.print(outer, size),
+                ctx.print(outer, init)
+            )?,
+            RvalueKind::ArraySize(arg) => write!(inner, "ArraySize({})", ctx.print(outer, arg))?,
+            RvalueKind::ArrayGet { array, index } => write!(
+                inner,
+                "ArrayGet({}, {})",
+                ctx.print(outer, array),
+                ctx.print(outer, index)
+            )?,
+            RvalueKind::ArraySet {
+                array,
+                index,
+                value,
+            } => write!(
+                inner,
+                "ArraySet({}, {}, {})",
+                ctx.print(outer, array),
+                ctx.print(outer, index),
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::ArrayDelete { array, index } => write!(
+                inner,
+                "ArrayDelete({}, {})",
+                ctx.print(outer, array),
+                ctx.print(outer, index)
+            )?,
+            RvalueKind::ArrayExists { array, index } => write!(
+                inner,
+                "ArrayExists({}, {})",
+                ctx.print(outer, array),
+                ctx.print(outer, index)
+            )?,
+            RvalueKind::QueuePush {
+                front,
+                queue,
+                value,
+            } => write!(
+                inner,
+                "QueuePush{}({}, {})",
+                if front { "Front" } else { "Back" },
+                ctx.print(outer, queue),
+                ctx.print(outer, value)
+            )?,
+            RvalueKind::QueuePop { front, queue } => write!(
+                inner,
+                "QueuePop{}({})",
+                if front { "Front" } else { "Back" },
+                ctx.print(outer, queue)
+            )?,
+            RvalueKind::QueuePeek { front, queue } => write!(
+                inner,
+                "QueuePeek{}({})",
+                if front { "Front" } else { "Back" },
+                ctx.print(outer, queue)
+            )?,
+            RvalueKind::AssocTraverse { op, array, key } => write!(
+                inner,
+                "AssocTraverse {:?} ({}, {})",
+                op,
+                ctx.print(outer, array),
+                ctx.print(outer, key)
+            )?,
+            RvalueKind::AssocTraverseFound { op, array, key } => write!(
+                inner,
+                "AssocTraverseFound {:?} ({}, {})",
+                op,
+                ctx.print(outer, array),
+                ctx.print(outer, key)
+            )?,
             RvalueKind::Call { target, ref args } => {
                 write!(
                     inner,
@@ -425,8 +588,157 @@ pub enum RvalueKind<'a> {
         lhs: &'a Rvalue<'a>,
         rhs: &'a Rvalue<'a>,
     },
+    /// Concatenate multiple strings.
+    StringConcat(Vec<&'a Rvalue<'a>>),
+    /// The number of characters in a string.
+    StringLen(&'a Rvalue<'a>),
+    /// The character at an index of a string, or zero if the index is out of
+    /// range.
+    StringGetc {
+        value: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
+    /// Replace the character at an index of a string, and produce the modified
+    /// string. The string is unchanged if the index is out of range or the
+    /// character is zero.
+    StringPutc {
+        value: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+        character: &'a Rvalue<'a>,
+    },
+    /// The characters of a string from index `start` to `stop`, inclusive. The
+    /// result is empty if the indices are out of range.
+    StringSubstr {
+        value: &'a Rvalue<'a>,
+        start: &'a Rvalue<'a>,
+        stop: &'a Rvalue<'a>,
+    },
+    /// Parse a string as an integer in the given radix.
+    StringToInt {
+        #[dont_visit]
+        radix: u32,
+        value: &'a Rvalue<'a>,
+    },
+    /// Format an integer as a string in the given radix.
+    StringFromInt {
+        #[dont_visit]
+        radix: u32,
+        sign: Sign,
+        value: &'a Rvalue<'a>,
+    },
+    /// Allocate a dynamic array of `size` elements, initialized with the
+    /// elements of `init`, as with `new[size](init)`.
+    ArrayNew {
+        size: &'a Rvalue<'a>,
+        init: &'a Rvalue<'a>,
+    },
+    /// The number of elements in a dynamic array, queue, or associative array.
+    ArraySize(&'a Rvalue<'a>),
+    /// The element at an index or key of an array, or the default value of the
+    /// element type if there is no such element.
+    ArrayGet {
+        array: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
+    /// Replace the element at an index or key of an array, and produce the
+    /// modified array. Associative arrays insert the element if the key does
+    /// not exist yet; other arrays are unchanged if the index is out of range.
+    ArraySet {
+        array: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+        value: &'a Rvalue<'a>,
+    },
+    /// Remove the element at an index or key of an array, and produce the
+    /// modified array.
+    ArrayDelete {
+        array: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
+    /// Whether an associative array has an element with the given key.
+    ArrayExists {
+        array: &'a Rvalue<'a>,
+        index: &'a Rvalue<'a>,
+    },
+    /// Insert an element at the front or back of a queue, and produce the
+    /// modified queue.
+    QueuePush {
+        #[dont_visit]
+        front: bool,
+        queue: &'a Rvalue<'a>,
+        value: &'a Rvalue<'a>,
+    },
+    /// Remove the element at the front or back of a queue, and produce the
+    /// modified queue.
+    QueuePop {
+        #[dont_visit]
+        front: bool,
+        queue: &'a Rvalue<'a>,
+    },
+    /// The element at the front or back of a queue, or the default value of
+    /// the element type if the queue is empty.
+    QueuePeek {
+        #[dont_visit]
+        front: bool,
+        queue: &'a Rvalue<'a>,
+    },
+    /// The key of an associative array that comes first, last, after, or before
+    /// `key`. Produces `key` unchanged if there is no such key.
+    AssocTraverse {
+        op: AssocTraverseOp,
+        array: &'a Rvalue<'a>,
+        key: &'a Rvalue<'a>,
+    },
+    /// Whether `AssocTraverse` with the same operands finds a key.
+    AssocTraverseFound {
+        op: AssocTraverseOp,
+        array: &'a Rvalue<'a>,
+        key: &'a Rvalue<'a>,
+    },
+    /// Convert a simple bit vector to a real number.
+    IntToReal { sign: Sign, value: &'a Rvalue<'a> },
+    /// Convert a real number to a 64 bit signed integer. Rounds to the nearest
+    /// integer, with ties away from zero, if `round` is set. Truncates towards
+    /// zero otherwise.
+    RealToInt { round: bool, value: &'a Rvalue<'a> },
+    /// Convert a real number to a different precision. E.g. `real` to
+    /// `shortreal`.
+    ResizeReal(&'a Rvalue<'a>),
+    /// A real negation.
+    RealNeg(&'a Rvalue<'a>),
+    /// A real binary arithmetic operator.
+    RealArith {
+        op: RealArithOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
+    /// A real comparison operator.
+    RealComp {
+        op: RealCompOp,
+        lhs: &'a Rvalue<'a>,
+        rhs: &'a Rvalue<'a>,
+    },
     /// Convert an integer to a time value by applying the currently active timescale.
     ApplyTimescale(&'a Rvalue<'a>, BigRational),
+    /// A call to a display, simulation control, or severity task.
+    SysTask {
+        #[dont_visit]
+        task: hir::SysTask,
+        /// The finish number of `$finish`, `$stop`, and `$fatal`.
+        #[dont_visit]
+        diag: usize,
+        /// The message printed by the task.
+        msg: Vec<FormatArg<'a>>,
+    },
+    /// The sampled value of an expression a number of clock ticks in the
+    /// past, as produced by `$past`. Only valid within concurrent assertions.
+    Past {
+        value: &'a Rvalue<'a>,
+        #[dont_visit]
+        depth: usize,
+    },
+    /// Whether an event has been triggered in the current time step, as with
+    /// `e.triggered`.
+    EventTriggered(&'a Rvalue<'a>),
     /// A function or task call.
     Call {
         /// The called function.
@@ -462,6 +774,13 @@ impl<'a> RvalueKind<'a> {
             | RvalueKind::Member { value, .. }
             | RvalueKind::PackString(value)
             | RvalueKind::UnpackString(value)
+            | RvalueKind::IntToReal { value, .. }
+            | RvalueKind::RealToInt { value, .. }
+            | RvalueKind::ResizeReal(value)
+            | RvalueKind::RealNeg(value)
+            | RvalueKind::StringLen(value)
+            | RvalueKind::StringToInt { value, .. }
+            | RvalueKind::StringFromInt { value, .. }
             | RvalueKind::ApplyTimescale(value, _) => value.is_const(),
             RvalueKind::ConstructArray(values) => values.values().all(|v| v.is_const()),
             RvalueKind::ConstructStruct(values) => values.iter().all(|v| v.is_const()),
@@ -472,8 +791,21 @@ impl<'a> RvalueKind<'a> {
             RvalueKind::BinaryBitwise { lhs, rhs, .. }
             | RvalueKind::IntBinaryArith { lhs, rhs, .. }
             | RvalueKind::IntComp { lhs, rhs, .. }
-            | RvalueKind::StringComp { lhs, rhs, .. } => lhs.is_const() && rhs.is_const(),
-            RvalueKind::Concat(values) => values.iter().all(|v| v.is_const()),
+            | RvalueKind::StringComp { lhs, rhs, .. }
+            | RvalueKind::RealArith { lhs, rhs, .. }
+            | RvalueKind::RealComp { lhs, rhs, .. } => lhs.is_const() && rhs.is_const(),
+            RvalueKind::Concat(values) | RvalueKind::StringConcat(values) => {
+                values.iter().all(|v| v.is_const())
+            }
+            RvalueKind::StringGetc { value, index } => value.is_const() && index.is_const(),
+            RvalueKind::StringPutc {
+                value,
+                index,
+                character,
+            } => value.is_const() && index.is_const() && character.is_const(),
+            RvalueKind::StringSubstr { value, start, stop } => {
+                value.is_const() && start.is_const() && stop.is_const()
+            }
             RvalueKind::Var(_) => false,
             RvalueKind::Port(_) => false,
             RvalueKind::Arg(_) => false,
@@ -488,6 +820,20 @@ impl<'a> RvalueKind<'a> {
             } => cond.is_const() && true_value.is_const() && false_value.is_const(),
             RvalueKind::Shift { value, amount, .. } => value.is_const() && amount.is_const(),
             RvalueKind::Assignment { .. } => false,
+            RvalueKind::Past { .. } => false,
+            RvalueKind::EventTriggered(_) => false,
+            RvalueKind::ArrayNew { .. }
+            | RvalueKind::ArraySize(_)
+            | RvalueKind::ArrayGet { .. }
+            | RvalueKind::ArraySet { .. }
+            | RvalueKind::ArrayDelete { .. }
+            | RvalueKind::ArrayExists { .. }
+            | RvalueKind::QueuePush { .. }
+            | RvalueKind::QueuePop { .. }
+            | RvalueKind::QueuePeek { .. }
+            | RvalueKind::AssocTraverse { .. }
+            | RvalueKind::AssocTraverseFound { .. } => false,
+            RvalueKind::SysTask { .. } => false,
             // TODO(fschuiki): This is wrong; function calls *may* be constant
             // under certain circumstances.
             RvalueKind::Call { .. } => false,
@@ -542,6 +888,10 @@ pub enum IntBinaryArithOp {
 pub enum IntCompOp {
     Eq,
     Neq,
+    CaseEq,
+    CaseNeq,
+    WildcardEq,
+    WildcardNeq,
     Lt,
     Leq,
     Gt,
@@ -555,6 +905,46 @@ pub enum IntCompOp {
 pub enum StringCompOp {
     Eq,
     Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
+}
+
+/// The associative array traversal methods.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum AssocTraverseOp {
+    First,
+    Last,
+    Next,
+    Prev,
+}
+
+/// The real arithmetic operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// The real comparison operators.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RealCompOp {
+    Eq,
+    Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
 }
 
 /// The shift operators.
@@ -590,6 +980,16 @@ impl<'a> Print for CallInput<'a> {
     }
 }
 
+/// A piece of the message printed by a system task.
+#[moore_derive::visit_without_foreach]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatArg<'a> {
+    /// Literal text.
+    Literal(#[dont_visit] String),
+    /// A value formatted according to a format specifier.
+    Value(#[dont_visit] crate::format::FormatSpec, &'a Rvalue<'a>),
+}
+
 /// A call argument.
 #[moore_derive::visit_without_foreach]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,6 +81,8 @@ pub struct ParamEnvData<'t> {
     values: Vec<(NodeId, ParamEnvBinding<Value<'t>>)>,
     types: Vec<(NodeId, ParamEnvBinding<&'t UnpackedType<'t>>)>,
     intfs: Vec<(NodeId, NodeEnvId)>,
+    /// The functions being evaluated at elaboration time, outermost first.
+    calls: Vec<NodeId>,
 }
 
 impl<'t> ParamEnvData<'t> {
@@ -129,6 +131,19 @@ impl<'t> ParamEnvData<'t> {
     pub fn add_interfaces(&mut self, iter: impl IntoIterator<Item = (NodeId, NodeEnvId)>) {
         self.intfs.extend(iter);
     }
+
+    /// Get the functions being evaluated at elaboration time.
+    ///
+    /// Non-empty if this environment binds the arguments and locals of a
+    /// constant function call, with the innermost call last.
+    pub fn calls(&self) -> &[NodeId] {
+        &self.calls
+    }
+
+    /// Enter a constant function call.
+    pub fn push_call(&mut self, func_id: NodeId) {
+        self.calls.push(func_id);
+    }
 }
 
 /// A binding in a parameter environment.
@@ -294,6 +309,7 @@ fn param_env_from_instance<'a>(
         types,
         values,
         intfs: Default::default(),
+        calls: Default::default(),
     });
     cx.add_param_env_context(env, node.id());
     Ok(env)
@@ -110,6 +110,94 @@ pub(crate) fn map_pattern<'a>(
     }))
 }
 
+/// A mapping of a `tagged` expression to the member of a tagged union.
+#[derive(Clone, Debug)]
+pub struct TaggedMapping<'a> {
+    /// The corresponding `tagged` expression.
+    pub hir: &'a hir::Expr<'a>,
+    /// The tagged union type the expression constructs.
+    pub ty: &'a ty::UnpackedType<'a>,
+    /// The index of the selected member, which is also the tag value.
+    pub index: usize,
+    /// The selected member.
+    pub member: &'a ty::StructMember<'a>,
+    /// The value assigned to the member, if any.
+    pub value: Option<&'a hir::Expr<'a>>,
+}
+
+/// Determine the member of a tagged union a `tagged` expression refers to.
+#[moore_derive::query]
+pub(crate) fn map_tagged<'a>(
+    cx: &impl Context<'a>,
+    Ref(expr): Ref<'a, hir::Expr<'a>>,
+    env: ParamEnv,
+) -> Result<Arc<TaggedMapping<'a>>> {
+    let (name, value) = match expr.kind {
+        hir::ExprKind::Tagged(name, value) => (name, value),
+        _ => bug_span!(expr.span, cx, "expression is not a `tagged` expression"),
+    };
+    let ty = cx.need_type_context(Ref(expr), env);
+    if ty.is_error() {
+        return Err(());
+    }
+    let ty = ty.ty();
+    let (index, member) = find_tagged_member(cx, ty, name, expr.span)?;
+    let value = match value {
+        Some(id) => Some(hir_of_expr(cx, id)?),
+        None => None,
+    };
+    Ok(Arc::new(TaggedMapping {
+        hir: expr,
+        ty,
+        index,
+        member,
+        value,
+    }))
+}
+
+/// Find a member of a tagged union by name.
+///
+/// Returns the index of the member within the union, together with the member
+/// itself. Emits a diagnostic if `ty` is not a tagged union or has no such
+/// member.
+pub(crate) fn find_tagged_member<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a ty::UnpackedType<'a>,
+    name: Spanned<Name>,
+    span: Span,
+) -> Result<(usize, &'a ty::StructMember<'a>)> {
+    let union = match ty.get_union() {
+        Some(u) if u.is_tagged() => u,
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`tagged` expression requires a tagged union, but `{}` is not one",
+                    ty
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+    };
+    match union
+        .members
+        .iter()
+        .enumerate()
+        .find(|(_, m)| m.name.value == name.value)
+    {
+        Some(x) => Ok(x),
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("tagged union `{}` has no member `{}`", ty, name))
+                    .span(name.span)
+                    .add_note("Union definition was here:")
+                    .span(union.ast.span()),
+            );
+            Err(())
+        }
+    }
+}
+
 /// Helper function to get the HIR expr associated with a node ID. This should
 /// eventually go into the HIR module.
 fn hir_of_expr<'a>(cx: &impl Context<'a>, node: NodeId) -> Result<&'a hir::Expr<'a>> {
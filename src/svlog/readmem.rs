@@ -0,0 +1,344 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Memory initialization with `$readmemh` and `$readmemb`.
+//!
+//! Memory files are read and parsed at compile time. The loaded words become
+//! the initial value of the memory variable, such that the `initial` procedure
+//! that contains the call needs no runtime file access.
+
+use crate::{
+    crate_prelude::*,
+    ty::UnpackedType,
+    value::{self, Value},
+    ParamEnv,
+};
+use num::{BigInt, ToPrimitive, Zero};
+use std::path::{Path, PathBuf};
+
+/// An entry in a memory file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemItem {
+    /// An address specification `@hh...`.
+    Addr(usize),
+    /// A data word.
+    ///
+    /// Holds the value of the word, followed by masks of the bits given as X
+    /// and Z digits. Unknown bits read as zero in the value.
+    Word(BigInt, BigInt, BigInt),
+}
+
+/// Parse the contents of a memory file.
+///
+/// Words are given in hexadecimal if `hex` is set, and in binary otherwise.
+/// The bits of X and Z digits are collected in separate masks of the word.
+pub fn parse_mem_file(text: &str, hex: bool) -> std::result::Result<Vec<MemItem>, String> {
+    let radix = if hex { 16 } else { 2 };
+    let mut items = vec![];
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => (),
+
+            // Comments.
+            '/' if chars.peek() == Some(&'/') => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => return Err(format!("unterminated comment in line {}", line)),
+                    }
+                }
+            }
+
+            // Addresses and data words.
+            _ => {
+                let is_addr = c == '@';
+                let mut token = String::new();
+                if !is_addr {
+                    token.push(c);
+                }
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '/' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                if is_addr {
+                    let addr = usize::from_str_radix(&token.replace('_', ""), 16)
+                        .map_err(|_| format!("invalid address `@{}` in line {}", token, line))?;
+                    items.push(MemItem::Addr(addr));
+                    continue;
+                }
+                let digits: String = token.chars().filter(|&c| c != '_').collect();
+                let all_ones = if hex { 'f' } else { '1' };
+                let mask = |pred: fn(char) -> bool| -> String {
+                    digits
+                        .chars()
+                        .map(|c| if pred(c) { all_ones } else { '0' })
+                        .collect()
+                };
+                let x_digits = mask(|c| c == 'x' || c == 'X');
+                let z_digits = mask(|c| c == 'z' || c == 'Z' || c == '?');
+                let known_digits: String = digits
+                    .chars()
+                    .map(|c| match c {
+                        'x' | 'X' | 'z' | 'Z' | '?' => '0',
+                        c => c,
+                    })
+                    .collect();
+                let parse = |digits: &str| BigInt::parse_bytes(digits.as_bytes(), radix);
+                match (parse(&known_digits), parse(&x_digits), parse(&z_digits)) {
+                    (Some(word), Some(x), Some(z)) if !digits.is_empty() => {
+                        items.push(MemItem::Word(word, x, z))
+                    }
+                    _ => {
+                        return Err(format!(
+                            "invalid {} number `{}` in line {}",
+                            if hex { "hexadecimal" } else { "binary" },
+                            token,
+                            line
+                        ))
+                    }
+                }
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Find a memory file referenced from a source file.
+///
+/// Relative paths are looked up in the directory of the referencing source
+/// file first, followed by the include paths.
+pub fn find_mem_file(name: &str, source: &str, include_paths: &[PathBuf]) -> Option<PathBuf> {
+    let name = Path::new(name);
+    if name.is_absolute() {
+        return Some(name.to_path_buf()).filter(|p| p.is_file());
+    }
+    let source_dir = Path::new(source).parent().map(Path::to_path_buf);
+    source_dir
+        .into_iter()
+        .chain(include_paths.iter().cloned())
+        .map(|dir| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+/// Compute the value of a memory after loading it with `$readmemh` or
+/// `$readmemb`.
+///
+/// The memory starts out with the default value of its type. Words are loaded
+/// from the `start` address towards the `end` address. Without an explicit
+/// range, loading starts at the lowest address of the memory. X and Z digits
+/// are kept if the memory is four-state, and read as zero otherwise.
+pub fn load_mem<'a>(
+    cx: &impl Context<'a>,
+    span: Span,
+    hex: bool,
+    file: NodeId,
+    mem_ty: &'a UnpackedType<'a>,
+    start: Option<NodeId>,
+    end: Option<NodeId>,
+    env: ParamEnv,
+) -> Result<Value<'a>> {
+    let name = if hex { "$readmemh" } else { "$readmemb" };
+
+    // Determine the memory geometry.
+    let mem_ty = mem_ty.resolve_full();
+    let range = match (mem_ty.dims.is_empty(), mem_ty.outermost_dim()) {
+        (false, Some(dim)) => dim
+            .get_range()
+            .or_else(|| dim.get_size().map(ty::Range::with_size)),
+        _ => None,
+    };
+    let range = match range {
+        Some(r) => r,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` requires an unpacked array of fixed size; memory has type `{}`",
+                    name, mem_ty
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+    };
+    let elem_ty = mem_ty.pop_dim(cx).unwrap();
+    let width = match elem_ty.get_simple_bit_vector() {
+        Some(sbv) => sbv.size,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` requires memory words to be bit vectors; memory has type `{}`",
+                    name, mem_ty
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+    };
+
+    // Determine the addressing range.
+    let low = range.low();
+    let high = range.high();
+    let address_of = |id: Option<NodeId>, default: isize| -> Result<isize> {
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(default),
+        };
+        match cx.constant_int_value_of(id, env)?.to_isize() {
+            Some(addr) if addr >= low && addr <= high => Ok(addr),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "address `{}` is outside the memory range [{}:{}]",
+                        cx.span(id).extract(),
+                        low,
+                        high
+                    ))
+                    .span(cx.span(id)),
+                );
+                Err(())
+            }
+        }
+    };
+    let start = address_of(start, low)?;
+    let end = address_of(end, high)?;
+    let step = if start <= end { 1 } else { -1 };
+
+    // Read the file.
+    let file_name = match cx.constant_value_of(file, env).kind {
+        value::ValueKind::String(ref bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        value::ValueKind::Int(ref v, ..) if !v.is_zero() => {
+            String::from_utf8_lossy(&v.to_bytes_be().1).into_owned()
+        }
+        _ => {
+            cx.emit(
+                DiagBuilder2::error(format!("file name of `{}` must be a constant string", name))
+                    .span(cx.span(file)),
+            );
+            return Err(());
+        }
+    };
+    let include_paths = &cx.sess().opts.include_paths;
+    let path = match find_mem_file(&file_name, &span.source.get_path(), include_paths) {
+        Some(path) => path,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("memory file `{}` not found", file_name))
+                    .span(cx.span(file)),
+            );
+            return Err(());
+        }
+    };
+    let items = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| parse_mem_file(&text, hex))
+    {
+        Ok(items) => items,
+        Err(msg) => {
+            cx.emit(
+                DiagBuilder2::error(format!("cannot read `{}`: {}", path.display(), msg))
+                    .span(span),
+            );
+            return Err(());
+        }
+    };
+
+    // Load the words into the memory.
+    let mut words: Vec<_> = match cx.type_default_value(mem_ty).kind {
+        value::ValueKind::StructOrArray(ref elements) => elements.clone(),
+        _ => unreachable!("default value of memory `{}` is not an array", mem_ty),
+    };
+    let mask = (BigInt::from(1) << width) - 1;
+    let in_range = |addr: isize| {
+        if step > 0 {
+            addr >= start && addr <= end
+        } else {
+            addr <= start && addr >= end
+        }
+    };
+    let mut addr = start;
+    let mut warned_unknown = false;
+    for item in items {
+        match item {
+            MemItem::Addr(a) => {
+                addr = a as isize;
+                if !in_range(addr) {
+                    cx.emit(
+                        DiagBuilder2::error(format!(
+                            "address `@{:x}` in `{}` is outside the loaded range [{}:{}]",
+                            a,
+                            path.display(),
+                            start,
+                            end
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            }
+            MemItem::Word(word, x, z) => {
+                if !in_range(addr) {
+                    cx.emit(
+                        DiagBuilder2::warning(format!(
+                            "`{}` contains more words than fit into the loaded range [{}:{}]",
+                            path.display(),
+                            start,
+                            end
+                        ))
+                        .span(span),
+                    );
+                    break;
+                }
+                let (x, z) = (x & &mask, z & &mask);
+                let value = if x.is_zero() && z.is_zero() {
+                    value::make_int(elem_ty, word & &mask)
+                } else if cx.is_four_state(elem_ty) {
+                    // Z bits are encoded as special bits that are not X.
+                    let special = &x | &z;
+                    value::make_int_special(
+                        elem_ty,
+                        word & &mask,
+                        value::int_to_bits(&special, width),
+                        value::int_to_bits(&x, width),
+                    )
+                } else {
+                    if !warned_unknown {
+                        cx.emit(
+                            DiagBuilder2::warning(format!(
+                                "memory file `{}` contains X or Z digits; they are read as \
+                                 0 since the memory is two-state",
+                                file_name
+                            ))
+                            .span(span),
+                        );
+                        warned_unknown = true;
+                    }
+                    value::make_int(elem_ty, word & &mask)
+                };
+                words[(addr - low) as usize] = cx.intern_value(value);
+                addr += step;
+            }
+        }
+    }
+    Ok(cx.intern_value(value::make_array(mem_ty, words)))
+}
@@ -401,10 +401,10 @@ pub(crate) fn resolve_field_access<'a>(
     };
 
     let ty = cx.type_of(target_id, env)?;
-    let strukt = if let Some(strukt) = ty.get_struct() {
+    let strukt = if let Some(strukt) = ty.get_struct().or_else(|| ty.get_union()) {
         strukt
     } else {
-        let mut d = DiagBuilder2::error(format!("value of type `{}` is not a struct", ty))
+        let mut d = DiagBuilder2::error(format!("value of type `{}` is not a struct or union", ty))
             .span(hir.human_span());
         if ty.resolve_full() != ty {
             d = d.add_note(format!("`{}` is defined as `{}`", ty, ty.resolve_full()));
@@ -431,6 +431,93 @@ pub(crate) fn resolve_field_access<'a>(
     }
 }
 
+/// Resolve a method call.
+///
+/// Determines which built-in method is called, based on the type of the
+/// target, and checks the number of arguments. Methods without arguments may
+/// also be called without parentheses, in which case the node is a field
+/// access.
+#[moore_derive::query]
+pub(crate) fn resolve_method_call<'a>(
+    cx: &impl Context<'a>,
+    node_id: NodeId,
+    env: ParamEnv,
+) -> Result<hir::Method> {
+    let hir = match cx.hir_of(node_id)? {
+        HirNode::Expr(x) => x,
+        _ => unreachable!(),
+    };
+    let (target_id, name, num_args, with) = match hir.kind {
+        hir::ExprKind::MethodCall(target_id, name, ref args, with) => {
+            (target_id, name, args.len(), with)
+        }
+        hir::ExprKind::Field(target_id, name) => (target_id, name, 0, None),
+        _ => unreachable!(),
+    };
+
+    let ty = cx.self_determined_type(target_id, env).ok_or(())?;
+    if ty.is_error() {
+        return Err(());
+    }
+    let method = if ty.is_string() {
+        hir::StringMethod::from_name(name.value).map(hir::Method::String)
+    } else if ty.is_event() {
+        hir::EventMethod::from_name(name.value).map(hir::Method::Event)
+    } else if let Some(dim) = ty.get_dynamic_dim() {
+        hir::ArrayMethod::from_name(name.value, num_args)
+            .filter(|method| method.applies_to(dim))
+            .map(hir::Method::Array)
+    } else if ty.get_enum().is_some() {
+        hir::EnumMethod::from_name(name.value, num_args).map(hir::Method::Enum)
+    } else if ty.is_fixed_array() {
+        hir::ArrayManipMethod::from_name(name.value).map(hir::Method::ArrayManip)
+    } else {
+        None
+    };
+    let method = match method {
+        Some(method) => method,
+        None => {
+            cx.emit(
+                DiagBuilder2::error(format!("value of type `{}` has no method `{}`", ty, name))
+                    .span(name.span()),
+            );
+            return Err(());
+        }
+    };
+
+    let expected_args = method.num_args();
+    if num_args != expected_args {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "method `{}` expects {} argument{}, but {} were given",
+                name,
+                expected_args,
+                if expected_args == 1 { "" } else { "s" },
+                num_args
+            ))
+            .span(hir.human_span()),
+        );
+        return Err(());
+    }
+
+    // Check the `with` clause.
+    if with.is_some() && !method.allows_with() {
+        cx.emit(
+            DiagBuilder2::error(format!("method `{}` does not take a `with` clause", name))
+                .span(hir.human_span()),
+        );
+        return Err(());
+    }
+    if with.is_none() && method.requires_with() {
+        cx.emit(
+            DiagBuilder2::error(format!("method `{}` requires a `with` clause", name))
+                .span(hir.human_span()),
+        );
+        return Err(());
+    }
+    Ok(method)
+}
+
 /// Determine the scope generated by a node.
 pub fn generated_scope_id<'gcx>(
     cx: &impl Context<'gcx>,
@@ -452,6 +539,8 @@ impl<'a> ScopedNode<'a> for ast::Module<'a> {}
 impl<'a> ScopedNode<'a> for ast::Interface<'a> {}
 impl<'a> ScopedNode<'a> for ast::Package<'a> {}
 impl<'a> ScopedNode<'a> for ast::Stmt<'a> {}
+impl<'a> ScopedNode<'a> for ast::Expr<'a> {}
+impl<'a> ScopedNode<'a> for ast::CasePattern<'a> {}
 impl<'a> ScopedNode<'a> for ast::Procedure<'a> {}
 impl<'a> ScopedNode<'a> for ast::ClassDecl<'a> {}
 impl<'a> ScopedNode<'a> for ast::SubroutineDecl<'a> {}
@@ -506,6 +595,11 @@ impl<'a> AsScopedNode<'a> for ast::AllNode<'a> {
                 | ast::ForeachStmt(..) => Some(x),
                 _ => None,
             },
+            ast::AllNode::Expr(x) => match x.data {
+                ast::WithExpr { .. } => Some(x),
+                _ => None,
+            },
+            ast::AllNode::CasePattern(x) => Some(x),
             ast::AllNode::Procedure(x) => Some(x),
             ast::AllNode::ClassDecl(x) => Some(x),
             ast::AllNode::SubroutineDecl(x) => Some(x),
@@ -1054,12 +1148,52 @@ impl<'a, C: Context<'a>> ast::Visitor<'a> for ScopeGenerator<'a, '_, C> {
         true
     }
 
+    fn pre_visit_expr(&mut self, node: &'a ast::Expr<'a>) -> bool {
+        // Do not traverse into `with` clauses, which define their iterator.
+        match node.data {
+            ast::WithExpr { .. } => {
+                self.add_subscope(node);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn pre_visit_with_iterator(&mut self, node: &'a ast::WithIterator<'a>) -> bool {
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: DefVis::LOCAL,
+            may_override: false,
+            ordered: true,
+        });
+        true
+    }
+
+    fn pre_visit_case_pattern(&mut self, node: &'a ast::CasePattern<'a>) -> bool {
+        // Do not traverse into case patterns, which define their variables.
+        self.add_subscope(node);
+        false
+    }
+
+    fn pre_visit_pattern_var(&mut self, node: &'a ast::PatternVar<'a>) -> bool {
+        self.add_def(Def {
+            node: DefNode::Ast(node),
+            name: node.name,
+            vis: DefVis::LOCAL,
+            may_override: false,
+            ordered: true,
+        });
+        true
+    }
+
     fn pre_visit_dpi_decl(&mut self, node: &'a ast::DpiDecl<'a>) -> bool {
         match node.data {
-            ast::DpiDeclData::Import { ref prototype, .. } => {
+            ast::DpiDeclData::Import { ref decl, .. } => {
+                self.add_subscope(decl);
                 self.add_def(Def {
-                    node: DefNode::Ast(node),
-                    name: prototype.name,
+                    node: DefNode::Ast(decl),
+                    name: decl.prototype.name,
                     vis: DefVis::LOCAL | DefVis::NAMESPACE,
                     may_override: false,
                     ordered: true,
@@ -0,0 +1,420 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Interface to the simulation runtime library.
+//!
+//! Some constructs, such as failing assertions, have no hardware equivalent.
+//! The code generator lowers them to calls to external functions which a
+//! simulator is expected to provide. All such functions are prefixed with
+//! `__moore_` and are declared in the output upon first use, as a private
+//! `func.func` in MLIR and as an external unit in LLHD.
+//!
+//! The functions follow a simple calling convention:
+//!
+//! - Integers are passed and returned by value as `iN`.
+//! - Strings known at compile time are passed as an `i32` index into the
+//!   string table of the design. The table is attached to the emitted MLIR
+//!   module as the `moore.strings` array attribute.
+//! - Source locations are passed as three `i32`: the string table index of the
+//!   file name, followed by the 1-based line and column.
+//! - Real numbers are passed and returned as the `i64` bit pattern of an IEEE
+//!   754 double. A `shortreal` is widened to a double before it is passed to
+//!   the runtime, and narrowed again afterwards.
+//! - Dynamic `string` values are passed and returned as an `i64` handle to an
+//!   immutable string owned by the runtime. Handle 0 always refers to the
+//!   empty string, such that zero-initialized string variables are empty.
+//!   Operations that modify a string return a handle to a new string.
+//! - Dynamic arrays, queues, and associative arrays are passed and returned
+//!   as an `i64` handle to an immutable array owned by the runtime, just like
+//!   strings. Handle 0 always refers to an empty array. Elements, indices, and
+//!   keys are passed as `i64` words, zero- or sign-extended from narrower
+//!   types. Functions that look up elements take a `kind` argument, which is
+//!   0 for dynamic arrays, 1 for queues, 2 for associative arrays with integer
+//!   keys, and 3 for associative arrays with `string` keys.
+//!
+//! The display and severity tasks (`$display`, `$error`, etc.) assemble their
+//! message piece by piece. Their format strings are parsed at compile time
+//! (see [`crate::format`]), and each piece is passed to the runtime through a
+//! `__moore_fmt_*` call which appends it to a pending message buffer. A final
+//! `__moore_print` call then emits the buffered message and clears the buffer.
+//! For example, `$display("x = %0d", x)` with a 8 bit `x` becomes:
+//!
+//! ```text
+//! __moore_fmt_literal(<"x = ">)
+//! __moore_fmt_int('d', 0, 0, 8, zext(x))
+//! __moore_print(0, <file>, <line>, <col>)
+//! ```
+
+/// A function provided by the runtime library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuntimeFunc {
+    /// `void __moore_assert_failed(i32 kind, i32 msg, i32 file, i32 line, i32 col)`
+    ///
+    /// Called when an immediate or deferred `assert` or `assume` fails and no
+    /// fail action has been specified. The `kind` is 0 for `assert` and 1 for
+    /// `assume`. The `msg` is the label of the assertion, or the source text
+    /// of its condition if it is unlabeled.
+    AssertFailed,
+    /// `void __moore_assert_deferred(i32 mode, i32 kind, i1 passed, i1 has_fail, i32 msg, i32 file, i32 line, i32 col)`
+    ///
+    /// Called whenever a deferred assertion is evaluated. The `mode` is 0 for
+    /// `#0` and 1 for `final` assertions. The `kind` is 0 for `assert`, 1 for
+    /// `assume`, and 2 for `cover`; `msg` is as for `__moore_assert_failed`.
+    /// The `has_fail` flag is set if the assertion has a fail action, which
+    /// replaces the failure report; the action blocks themselves are run by
+    /// the generated code.
+    /// The runtime keeps only the latest result of each assertion per calling
+    /// process, identified by its source location, and reports it once it
+    /// matures: in the observed region for `#0`, and at the end of the time
+    /// step for `final`. Earlier results in the same time step are flushed,
+    /// such that glitches do not cause spurious failures.
+    AssertDeferred,
+    /// `void __moore_fmt_literal(i32 str)`
+    ///
+    /// Append the string with table index `str` to the pending message.
+    FormatLiteral,
+    /// `void __moore_fmt_word(i64 word)`
+    ///
+    /// Push 64 bits of a value wider than 64 bits. Such values are passed as a
+    /// sequence of `__moore_fmt_word` calls, most significant word first,
+    /// followed by a `__moore_fmt_int` call with the least significant word.
+    FormatWord,
+    /// `void __moore_fmt_int(i32 spec, i32 flags, i32 width, i32 size, i64 value)`
+    ///
+    /// Append an integer value of `size` bits to the pending message. The
+    /// `spec` is the ASCII code of the format specifier letter (`b`, `o`, `d`,
+    /// `h`, `c`, `s`, or `t`). Bit 0 of `flags` is set if the value is
+    /// signed, bit 1 if it is left-aligned. The `width` is the field width
+    /// given in the format specifier, or -1 if none was given. The `value`
+    /// holds the least significant 64 bits of the value, zero- or
+    /// sign-extended as appropriate.
+    FormatInt,
+    /// `void __moore_fmt_unknown(i64 word)`
+    ///
+    /// Push 64 bits of the unknown plane of a four-state value, most
+    /// significant word first. All words of the unknown plane are passed this
+    /// way, before the `__moore_fmt_word` and `__moore_fmt_int` calls of the
+    /// value plane. A bit set in the unknown plane is Z if the corresponding
+    /// bit of the value plane is set, and X otherwise. The runtime prints such
+    /// bits as `x` and `z` digits, as described in §21.2.1.3.
+    FormatUnknown,
+    /// `void __moore_fmt_string(i32 flags, i32 width, i64 str)`
+    ///
+    /// Append a dynamic string to the pending message, as with a `%s` format
+    /// specifier. The `flags` and `width` are the same as for
+    /// `__moore_fmt_int`.
+    FormatString,
+    /// `void __moore_fmt_real(i32 spec, i32 flags, i32 width, i32 precision, i64 value)`
+    ///
+    /// Append a real value to the pending message, as with the C `printf`
+    /// function. The `spec` is the ASCII code of `e`, `f`, or `g`. The
+    /// `flags` and `width` are the same as for `__moore_fmt_int`, and
+    /// `precision` is the number of digits after the decimal point, or -1 if
+    /// none was given.
+    FormatReal,
+    /// `void __moore_print(i32 kind, i32 file, i32 line, i32 col)`
+    ///
+    /// Emit the pending message and clear it. The `kind` identifies the task
+    /// that produced the message: 0 for `$display`, 1 for `$write`, 2 for
+    /// `$strobe`, 3 for `$info`, 4 for `$warning`, 5 for `$error`, and 6 for
+    /// `$fatal`. Only `$display` and `$strobe` append a newline. `$strobe`
+    /// messages should be deferred to the end of the current time step.
+    Print,
+    /// `void __moore_finish(i32 diag)`
+    ///
+    /// Terminate the simulation, as with `$finish`. The `diag` level is 0, 1,
+    /// or 2 and controls how much diagnostic information is printed.
+    Finish,
+    /// `void __moore_stop(i32 diag)`
+    ///
+    /// Suspend the simulation, as with `$stop`.
+    Stop,
+    /// `i64 __moore_real_arith(i32 op, i64 lhs, i64 rhs)`
+    ///
+    /// Compute a real arithmetic operation. The `op` is 0 for `+`, 1 for `-`,
+    /// 2 for `*`, 3 for `/`, and 4 for `**`.
+    RealArith,
+    /// `i1 __moore_real_comp(i32 op, i64 lhs, i64 rhs)`
+    ///
+    /// Compare two reals. The `op` is 0 for `==`, 1 for `!=`, 2 for `<`, 3 for
+    /// `<=`, 4 for `>`, and 5 for `>=`.
+    RealComp,
+    /// `i64 __moore_real_from_int(i32 signed, i64 value)`
+    ///
+    /// Convert an integer to a real. The `value` is interpreted as a signed
+    /// integer if `signed` is 1, and as an unsigned one otherwise. Integers
+    /// wider than 64 bits are truncated before the conversion.
+    RealFromInt,
+    /// `i64 __moore_real_to_int(i32 round, i64 value)`
+    ///
+    /// Convert a real to a 64 bit signed integer. Rounds to the nearest
+    /// integer, with ties away from zero, if `round` is 1, as required for
+    /// implicit conversions. Truncates towards zero otherwise, as required
+    /// for `$rtoi`.
+    RealToInt,
+    /// `i64 __moore_real_from_shortreal(i32 value)`
+    ///
+    /// Widen the bit pattern of an IEEE 754 single to a double.
+    RealFromShortReal,
+    /// `i32 __moore_real_to_shortreal(i64 value)`
+    ///
+    /// Narrow a double to the bit pattern of an IEEE 754 single.
+    RealToShortReal,
+    /// `i64 __moore_string_literal(i32 str)`
+    ///
+    /// Get a handle to the string with table index `str`.
+    StringLiteral,
+    /// `i64 __moore_string_pack(i64 str, i32 word)`
+    ///
+    /// Convert a string to a packed vector, with the last character in the
+    /// least significant byte. Returns the 64 bit word with index `word` of
+    /// that vector, counting from the least significant word.
+    StringPack,
+    /// `i64 __moore_string_unpack(i64 str, i64 word)`
+    ///
+    /// Append the 8 characters in `word` to `str`, most significant byte
+    /// first, skipping any NUL characters. Packed vectors are converted to a
+    /// string by calling this function once per 64 bit word, most significant
+    /// word first, starting with the empty string.
+    StringUnpack,
+    /// `i64 __moore_string_concat(i64 lhs, i64 rhs)`
+    ///
+    /// Concatenate two strings.
+    StringConcat,
+    /// `i1 __moore_string_comp(i32 op, i64 lhs, i64 rhs)`
+    ///
+    /// Compare two strings lexicographically. The `op` is 0 for `==`, 1 for
+    /// `!=`, 2 for `<`, 3 for `<=`, 4 for `>`, and 5 for `>=`.
+    StringComp,
+    /// `i32 __moore_string_len(i64 str)`
+    ///
+    /// Get the number of characters in a string, as with `str.len()`.
+    StringLen,
+    /// `i64 __moore_string_substr(i64 str, i32 i, i32 j)`
+    ///
+    /// Get the characters `i` to `j` of a string, as with `str.substr(i, j)`.
+    /// Returns the empty string if the range is out of bounds.
+    StringSubstr,
+    /// `i8 __moore_string_getc(i64 str, i32 i)`
+    ///
+    /// Get character `i` of a string, or 0 if `i` is out of bounds, as with
+    /// `str.getc(i)`.
+    StringGetc,
+    /// `i64 __moore_string_putc(i64 str, i32 i, i8 c)`
+    ///
+    /// Replace character `i` of a string with `c`, as with `str.putc(i, c)`.
+    /// The string is left unchanged if `i` is out of bounds or `c` is 0.
+    StringPutc,
+    /// `i64 __moore_string_atoi(i32 radix, i64 str)`
+    ///
+    /// Interpret the leading digits of a string as an integer in the given
+    /// `radix` (2, 8, 10, or 16), as with `str.atoi()`, `str.atohex()`, etc.
+    StringToInt,
+    /// `i64 __moore_string_itoa(i32 radix, i32 signed, i64 value)`
+    ///
+    /// Format an integer in the given `radix` (2, 8, 10, or 16), as with
+    /// `str.itoa(value)`, `str.hextoa(value)`, etc.
+    StringFromInt,
+    /// `i64 __moore_string_to_c(i64 str)`
+    ///
+    /// Get a pointer to a NUL-terminated copy of a string, to be passed to a
+    /// DPI import as a `const char*`. The copy is owned by the runtime.
+    StringToC,
+    /// `i64 __moore_string_from_c(i64 ptr)`
+    ///
+    /// Get a handle to a copy of the NUL-terminated string at `ptr`, as
+    /// returned from a DPI import as a `const char*`.
+    StringFromC,
+    /// `i64 __moore_string_format()`
+    ///
+    /// Return the pending message as a string and clear it. Used to implement
+    /// `$sformatf`.
+    StringFormat,
+    /// `i64 __moore_array_new(i32 size, i64 init)`
+    ///
+    /// Allocate a dynamic array of `size` elements, as with `new[size](init)`.
+    /// The leading elements are copied from `init`, the remaining ones are
+    /// zero.
+    ArrayNew,
+    /// `i32 __moore_array_size(i64 array)`
+    ///
+    /// Get the number of elements in an array, as with `array.size()`.
+    ArraySize,
+    /// `i64 __moore_array_get(i32 kind, i64 array, i64 index)`
+    ///
+    /// Get the element at an index or key, as with `array[index]`. Returns 0 if
+    /// there is no such element.
+    ArrayGet,
+    /// `i64 __moore_array_set(i32 kind, i64 array, i64 index, i64 value)`
+    ///
+    /// Replace the element at an index or key, as with `array[index] = value`.
+    /// Associative arrays insert a new element if the key does not exist, and
+    /// queues append one if `index` is equal to their size. Other arrays are
+    /// left unchanged if the index is out of bounds.
+    ArraySet,
+    /// `i64 __moore_array_delete(i32 kind, i64 array, i64 index)`
+    ///
+    /// Remove the element at an index or key, as with `array.delete(index)`.
+    ArrayDelete,
+    /// `i1 __moore_array_exists(i32 kind, i64 array, i64 index)`
+    ///
+    /// Check whether an element with the given key exists, as with
+    /// `array.exists(index)`.
+    ArrayExists,
+    /// `i64 __moore_queue_push(i32 front, i64 queue, i64 value)`
+    ///
+    /// Insert an element at the front of a queue if `front` is 1, or at the
+    /// back otherwise, as with `queue.push_front(value)` and
+    /// `queue.push_back(value)`.
+    QueuePush,
+    /// `i64 __moore_queue_pop(i32 front, i64 queue)`
+    ///
+    /// Remove the element at the front of a queue if `front` is 1, or at the
+    /// back otherwise. The queue is left unchanged if it is empty.
+    QueuePop,
+    /// `i64 __moore_queue_peek(i32 front, i64 queue)`
+    ///
+    /// Get the element at the front of a queue if `front` is 1, or at the back
+    /// otherwise. Returns 0 if the queue is empty. Together with
+    /// `__moore_queue_pop` this implements `queue.pop_front()` and
+    /// `queue.pop_back()`.
+    QueuePeek,
+    /// `i64 __moore_assoc_traverse(i32 op, i32 kind, i64 array, i64 key)`
+    ///
+    /// Find the first key of an associative array if `op` is 0, the last key
+    /// if it is 1, the next key after `key` if it is 2, or the previous key
+    /// before `key` if it is 3, as with `array.first(key)`, etc. Returns `key`
+    /// unchanged if there is no such key.
+    AssocTraverse,
+}
+
+impl RuntimeFunc {
+    /// Get the name of the function.
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeFunc::AssertFailed => "__moore_assert_failed",
+            RuntimeFunc::AssertDeferred => "__moore_assert_deferred",
+            RuntimeFunc::FormatLiteral => "__moore_fmt_literal",
+            RuntimeFunc::FormatWord => "__moore_fmt_word",
+            RuntimeFunc::FormatInt => "__moore_fmt_int",
+            RuntimeFunc::FormatUnknown => "__moore_fmt_unknown",
+            RuntimeFunc::FormatString => "__moore_fmt_string",
+            RuntimeFunc::FormatReal => "__moore_fmt_real",
+            RuntimeFunc::Print => "__moore_print",
+            RuntimeFunc::Finish => "__moore_finish",
+            RuntimeFunc::Stop => "__moore_stop",
+            RuntimeFunc::RealArith => "__moore_real_arith",
+            RuntimeFunc::RealComp => "__moore_real_comp",
+            RuntimeFunc::RealFromInt => "__moore_real_from_int",
+            RuntimeFunc::RealToInt => "__moore_real_to_int",
+            RuntimeFunc::RealFromShortReal => "__moore_real_from_shortreal",
+            RuntimeFunc::RealToShortReal => "__moore_real_to_shortreal",
+            RuntimeFunc::StringLiteral => "__moore_string_literal",
+            RuntimeFunc::StringPack => "__moore_string_pack",
+            RuntimeFunc::StringUnpack => "__moore_string_unpack",
+            RuntimeFunc::StringConcat => "__moore_string_concat",
+            RuntimeFunc::StringComp => "__moore_string_comp",
+            RuntimeFunc::StringLen => "__moore_string_len",
+            RuntimeFunc::StringSubstr => "__moore_string_substr",
+            RuntimeFunc::StringGetc => "__moore_string_getc",
+            RuntimeFunc::StringPutc => "__moore_string_putc",
+            RuntimeFunc::StringToInt => "__moore_string_atoi",
+            RuntimeFunc::StringFromInt => "__moore_string_itoa",
+            RuntimeFunc::StringToC => "__moore_string_to_c",
+            RuntimeFunc::StringFromC => "__moore_string_from_c",
+            RuntimeFunc::StringFormat => "__moore_string_format",
+            RuntimeFunc::ArrayNew => "__moore_array_new",
+            RuntimeFunc::ArraySize => "__moore_array_size",
+            RuntimeFunc::ArrayGet => "__moore_array_get",
+            RuntimeFunc::ArraySet => "__moore_array_set",
+            RuntimeFunc::ArrayDelete => "__moore_array_delete",
+            RuntimeFunc::ArrayExists => "__moore_array_exists",
+            RuntimeFunc::QueuePush => "__moore_queue_push",
+            RuntimeFunc::QueuePop => "__moore_queue_pop",
+            RuntimeFunc::QueuePeek => "__moore_queue_peek",
+            RuntimeFunc::AssocTraverse => "__moore_assoc_traverse",
+        }
+    }
+
+    /// Get the bit widths of the function's arguments.
+    pub fn arg_widths(self) -> &'static [usize] {
+        match self {
+            RuntimeFunc::AssertFailed => &[32, 32, 32, 32, 32],
+            RuntimeFunc::AssertDeferred => &[32, 32, 1, 1, 32, 32, 32, 32],
+            RuntimeFunc::FormatLiteral => &[32],
+            RuntimeFunc::FormatWord | RuntimeFunc::FormatUnknown => &[64],
+            RuntimeFunc::FormatInt => &[32, 32, 32, 32, 64],
+            RuntimeFunc::FormatString => &[32, 32, 64],
+            RuntimeFunc::FormatReal => &[32, 32, 32, 32, 64],
+            RuntimeFunc::Print => &[32, 32, 32, 32],
+            RuntimeFunc::Finish | RuntimeFunc::Stop => &[32],
+            RuntimeFunc::RealArith | RuntimeFunc::RealComp => &[32, 64, 64],
+            RuntimeFunc::RealFromInt | RuntimeFunc::RealToInt => &[32, 64],
+            RuntimeFunc::RealFromShortReal => &[32],
+            RuntimeFunc::RealToShortReal => &[64],
+            RuntimeFunc::StringLiteral => &[32],
+            RuntimeFunc::StringPack | RuntimeFunc::StringGetc => &[64, 32],
+            RuntimeFunc::StringUnpack | RuntimeFunc::StringConcat => &[64, 64],
+            RuntimeFunc::StringComp => &[32, 64, 64],
+            RuntimeFunc::StringLen | RuntimeFunc::StringToC | RuntimeFunc::StringFromC => &[64],
+            RuntimeFunc::StringSubstr => &[64, 32, 32],
+            RuntimeFunc::StringPutc => &[64, 32, 8],
+            RuntimeFunc::StringToInt => &[32, 64],
+            RuntimeFunc::StringFromInt => &[32, 32, 64],
+            RuntimeFunc::StringFormat => &[],
+            RuntimeFunc::ArrayNew => &[32, 64],
+            RuntimeFunc::ArraySize => &[64],
+            RuntimeFunc::ArrayGet | RuntimeFunc::ArrayDelete | RuntimeFunc::ArrayExists => {
+                &[32, 64, 64]
+            }
+            RuntimeFunc::ArraySet => &[32, 64, 64, 64],
+            RuntimeFunc::QueuePush => &[32, 64, 64],
+            RuntimeFunc::QueuePop | RuntimeFunc::QueuePeek => &[32, 64],
+            RuntimeFunc::AssocTraverse => &[32, 32, 64, 64],
+        }
+    }
+
+    /// Get the bit width of the function's result, if it has one.
+    pub fn result_width(self) -> Option<usize> {
+        match self {
+            RuntimeFunc::AssertFailed
+            | RuntimeFunc::AssertDeferred
+            | RuntimeFunc::FormatLiteral
+            | RuntimeFunc::FormatWord
+            | RuntimeFunc::FormatInt
+            | RuntimeFunc::FormatUnknown
+            | RuntimeFunc::FormatString
+            | RuntimeFunc::FormatReal
+            | RuntimeFunc::Print
+            | RuntimeFunc::Finish
+            | RuntimeFunc::Stop => None,
+            RuntimeFunc::RealArith
+            | RuntimeFunc::RealFromInt
+            | RuntimeFunc::RealToInt
+            | RuntimeFunc::RealFromShortReal
+            | RuntimeFunc::StringLiteral
+            | RuntimeFunc::StringPack
+            | RuntimeFunc::StringUnpack
+            | RuntimeFunc::StringConcat
+            | RuntimeFunc::StringSubstr
+            | RuntimeFunc::StringPutc
+            | RuntimeFunc::StringToInt
+            | RuntimeFunc::StringFromInt
+            | RuntimeFunc::StringToC
+            | RuntimeFunc::StringFromC
+            | RuntimeFunc::StringFormat
+            | RuntimeFunc::ArrayNew
+            | RuntimeFunc::ArrayGet
+            | RuntimeFunc::ArraySet
+            | RuntimeFunc::ArrayDelete
+            | RuntimeFunc::QueuePush
+            | RuntimeFunc::QueuePop
+            | RuntimeFunc::QueuePeek
+            | RuntimeFunc::AssocTraverse => Some(64),
+            RuntimeFunc::RealComp | RuntimeFunc::StringComp | RuntimeFunc::ArrayExists => Some(1),
+            RuntimeFunc::RealToShortReal | RuntimeFunc::StringLen | RuntimeFunc::ArraySize => {
+                Some(32)
+            }
+            RuntimeFunc::StringGetc => Some(8),
+        }
+    }
+}
@@ -0,0 +1,565 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Lowering of the emitted LLHD entities to structural HW modules.
+//!
+//! The code generator emits every module as an `llhd.entity` whose variables
+//! and nets are signals that are probed and driven. In the synthesizable
+//! subset, where each signal is driven exactly once and without delay, these
+//! signals are plain wires. This lowering replaces each probe with the value
+//! driven onto the signal, entities with `hw.module`s, and entity instances
+//! with `hw.instance`s, such that only `hw`, `comb`, and `seq` operations
+//! remain. Struct and array ports are flattened into bit vectors with
+//! `hw.bitcast`s at the module and instance boundaries.
+//!
+//! Constructs without a structural equivalent, such as processes and delays,
+//! are reported as errors at their source location.
+
+use crate::crate_prelude::*;
+use moore_circt::{self as circt, mlir, prelude::*};
+use std::collections::{HashMap, HashSet};
+
+/// The names of the input and output ports of each emitted entity.
+pub(crate) type PortNames = HashMap<String, (Vec<String>, Vec<String>)>;
+
+/// Lower the LLHD entities in an MLIR module to structural HW modules.
+///
+/// Emits an error for every construct that cannot be represented, and returns
+/// `Err` without modifying the module in that case.
+pub(crate) fn lower_to_hw(module: circt::ModuleOp, port_names: &PortNames) -> Result<()> {
+    let ops = mlir::block_operations(module.block());
+
+    // Find the entities and processes, and the names of the entity ports.
+    let mut entities = vec![];
+    let mut procs = HashMap::new();
+    let mut ports = HashMap::new();
+    for &op in &ops {
+        let name = op.get_attr_string("sym_name");
+        match op.name().as_str() {
+            "llhd.entity" => {
+                let entity = circt::llhd::EntityOp::from_raw(op.raw());
+                let name = name.unwrap();
+                let names = match port_names.get(&name) {
+                    Some(names) => names.clone(),
+                    None => (
+                        (0..entity.num_inputs())
+                            .map(|i| format!("in{}", i))
+                            .collect(),
+                        (0..entity.num_outputs())
+                            .map(|i| format!("out{}", i))
+                            .collect(),
+                    ),
+                };
+                ports.insert(name, names);
+                entities.push(entity);
+            }
+            "llhd.proc" => {
+                procs.insert(name.unwrap(), op);
+            }
+            _ => (),
+        }
+    }
+
+    // Check that all entities can be lowered before modifying any of them.
+    let mut failed = false;
+    for &entity in &entities {
+        failed |= !check_entity(entity, &procs, &ports);
+    }
+    if failed {
+        return Err(());
+    }
+
+    // Lower the entities and remove the processes and functions, which are no
+    // longer used.
+    for entity in entities {
+        lower_entity(entity, &ports);
+    }
+    for op in ops {
+        match op.name().as_str() {
+            "llhd.proc" | "func.func" => op.erase(),
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Check whether an entity can be lowered to a structural HW module.
+///
+/// Emits an error for every operation that prevents this.
+fn check_entity(
+    entity: circt::llhd::EntityOp,
+    procs: &HashMap<String, mlir::Operation>,
+    ports: &PortNames,
+) -> bool {
+    let ops = mlir::block_operations(entity.block());
+    let inputs: HashSet<mlir::Value> = entity.input_ports().collect();
+    let mut signals: HashSet<mlir::Value> = entity.output_ports().collect();
+    for op in &ops {
+        if op.name() == "llhd.sig" {
+            signals.insert(op.result(0));
+        }
+    }
+
+    // The value driven onto each signal, or `None` if it is driven by an
+    // instance.
+    let mut drivers = HashMap::new();
+
+    // Every signal must be driven as a whole, at most once.
+    let mut driven = HashSet::new();
+    let mut ok = true;
+    let mut check_target = |op: mlir::Operation, target: mlir::Value| {
+        let msg = if inputs.contains(&target) {
+            "input port cannot be driven in structural HW"
+        } else if !signals.contains(&target) {
+            "partial drive of a signal cannot be lowered to structural HW"
+        } else if !driven.insert(target) {
+            "signal with multiple drivers cannot be lowered to structural HW"
+        } else {
+            return true;
+        };
+        op.emit_error(msg);
+        false
+    };
+
+    for &op in &ops {
+        let name = op.name();
+        match name.as_str() {
+            "llhd.terminator"
+            | "llhd.sig"
+            | "llhd.prb"
+            | "llhd.sig.extract"
+            | "llhd.sig.array_get"
+            | "llhd.sig.array_slice"
+            | "llhd.sig.struct_extract"
+            | "llhd.constant_time" => (),
+            "llhd.drv" => {
+                ok &= check_target(op, op.operand(0));
+                drivers.insert(op.operand(0), Some(op.operand(1)));
+                if op.num_operands() > 3 {
+                    op.emit_error("conditional drive cannot be lowered to structural HW");
+                    ok = false;
+                }
+                if !is_zero_delay(op.operand(2)) {
+                    op.emit_error("delay cannot be lowered to structural HW");
+                    ok = false;
+                }
+            }
+            "llhd.inst" => {
+                let callee = op.get_attr_symbol("callee").unwrap();
+                if let Some(prok) = procs.get(&callee) {
+                    match find_delay(*prok) {
+                        Some(op) => op.emit_error("delay cannot be lowered to structural HW"),
+                        None => prok.emit_error(
+                            "procedure cannot be lowered to structural HW; only registers and \
+                             combinational logic without latches are supported",
+                        ),
+                    }
+                    ok = false;
+                    continue;
+                }
+                let num_inputs = ports[&callee].0.len();
+                for target in op.operands().skip(num_inputs) {
+                    ok &= check_target(op, target);
+                    drivers.insert(target, None);
+                }
+            }
+            "func.call" => {
+                let callee = op.get_attr_symbol("callee").unwrap();
+                op.emit_error(&format!(
+                    "call to function `{}` cannot be lowered to structural HW",
+                    callee
+                ));
+                ok = false;
+            }
+            _ if name.starts_with("hw.")
+                || name.starts_with("comb.")
+                || name.starts_with("seq.") => {}
+            _ => {
+                op.emit_error(&format!("`{}` cannot be lowered to structural HW", name));
+                ok = false;
+            }
+        }
+    }
+
+    // Signals take on the value driven onto them, or their initial value if
+    // they are not driven. Following these values through the probes of
+    // other signals must not lead back to the same signal.
+    let entity_name = entity.get_attr_string("sym_name").unwrap();
+    let mut chain: Vec<_> = entity
+        .output_ports()
+        .zip(ports[&entity_name].1.iter().cloned())
+        .map(|(port, name)| (port, Some(name), None))
+        .collect();
+    for &op in &ops {
+        if op.name() == "llhd.sig" {
+            let name = op.get_attr_string("name");
+            chain.push((op.result(0), name, Some(op)));
+        }
+    }
+    let indices: HashMap<_, _> = chain
+        .iter()
+        .enumerate()
+        .map(|(index, &(signal, _, _))| (signal, index))
+        .collect();
+    let mut reported = HashSet::new();
+    for index in 0..chain.len() {
+        let mut seen = HashSet::new();
+        let mut current = index;
+        let mut cycle = false;
+        while !cycle {
+            seen.insert(current);
+            let (signal, _, op) = &chain[current];
+            let value = match drivers.get(signal) {
+                Some(&driver) => driver,
+                None => op.map(|op| op.operand(0)),
+            };
+            let probed = value
+                .and_then(|value| value.defining_op())
+                .filter(|op| op.name() == "llhd.prb")
+                .and_then(|op| indices.get(&op.operand(0)));
+            match probed {
+                Some(&next) => {
+                    cycle = seen.contains(&next);
+                    current = next;
+                }
+                None => break,
+            }
+        }
+        if cycle && !reported.contains(&current) {
+            reported.extend(seen);
+            let (_, name, op) = &chain[current];
+            let msg = format!(
+                "signal `{}` is driven by itself and cannot be lowered to structural HW",
+                name.as_deref().unwrap_or("<anonymous>")
+            );
+            match op {
+                Some(op) => op.emit_error(&msg),
+                None => entity.emit_error(&msg),
+            }
+            ok = false;
+        }
+    }
+    ok
+}
+
+/// Check that the structural HW modules in an MLIR module can be emitted as
+/// Verilog.
+///
+/// Struct and array ports are flattened into bit vectors by `lower_to_hw`.
+/// Emits an error for every port whose type has no known width and therefore
+/// remains an aggregate, since Verilog-2005 cannot represent it.
+pub(crate) fn check_verilog(module: circt::ModuleOp, port_names: &PortNames) -> Result<()> {
+    let is_aggregate =
+        |ty: mlir::Type| circt::hw::is_struct_type(ty) || circt::hw::is_array_type(ty);
+    let mut ok = true;
+    for op in mlir::block_operations(module.block()) {
+        if op.name() != "hw.module" {
+            continue;
+        }
+        let module_op = circt::hw::ModuleOp::from_raw(op.raw());
+        let name = op.get_attr_string("sym_name").unwrap();
+        let (input_names, output_names) = &port_names[&name];
+        let port_error = |dir: &str, name: &str, ty: mlir::Type| {
+            op.emit_error(&format!(
+                "{} port `{}` of type `{}` cannot be emitted as Verilog",
+                dir, name, ty
+            ));
+        };
+        for (index, name) in input_names.iter().enumerate().take(module_op.num_inputs()) {
+            let ty = module_op.input(index).ty();
+            if is_aggregate(ty) {
+                port_error("input", name, ty);
+                ok = false;
+            }
+        }
+        for body_op in mlir::block_operations(module_op.block()) {
+            if body_op.name() == "hw.output" {
+                for (value, name) in body_op.operands().zip(output_names) {
+                    if is_aggregate(value.ty()) {
+                        port_error("output", name, value.ty());
+                        ok = false;
+                    }
+                }
+            }
+        }
+    }
+    if ok {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+/// Find a wait or drive in a process which delays by a real amount of time.
+fn find_delay(prok: mlir::Operation) -> Option<mlir::Operation> {
+    let region = circt::llhd::ProcessOp::from_raw(prok.raw()).region();
+    let blocks = mlir::region_blocks(region);
+    blocks
+        .into_iter()
+        .flat_map(mlir::block_operations)
+        .find(|op| match op.name().as_str() {
+            "llhd.wait" => op
+                .operands()
+                .any(|value| circt::llhd::is_time_type(value.ty())),
+            "llhd.drv" => !is_zero_delay(op.operand(2)),
+            _ => false,
+        })
+}
+
+/// Check whether a delay is a constant without a real time component.
+fn is_zero_delay(delay: mlir::Value) -> bool {
+    match delay.defining_op() {
+        Some(op) if op.name() == "llhd.constant_time" => {
+            circt::llhd::time_attr_time(mlir::Attribute::from_raw(op.attr("value"))) == 0
+        }
+        _ => false,
+    }
+}
+
+/// A signal of an entity being lowered.
+struct Signal {
+    /// The name of the signal, if any.
+    name: Option<String>,
+    /// A placeholder for the value of the signal.
+    placeholder: mlir::Value,
+    /// The initial value of the signal, which it keeps if it is not driven.
+    init: Option<mlir::Value>,
+    /// The value driven onto the signal.
+    driver: Option<mlir::Value>,
+}
+
+/// Lower an entity to a structural HW module.
+///
+/// The entity must have been accepted by `check_entity`.
+fn lower_entity(entity: circt::llhd::EntityOp, ports: &PortNames) {
+    let name = entity.get_attr_string("sym_name").unwrap();
+    let (input_names, output_names) = &ports[&name];
+    let cx = entity.context();
+    let mut builder = mlir::Builder::new(cx);
+    builder.set_loc(entity.loc());
+    builder.set_insertion_point_before(entity);
+
+    // Create the module and move the contents of the entity into it.
+    let mut module_op = circt::hw::ModuleBuilder::new(&name);
+    for (port, name) in entity.input_ports().zip(input_names) {
+        module_op.add_input(name, port_type(cx, port.ty()));
+    }
+    for (port, name) in entity.output_ports().zip(output_names) {
+        module_op.add_output(name, port_type(cx, port.ty()));
+    }
+    let module_op = module_op.build(&mut builder);
+    let body = module_op.block();
+    let ops: Vec<_> = mlir::block_operations(entity.block())
+        .into_iter()
+        .filter(|op| op.name() != "llhd.terminator")
+        .collect();
+    for op in &ops {
+        op.move_to_end(body);
+    }
+
+    // Input ports carry the module's input values. All other signals are
+    // represented by a placeholder until their driven value is known.
+    builder.set_insertion_point_to_start(body);
+    let mut wires = HashMap::new();
+    for (index, port) in entity.input_ports().enumerate() {
+        let ty = circt::llhd::signal_type_element(port.ty());
+        wires.insert(port, bitcast(&mut builder, ty, module_op.input(index)));
+    }
+    let mut signals = vec![];
+    let mut signal_indices = HashMap::new();
+    let mut add_signal = |builder: &mut mlir::Builder, signal: mlir::Value, name, init| {
+        let ty = circt::llhd::signal_type_element(signal.ty());
+        let placeholder = circt::UnrealizedConversionCastOp::new(builder, vec![], Some(ty));
+        let placeholder = placeholder.result(0);
+        wires.insert(signal, placeholder);
+        signal_indices.insert(signal, signals.len());
+        signals.push(Signal {
+            name,
+            placeholder,
+            init,
+            driver: None,
+        });
+    };
+    let outputs: Vec<_> = entity.output_ports().collect();
+    for &port in &outputs {
+        add_signal(&mut builder, port, None, None);
+    }
+    for op in &ops {
+        if op.name() == "llhd.sig" {
+            let name = op.get_attr_string("name");
+            add_signal(&mut builder, op.result(0), name, Some(op.operand(0)));
+        }
+    }
+
+    // Replace probes with the value of the probed signal.
+    for &op in &ops {
+        if op.name() == "llhd.prb" {
+            builder.set_loc(op.loc());
+            builder.set_insertion_point_before(op);
+            let value = probe(&mut builder, &wires, op.operand(0));
+            op.result(0).replace_all_uses_with(value);
+        }
+    }
+
+    // Determine the value driven onto each signal, and replace entity
+    // instances with module instances.
+    for &op in &ops {
+        match op.name().as_str() {
+            "llhd.drv" => {
+                signals[signal_indices[&op.operand(0)]].driver = Some(op.operand(1));
+            }
+            "llhd.inst" => {
+                let callee = op.get_attr_symbol("callee").unwrap();
+                let (input_names, output_names) = &ports[&callee];
+                builder.set_loc(op.loc());
+                builder.set_insertion_point_before(op);
+                let mut inputs = vec![];
+                for (index, name) in input_names.iter().enumerate() {
+                    let signal = op.operand(index);
+                    let value = probe(&mut builder, &wires, signal);
+                    let value = bitcast(&mut builder, port_type(cx, signal.ty()), value);
+                    inputs.push((name.as_str(), value));
+                }
+                let targets: Vec<_> = op.operands().skip(input_names.len()).collect();
+                let outputs = output_names
+                    .iter()
+                    .zip(&targets)
+                    .map(|(name, target)| (name.as_str(), port_type(cx, target.ty())));
+                let inst = circt::hw::InstanceOp::new(
+                    &mut builder,
+                    &op.get_attr_string("name").unwrap_or_default(),
+                    &callee,
+                    inputs,
+                    outputs,
+                );
+                for (index, target) in targets.iter().enumerate() {
+                    let ty = circt::llhd::signal_type_element(target.ty());
+                    let value = bitcast(&mut builder, ty, inst.result(index));
+                    signals[signal_indices[target]].driver = Some(value);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Replace the placeholders with the driven values. Signals which are
+    // driven with the value of another signal take on that signal's value;
+    // `check_entity` has rejected cycles among them.
+    let placeholders: HashMap<_, _> = signals
+        .iter()
+        .enumerate()
+        .map(|(index, signal)| (signal.placeholder, index))
+        .collect();
+    let mut values = vec![];
+    for index in 0..signals.len() {
+        let mut current = index;
+        let value = loop {
+            // Every output port is driven by `emit_module`, and every other
+            // signal has an initial value.
+            let value = signals[current]
+                .driver
+                .or(signals[current].init)
+                .expect("signal has neither a driver nor an initial value");
+            match placeholders.get(&value) {
+                Some(&next) => current = next,
+                None => break value,
+            }
+        };
+        values.push(value);
+    }
+    for (signal, &value) in signals.iter().zip(&values) {
+        signal.placeholder.replace_all_uses_with(value);
+
+        // Keep the signal name as a hint for the names in the Verilog output.
+        if let (Some(name), Some(op)) = (&signal.name, value.defining_op()) {
+            if op.attr("sv.namehint").ptr.is_null() {
+                op.set_attr("sv.namehint", mlir::get_string_attr(cx, name));
+            }
+        }
+    }
+    builder.set_loc(entity.loc());
+    builder.set_insertion_point_to_end(body);
+    let results: Vec<_> = outputs
+        .iter()
+        .zip(&values)
+        .map(|(port, &value)| bitcast(&mut builder, port_type(cx, port.ty()), value))
+        .collect();
+    circt::hw::OutputOp::new(&mut builder, results);
+
+    // Remove the signals and the operations on them.
+    for &op in ops.iter().rev() {
+        let name = op.name();
+        if name == "llhd.drv"
+            || name == "llhd.inst"
+            || name == "llhd.prb"
+            || name == "llhd.constant_time"
+            || name.starts_with("llhd.sig")
+        {
+            op.erase();
+        }
+    }
+    for signal in &signals {
+        signal.placeholder.defining_op().unwrap().erase();
+    }
+    entity.erase();
+}
+
+/// Determine the type of the module port for a signal.
+///
+/// Structs and arrays are flattened into an integer of the same width, such
+/// that the port can be emitted as Verilog. Types without a known width are
+/// kept as they are.
+fn port_type(cx: mlir::Context, signal_ty: mlir::Type) -> mlir::Type {
+    let ty = circt::llhd::signal_type_element(signal_ty);
+    if !circt::hw::is_struct_type(ty) && !circt::hw::is_array_type(ty) {
+        return ty;
+    }
+    match circt::hw::bit_width(ty) {
+        Some(width) => mlir::get_integer_type(cx, width),
+        None => ty,
+    }
+}
+
+/// Convert a value to a type of the same width, if it has a different type.
+fn bitcast(builder: &mut mlir::Builder, ty: mlir::Type, value: mlir::Value) -> mlir::Value {
+    if value.ty() == ty {
+        value
+    } else {
+        circt::hw::BitcastOp::new(builder, ty, value).into()
+    }
+}
+
+/// Determine the value of a signal, or a part of a signal.
+fn probe(
+    builder: &mut mlir::Builder,
+    wires: &HashMap<mlir::Value, mlir::Value>,
+    signal: mlir::Value,
+) -> mlir::Value {
+    if let Some(&value) = wires.get(&signal) {
+        return value;
+    }
+    let op = signal.defining_op().unwrap();
+    let value = probe(builder, wires, op.operand(0));
+    let ty = circt::llhd::signal_type_element(signal.ty());
+    match op.name().as_str() {
+        "llhd.sig.extract" => {
+            let shifted = circt::comb::ShrUOp::with_sizes(builder, value, op.operand(1));
+            circt::comb::ExtractOp::new(builder, ty, shifted.into(), 0).into()
+        }
+        "llhd.sig.array_get" => circt::hw::ArrayGetOp::new(builder, value, op.operand(1)).into(),
+        "llhd.sig.array_slice" => circt::hw::ArraySliceOp::with_sizes(
+            builder,
+            value,
+            op.operand(1),
+            circt::hw::array_type_size(ty),
+        )
+        .into(),
+        "llhd.sig.struct_extract" => {
+            let field = op.get_attr_string("field").unwrap();
+            let index = circt::hw::struct_type_fields(value.ty())
+                .position(|(name, _)| name == field)
+                .unwrap();
+            circt::hw::StructExtractOp::new(builder, value, index).into()
+        }
+        _ => unreachable!(),
+    }
+}
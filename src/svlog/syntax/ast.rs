@@ -677,12 +677,14 @@ pub enum Item<'a> {
     Procedure(#[forward] Procedure<'a>),
     SubroutineDecl(#[forward] SubroutineDecl<'a>),
     ContAssign(#[forward] ContAssign<'a>),
+    PullGate(#[forward] PullGate<'a>),
     GenvarDecl(Vec<GenvarDecl<'a>>),
     GenerateRegion(Span, Vec<Item<'a>>),
     GenerateFor(#[forward] GenerateFor<'a>),
     GenerateIf(#[forward] GenerateIf<'a>),
     GenerateCase(#[forward] GenerateCase<'a>),
     Assertion(Assertion<'a>),
+    ElabSystemTask(#[forward] ElabSystemTask<'a>),
     NetDecl(NetDecl<'a>),
     VarDecl(#[forward] VarDecl<'a>),
     Inst(Inst<'a>),
@@ -1189,6 +1191,10 @@ pub enum StmtKind<'a> {
     AssertionStmt(Box<Assertion<'a>>),
     WaitExprStmt(Expr<'a>, Box<Stmt<'a>>),
     WaitForkStmt,
+    EventTriggerStmt {
+        nonblocking: bool,
+        target: Expr<'a>,
+    },
     DisableForkStmt,
     DisableStmt(Name),
 }
@@ -1242,6 +1248,19 @@ pub enum CaseMode {
 pub enum CaseItem<'a> {
     Default(Box<Stmt<'a>>),
     Expr(Vec<Expr<'a>>, Box<Stmt<'a>>),
+    Pattern(Box<CasePattern<'a>>),
+}
+
+/// A case item of a `case ... matches` statement.
+///
+/// For example the `tagged Valid .v: x = v;` in `case (a) matches ...`. The
+/// item forms a scope for the variables bound by its pattern.
+#[moore_derive::node]
+#[indefinite("case pattern")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CasePattern<'a> {
+    pub pattern: Expr<'a>,
+    pub stmt: Box<Stmt<'a>>,
 }
 
 #[moore_derive::visit]
@@ -1344,6 +1363,29 @@ pub struct ForeachIndex {
     pub index: usize,
 }
 
+/// The iterator of an array method's `with` clause.
+///
+/// This is either given explicitly as an argument, like the `x` in
+/// `a.find(x) with (x > 0)`, or is the implicit `item`.
+#[moore_derive::node]
+#[indefinite("iterator")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithIterator {
+    /// The name of the iterator.
+    #[name]
+    pub name: Spanned<Name>,
+}
+
+/// A variable bound by a pattern, like the `v` in `tagged Valid .v`.
+#[moore_derive::node]
+#[indefinite("pattern variable")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternVar {
+    /// The name of the variable.
+    #[name]
+    pub name: Spanned<Name>,
+}
+
 /// An expression.
 #[moore_derive::node]
 #[indefinite("expression")]
@@ -1390,6 +1432,7 @@ pub enum Expr<'a> {
     ArrayNewExpr(Box<Expr<'a>>, Option<Box<Expr<'a>>>),
     EmptyQueueExpr,
     StreamConcatExpr {
+        dir: StreamDir,
         slice: Option<StreamConcatSlice<'a>>,
         exprs: Vec<StreamExpr<'a>>,
     },
@@ -1422,6 +1465,23 @@ pub enum Expr<'a> {
         name: Spanned<Name>,
         arg: TypeOrExpr<'a>,
     },
+    /// An array method call with a `with` clause, like
+    /// `a.sum() with (item * 2)`.
+    WithExpr {
+        call: Box<Expr<'a>>,
+        iterator: WithIterator<'a>,
+        expr: Box<Expr<'a>>,
+    },
+    /// A tagged union expression, like `tagged Valid 42`, or a pattern
+    /// matching a tagged union, like `tagged Valid .v`.
+    TaggedExpr {
+        member: Spanned<Name>,
+        expr: Option<Box<Expr<'a>>>,
+    },
+    /// A pattern variable, like the `.v` in `tagged Valid .v`.
+    PatternVarExpr(PatternVar<'a>),
+    /// A wildcard pattern, like the `.*` in `tagged Valid .*`.
+    WildcardPatternExpr,
 }
 
 /// An ambiguous node that can either be a type or and expression.
@@ -1557,6 +1617,17 @@ pub struct CallArg<'a> {
     pub expr: Option<Expr<'a>>,
 }
 
+/// The direction of a streaming concatenation.
+#[moore_derive::visit]
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum StreamDir {
+    /// A `{>>{...}}` streaming concatenation, which keeps the order of slices.
+    In,
+    /// A `{<<{...}}` streaming concatenation, which reverses the order of
+    /// slices.
+    Out,
+}
+
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamConcatSlice<'a> {
@@ -1731,6 +1802,31 @@ pub struct SubroutineDecl<'a> {
     pub items: Vec<SubroutineItem<'a>>,
 }
 
+impl<'a> SubroutineDecl<'a> {
+    /// Check whether the subroutine has an automatic lifetime.
+    ///
+    /// Subroutines without an explicit lifetime inherit the default lifetime
+    /// of the enclosing module, interface, or package. Class methods are
+    /// always automatic.
+    pub fn is_automatic(&self) -> bool {
+        if let Some(lifetime) = &self.prototype.lifetime {
+            return *lifetime == Lifetime::Automatic;
+        }
+        let mut parent = self.get_parent();
+        while let Some(node) = parent {
+            match node.as_all() {
+                AllNode::Module(x) => return x.lifetime == Lifetime::Automatic,
+                AllNode::Interface(x) => return x.lifetime == Lifetime::Automatic,
+                AllNode::Package(x) => return x.lifetime == Lifetime::Automatic,
+                AllNode::ClassDecl(..) => return true,
+                _ => (),
+            }
+            parent = node.get_parent();
+        }
+        false
+    }
+}
+
 /// A function or task prototype.
 #[moore_derive::node]
 #[indefinite("subroutine prototype")]
@@ -1890,6 +1986,41 @@ pub enum ChargeStrength {
     Large,
 }
 
+impl DriveStrength {
+    /// Check whether this is the strength of a 0 value.
+    pub fn is_strength0(self) -> bool {
+        match self {
+            DriveStrength::Supply0
+            | DriveStrength::Strong0
+            | DriveStrength::Pull0
+            | DriveStrength::Weak0
+            | DriveStrength::HighZ0 => true,
+            _ => false,
+        }
+    }
+
+    /// The strength level, from 0 for `highz` to 7 for `supply`.
+    pub fn level(self) -> usize {
+        match self {
+            DriveStrength::Supply0 | DriveStrength::Supply1 => 7,
+            DriveStrength::Strong0 | DriveStrength::Strong1 => 6,
+            DriveStrength::Pull0 | DriveStrength::Pull1 => 5,
+            DriveStrength::Weak0 | DriveStrength::Weak1 => 3,
+            DriveStrength::HighZ0 | DriveStrength::HighZ1 => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ChargeStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ChargeStrength::Small => write!(f, "small"),
+            ChargeStrength::Medium => write!(f, "medium"),
+            ChargeStrength::Large => write!(f, "large"),
+        }
+    }
+}
+
 /// A field in a `'{...}` pattern.
 #[moore_derive::node]
 #[indefinite("pattern field")]
@@ -1959,12 +2090,12 @@ pub enum BlockingAssertion<'a> {
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConcurrentAssertion<'a> {
-    AssertProperty(PropSpec, AssertionActionBlock<'a>),
-    AssumeProperty(PropSpec, AssertionActionBlock<'a>),
-    CoverProperty(PropSpec, Stmt<'a>),
+    AssertProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    AssumeProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    CoverProperty(PropSpec<'a>, Stmt<'a>),
     CoverSequence,
-    ExpectProperty(PropSpec, AssertionActionBlock<'a>),
-    RestrictProperty(PropSpec),
+    ExpectProperty(PropSpec<'a>, AssertionActionBlock<'a>),
+    RestrictProperty(PropSpec<'a>),
 }
 
 #[moore_derive::visit]
@@ -1986,6 +2117,8 @@ pub struct SeqExpr<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SeqExprData<'a> {
     Expr(Expr<'a>, Option<SeqRep<'a>>),
+    Paren(Box<SeqExpr<'a>>, Option<SeqRep<'a>>),
+    Delay(Option<Box<SeqExpr<'a>>>, SeqDelay<'a>, Box<SeqExpr<'a>>),
     BinOp(SeqBinOp, Box<SeqExpr<'a>>, Box<SeqExpr<'a>>),
     Throughout(Expr<'a>, Box<SeqExpr<'a>>),
     Clocked(EventExpr<'a>, Box<SeqExpr<'a>>),
@@ -1994,11 +2127,21 @@ pub enum SeqExprData<'a> {
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SeqRep<'a> {
-    Consec(Expr<'a>),    // [* expr]
-    ConsecStar,          // [*]
-    ConsecPlus,          // [+]
-    Nonconsec(Expr<'a>), // [= expr]
-    Goto(Expr<'a>),      // [-> expr]
+    Consec(Expr<'a>),                        // [* expr]
+    ConsecRange(Expr<'a>, Option<Expr<'a>>), // [* expr : expr], [* expr : $]
+    ConsecStar,                              // [*]
+    ConsecPlus,                              // [+]
+    Nonconsec(Expr<'a>),                     // [= expr]
+    Goto(Expr<'a>),                          // [-> expr]
+}
+
+#[moore_derive::visit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeqDelay<'a> {
+    Fixed(Expr<'a>),                   // ## expr
+    Range(Expr<'a>, Option<Expr<'a>>), // ##[expr : expr], ##[expr : $]
+    Star,                              // ##[*]
+    Plus,                              // ##[+]
 }
 
 #[moore_derive::visit]
@@ -2010,9 +2153,20 @@ pub enum SeqBinOp {
     Within,
 }
 
+/// A property specification in a concurrent assertion.
+///
+/// For example `@(posedge clk) disable iff (rst) a |-> b`.
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PropSpec;
+pub struct PropSpec<'a> {
+    pub span: Span,
+    /// The optional clocking event.
+    pub clock: Option<EventExpr<'a>>,
+    /// The optional `disable iff` condition.
+    pub disable: Option<Expr<'a>>,
+    /// The property expression.
+    pub prop: PropExpr<'a>,
+}
 
 #[moore_derive::visit]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2233,6 +2387,39 @@ pub struct ContAssign<'a> {
     pub assignments: Vec<(Expr<'a>, Expr<'a>)>,
 }
 
+/// A pull gate instantiation.
+///
+/// ```text
+/// ("pullup"|"pulldown") [pull_strength] pull_gate_instance {"," pull_gate_instance} ";"
+/// pull_gate_instance: [ident] "(" expr ")"
+/// ```
+#[moore_derive::node]
+#[indefinite("pull gate")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullGate<'a> {
+    /// Whether this is a `pullup` rather than a `pulldown`.
+    pub pullup: bool,
+    /// The strength with which the gate drives its value.
+    pub strength: Option<DriveStrength>,
+    /// The instance names and the nets they drive.
+    pub instances: Vec<(Option<Spanned<Name>>, Expr<'a>)>,
+}
+
+/// An elaboration system task.
+///
+/// ```text
+/// "$fatal" ["(" finish_number ["," list_of_arguments] ")"] ";"
+/// ("$error"|"$warning"|"$info") ["(" [list_of_arguments] ")"] ";"
+/// ```
+#[moore_derive::node]
+#[indefinite("elaboration system task")]
+#[definite("`${}`", name)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElabSystemTask<'a> {
+    pub name: Spanned<Name>,
+    pub args: Vec<CallArg<'a>>,
+}
+
 /// A `for` generate statement.
 #[moore_derive::node]
 #[indefinite("for-generate statement")]
@@ -2327,8 +2514,9 @@ pub enum DpiDecl<'a> {
         spec: Spanned<Name>,
         property: Option<Spanned<DpiProperty>>,
         cident: Option<Spanned<Name>>,
+        /// The imported function or task, as a declaration without a body.
         #[forward]
-        prototype: SubroutinePrototype<'a>,
+        decl: SubroutineDecl<'a>,
     },
     /// An `export`.
     Export {
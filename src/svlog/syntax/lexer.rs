@@ -314,6 +314,10 @@ impl<'a> Lexer<'a> {
                     } else {
                         None
                     };
+                    if let Some(exp) = self.try_exponent(&mut sp)? {
+                        let exp = name_table.intern(&exp, true);
+                        return Ok((Literal(Real(value, frac, exp)), sp));
+                    }
                     if let Some(unit) = self.try_time_unit() {
                         sp.expand(self.peek[0].1);
                         self.bump()?; // eat the unit
@@ -631,6 +635,41 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// Try to parse the exponent of a real number, such as the `e-3` in
+    /// `1.5e-3`.
+    ///
+    /// IEEE 1800-2017 5.7.2 Real literal constants
+    fn try_exponent(&mut self, span: &mut Span) -> DiagResult2<Option<String>> {
+        if self.peek[0].0 != CatTokenKind::Text {
+            return Ok(None);
+        }
+        let text = self.peek[0].1.extract();
+        if !text.starts_with(|c| c == 'e' || c == 'E') {
+            return Ok(None);
+        }
+        let mut exp = String::new();
+        if text.len() == 1 {
+            // A signed exponent splits into separate tokens, as in `1e-3`.
+            match (self.peek[1].0, self.peek[2].0) {
+                (CatTokenKind::Symbol(c), CatTokenKind::Digits) if c == '+' || c == '-' => {
+                    exp.push(c);
+                    span.expand(self.peek[1].1);
+                    self.bump()?; // eat the `e`
+                    self.bump()?; // eat the sign
+                }
+                _ => return Ok(None),
+            }
+        } else if text[1..].chars().all(|c| c.is_ascii_digit() || c == '_') {
+            exp.extend(text[1..].chars().filter(|&c| c != '_'));
+            span.expand(self.peek[0].1);
+            self.bump()?; // eat the `e` and the digits
+        } else {
+            return Ok(None);
+        }
+        self.eat_number_body_into(&mut exp, span, false)?;
+        Ok(Some(exp))
+    }
+
     /// Try to parse the next text token as a time unit.
     fn try_time_unit(&mut self) -> Option<TimeUnit> {
         if self.peek[0].0 == CatTokenKind::Text {
@@ -856,4 +895,18 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn real_literal() {
+        check(
+            "1.2E12 1.30e-2 0.1e-0 23E10 29E+5",
+            &[
+                Literal(Real(name("1"), Some(name("2")), name("12"))),
+                Literal(Real(name("1"), Some(name("30")), name("-2"))),
+                Literal(Real(name("0"), Some(name("1")), name("-0"))),
+                Literal(Real(name("23"), None, name("10"))),
+                Literal(Real(name("29"), None, name("+5"))),
+            ],
+        );
+    }
 }
@@ -1080,6 +1080,11 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
             return parse_continuous_assign(p).map(|x| ItemData::ContAssign(x));
         }
 
+        // Pull gates
+        Keyword(Kw::Pullup) | Keyword(Kw::Pulldown) => {
+            return parse_pull_gate(p).map(|x| ItemData::PullGate(x));
+        }
+
         // Genvar declaration
         Keyword(Kw::Genvar) => {
             p.bump();
@@ -1136,8 +1141,8 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
             return Err(());
         }
 
-        // Unsupported constructs as of now.
-        SysIdent(..) => return parse_elab_system_task(p).map(|_| ItemData::Dummy),
+        // Elaboration system tasks.
+        SysIdent(..) => return parse_elab_system_task(p).map(ItemData::ElabSystemTask),
 
         _ => (),
     }
@@ -1160,16 +1165,19 @@ fn parse_item_data<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ItemDat
     res
 }
 
-fn parse_elab_system_task<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()> {
+fn parse_elab_system_task<'n>(
+    p: &mut dyn AbstractParser<'n>,
+) -> ReportedResult<ElabSystemTask<'n>> {
     let mut span = p.peek(0).1;
     let name = match p.peek(0).0 {
-        SysIdent(name) => name,
+        SysIdent(name) => Spanned::new(name, span),
         _ => unreachable!(),
     };
-    p.recover_balanced(&[Semicolon], true);
+    p.bump();
+    let args = try_flanked(p, Paren, parse_call_args)?.unwrap_or(Vec::new());
+    p.require_reported(Semicolon)?;
     span.expand(p.last_span());
-    p.add_diag(DiagBuilder2::warning("unsupported elaboration system task").span(span));
-    Ok(())
+    Ok(ElabSystemTask::new(span, ElabSystemTaskData { name, args }))
 }
 
 fn parse_localparam_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<()> {
@@ -2089,7 +2097,9 @@ fn parse_expr_suffix<'n>(
     let (tkn, sp) = p.peek(0);
     match tkn {
         // Index: "[" range_expression "]"
-        OpenDelim(Brack) if precedence <= Precedence::Postfix => {
+        OpenDelim(Brack)
+            if precedence <= Precedence::Postfix && !is_seqrep_start(p.peek(1).0, p.peek(2).0) =>
+        {
             p.bump();
             let expr = match parse_range_expr(p) {
                 Ok(x) => x,
@@ -2130,7 +2140,18 @@ fn parse_expr_suffix<'n>(
         // expr "." ident
         Period if precedence <= Precedence::Scope => {
             p.bump();
-            let name = parse_identifier_name(p, "member name")?;
+            let name = match p.peek(0) {
+                // Array manipulation methods may be named after keywords. See
+                // §7.12 "Array manipulation methods".
+                (Keyword(kw @ Kw::And), sp)
+                | (Keyword(kw @ Kw::Or), sp)
+                | (Keyword(kw @ Kw::Xor), sp)
+                | (Keyword(kw @ Kw::Unique), sp) => {
+                    p.bump();
+                    Spanned::new(get_name_table().intern(kw.as_str(), true), sp)
+                }
+                _ => parse_identifier_name(p, "member name")?,
+            };
             let expr = Expr::new(
                 Span::union(prefix.span, p.last_span()),
                 MemberExpr {
@@ -2152,6 +2173,24 @@ fn parse_expr_suffix<'n>(
             return parse_expr_suffix(p, expr, precedence);
         }
 
+        // expr "with" "(" expr ")"
+        Keyword(Kw::With)
+            if precedence <= Precedence::Postfix && p.peek(1).0 == OpenDelim(Paren) =>
+        {
+            p.bump();
+            let (call, iterator) = split_with_iterator(prefix, sp);
+            let expr = Box::new(flanked(p, Paren, parse_expr)?);
+            let expr = Expr::new(
+                Span::union(call.span, p.last_span()),
+                WithExpr {
+                    call: Box::new(call),
+                    iterator,
+                    expr,
+                },
+            );
+            return parse_expr_suffix(p, expr, precedence);
+        }
+
         // expr "++"
         Operator(Op::Inc) if precedence <= Precedence::Unary => {
             p.bump();
@@ -2274,6 +2313,37 @@ fn parse_expr_suffix<'n>(
     Ok(prefix)
 }
 
+/// Split the iterator off an array method call that is followed by a `with`
+/// clause.
+///
+/// A single identifier argument, like the `x` in `a.find(x) with (x > 0)`,
+/// names the iterator. Otherwise the iterator is the implicit `item`, located
+/// at the `with` keyword.
+fn split_with_iterator<'n>(
+    mut call: Expr<'n>,
+    with_span: Span,
+) -> (Expr<'n>, ast::WithIterator<'n>) {
+    let mut name = Spanned::new(get_name_table().intern("item", true), with_span);
+    if let CallExpr(_, ref mut args) = call.data {
+        let explicit = match args.as_slice() {
+            [arg] if arg.name.is_none() => match arg.expr {
+                Some(Expr {
+                    data: IdentExpr(name),
+                    ..
+                }) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(explicit) = explicit {
+            name = explicit;
+            args.clear();
+        }
+    }
+    let iterator = ast::WithIterator::new(name.span, ast::WithIteratorData { name });
+    (call, iterator)
+}
+
 fn parse_expr_first<'n>(
     p: &mut dyn AbstractParser<'n>,
     precedence: Precedence,
@@ -2309,9 +2379,25 @@ fn parse_expr_first<'n>(
             ));
         }
 
-        (Keyword(Kw::Tagged), sp) => {
-            p.add_diag(DiagBuilder2::error("Tagged union expressions not implemented").span(sp));
-            return Err(());
+        // "tagged" member_identifier [primary]
+        (Keyword(Kw::Tagged), _) => {
+            p.bump();
+            let member = parse_identifier_name(p, "union member name")?;
+            let expr = match p.peek(0).0 {
+                Period => Some(parse_pattern_binding(p)?),
+                Literal(_) | Ident(_) | EscIdent(_) | SysIdent(_) | OpenDelim(Paren)
+                | OpenDelim(Brace) | Apostrophe | Keyword(Kw::Tagged) => {
+                    Some(parse_expr_prec(p, Precedence::Scope)?)
+                }
+                _ => None,
+            };
+            return Ok(Expr::new(
+                Span::union(first, p.last_span()),
+                TaggedExpr {
+                    member,
+                    expr: expr.map(Box::new),
+                },
+            ));
         }
 
         _ => (),
@@ -2335,6 +2421,25 @@ fn parse_expr_first<'n>(
     parse_primary_expr(p)
 }
 
+/// Parse a pattern variable, like `.v`, or a wildcard pattern `.*`.
+///
+/// See §12.6 "Pattern matching conditional statements".
+fn parse_pattern_binding<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Expr<'n>> {
+    let first = p.peek(0).1;
+    p.require_reported(Period)?;
+    if p.try_eat(Operator(Op::Mul)) {
+        return Ok(Expr::new(
+            Span::union(first, p.last_span()),
+            WildcardPatternExpr,
+        ));
+    }
+    let name = parse_identifier_name(p, "pattern variable name")?;
+    Ok(Expr::new(
+        Span::union(first, p.last_span()),
+        PatternVarExpr(PatternVar::new(name.span, PatternVarData { name })),
+    ))
+}
+
 fn parse_primary_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Expr<'n>> {
     let (tkn, sp) = p.peek(0);
     match tkn {
@@ -2488,11 +2593,6 @@ fn parse_pattern_field<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<Pat
     Ok(PatternField::new(span, data))
 }
 
-pub enum StreamDir {
-    In,
-    Out,
-}
-
 fn parse_concat_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ExprData<'n>> {
     // Streaming concatenations have a "<<" or ">>" following the opening "{".
     let stream = match p.peek(0).0 {
@@ -2545,6 +2645,7 @@ fn parse_concat_expr<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ExprD
         })?;
 
         return Ok(StreamConcatExpr {
+            dir,
             slice: slice_size,
             exprs: exprs,
         });
@@ -3650,6 +3751,18 @@ fn parse_stmt_kind<'n>(
             return Err(());
         }
 
+        // Event trigger statements, as per IEEE 1800-2017 section 15.5.1.
+        Operator(Op::LogicImpl) => {
+            p.bump();
+            let nonblocking = p.try_eat(Operator(Op::Gt));
+            let target = parse_expr(p)?;
+            p.require_reported(Semicolon)?;
+            EventTriggerStmt {
+                nonblocking,
+                target,
+            }
+        }
+
         // Disable statements
         Keyword(Kw::Disable) => {
             p.bump();
@@ -3811,6 +3924,66 @@ fn parse_continuous_assign<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult
     ))
 }
 
+/// Parse a pull gate instantiation.
+/// ```text
+/// ("pullup"|"pulldown") [pull_strength] pull_gate_instance {"," pull_gate_instance} ";"
+/// pull_strength: "(" strength0 ["," strength1] ")" | "(" strength1 ["," strength0] ")"
+/// pull_gate_instance: [ident] "(" expr ")"
+/// ```
+fn parse_pull_gate<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ast::PullGate<'n>> {
+    let mut span = p.peek(0).1;
+    let pullup = p.peek(0).0 == Keyword(Kw::Pullup);
+    p.bump();
+
+    // Consume the optional strength. Only the strength of the value the gate
+    // drives is relevant.
+    let has_strength = p.peek(0).0 == OpenDelim(Paren) && as_drive_strength(p.peek(1).0).is_some();
+    let strength = if !has_strength {
+        None
+    } else {
+        flanked(p, Paren, |p| {
+            let mut strength = None;
+            loop {
+                let (tkn, sp) = p.peek(0);
+                match as_drive_strength(tkn) {
+                    Some(s) => {
+                        p.bump();
+                        if s.is_strength0() != pullup {
+                            strength = Some(s);
+                        }
+                    }
+                    None => {
+                        p.add_diag(DiagBuilder2::error("expected drive strength").span(sp));
+                        return Err(());
+                    }
+                }
+                if !p.try_eat(Comma) {
+                    break;
+                }
+            }
+            Ok(strength)
+        })?
+    };
+
+    // Parse the instances.
+    let instances = comma_list_nonempty(p, Semicolon, "pull gate instance", |p| {
+        let name = try_identifier_name(p)?;
+        let expr = flanked(p, Paren, parse_expr)?;
+        Ok((name, expr))
+    })?;
+    p.require_reported(Semicolon)?;
+
+    span.expand(p.last_span());
+    Ok(ast::PullGate::new(
+        span,
+        ast::PullGateData {
+            pullup,
+            strength,
+            instances,
+        },
+    ))
+}
+
 fn parse_if_or_case<'n>(
     p: &mut dyn AbstractParser<'n>,
     up: Option<UniquePriority>,
@@ -3892,6 +4065,23 @@ fn parse_case<'n>(
             let stmt = Box::new(parse_stmt(p)?);
             items.push(CaseItem::Default(stmt));
         }
+        // Handle pattern case items, which consist of a single pattern.
+        else if mode == CaseMode::Pattern {
+            let pattern = match parse_expr(p) {
+                Ok(x) => x,
+                Err(()) => {
+                    p.recover_balanced(&[Colon], false);
+                    return Err(());
+                }
+            };
+            p.require_reported(Colon)?;
+            let stmt = Box::new(parse_stmt(p)?);
+            span.expand(p.last_span());
+            items.push(CaseItem::Pattern(Box::new(CasePattern::new(
+                span,
+                CasePatternData { pattern, stmt },
+            ))));
+        }
         // Handle regular case items.
         else {
             let mut exprs = Vec::new();
@@ -4008,7 +4198,7 @@ fn try_delay_control<'n>(
         }
 
         // Literals
-        Literal(Number(..)) | Literal(Time(..)) | Ident(..) => {
+        Literal(Number(..)) | Literal(Real(..)) | Literal(Time(..)) | Ident(..) => {
             parse_expr_first(p, Precedence::Max)?
         }
 
@@ -5339,11 +5529,18 @@ fn parse_dpi_decl<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<ast::Dpi
         };
         let prototype = parse_subroutine_prototype(p)?;
         // Semicolon is part of the prototype.
+        let decl = ast::SubroutineDecl::new(
+            prototype.span,
+            ast::SubroutineDeclData {
+                prototype,
+                items: vec![],
+            },
+        );
         ast::DpiDeclData::Import {
             spec,
             property,
             cident,
-            prototype,
+            decl,
         }
     } else if p.try_eat(Keyword(Kw::Export)) {
         let spec = parse_string_literal(p, "DPI specifier string")?;
@@ -5595,31 +5792,34 @@ fn parse_assertion_action_block<'n>(
     }
 }
 
-fn parse_property_spec<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PropSpec> {
+fn parse_property_spec<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<PropSpec<'n>> {
     let mut span = p.peek(0).1;
 
-    // TODO: Actually parse this stuff, rather than just chicken out.
-    p.recover_balanced(&[CloseDelim(Paren)], false);
-    return Ok(PropSpec);
-
-    // // Parse the optional event expression.
-    // let event = if p.try_eat(At) {
-    //     Some(parse_event_expr(p, EventPrecedence::Min)?)
-    // } else {
-    //     None
-    // };
+    // Parse the optional clocking event. The event expression must not consume
+    // any `or` or `iff` that belongs to the property.
+    let clock = if p.try_eat(At) {
+        Some(parse_event_expr(p, EventPrecedence::Max)?)
+    } else {
+        None
+    };
 
-    // // Parse the optional "disable iff" clause.
-    // let disable = if p.try_eat(Keyword(Kw::Disable)) {
-    //     p.require_reported(Keyword(Kw::Iff))?;
-    //     Some(flanked(p, Paren, parse_expr)?)
-    // } else {
-    //     None
-    // };
+    // Parse the optional "disable iff" clause.
+    let disable = if p.try_eat(Keyword(Kw::Disable)) {
+        p.require_reported(Keyword(Kw::Iff))?;
+        Some(flanked(p, Paren, parse_expr)?)
+    } else {
+        None
+    };
 
-    // // Parse the property expression.
-    // let prop = parse_propexpr(p)?;
-    // Ok(PropSpec)
+    // Parse the property expression.
+    let prop = parse_propexpr(p)?;
+    span.expand(p.last_span());
+    Ok(PropSpec {
+        span,
+        clock,
+        disable,
+        prop,
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -5793,9 +5993,13 @@ fn parse_seqexpr_prec<'n>(
 
     // See parse_propexpr_prec for an explanation of why we need a parallel
     // parser here.
+    //
+    // Sequences that start with an expression are tried first, such that a
+    // parenthesized expression like `(a + b) == c` is not mistaken for a
+    // parenthesized sequence.
     let mut pp = ParallelParser::new();
-    pp.add_greedy("expression", move |p| parse_seqexpr_expr(p, precedence));
     pp.add_greedy("sequence", move |p| parse_seqexpr_nonexpr(p, precedence));
+    pp.add_greedy("expression", move |p| parse_seqexpr_expr(p, precedence));
     let data = pp.finish(p, "sequence or primary property expression")?;
 
     span.expand(p.last_span());
@@ -5808,17 +6012,38 @@ fn parse_seqexpr_prec<'n>(
 
 fn parse_seqexpr_expr<'n>(
     p: &mut dyn AbstractParser<'n>,
-    precedence: PropSeqPrecedence,
+    _precedence: PropSeqPrecedence,
 ) -> ReportedResult<SeqExprData<'n>> {
-    // TODO: Handle all the non-trivial cases.
-    let q = p.peek(0).1;
-    p.add_diag(
-        DiagBuilder2::error(
-            "Don't know how to parse sequence expression that don't start with an expression",
-        )
-        .span(q),
-    );
-    Err(())
+    match p.peek(0).0 {
+        // Sequence starting with a cycle delay, e.g. `##1 a`.
+        DoubleHashtag => {
+            p.bump();
+            let delay = parse_seqdelay(p)?;
+            let rhs = parse_seqexpr_prec(p, PropSeqPrecedence::Brack)?;
+            Ok(SeqExprData::Delay(None, delay, Box::new(rhs)))
+        }
+
+        // Parenthesized sequence expression with optional repetition.
+        OpenDelim(Paren) => {
+            let inner = flanked(p, Paren, parse_seqexpr)?;
+            let rep = try_flanked(p, Brack, parse_seqrep)?;
+            Ok(SeqExprData::Paren(Box::new(inner), rep))
+        }
+
+        // Clocking event
+        At => {
+            p.bump();
+            let ev = parse_event_expr(p, EventPrecedence::Max)?;
+            let expr = parse_seqexpr(p)?;
+            Ok(SeqExprData::Clocked(ev, Box::new(expr)))
+        }
+
+        _ => {
+            let q = p.peek(0).1;
+            p.add_diag(DiagBuilder2::error("expected sequence expression").span(q));
+            Err(())
+        }
+    }
 }
 
 fn parse_seqexpr_nonexpr<'n>(
@@ -5847,10 +6072,88 @@ fn parse_seqexpr_suffix<'n>(
     prefix: SeqExpr<'n>,
     precedence: PropSeqPrecedence,
 ) -> ReportedResult<SeqExpr<'n>> {
-    // TODO: Handle all the binary operators.
+    // Handle the cycle delay operator, e.g. `a ##1 b`.
+    if precedence < PropSeqPrecedence::CycleDelay && p.try_eat(DoubleHashtag) {
+        let delay = parse_seqdelay(p)?;
+        let rhs = parse_seqexpr_prec(p, PropSeqPrecedence::CycleDelay)?;
+        let expr = SeqExpr {
+            span: Span::union(prefix.span, rhs.span),
+            data: SeqExprData::Delay(Some(Box::new(prefix)), delay, Box::new(rhs)),
+        };
+        return parse_seqexpr_suffix(p, expr, precedence);
+    }
+
+    // Handle the binary operators that have a sequence expression on both
+    // their left and right hand side.
+    if let Some((op, prec)) = match p.peek(0).0 {
+        Keyword(Kw::Or) => Some((SeqBinOp::Or, PropSeqPrecedence::Or)),
+        Keyword(Kw::And) => Some((SeqBinOp::And, PropSeqPrecedence::And)),
+        Keyword(Kw::Intersect) => Some((SeqBinOp::Intersect, PropSeqPrecedence::Intersect)),
+        Keyword(Kw::Within) => Some((SeqBinOp::Within, PropSeqPrecedence::Within)),
+        _ => None,
+    } {
+        if precedence < prec {
+            p.bump();
+            let rhs = parse_seqexpr_prec(p, prec)?;
+            let expr = SeqExpr {
+                span: Span::union(prefix.span, rhs.span),
+                data: SeqExprData::BinOp(op, Box::new(prefix), Box::new(rhs)),
+            };
+            return parse_seqexpr_suffix(p, expr, precedence);
+        }
+    }
+
     Ok(prefix)
 }
 
+fn parse_seqdelay<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqDelay<'n>> {
+    // ##[*], ##[+], ##[expr:expr], ##[expr:$]
+    if p.peek(0).0 == OpenDelim(Brack) {
+        return flanked(p, Brack, |p| match p.peek(0).0 {
+            Operator(Op::Mul) => {
+                p.bump();
+                Ok(SeqDelay::Star)
+            }
+            Operator(Op::Add) => {
+                p.bump();
+                Ok(SeqDelay::Plus)
+            }
+            _ => {
+                let (lo, hi) = parse_seqrange(p)?;
+                Ok(SeqDelay::Range(lo, hi))
+            }
+        });
+    }
+
+    // ## primary
+    Ok(SeqDelay::Fixed(parse_primary_expr(p)?))
+}
+
+/// Parse the `expr : expr` or `expr : $` range of a cycle delay or repetition.
+fn parse_seqrange<'n>(
+    p: &mut dyn AbstractParser<'n>,
+) -> ReportedResult<(Expr<'n>, Option<Expr<'n>>)> {
+    let lo = parse_expr(p)?;
+    p.require_reported(Colon)?;
+    if p.try_eat(Dollar) {
+        Ok((lo, None))
+    } else {
+        Ok((lo, Some(parse_expr(p)?)))
+    }
+}
+
+/// Check whether the tokens following a `[` start a sequence repetition, i.e.
+/// `[*`, `[=`, `[->`, or `[+]`, rather than an index expression.
+fn is_seqrep_start(first: Token, second: Token) -> bool {
+    match (first, second) {
+        (Operator(Op::Mul), _)
+        | (Operator(Op::Assign), _)
+        | (Operator(Op::LogicImpl), _)
+        | (Operator(Op::Add), CloseDelim(Brack)) => true,
+        _ => false,
+    }
+}
+
 fn parse_seqrep<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqRep<'n>> {
     match p.peek(0).0 {
         // [*]
@@ -5860,7 +6163,14 @@ fn parse_seqrep<'n>(p: &mut dyn AbstractParser<'n>) -> ReportedResult<SeqRep<'n>
             if p.peek(0).0 == CloseDelim(Brack) {
                 Ok(SeqRep::ConsecStar)
             } else {
-                Ok(SeqRep::Consec(parse_expr(p)?))
+                let lo = parse_expr(p)?;
+                if !p.try_eat(Colon) {
+                    Ok(SeqRep::Consec(lo))
+                } else if p.try_eat(Dollar) {
+                    Ok(SeqRep::ConsecRange(lo, None))
+                } else {
+                    Ok(SeqRep::ConsecRange(lo, Some(parse_expr(p)?)))
+                }
             }
         }
 
@@ -751,6 +751,10 @@ impl<'a> PackedType<'a> {
     pub fn coalesces_to_llhd_scalar(&self) -> bool {
         if let Some(enm) = self.get_enum() {
             enm.base.coalesces_to_llhd_scalar()
+        } else if self.get_union().is_some() {
+            // Packed unions are reinterpreted as whichever member is accessed,
+            // so they are represented as a plain bit vector.
+            true
         } else {
             !self.is_time()
                 && (self.is_integer_vec() || self.is_integer_atom() || self.is_single_bit())
@@ -820,7 +824,16 @@ impl<'a> PackedType<'a> {
     pub fn get_struct(&self) -> Option<&StructType<'a>> {
         let ty = self.resolve_full();
         match ty.core {
-            PackedCore::Struct(ref x) if ty.dims.is_empty() => Some(x),
+            PackedCore::Struct(ref x) if ty.dims.is_empty() && !x.is_union() => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Get the underlying union, or `None` if the type is no union.
+    pub fn get_union(&self) -> Option<&StructType<'a>> {
+        let ty = self.resolve_full();
+        match ty.core {
+            PackedCore::Struct(ref x) if ty.dims.is_empty() && x.is_union() => Some(x),
             _ => None,
         }
     }
@@ -1277,6 +1290,30 @@ impl<'a> UnpackedType<'a> {
         unsafe { std::mem::transmute(ty) }
     }
 
+    /// Create a `string` type.
+    pub fn make_string() -> &'a Self {
+        static TYPE: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(UnpackedCore::String));
+        let ty: &UnpackedType = &TYPE;
+        // SAFETY: This is safe since the cell which causes 'a to need to
+        // outlive 'static is actually never mutated after AST construction.
+        unsafe { std::mem::transmute(ty) }
+    }
+
+    /// Create a real type, such as `real` or `shortreal`.
+    pub fn make_real(real: RealType) -> &'a Self {
+        static SHORTREAL: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::ShortReal));
+        static REAL: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::Real));
+        static REALTIME: Lazy<UnpackedType> = Lazy::new(|| UnpackedType::new(RealType::RealTime));
+        let ty: &UnpackedType = match real {
+            RealType::ShortReal => &SHORTREAL,
+            RealType::Real => &REAL,
+            RealType::RealTime => &REALTIME,
+        };
+        // SAFETY: This is safe since the cell which causes 'a to need to
+        // outlive 'static is actually never mutated after AST construction.
+        unsafe { std::mem::transmute(ty) }
+    }
+
     /// Internalize this type in a context and resolve it.
     pub fn intern(mut self, cx: &impl TypeContext<'a>) -> &'a Self {
         let inner = match self.core {
@@ -1465,6 +1502,67 @@ impl<'a> UnpackedType<'a> {
         self.dims.is_empty() && self.resolve_full().core == UnpackedCore::String
     }
 
+    /// Check if this type is an event, like `event`.
+    pub fn is_event(&self) -> bool {
+        self.dims.is_empty() && self.resolve_full().core == UnpackedCore::Event
+    }
+
+    /// Check if this type is a C handle, like `chandle`.
+    pub fn is_chandle(&self) -> bool {
+        self.dims.is_empty() && self.resolve_full().core == UnpackedCore::Chandle
+    }
+
+    /// Check if this type is a dynamic array, queue, or associative array,
+    /// like `int []`, `int [$]`, or `int [string]`.
+    pub fn is_dynamic_array(&self) -> bool {
+        self.get_dynamic_dim().is_some()
+    }
+
+    /// Get the outermost dimension if it is a dynamic array, queue, or
+    /// associative array dimension.
+    pub fn get_dynamic_dim(&self) -> Option<UnpackedDim<'a>> {
+        self.unpacked_dims().next().filter(|dim| dim.is_dynamic())
+    }
+
+    /// Check if this type is a fixed-size unpacked array, like `int [4]`.
+    pub fn is_fixed_array(&self) -> bool {
+        self.unpacked_dims()
+            .next()
+            .and_then(|dim| dim.get_size())
+            .is_some()
+    }
+
+    /// Create a queue with elements of this type, like `T [$]`.
+    pub fn make_queue(&self, cx: &impl TypeContext<'a>) -> &'a Self {
+        let mut new = self.resolve_full().clone();
+        new.dims.insert(0, UnpackedDim::Queue(None));
+        new.intern(cx)
+    }
+
+    /// Check if this type has built-in methods, like `string`, `event`,
+    /// queues, enums, or unpacked arrays.
+    pub fn has_methods(&self) -> bool {
+        self.is_string()
+            || self.is_event()
+            || self.is_dynamic_array()
+            || self.is_fixed_array()
+            || self.get_enum().is_some()
+    }
+
+    /// Check if this type is a real type, like `real` or `shortreal`.
+    pub fn is_real(&self) -> bool {
+        self.get_real().is_some()
+    }
+
+    /// Get the real type this type represents, if any.
+    pub fn get_real(&self) -> Option<RealType> {
+        let ty = self.resolve_full();
+        match ty.core {
+            UnpackedCore::Real(real) if ty.dims.is_empty() => Some(real),
+            _ => None,
+        }
+    }
+
     /// Check if this type will coalesce to a scalar type in LLHD, like `i42`.
     pub fn coalesces_to_llhd_scalar(&self) -> bool {
         self.get_packed()
@@ -1581,6 +1679,15 @@ impl<'a> UnpackedType<'a> {
         }
     }
 
+    /// Get the underlying union, or `None` if the type is no union.
+    pub fn get_union(&self) -> Option<&StructType<'a>> {
+        if self.dims.is_empty() {
+            self.resolve_full().core.get_union()
+        } else {
+            None
+        }
+    }
+
     /// Get the underlying enum, or `None` if the type is no enum.
     pub fn get_enum(&self) -> Option<&EnumType<'a>> {
         if self.dims.is_empty() {
@@ -1739,12 +1846,22 @@ impl<'a> UnpackedCore<'a> {
     pub fn get_struct(&self) -> Option<&StructType<'a>> {
         match *self {
             UnpackedCore::Packed(x) => x.get_struct(),
-            UnpackedCore::Struct(ref x) => Some(x),
+            UnpackedCore::Struct(ref x) if !x.is_union() => Some(x),
             UnpackedCore::Named { ty, .. } | UnpackedCore::Ref { ty, .. } => ty.get_struct(),
             _ => None,
         }
     }
 
+    /// Get the underlying union, or `None` if the type is no union.
+    pub fn get_union(&self) -> Option<&StructType<'a>> {
+        match *self {
+            UnpackedCore::Packed(x) => x.get_union(),
+            UnpackedCore::Struct(ref x) if x.is_union() => Some(x),
+            UnpackedCore::Named { ty, .. } | UnpackedCore::Ref { ty, .. } => ty.get_union(),
+            _ => None,
+        }
+    }
+
     /// Get the underlying enum, or `None` if the type is no enum.
     pub fn get_enum(&self) -> Option<&EnumType<'a>> {
         self.get_packed().and_then(|packed| packed.get_enum())
@@ -1837,6 +1954,15 @@ impl UnpackedDim<'_> {
             _ => None,
         }
     }
+
+    /// Check if the dimension grows and shrinks at runtime, like `[]`, `[$]`,
+    /// or `[T]`.
+    pub fn is_dynamic(&self) -> bool {
+        match *self {
+            Self::Unsized | Self::Assoc(..) | Self::Queue(..) => true,
+            Self::Array(..) | Self::Range(..) => false,
+        }
+    }
 }
 
 impl From<usize> for UnpackedDim<'_> {
@@ -1898,13 +2024,46 @@ impl<'a> StructType<'a> {
         }
     }
 
+    /// Check whether this is a `union` or `union tagged`.
+    pub fn is_union(&self) -> bool {
+        self.kind != ast::StructKind::Struct
+    }
+
+    /// Check whether this is a `union tagged`.
+    pub fn is_tagged(&self) -> bool {
+        self.kind == ast::StructKind::TaggedUnion
+    }
+
+    /// Compute the number of bits needed to store the tag of a tagged union.
+    pub fn tag_bit_size(&self) -> usize {
+        let mut size = 1;
+        while (1 << size) < self.members.len() {
+            size += 1;
+        }
+        size
+    }
+
+    /// Get the type of the tag of a tagged union.
+    pub fn tag_type(&self, cx: &impl TypeContext<'a>) -> &'a UnpackedType<'a> {
+        SbvType::new(Domain::TwoValued, Sign::Unsigned, self.tag_bit_size()).to_unpacked(cx)
+    }
+
     /// Compute the size of this struct in bits.
     ///
-    /// Returns `None` if any member of the type has a `[]` dimension.
+    /// Unions are as large as their largest member, plus the tag for tagged
+    /// unions. Returns `None` if any member of the type has a `[]` dimension.
     pub fn get_bit_size(&self) -> Option<usize> {
         let mut size = 0;
         for m in &self.members {
-            size += m.ty.get_bit_size()?;
+            let member_size = m.ty.get_bit_size()?;
+            if self.is_union() {
+                size = std::cmp::max(size, member_size);
+            } else {
+                size += member_size;
+            }
+        }
+        if self.is_tagged() {
+            size += self.tag_bit_size();
         }
         Some(size)
     }
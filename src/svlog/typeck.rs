@@ -33,6 +33,7 @@ use crate::{
     call_mapping::CallArgSource,
     common::arenas::Alloc,
     hir::HirNode,
+    pattern_mapping::find_tagged_member,
     port_list,
     resolver::{DefNode, InstTarget},
     syntax::ast::BasicNode,
@@ -101,6 +102,24 @@ pub(crate) fn type_of<'a>(
             };
             Ok(enm.base.to_unpacked(cx))
         }
+        HirNode::WithIterator(it) => Ok(cx
+            .self_determined_type(it.array, env)
+            .and_then(|ty| ty.pop_dim(cx))
+            .unwrap_or_else(|| UnpackedType::make_error())),
+        HirNode::PatternVar(v) => {
+            // Follow the nested patterns down from the scrutinee, resolving
+            // each member against the type of the enclosing pattern.
+            let mut ty = cx.need_self_determined_type(v.scrutinee, env);
+            for &name in &v.path {
+                if ty.is_error() {
+                    break;
+                }
+                ty = find_tagged_member(cx, ty, name, v.span)
+                    .map(|(_, member)| member.ty)
+                    .unwrap_or_else(|_| UnpackedType::make_error());
+            }
+            Ok(ty)
+        }
         HirNode::Package(_) => Ok(UnpackedType::make_void()),
         HirNode::Assign(_) => unreachable!("has no type: {:?}", hir),
         HirNode::Inst(hir) => Ok(cx.type_of_inst(Ref(hir), env)),
@@ -663,6 +682,50 @@ pub(crate) fn packed_type_from_ast<'a>(
                 }
             }
 
+            // All members of a packed union must have the same width, such
+            // that accessing a member simply reinterprets the union's bits.
+            // See §7.3.1 "Packed unions".
+            if strukt.kind == ast::StructKind::Union && strukt.packed {
+                let mut sizes = def
+                    .members
+                    .iter()
+                    .filter_map(|m| Some((m, m.ty.get_bit_size()?)));
+                if let Some((first, first_size)) = sizes.next() {
+                    for (member, size) in sizes.filter(|&(_, size)| size != first_size) {
+                        cx.emit(
+                            DiagBuilder2::error(
+                                "members of a packed union must all have the same width",
+                            )
+                            .span(member.name.span)
+                            .add_note(format!(
+                                "`{}` has {} bits, but `{}` has {} bits",
+                                member.name, size, first.name, first_size
+                            )),
+                        );
+                        failed = true;
+                    }
+                }
+            }
+
+            // Only some flavors of unions are supported for now.
+            match strukt.kind {
+                ast::StructKind::TaggedUnion if strukt.packed => {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: packed tagged unions").span(ast.span()),
+                    );
+                    failed = true;
+                }
+                ast::StructKind::Union if !strukt.packed => {
+                    cx.emit(
+                        DiagBuilder2::error("unsupported: unpacked untagged unions")
+                            .span(ast.span())
+                            .add_note("Use a `union packed` or `union tagged` instead."),
+                    );
+                    failed = true;
+                }
+                _ => (),
+            }
+
             // Keep track of the sign, and complain if the packed type itself
             // has separate sign information.
             if ast_sign != ast::TypeSign::None {
@@ -1075,6 +1138,7 @@ pub(crate) fn type_of_expr<'a>(
         // These expressions have a fully self-determined type.
         hir::ExprKind::IntConst { .. }
         | hir::ExprKind::TimeConst(..)
+        | hir::ExprKind::RealConst(..)
         | hir::ExprKind::StringConst(..)
         | hir::ExprKind::Ident(..)
         | hir::ExprKind::Scope(..)
@@ -1091,9 +1155,17 @@ pub(crate) fn type_of_expr<'a>(
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::ArrayDim(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Rose(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Past(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::SysTask(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem { .. })
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Index(..)
-        | hir::ExprKind::Assign { .. } => cx.need_self_determined_type(expr.id, env),
+        | hir::ExprKind::Assign { .. }
+        | hir::ExprKind::PatternVar(..) => cx.need_self_determined_type(expr.id, env),
 
         // Unsized constants infer their type from the context if possible, and
         // otherwise fall back to a self-determined mode.
@@ -1152,6 +1224,10 @@ pub(crate) fn type_of_expr<'a>(
                 // And some have a fixed return type.
                 hir::BinaryOp::Eq
                 | hir::BinaryOp::Neq
+                | hir::BinaryOp::CaseEq
+                | hir::BinaryOp::CaseNeq
+                | hir::BinaryOp::WildcardEq
+                | hir::BinaryOp::WildcardNeq
                 | hir::BinaryOp::Lt
                 | hir::BinaryOp::Leq
                 | hir::BinaryOp::Gt
@@ -1164,20 +1240,90 @@ pub(crate) fn type_of_expr<'a>(
         // Ternary operators return their internal operation type.
         hir::ExprKind::Ternary(..) => cx.need_operation_type(expr.id, env),
 
+        // Streaming concatenations are left-justified and zero-filled on the
+        // right when assigned or cast to a wider type.
+        hir::ExprKind::Stream(..) => stream_pack_type(cx, expr, env),
+
         // Other things simply evaluate to their self-determined type.
         hir::ExprKind::Builtin(hir::BuiltinCall::Signed(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::Unsigned(_))
-        | hir::ExprKind::FunctionCall(..) => cx.need_self_determined_type(expr.id, env),
+        | hir::ExprKind::FunctionCall(..)
+        | hir::ExprKind::MethodCall(..) => cx.need_self_determined_type(expr.id, env),
 
         // Pattern expressions require a type context.
         hir::ExprKind::PositionalPattern(..)
         | hir::ExprKind::NamedPattern(..)
         | hir::ExprKind::RepeatPattern(..) => cx.need_type_context(Ref(expr), env).ty(),
 
+        // Dynamic array allocations infer the array type from the context.
+        hir::ExprKind::ArrayNew(..) => cx.need_type_context(Ref(expr), env).ty(),
+
+        // Tagged union expressions and wildcard patterns infer the union type
+        // from the context.
+        hir::ExprKind::Tagged(..) | hir::ExprKind::WildcardPattern => {
+            cx.need_type_context(Ref(expr), env).ty()
+        }
+
         hir::ExprKind::Ast(expr) => bug_span!(expr.span(), cx, "unsupported raw AST expr in HIR"),
     }
 }
 
+/// Determine the type of a streaming concatenation used as an rvalue.
+///
+/// In an assignment-like context the stream is left-justified in a wider
+/// target and zero-filled on the right. A target narrower than the stream is
+/// an error. See §11.4.14.1 "Concatenation of stream_expressions".
+fn stream_pack_type<'a>(
+    cx: &impl Context<'a>,
+    expr: &'a hir::Expr<'a>,
+    env: ParamEnv,
+) -> &'a UnpackedType<'a> {
+    let ty = cx.need_self_determined_type(expr.id, env);
+    if ty.is_error() || expr_is_lvalue(cx, expr.id, env) {
+        return ty;
+    }
+
+    // Only assignments and casts determine the size of the stream. Other
+    // operators simply use the stream as a bit vector.
+    let in_operator = match cx.hir_of(cx.parent_node_id(expr.id).unwrap()) {
+        Ok(HirNode::Expr(parent)) => match parent.kind {
+            hir::ExprKind::Cast(..) | hir::ExprKind::Assign { .. } => false,
+            _ => true,
+        },
+        _ => false,
+    };
+    if in_operator {
+        return ty;
+    }
+    let target = match cx.type_context(Ref(expr), env) {
+        Some(TypeContext::Type(target)) if !target.is_error() => target,
+        _ => return ty,
+    };
+    let stream = ty.get_simple_bit_vector().unwrap();
+    let size = match target.get_simple_bit_vector() {
+        Some(sbv) => sbv.size,
+        None => return ty,
+    };
+    if size < stream.size {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "streaming concatenation of {} bits does not fit into `{}`",
+                stream.size, target
+            ))
+            .span(expr.span)
+            .add_note(format!(
+                "`{}` only has {} bits; the stream is not truncated",
+                target, size
+            )),
+        );
+        UnpackedType::make_error()
+    } else if size > stream.size {
+        SbvType::new(stream.domain, Sign::Unsigned, size).to_unpacked(cx)
+    } else {
+        ty
+    }
+}
+
 /// Get the cast type of a node.
 #[moore_derive::query]
 pub(crate) fn cast_type<'a>(
@@ -1231,6 +1377,9 @@ pub(crate) fn cast_expr_type<'a>(
                 CastOp::PickModport => format!("implicitly picking modport `{}`", ty),
                 CastOp::PackString => format!("pack as string `{}`", ty),
                 CastOp::UnpackString => format!("unpack string as `{}`", ty),
+                CastOp::IntToReal => format!("convert to real `{}`", ty),
+                CastOp::RealToInt => format!("round to integer `{}`", ty),
+                CastOp::ResizeReal => format!("change precision to `{}`", ty),
             };
             d = d.add_note(msg);
         }
@@ -1316,6 +1465,33 @@ fn cast_expr_type_inner<'gcx>(
         }
     }
 
+    // Cast reals to the context type. Conversions to integral types round to
+    // the nearest integer, then go through the regular SBVT casts below.
+    //
+    // See §6.12.1 "Conversion".
+    let inferred = if inferred.is_real() {
+        match context {
+            TypeContext::Bool => {
+                trace!("  Casting real to bool ({})", context.ty());
+                cast.add_cast(CastOp::Bool, context.ty());
+                return cast;
+            }
+            TypeContext::Type(ty) if ty.is_real() => {
+                trace!("  Changing real precision ({})", ty);
+                cast.add_cast(CastOp::ResizeReal, ty);
+                return cast;
+            }
+            TypeContext::Type(_) => {
+                let ty = SbvType::new(ty::Domain::TwoValued, ty::Sign::Signed, 64).to_unpacked(cx);
+                trace!("  Rounding real to integer ({})", ty);
+                cast.add_cast(CastOp::RealToInt, ty);
+                ty
+            }
+        }
+    } else {
+        inferred
+    };
+
     // Cast strings to SBVTs.
     let inferred = match context.ty().get_simple_bit_vector() {
         Some(context_sbvt) if inferred.is_string() => {
@@ -1364,6 +1540,13 @@ fn cast_expr_type_inner<'gcx>(
         inferred_sbvt
     );
 
+    // Convert the SBVT to a real.
+    if context.ty().is_real() {
+        trace!("  Converting SBVT to real ({})", context.ty());
+        cast.add_cast(CastOp::IntToReal, context.ty());
+        return cast;
+    }
+
     // Cast the SBVT to a boolean.
     let context = match context {
         TypeContext::Bool => {
@@ -1598,6 +1781,9 @@ fn self_determined_expr_type<'gcx>(
         // Time constants are of time type.
         hir::ExprKind::TimeConst(_) => Some(UnpackedType::make_time()),
 
+        // Real constants are of real type.
+        hir::ExprKind::RealConst(_) => Some(UnpackedType::make_real(RealType::Real)),
+
         // String literals behave like a packed array containing the characters.
         hir::ExprKind::StringConst(string) => Some(
             ty::PackedType::make_dims(
@@ -1627,6 +1813,17 @@ fn self_determined_expr_type<'gcx>(
         hir::ExprKind::Concat(repeat, ref exprs) => {
             let mut failed = false;
 
+            // If any of the arguments is a string, the result is a string.
+            //
+            // See §11.4.12.2 "String concatenation".
+            if exprs.iter().any(|&expr| {
+                cx.self_determined_type(expr, env)
+                    .map(|ty| ty.is_string())
+                    .unwrap_or(false)
+            }) {
+                return Some(UnpackedType::make_string());
+            }
+
             // Determine the cumulative width of all fields.
             let mut bit_width = 0;
             let mut domain = ty::Domain::TwoValued;
@@ -1679,6 +1876,45 @@ fn self_determined_expr_type<'gcx>(
             }
         }
 
+        // Streaming concatenations produce a bit stream as wide as all their
+        // operands together.
+        //
+        // See §11.4.14 "Streaming operators (pack/unpack)".
+        hir::ExprKind::Stream(_, _, ref exprs) => {
+            let mut failed = false;
+            let mut bit_width = 0;
+            let mut domain = ty::Domain::TwoValued;
+            for &expr in exprs {
+                let ty = cx.need_self_determined_type(expr, env);
+                if ty.is_error() {
+                    failed = true;
+                    continue;
+                }
+                if ty.domain() == ty::Domain::FourValued {
+                    domain = ty::Domain::FourValued;
+                }
+                match ty.get_simple_bit_vector() {
+                    Some(sbv) => bit_width += sbv.size,
+                    None => {
+                        cx.emit(
+                            DiagBuilder2::error(format!("cannot stream a value of type `{}`", ty))
+                                .span(cx.span(expr))
+                                .add_note(format!(
+                                    "`{}` has no simple bit-vector type representation",
+                                    ty
+                                )),
+                        );
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                Some(UnpackedType::make_error())
+            } else {
+                Some(SbvType::new(domain, Sign::Unsigned, bit_width).to_unpacked(cx))
+            }
+        }
+
         // Casts trivially evaluate to the cast type.
         hir::ExprKind::Cast(ty, _) => Some(cx.packed_type_from_ast(
             Ref(cx.ast_for_id(ty).as_all().get_type().unwrap()),
@@ -1749,13 +1985,71 @@ fn self_determined_expr_type<'gcx>(
         // These builtin functions evaluate to the bit type.
         hir::ExprKind::Builtin(hir::BuiltinCall::OneHot(_))
         | hir::ExprKind::Builtin(hir::BuiltinCall::OneHot0(_))
-        | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_)) => {
+        | hir::ExprKind::Builtin(hir::BuiltinCall::IsUnknown(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Rose(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Fell(_))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::Stable(_)) => {
             Some(PackedType::make(cx, ty::IntVecType::Bit).to_unpacked(cx))
         }
 
+        // The real conversion functions have a fixed return type.
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, _)) => Some(match conv {
+            hir::RealConv::RealToInt => {
+                PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx)
+            }
+            hir::RealConv::IntToReal | hir::RealConv::BitsToReal => {
+                UnpackedType::make_real(RealType::Real)
+            }
+            hir::RealConv::BitsToShortReal => UnpackedType::make_real(RealType::ShortReal),
+            hir::RealConv::RealToBits => {
+                SbvType::new(Domain::TwoValued, Sign::Unsigned, 64).to_unpacked(cx)
+            }
+            hir::RealConv::ShortRealToBits => {
+                SbvType::new(Domain::TwoValued, Sign::Unsigned, 32).to_unpacked(cx)
+            }
+        }),
+
+        // The past value of an expression has the same type as the expression.
+        hir::ExprKind::Builtin(hir::BuiltinCall::Past(arg, _)) => cx.self_determined_type(arg, env),
+
+        // `$sformatf` produces a string.
+        hir::ExprKind::Builtin(hir::BuiltinCall::SysTask(hir::SysTask::SFormatF, _)) => {
+            Some(UnpackedType::make_string())
+        }
+
+        // System tasks produce no value.
+        hir::ExprKind::Builtin(hir::BuiltinCall::SysTask(..))
+        | hir::ExprKind::Builtin(hir::BuiltinCall::ReadMem { .. }) => {
+            Some(UnpackedType::make_void())
+        }
+
+        // Method calls resolve to the method's return type.
+        hir::ExprKind::MethodCall(target, _, _, with) => {
+            let target_ty = cx.need_self_determined_type(target, env);
+            Some(match (cx.resolve_method_call(expr.id, env), with) {
+                // Reductions with a `with` clause operate on the type of the
+                // clause's expression.
+                (Ok(hir::Method::ArrayManip(method)), Some((_, with_expr)))
+                    if method.is_reduction() =>
+                {
+                    cx.need_self_determined_type(with_expr, env)
+                }
+                (Ok(method), _) => method_return_type(cx, method, target_ty),
+                (Err(()), _) => UnpackedType::make_error(),
+            })
+        }
+
         // Member field accesses resolve to the type of the member.
         hir::ExprKind::Field(target, name) => {
             let target_ty = cx.self_determined_type(target, env)?;
+
+            // Accesses such as `e.triggered` call a method without arguments.
+            if target_ty.has_methods() {
+                return Some(match cx.resolve_method_call(expr.id, env) {
+                    Ok(method) => method_return_type(cx, method, target_ty),
+                    Err(()) => UnpackedType::make_error(),
+                });
+            }
             if let Some(intf) = target_ty.get_interface() {
                 let def = cx.resolve_hierarchical_or_error(name, intf.ast).ok()?;
                 // If we are selecting a modport, just modify the type of the
@@ -1901,7 +2195,9 @@ fn self_determined_expr_type<'gcx>(
             | hir::UnaryOp::PreDec
             | hir::UnaryOp::PostInc
             | hir::UnaryOp::PostDec => {
-                unify_operator_types(cx, env, cx.self_determined_type(arg, env).into_iter())
+                let targ = cx.self_determined_type(arg, env);
+                unify_real_operator_types(targ.into_iter())
+                    .or_else(|| unify_operator_types(cx, env, targ.into_iter()))
             }
         },
 
@@ -1910,6 +2206,10 @@ fn self_determined_expr_type<'gcx>(
             // Handle the self-determined cases.
             hir::BinaryOp::Eq
             | hir::BinaryOp::Neq
+            | hir::BinaryOp::CaseEq
+            | hir::BinaryOp::CaseNeq
+            | hir::BinaryOp::WildcardEq
+            | hir::BinaryOp::WildcardNeq
             | hir::BinaryOp::Lt
             | hir::BinaryOp::Leq
             | hir::BinaryOp::Gt
@@ -1932,12 +2232,21 @@ fn self_determined_expr_type<'gcx>(
             | hir::BinaryOp::BitXnor => {
                 let tlhs = cx.self_determined_type(lhs, env);
                 let trhs = cx.self_determined_type(rhs, env);
-                unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+                unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(|| {
+                    unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+                })
             }
 
-            // Exponentiation and shifts operate on the left-hand side type.
-            hir::BinaryOp::Pow
-            | hir::BinaryOp::LogicShL
+            // Exponentiation is performed on reals if either operand is a
+            // real.
+            hir::BinaryOp::Pow => {
+                let tlhs = cx.self_determined_type(lhs, env);
+                let trhs = cx.self_determined_type(rhs, env);
+                unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or(tlhs)
+            }
+
+            // Shifts operate on the left-hand side type.
+            hir::BinaryOp::LogicShL
             | hir::BinaryOp::LogicShR
             | hir::BinaryOp::ArithShL
             | hir::BinaryOp::ArithShR => cx.self_determined_type(lhs, env),
@@ -1948,7 +2257,8 @@ fn self_determined_expr_type<'gcx>(
         hir::ExprKind::Ternary(_, lhs, rhs) => {
             let tlhs = cx.self_determined_type(lhs, env);
             let trhs = cx.self_determined_type(rhs, env);
-            unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+            unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter()))
+                .or_else(|| unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter())))
         }
 
         // Function calls resolve to the function's return type.
@@ -1963,6 +2273,9 @@ fn self_determined_expr_type<'gcx>(
             .self_determined_type(lhs.id, env)
             .or_else(|| cx.self_determined_type(rhs.id, env)),
 
+        // Pattern variables have the type of the union member they bind to.
+        hir::ExprKind::PatternVar(var) => cx.type_of(var, env).ok(),
+
         _ => None,
     }
 }
@@ -2028,7 +2341,14 @@ pub(crate) fn operation_type<'a>(
                 | hir::UnaryOp::PostDec => {
                     let tc = cx.type_context(Ref(node), env).map(|x| x.ty());
                     let targ = cx.self_determined_type(arg, env);
-                    unify_operator_types(cx, env, tc.into_iter().chain(targ.into_iter()))
+                    if op == hir::UnaryOp::BitNot {
+                        if let Some(err) = reject_real_operands(cx, expr, targ.into_iter()) {
+                            return Some(err);
+                        }
+                    }
+                    unify_real_operator_types(targ.into_iter()).or_else(|| {
+                        unify_operator_types(cx, env, tc.into_iter().chain(targ.into_iter()))
+                    })
                 }
 
                 // Reductions simply operate on their argument type.
@@ -2037,7 +2357,13 @@ pub(crate) fn operation_type<'a>(
                 | hir::UnaryOp::RedXor
                 | hir::UnaryOp::RedNand
                 | hir::UnaryOp::RedNor
-                | hir::UnaryOp::RedXnor => cx.self_determined_type(arg, env),
+                | hir::UnaryOp::RedXnor => {
+                    let targ = cx.self_determined_type(arg, env);
+                    if let Some(err) = reject_real_operands(cx, expr, targ.into_iter()) {
+                        return Some(err);
+                    }
+                    targ
+                }
 
                 // Handle the self-determined cases.
                 hir::UnaryOp::LogicNot => Some(UnpackedType::make_logic()),
@@ -2078,12 +2404,28 @@ pub(crate) fn operation_type<'a>(
                     let tc = cx.type_context(Ref(node), env).map(|x| x.ty());
                     let tlhs = cx.self_determined_type(lhs, env);
                     let trhs = cx.self_determined_type(rhs, env);
-                    unify_operator_types(
-                        cx,
-                        env,
-                        tc.into_iter()
-                            .chain(tlhs.into_iter())
-                            .chain(trhs.into_iter()),
+                    match op {
+                        hir::BinaryOp::Add
+                        | hir::BinaryOp::Sub
+                        | hir::BinaryOp::Mul
+                        | hir::BinaryOp::Div => (),
+                        _ => {
+                            let operands = tlhs.into_iter().chain(trhs.into_iter());
+                            if let Some(err) = reject_real_operands(cx, expr, operands) {
+                                return Some(err);
+                            }
+                        }
+                    }
+                    unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(
+                        || {
+                            unify_operator_types(
+                                cx,
+                                env,
+                                tc.into_iter()
+                                    .chain(tlhs.into_iter())
+                                    .chain(trhs.into_iter()),
+                            )
+                        },
                     )
                 }
 
@@ -2091,6 +2433,10 @@ pub(crate) fn operation_type<'a>(
                 // use the maximum bit width of the operands.
                 hir::BinaryOp::Eq
                 | hir::BinaryOp::Neq
+                | hir::BinaryOp::CaseEq
+                | hir::BinaryOp::CaseNeq
+                | hir::BinaryOp::WildcardEq
+                | hir::BinaryOp::WildcardNeq
                 | hir::BinaryOp::Lt
                 | hir::BinaryOp::Leq
                 | hir::BinaryOp::Gt
@@ -2098,11 +2444,19 @@ pub(crate) fn operation_type<'a>(
                     let tlhs = cx.self_determined_type(lhs, env);
                     let trhs = cx.self_determined_type(rhs, env);
                     if tlhs.map(|t| t.is_string()).unwrap_or(false)
-                        && trhs.map(|t| t.is_string()).unwrap_or(false)
+                        || trhs.map(|t| t.is_string()).unwrap_or(false)
                     {
-                        tlhs
+                        Some(UnpackedType::make_string())
                     } else {
-                        unify_operator_types(cx, env, tlhs.into_iter().chain(trhs.into_iter()))
+                        unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(
+                            || {
+                                unify_operator_types(
+                                    cx,
+                                    env,
+                                    tlhs.into_iter().chain(trhs.into_iter()),
+                                )
+                            },
+                        )
                     }
                 }
 
@@ -2112,6 +2466,8 @@ pub(crate) fn operation_type<'a>(
                 }
 
                 // Exponentiation and shifts operate on the left-hand side type.
+                // Exponentiation is performed on reals if either operand is a
+                // real.
                 hir::BinaryOp::Pow
                 | hir::BinaryOp::LogicShL
                 | hir::BinaryOp::LogicShR
@@ -2119,7 +2475,18 @@ pub(crate) fn operation_type<'a>(
                 | hir::BinaryOp::ArithShR => {
                     let tc = cx.type_context(Ref(node), env).map(|x| x.ty());
                     let sdt = cx.self_determined_type(lhs, env);
-                    unify_operator_types(cx, env, tc.into_iter().chain(sdt.into_iter()))
+                    let real = if op == hir::BinaryOp::Pow {
+                        let trhs = cx.self_determined_type(rhs, env);
+                        unify_real_operator_types(sdt.into_iter().chain(trhs.into_iter()))
+                    } else {
+                        if let Some(err) = reject_real_operands(cx, expr, sdt.into_iter()) {
+                            return Some(err);
+                        }
+                        None
+                    };
+                    real.or_else(|| {
+                        unify_operator_types(cx, env, tc.into_iter().chain(sdt.into_iter()))
+                    })
                 }
             };
             if ty.is_none() {
@@ -2147,13 +2514,15 @@ pub(crate) fn operation_type<'a>(
             let tc = cx.type_context(Ref(node), env).map(|x| x.ty());
             let tlhs = cx.self_determined_type(lhs, env);
             let trhs = cx.self_determined_type(rhs, env);
-            unify_operator_types(
-                cx,
-                env,
-                tc.into_iter()
-                    .chain(tlhs.into_iter())
-                    .chain(trhs.into_iter()),
-            )
+            unify_real_operator_types(tlhs.into_iter().chain(trhs.into_iter())).or_else(|| {
+                unify_operator_types(
+                    cx,
+                    env,
+                    tc.into_iter()
+                        .chain(tlhs.into_iter())
+                        .chain(trhs.into_iter()),
+                )
+            })
         }
 
         // The inside expression uses an operation type for its comparisons. It
@@ -2242,6 +2611,52 @@ fn unify_operator_types<'gcx>(
     width.map(|w| SbvType::nice(domain, sign, w).to_unpacked(cx))
 }
 
+/// Determine the real type an operator operates on, if any of its operands is
+/// a real.
+///
+/// The operation is carried out on `real` values, unless all real operands are
+/// `shortreal`. Integral operands are converted to the real type.
+///
+/// See §11.3.1 "Operators with real operands".
+fn unify_real_operator_types<'gcx>(
+    types: impl Iterator<Item = &'gcx UnpackedType<'gcx>>,
+) -> Option<&'gcx UnpackedType<'gcx>> {
+    let mut reals = types.flat_map(|ty| ty.get_real()).peekable();
+    reals.peek()?;
+    Some(UnpackedType::make_real(
+        match reals.all(|real| real == RealType::ShortReal) {
+            true => RealType::ShortReal,
+            false => RealType::Real,
+        },
+    ))
+}
+
+/// Emit an error if any of the operands of an operator are reals.
+///
+/// Returns an error type if a real operand was found.
+///
+/// See §11.3.1 "Operators with real operands".
+fn reject_real_operands<'gcx>(
+    cx: &impl Context<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    types: impl Iterator<Item = &'gcx UnpackedType<'gcx>>,
+) -> Option<&'gcx UnpackedType<'gcx>> {
+    for ty in types {
+        if ty.is_real() {
+            cx.emit(
+                DiagBuilder2::error(format!(
+                    "operator in `{}` cannot be applied to a value of type `{}`",
+                    expr.span().extract(),
+                    ty
+                ))
+                .span(expr.human_span()),
+            );
+            return Some(UnpackedType::make_error());
+        }
+    }
+    None
+}
+
 /// Require a node to have an operation type.
 ///
 /// Emits an error if the node has no operation type.
@@ -2341,11 +2756,18 @@ pub(crate) fn type_context<'a>(
     match parent_hir {
         HirNode::Expr(e) => type_context_imposed_by_expr(cx, onto, e, env),
         HirNode::Stmt(s) => type_context_imposed_by_stmt(cx, onto, s, env),
+        HirNode::ConcAssert(a) => {
+            if a.disable == Some(onto) || a.prop.has_bool_operand(onto) {
+                Some(TypeContext::Bool)
+            } else {
+                None
+            }
+        }
         HirNode::Assign(a) => {
             if a.lhs == onto {
                 cx.self_determined_type(a.rhs, env).map(Into::into)
             } else if a.rhs == onto {
-                cx.self_determined_type(a.lhs, env).map(Into::into)
+                type_context_imposed_on_rhs(cx, a.lhs, a.rhs, env)
             } else {
                 None
             }
@@ -2509,8 +2931,9 @@ fn type_context_imposed_by_expr<'gcx>(
             | hir::BinaryOp::LogicShR
             | hir::BinaryOp::ArithShL
             | hir::BinaryOp::ArithShR => {
-                if onto == lhs {
-                    Some(cx.need_operation_type(expr.id, env).into())
+                let op_ty = cx.need_operation_type(expr.id, env);
+                if onto == lhs || op_ty.is_real() {
+                    Some(op_ty.into())
                 } else {
                     None
                 }
@@ -2530,6 +2953,10 @@ fn type_context_imposed_by_expr<'gcx>(
             | hir::BinaryOp::BitXnor
             | hir::BinaryOp::Eq
             | hir::BinaryOp::Neq
+            | hir::BinaryOp::CaseEq
+            | hir::BinaryOp::CaseNeq
+            | hir::BinaryOp::WildcardEq
+            | hir::BinaryOp::WildcardNeq
             | hir::BinaryOp::Lt
             | hir::BinaryOp::Leq
             | hir::BinaryOp::Gt
@@ -2559,6 +2986,37 @@ fn type_context_imposed_by_expr<'gcx>(
             Some(cx.need_self_determined_type(expr.id, env).into())
         }
 
+        // The real conversion functions impose the type they convert from onto
+        // their argument. Only `$itor` accepts any integral type.
+        hir::ExprKind::Builtin(hir::BuiltinCall::RealConv(conv, arg)) if onto == arg => {
+            match conv {
+                hir::RealConv::RealToInt | hir::RealConv::RealToBits => {
+                    Some(UnpackedType::make_real(RealType::Real).into())
+                }
+                hir::RealConv::ShortRealToBits => {
+                    Some(UnpackedType::make_real(RealType::ShortReal).into())
+                }
+                hir::RealConv::BitsToReal => Some(
+                    SbvType::new(Domain::TwoValued, Sign::Unsigned, 64)
+                        .to_unpacked(cx)
+                        .into(),
+                ),
+                hir::RealConv::BitsToShortReal => Some(
+                    SbvType::new(Domain::TwoValued, Sign::Unsigned, 32)
+                        .to_unpacked(cx)
+                        .into(),
+                ),
+                hir::RealConv::IntToReal => None,
+            }
+        }
+
+        // String concatenations impose the string type onto their arguments.
+        hir::ExprKind::Concat(repeat, _)
+            if repeat != Some(onto) && cx.need_self_determined_type(expr.id, env).is_string() =>
+        {
+            Some(UnpackedType::make_string().into())
+        }
+
         // Concatenations require their arguments (including repetition counts)
         // to map to a corresponding SBVT.
         hir::ExprKind::Concat(..) => {
@@ -2585,6 +3043,17 @@ fn type_context_imposed_by_expr<'gcx>(
             Some(sbvt.into())
         }
 
+        // Streaming concatenations require their operands to map to a
+        // corresponding SBVT. The slice size is self-determined.
+        hir::ExprKind::Stream(_, _, ref exprs) if exprs.contains(&onto) => {
+            let ty = cx.need_self_determined_type(onto, env);
+            if ty.is_error() {
+                return Some(ty.into());
+            }
+            ty.get_simple_bit_vector()
+                .map(|sbv| sbv.to_unpacked(cx).into())
+        }
+
         // Patterns impose the field types onto their arguments.
         hir::ExprKind::PositionalPattern(..) => {
             type_context_imposed_by_pattern(cx, onto, expr, env)
@@ -2622,6 +3091,82 @@ fn type_context_imposed_by_expr<'gcx>(
         // Assignments impose their operation type as context.
         hir::ExprKind::Assign { .. } => Some(cx.need_operation_type(expr.id, env).into()),
 
+        // Method calls impose the types of the method's arguments.
+        hir::ExprKind::MethodCall(_, _, ref args, _) if args.contains(&onto) => {
+            let index = args.iter().position(|&arg| arg == onto).unwrap();
+            match cx.resolve_method_call(expr.id, env) {
+                Ok(hir::Method::String(method)) => Some(match (method, index) {
+                    (hir::StringMethod::Putc, 1) => {
+                        PackedType::make(cx, ty::IntAtomType::Byte).to_unpacked(cx)
+                    }
+                    (hir::StringMethod::ItoA(_), _) => {
+                        PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx)
+                    }
+                    _ => PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx),
+                }),
+                Ok(hir::Method::Event(_)) => None,
+                Ok(hir::Method::Array(method)) => {
+                    let target = match expr.kind {
+                        hir::ExprKind::MethodCall(target, ..) => target,
+                        _ => unreachable!(),
+                    };
+                    let ty = cx.need_self_determined_type(target, env);
+                    match (method, ty.get_dynamic_dim()) {
+                        (hir::ArrayMethod::PushFront, _) | (hir::ArrayMethod::PushBack, _) => {
+                            ty.pop_dim(cx)
+                        }
+                        (_, Some(ty::UnpackedDim::Assoc(key))) => key,
+                        (_, Some(_)) => {
+                            Some(PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx))
+                        }
+                        (_, None) => Some(UnpackedType::make_error()),
+                    }
+                }
+                Ok(hir::Method::Enum(_)) => {
+                    Some(SbvType::new(Domain::TwoValued, Sign::Unsigned, 32).to_unpacked(cx))
+                }
+                Ok(hir::Method::ArrayManip(_)) => None,
+                Err(()) => Some(UnpackedType::make_error()),
+            }
+            .map(Into::into)
+        }
+
+        // The `with` clause of a locator method is a condition.
+        hir::ExprKind::MethodCall(_, _, _, Some((_, with))) if onto == with => {
+            match cx.resolve_method_call(expr.id, env) {
+                Ok(hir::Method::ArrayManip(method)) if method.requires_with() => {
+                    Some(TypeContext::Bool)
+                }
+                _ => None,
+            }
+        }
+
+        // Dynamic array allocations impose an `int` on the size, and their own
+        // type on the initial contents.
+        hir::ExprKind::ArrayNew(size, _) if onto == size => Some(
+            PackedType::make(cx, ty::IntAtomType::Int)
+                .to_unpacked(cx)
+                .into(),
+        ),
+        hir::ExprKind::ArrayNew(..) => Some(cx.need_type_context(Ref(expr), env)),
+
+        // Tagged union expressions impose the selected member's type on their
+        // value.
+        hir::ExprKind::Tagged(_, Some(value)) if onto == value => {
+            match cx.map_tagged(Ref(expr), env) {
+                Ok(map) => Some(map.member.ty.into()),
+                Err(()) => Some(UnpackedType::make_error().into()),
+            }
+        }
+
+        // Associative arrays impose their key type on the index.
+        hir::ExprKind::Index(target, hir::IndexMode::One(index)) if onto == index => {
+            match cx.need_self_determined_type(target, env).get_dynamic_dim() {
+                Some(ty::UnpackedDim::Assoc(Some(key))) => Some(key.into()),
+                _ => None,
+            }
+        }
+
         _ => None,
     }
 }
@@ -2667,6 +3212,64 @@ fn type_context_imposed_by_pattern<'gcx>(
     }
 }
 
+/// Determine the type a built-in method returns when called on a value of type
+/// `target`.
+fn method_return_type<'gcx>(
+    cx: &impl Context<'gcx>,
+    method: hir::Method,
+    target: &'gcx UnpackedType<'gcx>,
+) -> &'gcx UnpackedType<'gcx> {
+    match method {
+        hir::Method::String(method) => match method {
+            hir::StringMethod::Len => PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx),
+            hir::StringMethod::Getc => PackedType::make(cx, ty::IntAtomType::Byte).to_unpacked(cx),
+            hir::StringMethod::Substr => UnpackedType::make_string(),
+            hir::StringMethod::AtoI(_) => {
+                PackedType::make(cx, ty::IntAtomType::Integer).to_unpacked(cx)
+            }
+            hir::StringMethod::Putc | hir::StringMethod::ItoA(_) => UnpackedType::make_void(),
+        },
+        hir::Method::Event(hir::EventMethod::Triggered) => {
+            PackedType::make(cx, ty::IntVecType::Bit).to_unpacked(cx)
+        }
+        hir::Method::Array(method) => match method {
+            hir::ArrayMethod::Delete
+            | hir::ArrayMethod::DeleteIndex
+            | hir::ArrayMethod::PushFront
+            | hir::ArrayMethod::PushBack => UnpackedType::make_void(),
+            hir::ArrayMethod::PopFront | hir::ArrayMethod::PopBack => target
+                .pop_dim(cx)
+                .unwrap_or_else(|| UnpackedType::make_error()),
+            hir::ArrayMethod::Size
+            | hir::ArrayMethod::Exists
+            | hir::ArrayMethod::First
+            | hir::ArrayMethod::Last
+            | hir::ArrayMethod::Next
+            | hir::ArrayMethod::Prev => PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx),
+        },
+        hir::Method::Enum(method) => match method {
+            hir::EnumMethod::First
+            | hir::EnumMethod::Last
+            | hir::EnumMethod::Next(_)
+            | hir::EnumMethod::Prev(_) => target,
+            hir::EnumMethod::Num => PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx),
+            hir::EnumMethod::Name => UnpackedType::make_string(),
+        },
+        hir::Method::ArrayManip(method) => {
+            let elem = target
+                .pop_dim(cx)
+                .unwrap_or_else(|| UnpackedType::make_error());
+            let index = PackedType::make(cx, ty::IntAtomType::Int).to_unpacked(cx);
+            match method {
+                _ if method.is_reduction() => elem,
+                _ if method.modifies_target() => UnpackedType::make_void(),
+                _ if method.returns_indices() => index.make_queue(cx),
+                _ => elem.make_queue(cx),
+            }
+        }
+    }
+}
+
 /// Get the type context imposed by a statement.
 ///
 /// Determine the type context `stmt` imposes on `onto`.
@@ -2683,7 +3286,7 @@ fn type_context_imposed_by_stmt<'gcx>(
             if lhs == onto {
                 cx.self_determined_type(rhs, env).map(Into::into)
             } else if rhs == onto {
-                cx.self_determined_type(lhs, env).map(Into::into)
+                type_context_imposed_on_rhs(cx, lhs, rhs, env)
             } else {
                 None
             }
@@ -2692,6 +3295,9 @@ fn type_context_imposed_by_stmt<'gcx>(
         // If statements and do/while loops require a boolean condition.
         hir::StmtKind::If { cond, .. } if onto == cond => Some(TypeContext::Bool),
 
+        // Assertions require a boolean condition.
+        hir::StmtKind::Assert { cond, .. } if onto == cond => Some(TypeContext::Bool),
+
         // Do/while loops require a boolean condition.
         hir::StmtKind::Loop { kind, .. } => {
             match kind {
@@ -2720,6 +3326,16 @@ fn type_context_imposed_by_stmt<'gcx>(
             }
         }
 
+        // Pattern matching case statements impose the switch expression's
+        // self-determined type on the patterns.
+        hir::StmtKind::PatternCase { expr, ref ways, .. } => {
+            if ways.iter().any(|&(x, _)| x == onto) {
+                cx.self_determined_type(expr, env).map(Into::into)
+            } else {
+                None
+            }
+        }
+
         // Time control statements require time arguments.
         hir::StmtKind::Timed {
             control: hir::TimingControl::Delay(expr),
@@ -2762,6 +3378,13 @@ pub enum CastOp {
     PackString,
     /// Unpack a string from an SBVT.
     UnpackString,
+    /// Convert an SBVT to a real number.
+    IntToReal,
+    /// Convert a real number to a 64 bit signed integer, rounding to the
+    /// nearest integer.
+    RealToInt,
+    /// Convert a real number to a different precision.
+    ResizeReal,
 }
 
 impl<'a> CastType<'a> {
@@ -2797,6 +3420,56 @@ impl std::fmt::Display for CastType<'_> {
     }
 }
 
+/// Get the type context imposed on the right-hand side of an assignment.
+///
+/// This is usually the self-determined type of the left-hand side. A
+/// streaming concatenation on the left-hand side unpacks the leftmost bits of
+/// the right-hand side instead, which therefore keeps its own size as long as
+/// it provides enough bits. See §11.4.14.3 "Streaming concatenation as an
+/// assignment target (unpack)".
+fn type_context_imposed_on_rhs<'a>(
+    cx: &impl Context<'a>,
+    lhs: NodeId,
+    rhs: NodeId,
+    env: ParamEnv,
+) -> Option<TypeContext<'a>> {
+    let lhs_ty = cx.self_determined_type(lhs, env)?;
+    match cx.hir_of(lhs) {
+        Ok(HirNode::Expr(hir::Expr {
+            kind: hir::ExprKind::Stream(..),
+            ..
+        })) if !lhs_ty.is_error() => (),
+        _ => return Some(lhs_ty.into()),
+    }
+    let rhs_ty = match cx.self_determined_type(rhs, env) {
+        Some(ty) if !ty.is_error() => ty,
+        _ => return Some(lhs_ty.into()),
+    };
+    let stream = lhs_ty.get_simple_bit_vector().unwrap();
+    let size = match rhs_ty.get_simple_bit_vector() {
+        Some(sbv) => sbv.size,
+        None => return Some(lhs_ty.into()),
+    };
+    if size < stream.size {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "streaming concatenation needs {} bits, but `{}` only provides {}",
+                stream.size,
+                cx.span(rhs).extract(),
+                size
+            ))
+            .span(cx.span(rhs))
+            .add_note("The source of an unpack operation must not be narrower than the stream."),
+        );
+        return Some(UnpackedType::make_error().into());
+    }
+    Some(
+        SbvType::new(stream.domain, Sign::Unsigned, size)
+            .to_unpacked(cx)
+            .into(),
+    )
+}
+
 /// Check if an expression is in lvalue position.
 pub(crate) fn expr_is_lvalue<'gcx>(cx: &impl Context<'gcx>, onto: NodeId, _env: ParamEnv) -> bool {
     let hir = match cx.hir_of(cx.parent_node_id(onto).unwrap()) {
@@ -2912,6 +3585,7 @@ pub fn return_type_of_function<'a>(
 ) -> &'a UnpackedType<'a> {
     match &node.retty {
         Some(ty) => cx.packed_type_from_ast(Ref(ty), env, Some(IntVecType::Logic.into())),
+        None if node.kind == ast::SubroutineKind::Task => UnpackedType::make_void(),
         None => {
             cx.emit(
                 DiagBuilder2::error(format!("no return type: {}", node)).span(node.name.span()),
@@ -19,7 +19,8 @@ use crate::{
 };
 use bit_vec::BitVec;
 use itertools::Itertools;
-use num::{BigInt, BigRational, Integer, One, ToPrimitive, Zero};
+use num::{BigInt, BigRational, FromPrimitive, Integer, One, ToPrimitive, Zero};
+use std::collections::HashMap;
 
 /// A verilog value.
 pub type Value<'t> = &'t ValueData<'t>;
@@ -50,8 +51,12 @@ impl<'t> ValueData<'t> {
             ValueKind::Void => true,
             ValueKind::Int(ref v, ..) => v.is_zero(),
             ValueKind::Time(ref v) => v.is_zero(),
+            ValueKind::Real(v) => f64::from_bits(v) == 0.0,
             ValueKind::StructOrArray(_) => false,
             ValueKind::String(ref v) => v.is_empty(),
+            ValueKind::Event => true,
+            ValueKind::Null => true,
+            ValueKind::EmptyArray => true,
             ValueKind::Error => true,
         }
     }
@@ -63,6 +68,14 @@ impl<'t> ValueData<'t> {
             _ => None,
         }
     }
+
+    /// Convert the value to a real number.
+    pub fn get_real(&self) -> Option<f64> {
+        match self.kind {
+            ValueKind::Real(v) => Some(f64::from_bits(v)),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ValueData<'_> {
@@ -83,6 +96,12 @@ pub enum ValueKind<'t> {
     Int(BigInt, BitVec, BitVec),
     /// An arbitrary precision time interval.
     Time(BigRational),
+    /// A real number.
+    ///
+    /// Holds the bit pattern of the IEEE 754 double precision value, such that
+    /// values remain `Eq` and `Hash`. Values of `shortreal` type are rounded
+    /// to single precision.
+    Real(u64),
     /// A struct.
     StructOrArray(Vec<Value<'t>>),
     /// A string.
@@ -91,6 +110,12 @@ pub enum ValueKind<'t> {
     /// is due to `String` guaranteeing that the encoded byte sequence is valid
     /// UTF8, which SystemVerilog does not guarantee.
     String(Vec<u8>),
+    /// An event that has not been triggered.
+    Event,
+    /// A `null` C handle.
+    Null,
+    /// An empty dynamic array, queue, or associative array.
+    EmptyArray,
     /// An error occurred during value computation.
     Error,
 }
@@ -111,6 +136,7 @@ impl std::fmt::Display for ValueKind<'_> {
             ValueKind::Void => write!(f, "void"),
             ValueKind::Int(v, ..) => write!(f, "{}", v),
             ValueKind::Time(v) => write!(f, "{}", v),
+            ValueKind::Real(v) => write!(f, "{:?}", f64::from_bits(*v)),
             ValueKind::StructOrArray(v) => {
                 write!(f, "{{ {} }}", v.iter().map(|v| &v.kind).format(", "))
             }
@@ -125,6 +151,9 @@ impl std::fmt::Display for ValueKind<'_> {
                 }
                 write!(f, "\"")
             }
+            ValueKind::Event => write!(f, "<event>"),
+            ValueKind::Null => write!(f, "null"),
+            ValueKind::EmptyArray => write!(f, "'{{}}"),
             ValueKind::Error => write!(f, "<error>"),
         }
     }
@@ -170,6 +199,77 @@ pub fn make_int_special<'a>(
     }
 }
 
+/// Create a new integer value where all bits are X.
+///
+/// Panics if `ty` is not an integer type.
+pub fn make_int_x<'a>(ty: &'a UnpackedType<'a>) -> ValueData<'a> {
+    let w = ty.get_bit_size().unwrap();
+    make_int_special(
+        ty,
+        BigInt::zero(),
+        BitVec::from_elem(w, true),
+        BitVec::from_elem(w, true),
+    )
+}
+
+/// Create a new integer value where all bits are Z.
+///
+/// Panics if `ty` is not an integer type.
+pub fn make_int_z<'a>(ty: &'a UnpackedType<'a>) -> ValueData<'a> {
+    let w = ty.get_bit_size().unwrap();
+    make_int_special(
+        ty,
+        BigInt::zero(),
+        BitVec::from_elem(w, true),
+        BitVec::from_elem(w, false),
+    )
+}
+
+/// Create a new integer value, or an all-X value if the value is unknown.
+///
+/// Two-valued types cannot hold X, in which case the value is zero instead.
+pub fn make_int_or_x<'a>(ty: &'a UnpackedType<'a>, value: Option<BigInt>) -> ValueData<'a> {
+    match value {
+        Some(value) => make_int(ty, value),
+        None if ty.domain() == ty::Domain::FourValued => make_int_x(ty),
+        None => make_int(ty, BigInt::zero()),
+    }
+}
+
+/// Check whether any bit of an integer value is X or Z.
+fn has_unknown_bits(value: &ValueData) -> bool {
+    match value.kind {
+        ValueKind::Int(_, ref special_bits, _) => special_bits.any(),
+        _ => false,
+    }
+}
+
+/// Interpret the special or X bits of an integer value as a mask.
+///
+/// The bits are stored most significant bit first, such that the last bit
+/// corresponds to the least significant bit of the mask.
+pub fn bits_to_int(bits: &BitVec) -> BigInt {
+    let mut mask = BigInt::zero();
+    for b in bits {
+        mask <<= 1;
+        if b {
+            mask |= BigInt::one();
+        }
+    }
+    mask
+}
+
+/// Convert a mask into the special or X bits of an integer value.
+///
+/// This is the inverse of `bits_to_int` for a value of `width` bits.
+pub fn int_to_bits(mask: &BigInt, width: usize) -> BitVec {
+    let mut bits = BitVec::from_elem(width, false);
+    for i in 0..width {
+        bits.set(width - 1 - i, !((mask >> i) & BigInt::one()).is_zero());
+    }
+    bits
+}
+
 /// Create a new time value.
 pub fn make_time<'a>(value: BigRational) -> ValueData<'a> {
     ValueData {
@@ -178,9 +278,25 @@ pub fn make_time<'a>(value: BigRational) -> ValueData<'a> {
     }
 }
 
+/// Create a new real value.
+///
+/// Panics if `ty` is not a real type. Rounds the value to single precision if
+/// `ty` is a `shortreal`.
+pub fn make_real<'a>(ty: &'a UnpackedType<'a>, value: f64) -> ValueData<'a> {
+    let value = match ty.get_real() {
+        Some(ty::RealType::ShortReal) => value as f32 as f64,
+        Some(_) => value,
+        None => panic!("make_real got non-real type `{}`", ty),
+    };
+    ValueData {
+        ty,
+        kind: ValueKind::Real(value.to_bits()),
+    }
+}
+
 /// Create a new struct value.
 pub fn make_struct<'a>(ty: &'a UnpackedType<'a>, fields: Vec<Value<'a>>) -> ValueData<'a> {
-    assert!(ty.dims().next().is_none() && ty.get_struct().is_some());
+    assert!(ty.dims().next().is_none() && (ty.get_struct().is_some() || ty.get_union().is_some()));
     ValueData {
         ty,
         kind: ValueKind::StructOrArray(fields),
@@ -308,7 +424,18 @@ pub(crate) fn const_mir_rvalue_int<'a>(
     cx: &impl Context<'a>,
     mir: Ref<'a, mir::Rvalue<'a>>,
 ) -> Result<&'a num::BigInt> {
-    match cx.const_mir_rvalue(mir).kind {
+    expect_const_int(cx, mir.0, cx.const_mir_rvalue(mir))
+}
+
+/// Extract the integer from the constant value of an MIR rvalue.
+///
+/// Emits a diagnostic if the value is not an integer.
+fn expect_const_int<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a mir::Rvalue<'a>,
+    value: Value<'a>,
+) -> Result<&'a num::BigInt> {
+    match value.kind {
         ValueKind::Int(ref x, ..) => Ok(x),
         ValueKind::Error => Err(()),
         _ => {
@@ -332,7 +459,18 @@ pub(crate) fn const_mir_rvalue_string<'a>(
     cx: &impl Context<'a>,
     mir: Ref<'a, mir::Rvalue<'a>>,
 ) -> Result<&'a Vec<u8>> {
-    match cx.const_mir_rvalue(mir).kind {
+    expect_const_string(cx, mir.0, cx.const_mir_rvalue(mir))
+}
+
+/// Extract the string from the constant value of an MIR rvalue.
+///
+/// Emits a diagnostic if the value is not a string.
+fn expect_const_string<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a mir::Rvalue<'a>,
+    value: Value<'a>,
+) -> Result<&'a Vec<u8>> {
+    match value.kind {
         ValueKind::String(ref x) => Ok(x),
         ValueKind::Error => Err(()),
         _ => {
@@ -351,7 +489,7 @@ pub(crate) fn const_mir_rvalue<'a>(
     cx: &impl Context<'a>,
     Ref(mir): Ref<'a, mir::Rvalue<'a>>,
 ) -> Value<'a> {
-    let v = const_mir_rvalue_inner(cx, mir);
+    let v = const_mir_rvalue_inner(cx, mir, None);
     if cx.sess().has_verbosity(Verbosity::CONSTS) {
         let ext = mir.span.extract();
         let line = mir.span.begin().human_line();
@@ -360,7 +498,29 @@ pub(crate) fn const_mir_rvalue<'a>(
     v
 }
 
-fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -> Value<'a> {
+/// Determine the constant value of an MIR rvalue, reading the arguments and
+/// local variables of a function called at elaboration time from `locals`.
+///
+/// Without `locals`, operands are evaluated through the `const_mir_rvalue`
+/// query, such that their values are cached.
+fn const_mir_rvalue_inner<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a mir::Rvalue<'a>,
+    locals: Option<&ConstLocals<'a>>,
+) -> Value<'a> {
+    let eval = |mir: &'a mir::Rvalue<'a>| match locals {
+        Some(_) => const_mir_rvalue_inner(cx, mir, locals),
+        None => cx.const_mir_rvalue(mir.into()),
+    };
+    let eval_int = |mir: &'a mir::Rvalue<'a>| match locals {
+        Some(_) => expect_const_int(cx, mir, eval(mir)),
+        None => cx.const_mir_rvalue_int(mir.into()),
+    };
+    let eval_string = |mir: &'a mir::Rvalue<'a>| match locals {
+        Some(_) => expect_const_string(cx, mir, eval(mir)),
+        None => cx.const_mir_rvalue_string(mir.into()),
+    };
+
     // Propagate MIR tombstones immediately.
     if mir.is_error() {
         return cx.intern_value(make_error(mir.ty));
@@ -379,7 +539,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
                 value.ty,
                 mir.ty
             );
-            let v = cx.const_mir_rvalue(value.into());
+            let v = eval(value);
             // TODO: This is an incredibly ugly hack.
             cx.intern_value(ValueData {
                 ty: mir.ty,
@@ -388,23 +548,119 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Transmute(value) => {
-            let v = cx.const_mir_rvalue(value.into());
-            cx.intern_value(ValueData {
-                ty: mir.ty,
-                kind: v.kind.clone(),
-            })
+            let v = eval(value);
+            if v.is_error() {
+                return cx.intern_value(make_error(mir.ty));
+            }
+            match (&v.kind, mir.ty.get_real()) {
+                // Reinterpret the IEEE 754 representation of a real as bits.
+                (&ValueKind::Real(bits), None) => {
+                    let bits = match v.ty.get_real() {
+                        Some(ty::RealType::ShortReal) => {
+                            (f64::from_bits(bits) as f32).to_bits() as u64
+                        }
+                        _ => bits,
+                    };
+                    cx.intern_value(make_int(mir.ty, bits.into()))
+                }
+                // Reinterpret bits as the IEEE 754 representation of a real.
+                (ValueKind::Int(int, ..), Some(real)) => {
+                    let mask = (BigInt::one() << 64) - 1;
+                    let bits = (int & mask).to_u64().unwrap();
+                    let value = match real {
+                        ty::RealType::ShortReal => f32::from_bits(bits as u32) as f64,
+                        _ => f64::from_bits(bits),
+                    };
+                    cx.intern_value(make_real(mir.ty, value))
+                }
+                _ => cx.intern_value(ValueData {
+                    ty: mir.ty,
+                    kind: v.kind.clone(),
+                }),
+            }
         }
 
         mir::RvalueKind::CastToBool(value) => {
-            let value = cx.const_mir_rvalue(value.into());
+            let value = eval(value);
             if value.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
             cx.intern_value(make_int(mir.ty, (value.is_true() as usize).into()))
         }
 
+        mir::RvalueKind::IntToReal { sign, value } => {
+            let size = value.ty.simple_bit_vector(cx, value.span).size;
+            let mut int = match eval_int(value) {
+                Ok(v) => v.clone(),
+                Err(()) => return cx.intern_value(make_error(mir.ty)),
+            };
+            // Interpret the bits according to the sign of the operand.
+            let modulus = BigInt::one() << size;
+            if sign.is_signed() && int >= (&modulus >> 1) {
+                int -= &modulus;
+            } else if sign.is_unsigned() && int < BigInt::zero() {
+                int += &modulus;
+            }
+            cx.intern_value(make_real(mir.ty, int.to_f64().unwrap_or(std::f64::NAN)))
+        }
+
+        mir::RvalueKind::RealToInt { round, value } => {
+            let value = match eval(value).get_real() {
+                Some(v) => v,
+                None => return cx.intern_value(make_error(mir.ty)),
+            };
+            if !value.is_finite() {
+                cx.emit(
+                    DiagBuilder2::warning(format!(
+                        "`{}` is {} and cannot be converted to an integer; using 0",
+                        mir.span.extract(),
+                        value
+                    ))
+                    .span(mir.span),
+                );
+            }
+            let value = if round { value.round() } else { value.trunc() };
+            cx.intern_value(make_int(
+                mir.ty,
+                BigInt::from_f64(value).unwrap_or_else(BigInt::zero),
+            ))
+        }
+
+        mir::RvalueKind::ResizeReal(value) => match eval(value).get_real() {
+            Some(v) => cx.intern_value(make_real(mir.ty, v)),
+            None => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::RealNeg(value) => match eval(value).get_real() {
+            Some(v) => cx.intern_value(make_real(mir.ty, -v)),
+            None => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::RealArith { op, lhs, rhs } => {
+            let lhs_val = eval(lhs).get_real();
+            let rhs_val = eval(rhs).get_real();
+            match (lhs_val, rhs_val) {
+                (Some(lhs), Some(rhs)) => {
+                    cx.intern_value(make_real(mir.ty, const_arith_real(op, lhs, rhs)))
+                }
+                _ => cx.intern_value(make_error(mir.ty)),
+            }
+        }
+
+        mir::RvalueKind::RealComp { op, lhs, rhs } => {
+            let lhs_val = eval(lhs).get_real();
+            let rhs_val = eval(rhs).get_real();
+            match (lhs_val, rhs_val) {
+                (Some(lhs), Some(rhs)) => cx.intern_value(make_int(
+                    mir.ty,
+                    (const_comp_real(op, lhs, rhs) as usize).into(),
+                )),
+                _ => cx.intern_value(make_error(mir.ty)),
+            }
+        }
+
         mir::RvalueKind::ApplyTimescale(value, ref scale) => {
-            let value = cx.const_mir_rvalue(value.into());
+            let value = eval(value);
             if value.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -417,22 +673,19 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         mir::RvalueKind::ConstructArray(ref values) => cx.intern_value(make_array(
             mir.ty,
             (0..values.len())
-                .map(|index| cx.const_mir_rvalue(values[&index].into()))
+                .map(|index| eval(values[&index]))
                 .collect(),
         )),
 
         mir::RvalueKind::ConstructStruct(ref values) => cx.intern_value(make_struct(
             mir.ty,
-            values
-                .iter()
-                .map(|&value| cx.const_mir_rvalue(value.into()))
-                .collect(),
+            values.iter().map(|&value| eval(value)).collect(),
         )),
 
         mir::RvalueKind::Const(value) => value,
 
         mir::RvalueKind::UnaryBitwise { op, arg } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -451,8 +704,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::BinaryBitwise { op, lhs, rhs } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -474,11 +727,15 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::IntUnaryArith { op, arg, .. } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
             match arg_val.kind {
+                // Any X or Z bit makes the entire result X.
+                ValueKind::Int(_, ref arg_special, _) if arg_special.any() => {
+                    cx.intern_value(make_int_or_x(mir.ty, None))
+                }
                 ValueKind::Int(ref arg_int, ..) => cx.intern_value(make_int(
                     mir.ty,
                     const_unary_arith_int(cx, mir.ty.simple_bit_vector(cx, mir.span), op, arg_int),
@@ -487,15 +744,46 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             }
         }
 
+        mir::RvalueKind::IntBinaryArith {
+            op: mir::IntBinaryArithOp::Pow,
+            lhs,
+            rhs,
+            sign,
+            ..
+        } => {
+            let (lhs_int, rhs_int) = match (eval_int(lhs), eval_int(rhs)) {
+                (Ok(lhs), Ok(rhs)) => (lhs, rhs),
+                _ => return cx.intern_value(make_error(mir.ty)),
+            };
+            if has_unknown_bits(eval(lhs)) || has_unknown_bits(eval(rhs)) {
+                return cx.intern_value(make_int_or_x(mir.ty, None));
+            }
+            let sbv = mir.ty.simple_bit_vector(cx, mir.span);
+            let rhs_sbv = rhs.ty.simple_bit_vector(cx, rhs.span);
+            let lhs_int = normalize_sign(lhs_int, sbv.size, sign);
+            let rhs_int = normalize_sign(rhs_int, rhs_sbv.size, rhs_sbv.sign);
+            // `0 ** y` with negative `y` is X.
+            cx.intern_value(make_int_or_x(
+                mir.ty,
+                const_pow_int(&lhs_int, &rhs_int, sbv.size),
+            ))
+        }
+
         mir::RvalueKind::IntBinaryArith { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
             match (&lhs_val.kind, &rhs_val.kind) {
+                // Any X or Z bit makes the entire result X.
+                (ValueKind::Int(_, lhs_special, _), ValueKind::Int(_, rhs_special, _))
+                    if lhs_special.any() || rhs_special.any() =>
+                {
+                    cx.intern_value(make_int_or_x(mir.ty, None))
+                }
                 (ValueKind::Int(lhs_int, ..), ValueKind::Int(rhs_int, ..)) => {
-                    cx.intern_value(make_int(
+                    cx.intern_value(make_int_or_x(
                         mir.ty,
                         const_binary_arith_int(
                             cx,
@@ -511,24 +799,25 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::IntComp { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
             match (&lhs_val.kind, &rhs_val.kind) {
-                (ValueKind::Int(lhs_int, ..), ValueKind::Int(rhs_int, ..)) => {
-                    cx.intern_value(make_int(
-                        mir.ty,
-                        const_comp_int(
-                            cx,
-                            mir.ty.simple_bit_vector(cx, mir.span),
-                            op,
-                            lhs_int,
-                            rhs_int,
-                        ),
-                    ))
-                }
+                (
+                    ValueKind::Int(lhs_int, lhs_special, lhs_x),
+                    ValueKind::Int(rhs_int, rhs_special, rhs_x),
+                ) => cx.intern_value(make_int_or_x(
+                    mir.ty,
+                    const_comp_int(
+                        cx,
+                        lhs.ty.simple_bit_vector(cx, lhs.span),
+                        op,
+                        (lhs_int, lhs_special, lhs_x),
+                        (rhs_int, rhs_special, rhs_x),
+                    ),
+                )),
                 _ => unreachable!(),
             }
         }
@@ -537,16 +826,13 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             let mut result = BigInt::zero();
             for &value in values {
                 result <<= value.ty.simple_bit_vector(cx, value.span).size;
-                result |= cx
-                    .const_mir_rvalue(value.into())
-                    .get_int()
-                    .expect("concat non-integer");
+                result |= eval(value).get_int().expect("concat non-integer");
             }
             cx.intern_value(make_int(mir.ty, result))
         }
 
         mir::RvalueKind::Repeat(count, value) => {
-            let value_const = cx.const_mir_rvalue(value.into());
+            let value_const = eval(value);
             if value_const.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -559,7 +845,28 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             cx.intern_value(make_int(mir.ty, result))
         }
 
+        // Arguments and locals of a function called at elaboration time.
+        mir::RvalueKind::Var(id) | mir::RvalueKind::Arg(id)
+            if locals.map(|l| l.contains_key(&id)).unwrap_or(false) =>
+        {
+            locals.unwrap()[&id]
+        }
+
         mir::RvalueKind::Assignment { .. }
+        | mir::RvalueKind::Past { .. }
+        | mir::RvalueKind::EventTriggered(_)
+        | mir::RvalueKind::ArrayNew { .. }
+        | mir::RvalueKind::ArraySize(_)
+        | mir::RvalueKind::ArrayGet { .. }
+        | mir::RvalueKind::ArraySet { .. }
+        | mir::RvalueKind::ArrayDelete { .. }
+        | mir::RvalueKind::ArrayExists { .. }
+        | mir::RvalueKind::QueuePush { .. }
+        | mir::RvalueKind::QueuePop { .. }
+        | mir::RvalueKind::QueuePeek { .. }
+        | mir::RvalueKind::AssocTraverse { .. }
+        | mir::RvalueKind::AssocTraverseFound { .. }
+        | mir::RvalueKind::SysTask { .. }
         | mir::RvalueKind::Var(_)
         | mir::RvalueKind::Port(_)
         | mir::RvalueKind::Arg(_)
@@ -570,7 +877,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Member { value, field } => {
-            let value_const = cx.const_mir_rvalue(value.into());
+            let value_const = eval(value);
             if value_const.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -585,9 +892,9 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             true_value,
             false_value,
         } => {
-            let cond_val = cx.const_mir_rvalue(cond.into());
-            let true_val = cx.const_mir_rvalue(true_value.into());
-            let false_val = cx.const_mir_rvalue(false_value.into());
+            let cond_val = eval(cond);
+            let true_val = eval(true_value);
+            let false_val = eval(false_value);
             match cond_val.is_true() {
                 true => true_val,
                 false => false_val,
@@ -601,8 +908,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             amount,
             ..
         } => {
-            let value_val = cx.const_mir_rvalue(value.into());
-            let amount_val = cx.const_mir_rvalue(amount.into());
+            let value_val = eval(value);
+            let amount_val = eval(amount);
             if value_val.is_error() || amount_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -625,7 +932,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::Reduction { op, arg } => {
-            let arg_val = cx.const_mir_rvalue(arg.into());
+            let arg_val = eval(arg);
             if arg_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -644,11 +951,11 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             length,
             ..
         } => {
-            let inner_val = cx.const_mir_rvalue(value.into());
+            let inner_val = eval(value);
             if inner_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
-            let base = match cx.const_mir_rvalue_int(Ref(base)) {
+            let base = match eval_int(base) {
                 Ok(x) => x.to_isize().expect("base out of bounds"),
                 _ => return cx.intern_value(make_error(mir.ty)),
             };
@@ -707,7 +1014,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         // Pack a string into a vector.
-        mir::RvalueKind::PackString(value) => match cx.const_mir_rvalue_string(value.into()) {
+        mir::RvalueKind::PackString(value) => match eval_string(value) {
             Ok(v) => cx.intern_value(make_int(
                 mir.ty,
                 BigInt::from_bytes_be(num::bigint::Sign::Plus, v),
@@ -717,7 +1024,7 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
 
         // Unpack a string from a vector.
         mir::RvalueKind::UnpackString(value) => {
-            let mut konst = match cx.const_mir_rvalue_int(value.into()) {
+            let mut konst = match eval_int(value) {
                 Ok(v) => v.clone(),
                 Err(()) => return cx.intern_value(make_error(mir.ty)),
             };
@@ -734,8 +1041,8 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
         }
 
         mir::RvalueKind::StringComp { op, lhs, rhs, .. } => {
-            let lhs_val = cx.const_mir_rvalue(lhs.into());
-            let rhs_val = cx.const_mir_rvalue(rhs.into());
+            let lhs_val = eval(lhs);
+            let rhs_val = eval(rhs);
             if lhs_val.is_error() || rhs_val.is_error() {
                 return cx.intern_value(make_error(mir.ty));
             }
@@ -747,14 +1054,437 @@ fn const_mir_rvalue_inner<'a>(cx: &impl Context<'a>, mir: &'a mir::Rvalue<'a>) -
             }
         }
 
-        // TODO(fschuiki): Fix this once we support constant function calls.
-        mir::RvalueKind::Call { .. } => cx.intern_value(make_error(mir.ty)),
+        mir::RvalueKind::StringConcat(ref values) => {
+            let mut bytes = vec![];
+            for &value in values {
+                match eval_string(value) {
+                    Ok(v) => bytes.extend(v),
+                    Err(()) => return cx.intern_value(make_error(mir.ty)),
+                }
+            }
+            cx.intern_value(make_string(mir.ty, bytes))
+        }
+
+        mir::RvalueKind::StringLen(value) => match eval_string(value) {
+            Ok(v) => cx.intern_value(make_int(mir.ty, v.len().into())),
+            Err(()) => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::StringGetc { value, index } => {
+            let (value, index) = match (eval_string(value), eval_int(index)) {
+                (Ok(value), Ok(index)) => (value, index),
+                _ => return cx.intern_value(make_error(mir.ty)),
+            };
+            let c = index
+                .to_usize()
+                .and_then(|index| value.get(index))
+                .cloned()
+                .unwrap_or(0);
+            cx.intern_value(make_int(mir.ty, c.into()))
+        }
+
+        mir::RvalueKind::StringPutc {
+            value,
+            index,
+            character,
+        } => {
+            let (value, index, character) =
+                match (eval_string(value), eval_int(index), eval_int(character)) {
+                    (Ok(value), Ok(index), Ok(character)) => (value, index, character),
+                    _ => return cx.intern_value(make_error(mir.ty)),
+                };
+            let mut value = value.clone();
+            let character = (character & BigInt::from(0xFF)).to_u8().unwrap();
+            if let Some(c) = index.to_usize().and_then(|index| value.get_mut(index)) {
+                if character != 0 {
+                    *c = character;
+                }
+            }
+            cx.intern_value(make_string(mir.ty, value))
+        }
+
+        mir::RvalueKind::StringSubstr { value, start, stop } => {
+            let (value, start, stop) = match (eval_string(value), eval_int(start), eval_int(stop)) {
+                (Ok(value), Ok(start), Ok(stop)) => (value, start, stop),
+                _ => return cx.intern_value(make_error(mir.ty)),
+            };
+            let bytes = match (start.to_usize(), stop.to_usize()) {
+                (Some(start), Some(stop)) if start <= stop && stop < value.len() => {
+                    value[start..=stop].to_vec()
+                }
+                _ => vec![],
+            };
+            cx.intern_value(make_string(mir.ty, bytes))
+        }
+
+        mir::RvalueKind::StringToInt { radix, value } => match eval_string(value) {
+            Ok(v) => cx.intern_value(make_int(mir.ty, const_string_to_int(v, radix))),
+            Err(()) => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::StringFromInt { radix, value, .. } => match eval_int(value) {
+            Ok(v) => cx.intern_value(make_string(mir.ty, v.to_str_radix(radix).into_bytes())),
+            Err(()) => cx.intern_value(make_error(mir.ty)),
+        },
+
+        mir::RvalueKind::Call { target, ref args } => const_call(cx, mir, target, args, locals),
 
         // Propagate tombstones.
         mir::RvalueKind::Error => cx.intern_value(make_error(mir.ty)),
     }
 }
 
+/// The maximum nesting depth of function calls evaluated at elaboration time.
+const MAX_CONST_CALL_DEPTH: usize = 128;
+
+/// The maximum number of iterations of a loop evaluated at elaboration time.
+const MAX_CONST_LOOP_ITERATIONS: usize = 1_000_000;
+
+/// The values of the arguments and local variables of a function called at
+/// elaboration time.
+type ConstLocals<'a> = HashMap<NodeId, Value<'a>>;
+
+/// Evaluate a function call at elaboration time.
+///
+/// The function body is interpreted statement by statement. The body is
+/// lowered in a parameter environment derived from the caller's, which tracks
+/// the functions being called. The arguments and local variables are kept in
+/// the frame of the call, such that automatic functions may recurse. The
+/// arguments are evaluated with the `locals` of the caller.
+fn const_call<'a>(
+    cx: &impl Context<'a>,
+    mir: &'a mir::Rvalue<'a>,
+    target: &'a ast::SubroutineDecl<'a>,
+    args: &[mir::CallArg<'a>],
+    locals: Option<&ConstLocals<'a>>,
+) -> Value<'a> {
+    let name = target.prototype.name;
+    let is_dpi = target
+        .get_parent()
+        .and_then(|x| x.as_all().get_dpi_decl())
+        .is_some();
+    if target.prototype.kind != ast::SubroutineKind::Func || is_dpi {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "`{}` cannot be called in a constant expression",
+                name
+            ))
+            .span(mir.span),
+        );
+        return cx.intern_value(make_error(mir.ty));
+    }
+
+    // Check the call stack for static recursion and runaway recursion.
+    let mut env_data = cx.param_env_data(mir.env).clone();
+    if env_data.calls().contains(&target.id()) && !target.is_automatic() {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "static function `{}` cannot be called recursively",
+                name
+            ))
+            .span(mir.span)
+            .add_note("Declare the function `automatic` to allow recursion."),
+        );
+        return cx.intern_value(make_error(mir.ty));
+    }
+    if env_data.calls().len() >= MAX_CONST_CALL_DEPTH {
+        cx.emit(
+            DiagBuilder2::error(format!(
+                "call to `{}` exceeds the maximum constant evaluation depth of {}",
+                name, MAX_CONST_CALL_DEPTH
+            ))
+            .span(mir.span)
+            .add_note("The recursion may not terminate."),
+        );
+        return cx.intern_value(make_error(mir.ty));
+    }
+    env_data.push_call(target.id());
+
+    // Bind the arguments.
+    let mut frame = ConstFrame {
+        cx,
+        env: cx.intern_param_env(env_data),
+        locals: HashMap::new(),
+        loops: 0,
+    };
+    let func_args = cx.canonicalize_func_args(Ref(target));
+    for (func_arg, arg) in func_args.args.iter().zip(args) {
+        let value = match *arg {
+            mir::CallArg::Input(value) => const_mir_rvalue_inner(cx, value, locals),
+            _ => {
+                cx.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` has output, inout, or ref arguments and cannot be called in a \
+                         constant expression",
+                        name
+                    ))
+                    .span(mir.span),
+                );
+                return cx.intern_value(make_error(mir.ty));
+            }
+        };
+        if value.is_error() {
+            return cx.intern_value(make_error(mir.ty));
+        }
+        frame.locals.insert(func_arg.ast.id(), value);
+    }
+
+    // Execute the body.
+    let mut result = None;
+    for item in &target.items {
+        if let ast::SubroutineItem::Stmt(stmt) = item {
+            match frame.exec(stmt.id()) {
+                Ok(ConstFlow::Return(value)) => {
+                    result = value;
+                    break;
+                }
+                Ok(_) => (),
+                Err(()) => return cx.intern_value(make_error(mir.ty)),
+            }
+        }
+    }
+
+    // Functions that do not return a value yield the default of their type.
+    match result {
+        Some(value) => cx.intern_value(ValueData {
+            ty: mir.ty,
+            kind: value.kind.clone(),
+        }),
+        None => cx.type_default_value(mir.ty),
+    }
+}
+
+/// A function call being evaluated at elaboration time.
+struct ConstFrame<'a, 'c, C> {
+    cx: &'c C,
+    /// The environment within which the function body is lowered.
+    env: ParamEnv,
+    /// The current values of the arguments and local variables.
+    locals: ConstLocals<'a>,
+    /// The number of loops around the statement being executed.
+    loops: usize,
+}
+
+/// How execution proceeds after a statement in a constant function call.
+enum ConstFlow<'a> {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Value<'a>>),
+}
+
+impl<'a, 'c, C: Context<'a>> ConstFrame<'a, 'c, C> {
+    /// Execute a statement.
+    fn exec(&mut self, stmt_id: NodeId) -> Result<ConstFlow<'a>> {
+        let hir = match self.cx.hir_of(stmt_id)? {
+            HirNode::Stmt(x) => x,
+            HirNode::VarDecl(decl) => {
+                let value = match decl.init {
+                    Some(init) => self.eval(init)?,
+                    None => self
+                        .cx
+                        .type_default_value(self.cx.type_of(stmt_id, self.env)?),
+                };
+                self.bind(stmt_id, value);
+                return Ok(ConstFlow::Next);
+            }
+            _ => unreachable!(),
+        };
+        match hir.kind {
+            hir::StmtKind::Null => (),
+            hir::StmtKind::Block(ref stmts) | hir::StmtKind::InlineGroup { ref stmts, .. } => {
+                for &stmt in stmts {
+                    match self.exec(stmt)? {
+                        ConstFlow::Next => (),
+                        flow => return Ok(flow),
+                    }
+                }
+            }
+            hir::StmtKind::Assign {
+                lhs,
+                rhs,
+                kind: kind @ hir::AssignKind::Block(_),
+            } => {
+                let assign = self
+                    .cx
+                    .mir_assignment_from_procedural(stmt_id, lhs, rhs, self.env, hir.span, kind);
+                self.assign(assign.lhs, assign.rhs)?;
+            }
+            hir::StmtKind::Expr(expr) => self.exec_expr(expr)?,
+            hir::StmtKind::If {
+                cond,
+                main_stmt,
+                else_stmt,
+            } => {
+                if self.eval(cond)?.is_true() {
+                    return self.exec(main_stmt);
+                } else if let Some(else_stmt) = else_stmt {
+                    return self.exec(else_stmt);
+                }
+            }
+            hir::StmtKind::Loop { kind, body } => {
+                self.loops += 1;
+                let result = self.exec_loop(kind, body, hir.span);
+                self.loops -= 1;
+                return result;
+            }
+            hir::StmtKind::Case {
+                expr,
+                ref ways,
+                default,
+                kind: ast::CaseKind::Normal,
+            } => {
+                let value = self.eval(expr)?;
+                for &(ref way_exprs, stmt) in ways {
+                    for &way_expr in way_exprs {
+                        if self.eval(way_expr)?.kind == value.kind {
+                            return self.exec(stmt);
+                        }
+                    }
+                }
+                if let Some(default) = default {
+                    return self.exec(default);
+                }
+            }
+            hir::StmtKind::Ast(stmt) => match stmt.kind {
+                ast::ReturnStmt(None) => return Ok(ConstFlow::Return(None)),
+                ast::ReturnStmt(Some(ref expr)) => {
+                    return Ok(ConstFlow::Return(Some(self.eval(expr.id())?)))
+                }
+                ast::BreakStmt if self.loops > 0 => return Ok(ConstFlow::Break),
+                ast::ContinueStmt if self.loops > 0 => return Ok(ConstFlow::Continue),
+                _ => return self.unsupported(hir.span),
+            },
+            _ => return self.unsupported(hir.span),
+        }
+        Ok(ConstFlow::Next)
+    }
+
+    /// Execute a loop.
+    ///
+    /// Emits a diagnostic if the loop does not terminate within
+    /// `MAX_CONST_LOOP_ITERATIONS` iterations.
+    fn exec_loop(
+        &mut self,
+        kind: hir::LoopKind,
+        body: NodeId,
+        span: Span,
+    ) -> Result<ConstFlow<'a>> {
+        let mut count = match kind {
+            hir::LoopKind::Repeat(count) => self.eval(count)?.get_int().cloned(),
+            hir::LoopKind::For(init, _, _) => {
+                self.exec(init)?;
+                None
+            }
+            _ => None,
+        };
+        for _ in 0..MAX_CONST_LOOP_ITERATIONS {
+            let enter = match kind {
+                hir::LoopKind::Repeat(_) => match count.as_mut() {
+                    Some(count) if *count > BigInt::zero() => {
+                        *count -= BigInt::one();
+                        true
+                    }
+                    _ => false,
+                },
+                hir::LoopKind::While(cond) | hir::LoopKind::For(_, cond, _) => {
+                    self.eval(cond)?.is_true()
+                }
+                hir::LoopKind::Forever | hir::LoopKind::Do(_) => true,
+            };
+            if !enter {
+                return Ok(ConstFlow::Next);
+            }
+            match self.exec(body)? {
+                ConstFlow::Next | ConstFlow::Continue => (),
+                ConstFlow::Break => return Ok(ConstFlow::Next),
+                flow @ ConstFlow::Return(_) => return Ok(flow),
+            }
+            match kind {
+                hir::LoopKind::Do(cond) if !self.eval(cond)?.is_true() => {
+                    return Ok(ConstFlow::Next)
+                }
+                hir::LoopKind::For(_, _, step) => self.exec_expr(step)?,
+                _ => (),
+            }
+        }
+        self.cx.emit(
+            DiagBuilder2::error(format!(
+                "loop exceeds the maximum of {} iterations in constant function call",
+                MAX_CONST_LOOP_ITERATIONS
+            ))
+            .span(span)
+            .add_note("The loop may not terminate."),
+        );
+        Err(())
+    }
+
+    /// Execute an expression for its side effects.
+    fn exec_expr(&mut self, expr_id: NodeId) -> Result<()> {
+        let mir = self.cx.mir_rvalue(expr_id, self.env);
+        match mir.kind {
+            mir::RvalueKind::Assignment { lvalue, rvalue, .. } => self.assign(lvalue, rvalue),
+            _ => self.eval_mir(mir).map(|_| ()),
+        }
+    }
+
+    /// Assign a value to an argument or local variable.
+    fn assign(&mut self, lhs: &'a mir::Lvalue<'a>, rhs: &'a mir::Rvalue<'a>) -> Result<()> {
+        let value = self.eval_mir(rhs)?;
+        let mut lhs = lhs;
+        while let mir::LvalueKind::Transmute(inner) = lhs.kind {
+            lhs = inner;
+        }
+        match lhs.kind {
+            mir::LvalueKind::Var(id) | mir::LvalueKind::Arg(id) => {
+                let value = self.cx.intern_value(ValueData {
+                    ty: lhs.ty,
+                    kind: value.kind.clone(),
+                });
+                self.bind(id, value);
+                Ok(())
+            }
+            _ => {
+                self.cx.emit(
+                    DiagBuilder2::error(format!(
+                        "unsupported: assignment to `{}` in constant function call",
+                        lhs.span.extract()
+                    ))
+                    .span(lhs.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Bind a new value to an argument or local variable.
+    fn bind(&mut self, id: NodeId, value: Value<'a>) {
+        self.locals.insert(id, value);
+    }
+
+    /// Evaluate an expression.
+    fn eval(&self, expr_id: NodeId) -> Result<Value<'a>> {
+        self.eval_mir(self.cx.mir_rvalue(expr_id, self.env))
+    }
+
+    /// Evaluate an MIR rvalue.
+    fn eval_mir(&self, mir: &'a mir::Rvalue<'a>) -> Result<Value<'a>> {
+        let value = const_mir_rvalue_inner(self.cx, mir, Some(&self.locals));
+        match value.is_error() {
+            true => Err(()),
+            false => Ok(value),
+        }
+    }
+
+    /// Report a statement that cannot be executed at elaboration time.
+    fn unsupported(&self, span: Span) -> Result<ConstFlow<'a>> {
+        self.cx.emit(
+            DiagBuilder2::error("unsupported: statement in constant function call").span(span),
+        );
+        Err(())
+    }
+}
+
 fn const_unary_bitwise_int<'gcx>(
     _cx: &impl Context<'gcx>,
     ty: SbvType,
@@ -791,46 +1521,127 @@ fn const_unary_arith_int<'gcx>(
     }
 }
 
+/// Compute a binary arithmetic operation on two integers.
+///
+/// Returns `None` if the result is X, which is the case for a division or
+/// modulus by zero.
 fn const_binary_arith_int<'gcx>(
     _cx: &impl Context<'gcx>,
-    _ty: SbvType,
+    ty: SbvType,
     op: mir::IntBinaryArithOp,
     lhs: &BigInt,
     rhs: &BigInt,
-) -> BigInt {
+) -> Option<BigInt> {
     match op {
-        mir::IntBinaryArithOp::Add => lhs + rhs,
-        mir::IntBinaryArithOp::Sub => lhs - rhs,
-        mir::IntBinaryArithOp::Mul => lhs * rhs,
-        mir::IntBinaryArithOp::Div => lhs / rhs,
-        mir::IntBinaryArithOp::Mod => lhs % rhs,
-        mir::IntBinaryArithOp::Pow => {
-            let mut result = num::one();
-            let mut cnt = rhs.clone();
-            while !cnt.is_zero() {
-                result = result * lhs;
-                cnt = cnt - 1;
-            }
-            result
-        }
+        mir::IntBinaryArithOp::Add => Some(lhs + rhs),
+        mir::IntBinaryArithOp::Sub => Some(lhs - rhs),
+        mir::IntBinaryArithOp::Mul => Some(lhs * rhs),
+        mir::IntBinaryArithOp::Div | mir::IntBinaryArithOp::Mod if rhs.is_zero() => None,
+        mir::IntBinaryArithOp::Div => Some(lhs / rhs),
+        mir::IntBinaryArithOp::Mod => Some(lhs % rhs),
+        mir::IntBinaryArithOp::Pow => const_pow_int(lhs, rhs, ty.size),
+    }
+}
+
+/// Compute `lhs ** rhs` truncated to `size` bits.
+///
+/// Follows §11.4.3 "Arithmetic operators": a negative exponent yields 0,
+/// unless the base is 1 or -1. Returns `None` if the result is X, which is the
+/// case for a zero base and a negative exponent.
+fn const_pow_int(lhs: &BigInt, rhs: &BigInt, size: usize) -> Option<BigInt> {
+    if *rhs < BigInt::zero() {
+        return if lhs.is_zero() {
+            None
+        } else if lhs.is_one() {
+            Some(BigInt::one())
+        } else if *lhs == -BigInt::one() {
+            Some(if rhs.is_odd() {
+                lhs.clone()
+            } else {
+                BigInt::one()
+            })
+        } else {
+            Some(BigInt::zero())
+        };
     }
+    Some(lhs.modpow(rhs, &(BigInt::one() << size)))
 }
 
+/// Interpret the lower `size` bits of an integer as a signed or unsigned
+/// number.
+fn normalize_sign(value: &BigInt, size: usize, sign: ty::Sign) -> BigInt {
+    let modulus = BigInt::one() << size;
+    let value = value.mod_floor(&modulus);
+    if sign.is_signed() && value >= (&modulus >> 1) {
+        value - modulus
+    } else {
+        value
+    }
+}
+
+/// Compare two integers given as their value, special bits, and X bits.
+///
+/// Returns `None` if the result is X. Case equality compares the X and Z bits
+/// exactly. Logical equality is only known if a pair of known bits differs or
+/// all bits are known, and wildcard equality ignores the bits where the right
+/// operand is X or Z. All other comparisons are X if any bit is X or Z. See
+/// §11.4.5 "Equality operators" and §11.4.6 "Wildcard equality operators".
 fn const_comp_int<'gcx>(
     _cx: &impl Context<'gcx>,
-    _ty: SbvType,
+    ty: SbvType,
     op: mir::IntCompOp,
-    lhs: &BigInt,
-    rhs: &BigInt,
-) -> BigInt {
-    match op {
-        mir::IntCompOp::Eq => ((lhs == rhs) as usize).into(),
-        mir::IntCompOp::Neq => ((lhs != rhs) as usize).into(),
-        mir::IntCompOp::Lt => ((lhs < rhs) as usize).into(),
-        mir::IntCompOp::Leq => ((lhs <= rhs) as usize).into(),
-        mir::IntCompOp::Gt => ((lhs > rhs) as usize).into(),
-        mir::IntCompOp::Geq => ((lhs >= rhs) as usize).into(),
-    }
+    (lhs, lhs_special, lhs_x): (&BigInt, &BitVec, &BitVec),
+    (rhs, rhs_special, rhs_x): (&BigInt, &BitVec, &BitVec),
+) -> Option<BigInt> {
+    let mask = (BigInt::one() << ty.size) - 1;
+    let lhs_unknown = bits_to_int(lhs_special);
+    let rhs_unknown = bits_to_int(rhs_special);
+    let diff = normalize_sign(lhs, ty.size, ty::Sign::Unsigned)
+        ^ normalize_sign(rhs, ty.size, ty::Sign::Unsigned);
+
+    // Determine the bits which are compared, and whether the operands are
+    // equal in those bits.
+    let (care, unknown) = match op {
+        mir::IntCompOp::CaseEq | mir::IntCompOp::CaseNeq => {
+            let equal = (&diff & (&mask ^ &lhs_unknown)).is_zero()
+                && lhs_unknown == rhs_unknown
+                && bits_to_int(lhs_x) == bits_to_int(rhs_x);
+            let equal = equal == (op == mir::IntCompOp::CaseEq);
+            return Some((equal as usize).into());
+        }
+        mir::IntCompOp::WildcardEq | mir::IntCompOp::WildcardNeq => {
+            let care = &mask ^ &rhs_unknown;
+            let unknown = &lhs_unknown & &care;
+            (care, unknown)
+        }
+        _ => (mask.clone(), &lhs_unknown | &rhs_unknown),
+    };
+    let equal = match op {
+        mir::IntCompOp::Eq
+        | mir::IntCompOp::Neq
+        | mir::IntCompOp::WildcardEq
+        | mir::IntCompOp::WildcardNeq => {
+            if !(&diff & &care & (&mask ^ &unknown)).is_zero() {
+                false
+            } else if !unknown.is_zero() {
+                return None;
+            } else {
+                true
+            }
+        }
+        _ if !unknown.is_zero() => return None,
+        _ => false,
+    };
+    let result = match op {
+        mir::IntCompOp::Eq | mir::IntCompOp::WildcardEq => equal,
+        mir::IntCompOp::Neq | mir::IntCompOp::WildcardNeq => !equal,
+        mir::IntCompOp::Lt => lhs < rhs,
+        mir::IntCompOp::Leq => lhs <= rhs,
+        mir::IntCompOp::Gt => lhs > rhs,
+        mir::IntCompOp::Geq => lhs >= rhs,
+        mir::IntCompOp::CaseEq | mir::IntCompOp::CaseNeq => unreachable!(),
+    };
+    Some((result as usize).into())
 }
 
 fn const_shift_int<'gcx>(
@@ -885,6 +1696,56 @@ fn const_comp_string<'gcx>(
     match op {
         mir::StringCompOp::Eq => ((lhs == rhs) as usize).into(),
         mir::StringCompOp::Neq => ((lhs != rhs) as usize).into(),
+        mir::StringCompOp::Lt => ((lhs < rhs) as usize).into(),
+        mir::StringCompOp::Leq => ((lhs <= rhs) as usize).into(),
+        mir::StringCompOp::Gt => ((lhs > rhs) as usize).into(),
+        mir::StringCompOp::Geq => ((lhs >= rhs) as usize).into(),
+    }
+}
+
+/// Parse the leading digits of a string as an integer, as done by the
+/// `atoi` family of string methods. Underscores are ignored, and parsing stops
+/// at the first character that is not a valid digit.
+fn const_string_to_int(value: &[u8], radix: u32) -> BigInt {
+    let (negative, digits) = match value.split_first() {
+        Some((b'-', rest)) if radix == 10 => (true, rest),
+        _ => (false, value),
+    };
+    let mut result = BigInt::zero();
+    for &c in digits {
+        if c == b'_' {
+            continue;
+        }
+        match (c as char).to_digit(radix) {
+            Some(digit) => result = result * radix + digit,
+            None => break,
+        }
+    }
+    if negative {
+        -result
+    } else {
+        result
+    }
+}
+
+fn const_arith_real(op: mir::RealArithOp, lhs: f64, rhs: f64) -> f64 {
+    match op {
+        mir::RealArithOp::Add => lhs + rhs,
+        mir::RealArithOp::Sub => lhs - rhs,
+        mir::RealArithOp::Mul => lhs * rhs,
+        mir::RealArithOp::Div => lhs / rhs,
+        mir::RealArithOp::Pow => lhs.powf(rhs),
+    }
+}
+
+fn const_comp_real(op: mir::RealCompOp, lhs: f64, rhs: f64) -> bool {
+    match op {
+        mir::RealCompOp::Eq => lhs == rhs,
+        mir::RealCompOp::Neq => lhs != rhs,
+        mir::RealCompOp::Lt => lhs < rhs,
+        mir::RealCompOp::Leq => lhs <= rhs,
+        mir::RealCompOp::Gt => lhs > rhs,
+        mir::RealCompOp::Geq => lhs >= rhs,
     }
 }
 
@@ -921,23 +1782,51 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
         return cx.intern_value(make_struct(ty, fields));
     }
 
+    // Handle unions. Packed unions are plain bit vectors. Tagged unions store
+    // their tag in front of the members, and start out tagged with the first
+    // member.
+    if let Some(union) = ty.get_union() {
+        if ty.get_packed().is_some() {
+            return default_int(cx, ty);
+        }
+        let tag = make_int(union.tag_type(cx), Zero::zero());
+        let fields = std::iter::once(cx.intern_value(tag))
+            .chain(
+                union
+                    .members
+                    .iter()
+                    .map(|field| type_default_value(cx, field.ty)),
+            )
+            .collect();
+        return cx.intern_value(make_struct(ty, fields));
+    }
+
     // Handle packed base cases.
     if let Some(packed) = ty.get_packed() {
         let packed = packed;
         match packed.core {
             ty::PackedCore::IntVec(_) if packed.dims.len() <= 1 => {
-                return cx.intern_value(make_int(ty, Zero::zero()));
+                return default_int(cx, ty);
             }
             ty::PackedCore::IntAtom(ty::IntAtomType::Time) if packed.dims.is_empty() => {
                 return cx.intern_value(make_time(Zero::zero()));
             }
             ty::PackedCore::IntAtom(_) if packed.dims.is_empty() => {
-                return cx.intern_value(make_int(ty, Zero::zero()));
+                return default_int(cx, ty);
             }
             _ => (),
         }
     }
 
+    // Handle dynamic arrays, queues, and associative arrays, which start out
+    // empty.
+    if ty.is_dynamic_array() {
+        return cx.intern_value(ValueData {
+            ty,
+            kind: ValueKind::EmptyArray,
+        });
+    }
+
     // Handle arrays.
     if let Some(dim) = ty.outermost_dim() {
         let length = dim
@@ -958,6 +1847,24 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
     );
 
     // Handle unpacked types.
+    if ty.is_real() {
+        return cx.intern_value(make_real(ty, 0.0));
+    }
+    if ty.is_string() {
+        return cx.intern_value(make_string(ty, vec![]));
+    }
+    if ty.is_event() {
+        return cx.intern_value(ValueData {
+            ty,
+            kind: ValueKind::Event,
+        });
+    }
+    if ty.is_chandle() {
+        return cx.intern_value(ValueData {
+            ty,
+            kind: ValueKind::Null,
+        });
+    }
     let packed = match ty.core {
         ty::UnpackedCore::Packed(p) => p,
         _ => panic!("cannot build const value of unpacked type `{}`", ty),
@@ -983,3 +1890,38 @@ pub(crate) fn type_default_value<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType
         _ => panic!("cannot build const value of packed type `{}`", packed),
     }
 }
+
+/// Determine the default value of an integer type.
+///
+/// Four-valued integers start out as X if their X and Z bits are preserved in
+/// the generated code, and as zero otherwise.
+fn default_int<'a>(cx: &impl Context<'a>, ty: &'a UnpackedType<'a>) -> Value<'a> {
+    if cx.is_four_state(ty) {
+        cx.intern_value(make_int_x(ty))
+    } else {
+        cx.intern_value(make_int(ty, Zero::zero()))
+    }
+}
+
+/// Determine the value of a net without any drivers.
+///
+/// Four-valued nets float at Z if their X and Z bits are preserved in the
+/// generated code. Pull and supply nets assume the value they are tied to.
+/// Charge storage nets start out as X.
+pub(crate) fn net_default_value<'a>(
+    cx: &impl Context<'a>,
+    ty: &'a UnpackedType<'a>,
+    net_ty: ast::NetType,
+) -> Value<'a> {
+    if !ty.coalesces_to_llhd_scalar() {
+        return cx.type_default_value(ty);
+    }
+    let ones = || (BigInt::one() << ty.get_bit_size().unwrap()) - 1;
+    match net_ty {
+        ast::NetType::Tri0 | ast::NetType::Supply0 => cx.intern_value(make_int(ty, Zero::zero())),
+        ast::NetType::Tri1 | ast::NetType::Supply1 => cx.intern_value(make_int(ty, ones())),
+        ast::NetType::TriReg if cx.is_four_state(ty) => cx.intern_value(make_int_x(ty)),
+        _ if cx.is_four_state(ty) => cx.intern_value(make_int_z(ty)),
+        _ => cx.type_default_value(ty),
+    }
+}
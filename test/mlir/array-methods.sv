@@ -0,0 +1,192 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @Sum(
+// CHECK-SAME:  ) -> i32 {
+function int Sum(int a[4]);
+    // CHECK: comb.add
+    // CHECK: comb.add
+    // CHECK: comb.add
+    return a.sum();
+endfunction
+
+// CHECK-LABEL: func @Xor(
+// CHECK-SAME:  ) -> i32 {
+function int Xor(int a[4]);
+    // CHECK: comb.xor
+    // CHECK: comb.xor
+    // CHECK: comb.xor
+    return a.xor();
+endfunction
+
+// CHECK-LABEL: func @CountPositive(
+function int CountPositive(int a[4]);
+    // CHECK: comb.icmp sgt
+    return a.sum() with (int'(item > 0));
+endfunction
+
+typedef int queue_t [$];
+
+// CHECK-LABEL: func @Min(
+// CHECK-SAME:  ) -> i64 {
+function queue_t Min(int a[3]);
+    // CHECK: comb.icmp slt
+    // CHECK: comb.icmp slt
+    // CHECK-NOT: comb.icmp
+    // CHECK: call @__moore_queue_push(
+    return a.min();
+endfunction
+
+// CHECK-LABEL: func @Max(
+function queue_t Max(int a[3]);
+    // CHECK: comb.icmp sgt
+    // CHECK: comb.icmp sgt
+    // CHECK-NOT: comb.icmp
+    // CHECK: call @__moore_queue_push(
+    return a.max();
+endfunction
+
+// CHECK-LABEL: func @Unique(
+function queue_t Unique(int a[3]);
+    // The first element is always unique, the others are compared against
+    // all preceding elements.
+    // CHECK: comb.icmp ne
+    // CHECK: comb.icmp ne
+    // CHECK: comb.and
+    // CHECK: comb.icmp ne
+    // CHECK: call @__moore_queue_push(
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux
+    return a.unique();
+endfunction
+
+// CHECK-LABEL: func @UniqueIndex(
+function queue_t UniqueIndex(int a[3]);
+    // CHECK: comb.icmp ne
+    // CHECK: comb.icmp ne
+    // CHECK: comb.and
+    // CHECK: comb.icmp ne
+    // CHECK: hw.constant 0 : i32
+    // CHECK: call @__moore_queue_push(
+    // CHECK: hw.constant 1 : i32
+    // CHECK: call @__moore_queue_push(
+    // CHECK: hw.constant 2 : i32
+    // CHECK: call @__moore_queue_push(
+    return a.unique_index();
+endfunction
+
+// CHECK-LABEL: func @Find(
+function queue_t Find(int a[3]);
+    // CHECK: comb.icmp sgt
+    // CHECK: comb.icmp sgt
+    // CHECK: comb.icmp sgt
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux
+    return a.find(x) with (x > 2);
+endfunction
+
+// CHECK-LABEL: func @FindNonBool(
+function queue_t FindNonBool(int a[3]);
+    // Conditions that are not a single bit are compared against zero.
+    // CHECK: [[ODD:%.+]] = comb.and {{%.+}}, {{%.+}} : i32
+    // CHECK: comb.icmp ne [[ODD]], {{%.+}} : i32
+    return a.find with (item & 1);
+endfunction
+
+// CHECK-LABEL: func @FindIndex(
+function queue_t FindIndex(int a[3]);
+    // CHECK: comb.icmp eq
+    // CHECK: comb.icmp eq
+    // CHECK: comb.icmp eq
+    // CHECK: hw.constant 0 : i32
+    // CHECK: call @__moore_queue_push(
+    return a.find_index with (item == 0);
+endfunction
+
+// CHECK-LABEL: func @FindFirst(
+function queue_t FindFirst(int a[3]);
+    // The first matching element takes precedence over all later ones.
+    // CHECK: [[C0:%.+]] = comb.icmp eq
+    // CHECK: call @__moore_queue_push(
+    // CHECK: [[C1:%.+]] = comb.icmp eq
+    // CHECK: call @__moore_queue_push(
+    // CHECK: [[C2:%.+]] = comb.icmp eq
+    // CHECK: call @__moore_queue_push(
+    // CHECK: comb.mux [[C2]]
+    // CHECK: comb.mux [[C1]]
+    // CHECK: comb.mux [[C0]]
+    return a.find_first with (item == 0);
+endfunction
+
+// CHECK-LABEL: func @FindLastIndex(
+function queue_t FindLastIndex(int a[3]);
+    // The last matching element takes precedence over all earlier ones.
+    // CHECK: [[C2:%.+]] = comb.icmp eq
+    // CHECK: [[C1:%.+]] = comb.icmp eq
+    // CHECK: [[C0:%.+]] = comb.icmp eq
+    // CHECK: comb.mux [[C0]]
+    // CHECK: comb.mux [[C1]]
+    // CHECK: comb.mux [[C2]]
+    return a.find_last_index with (item == 0);
+endfunction
+
+// CHECK-LABEL: func @Reverse(
+function int Reverse(int a[3]);
+    int b[3] = a;
+    // CHECK: hw.constant 2 : i32
+    // CHECK: hw.array_get
+    // CHECK: hw.constant 1 : i32
+    // CHECK: hw.array_get
+    // CHECK: hw.constant 0 : i32
+    // CHECK: hw.array_get
+    // CHECK: hw.array_create
+    b.reverse();
+    return b[0];
+endfunction
+
+// CHECK-LABEL: func @Sort(
+function int Sort(int a[3]);
+    int b[3] = a;
+    // An odd-even transposition network with one comparison per stage.
+    // CHECK: comb.icmp sgt
+    // CHECK: comb.icmp sgt
+    // CHECK: comb.icmp sgt
+    // CHECK-NOT: comb.icmp
+    // CHECK: hw.array_create
+    b.sort();
+    return b[0];
+endfunction
+
+// CHECK-LABEL: func @Rsort(
+function int Rsort(int a[3]);
+    int b[3] = a;
+    // CHECK: comb.icmp slt
+    // CHECK: comb.icmp slt
+    // CHECK: comb.icmp slt
+    // CHECK-NOT: comb.icmp
+    // CHECK: hw.array_create
+    b.rsort();
+    return b[0];
+endfunction
+
+// CHECK-LABEL: llhd.entity @foo
+module foo;
+    localparam int A [4] = '{3, 1, 4, 1};
+    // CHECK: [[SUM:%.+]] = hw.constant 9 : i32
+    // CHECK: llhd.sig "sum" [[SUM]] : i32
+    int sum = A.sum();
+    // CHECK: [[PROD:%.+]] = hw.constant 12 : i32
+    // CHECK: llhd.sig "prod" [[PROD]] : i32
+    int prod = A.product();
+    // CHECK: [[X:%.+]] = hw.constant 7 : i32
+    // CHECK: llhd.sig "x" [[X]] : i32
+    int x = A.xor();
+    // CHECK: [[BIG:%.+]] = hw.constant 2 : i32
+    // CHECK: llhd.sig "big" [[BIG]] : i32
+    int big = A.sum(v) with (int'(v > 2));
+endmodule
@@ -0,0 +1,72 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK: moore.strings = ["x", "{{.*}}assertions.sv", "Labeled"]
+
+// CHECK-LABEL: func @ImmediateAssert(
+function void ImmediateAssert(bit x);
+    // CHECK: cond_br %{{.+}}, ^[[PASS:.+]], ^[[FAIL:.+]]
+    // CHECK: ^[[PASS]]:
+    // CHECK-NEXT: br ^[[EXIT:.+]]
+    // CHECK: ^[[FAIL]]:
+    // CHECK: call @__moore_assert_failed(
+    // CHECK-NEXT: br ^[[EXIT]]
+    assert (x);
+endfunction
+// CHECK: func private @__moore_assert_failed(i32, i32, i32, i32, i32)
+
+// CHECK-LABEL: func @LabeledAssume(
+function void LabeledAssume(bit x);
+    // CHECK: call @__moore_assert_failed(
+    Labeled: assume (x);
+endfunction
+
+// CHECK-LABEL: func @ActionBlocks(
+function void ActionBlocks(bit x, output int y);
+    // CHECK: cond_br %{{.+}}, ^[[PASS:.+]], ^[[FAIL:.+]]
+    // CHECK: ^[[PASS]]:
+    // CHECK: hw.constant 1 :
+    // CHECK: ^[[FAIL]]:
+    // CHECK-NOT: call @__moore_assert_failed
+    // CHECK: hw.constant 2 :
+    assert (x) y = 1; else y = 2;
+endfunction
+
+// CHECK-LABEL: func @Deferred(
+function void Deferred(bit x);
+    // Deferred assertions pass their result on to the runtime, which reports
+    // it once it matures.
+    // CHECK-NOT: cond_br
+    // CHECK: call @__moore_assert_deferred(
+    assert #0 (x);
+    // CHECK: call @__moore_assert_deferred(
+    cover final (x);
+endfunction
+// CHECK: func private @__moore_assert_deferred(i32, i32, i1, i1, i32, i32, i32, i32)
+
+// CHECK-LABEL: func @DeferredCover(
+function void DeferredCover(bit x, output int y);
+    // The action block runs with the latest result once the report matures,
+    // which in a function is when it returns.
+    // CHECK: [[PENDING:%.+]] = llhd.var
+    // CHECK: [[PASSED:%.+]] = llhd.var
+    // CHECK: call @__moore_assert_deferred(
+    // CHECK: llhd.store [[PASSED]],
+    // CHECK: llhd.store [[PENDING]],
+    // CHECK: [[P:%.+]] = llhd.load [[PENDING]]
+    // CHECK: cond_br [[P]], ^[[MATURE:.+]], ^[[DONE:.+]]
+    // CHECK: ^[[MATURE]]:
+    // CHECK: llhd.store [[PENDING]],
+    // CHECK: [[R:%.+]] = llhd.load [[PASSED]]
+    // CHECK: cond_br [[R]], ^[[PASS:.+]], ^[[FAIL:.+]]
+    // CHECK: ^[[PASS]]:
+    // CHECK: hw.constant 3 :
+    // CHECK: br ^[[DONE]]
+    // CHECK: ^[[FAIL]]:
+    // CHECK-NEXT: br ^[[DONE]]
+    // CHECK: ^[[DONE]]:
+    // CHECK-NEXT: return
+    cover final (x) y = 3;
+endfunction
+
+module foo;
+endmodule
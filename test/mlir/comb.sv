@@ -0,0 +1,88 @@
+// RUN: moore -e Mux -e Decoder -e Defaults -e AlwaysStar -e Sequential -e Feedback -e Latch --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @Mux(
+module Mux (input bit sel, input bit [7:0] a, input bit [7:0] b, output bit [7:0] z);
+    // CHECK: [[Z:%.+]] = comb.mux %{{.+}}, %{{.+}}, %{{.+}} : i8
+    // CHECK: llhd.drv %{{.+}}, [[Z]] after
+    always_comb begin
+        if (sel)
+            z = a;
+        else
+            z = b;
+    end
+    // CHECK-NOT: llhd.inst
+endmodule
+
+// CHECK-LABEL: llhd.entity @Decoder(
+module Decoder (input bit [1:0] op, input bit [7:0] a, input bit [7:0] b, output bit [7:0] z);
+    // Earlier case items take precedence over later ones.
+    // CHECK: [[INNER:%.+]] = comb.mux
+    // CHECK: [[Z:%.+]] = comb.mux %{{.+}}, %{{.+}}, [[INNER]] : i8
+    // CHECK: llhd.drv %{{.+}}, [[Z]] after
+    always_comb begin
+        case (op)
+            2'd0: z = a + b;
+            2'd1: z = a - b;
+            default: z = 0;
+        endcase
+    end
+    // CHECK-NOT: llhd.inst
+endmodule
+
+// CHECK-LABEL: llhd.entity @Defaults(
+module Defaults (input bit en, input bit [7:0] a, output bit [7:0] z, output bit v);
+    // Default assignments at the top are overridden by later ones.
+    // CHECK: comb.mux
+    // CHECK: comb.mux
+    // CHECK: llhd.drv
+    // CHECK: llhd.drv
+    always_comb begin
+        z = 0;
+        v = 0;
+        if (en) begin
+            z = a;
+            v = 1;
+        end
+    end
+    // CHECK-NOT: llhd.inst
+endmodule
+
+// CHECK-LABEL: llhd.entity @AlwaysStar(
+module AlwaysStar (input bit a, input bit b, output bit z);
+    // CHECK: [[Z:%.+]] = comb.xor
+    // CHECK: llhd.drv %{{.+}}, [[Z]] after
+    always @* z = a ^ b;
+    // CHECK-NOT: llhd.inst
+endmodule
+
+// Reads of a variable after its assignment observe the assigned value.
+// CHECK-LABEL: llhd.entity @Sequential(
+// CHECK: [[T:%.+]] = comb.add %{{.+}}, %{{.+}} : i8
+// CHECK: [[Z:%.+]] = comb.add [[T]], %{{.+}} : i8
+// CHECK: llhd.drv %{{.+}}, [[Z]] after
+// CHECK-NOT: llhd.inst
+module Sequential (input bit [7:0] a, output bit [7:0] z);
+    bit [7:0] t;
+    always_comb begin
+        t = a + 1;
+        z = t + 1;
+    end
+endmodule
+
+// Procedures that read a variable before assigning it on some path, or do not
+// assign a variable on every path, remain processes.
+// CHECK-LABEL: llhd.entity @Feedback(
+// CHECK: llhd.inst "{{[^"]*}}" @Feedback.always_comb
+module Feedback (input bit [7:0] a, output bit [7:0] z);
+    bit [7:0] t;
+    always_comb begin
+        z = t + 1;
+        t = a + 1;
+    end
+endmodule
+
+// CHECK-LABEL: llhd.entity @Latch(
+// CHECK: llhd.inst "{{[^"]*}}" @Latch.always_comb
+module Latch (input bit en, input bit [7:0] a, output bit [7:0] z);
+    always_comb if (en) z = a;
+endmodule
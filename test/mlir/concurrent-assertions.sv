@@ -0,0 +1,37 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @foo(
+module foo (input bit clk, input bit rst, input bit a, input bit b);
+    // CHECK: [[DELAY:%.+]] = ltl.delay %{{.+}}, 1, 0 : i1
+    // CHECK: [[IMPL:%.+]] = ltl.implication %{{.+}}, [[DELAY]] : i1, !ltl.sequence
+    // CHECK: [[CLK:%.+]] = ltl.clock [[IMPL]], posedge %{{.+}} : !ltl.property
+    // CHECK: verif.assert [[CLK]] label "Handshake" : !ltl.property
+    Handshake: assert property (@(posedge clk) a |-> ##1 b);
+
+    // CHECK: [[IMPL:%.+]] = ltl.implication
+    // CHECK: [[DIS:%.+]] = ltl.disable [[IMPL]] if %{{.+}} : !ltl.property
+    // CHECK: ltl.clock [[DIS]], negedge %{{.+}} : !ltl.property
+    // CHECK: verif.assume
+    assume property (@(negedge clk) disable iff (rst) a |=> b);
+
+    // A reset during the three cycles after the request aborts the check,
+    // rather than only being sampled in the cycle of the request.
+    // CHECK: [[DELAY:%.+]] = ltl.delay %{{.+}}, 3, 0 : i1
+    // CHECK: [[IMPL:%.+]] = ltl.implication %{{.+}}, [[DELAY]] : i1, !ltl.sequence
+    // CHECK: [[DIS:%.+]] = ltl.disable [[IMPL]] if %{{.+}} : !ltl.property
+    // CHECK-NOT: ltl.or
+    // CHECK: [[CLK:%.+]] = ltl.clock [[DIS]], posedge %{{.+}} : !ltl.property
+    // CHECK: verif.assert [[CLK]] label "Abort" : !ltl.property
+    Abort: assert property (@(posedge clk) disable iff (rst) a |-> ##3 b);
+
+    // CHECK: [[REP:%.+]] = ltl.repeat %{{.+}}, 2, 1 : i1
+    // CHECK: [[CLK:%.+]] = ltl.clock [[REP]], posedge %{{.+}} : !ltl.sequence
+    // CHECK: verif.cover [[CLK]] : !ltl.sequence
+    cover property (@(posedge clk) a [*2:3]);
+
+    // CHECK: seq.to_clock
+    // CHECK: [[PAST:%.+]] = seq.compreg
+    // CHECK: comb.xor [[PAST]],
+    // CHECK: verif.assert
+    assert property (@(posedge clk) $rose(a) |-> b);
+endmodule
@@ -0,0 +1,15 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.proc @foo.initial.
+module foo;
+    int d;
+    bit x;
+    initial begin
+        // CHECK: llhd.prb
+        // CHECK: [[FS:%.+]] = hw.constant 1000000 : i64
+        // CHECK: [[MUL:%.+]] = comb.mul {{%.+}}, [[FS]] : i64
+        // CHECK: [[T:%.+]] = llhd.int_to_time [[MUL]]
+        // CHECK: llhd.wait for [[T]],
+        #d x = 1;
+    end
+endmodule
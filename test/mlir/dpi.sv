@@ -0,0 +1,43 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+import "DPI-C" function int c_model(int a, bit [7:0] b);
+import "DPI-C" function bit c_flag(bit a, logic b, logic [39:0] c);
+import "DPI-C" c_open = function chandle open_file(string name);
+import "DPI-C" function string c_name(chandle h);
+
+// CHECK-LABEL: func @Model(
+// CHECK-SAME:    [[A:%.+]]: i32
+// CHECK-SAME:    [[B:%.+]]: i8
+function int Model(int a, bit [7:0] b);
+    // CHECK: [[BVAR:%.+]] = llhd.var {{%.+}} : i32
+    // CHECK: call @c_model([[A]], [[BVAR]]) : (i32, !llhd.ptr<i32>) -> i32
+    return c_model(a, b);
+endfunction
+// CHECK: func private @c_model(i32, !llhd.ptr<i32>) -> i32
+
+// CHECK-LABEL: func @Flag(
+function bit Flag(bit a, logic b, logic [39:0] c);
+    // CHECK: [[C:%.+]] = llhd.var {{%.+}} : i128
+    // CHECK: [[R:%.+]] = call @c_flag({{%.+}}, {{%.+}}, [[C]]) : (i8, i8, !llhd.ptr<i128>) -> i8
+    // CHECK: comb.extract [[R]] from 0 : (i8) -> i1
+    return c_flag(a, b, c);
+endfunction
+// CHECK: func private @c_flag(i8, i8, !llhd.ptr<i128>) -> i8
+
+// CHECK-LABEL: func @Name(
+function string Name(string path);
+    chandle h;
+    // CHECK: [[P:%.+]] = call @__moore_string_to_c
+    // CHECK: call @c_open([[P]]) : (i64) -> i64
+    h = open_file(path);
+    // CHECK: [[R:%.+]] = call @c_name
+    // CHECK: call @__moore_string_from_c([[R]])
+    return c_name(h);
+endfunction
+// CHECK-DAG: func private @c_open(i64) -> i64
+// CHECK-DAG: func private @c_name(i64) -> i64
+// CHECK-DAG: func private @__moore_string_to_c(i64) -> i64
+// CHECK-DAG: func private @__moore_string_from_c(i64) -> i64
+
+module foo;
+endmodule
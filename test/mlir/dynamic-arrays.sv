@@ -0,0 +1,68 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @DynamicArray(
+// CHECK-SAME:    [[N:%.+]]: i32
+// CHECK-SAME:  ) -> i32 {
+function int DynamicArray(int n);
+    int a[];
+    // CHECK: [[EMPTY:%.+]] = hw.constant 0 : i64
+    // CHECK: call @__moore_array_new([[N]], [[EMPTY]]) : (i32, i64) -> i64
+    a = new[n];
+    // CHECK: [[KIND:%.+]] = hw.constant 0 : i32
+    // CHECK: call @__moore_array_set([[KIND]], {{%.+}}, {{%.+}}, {{%.+}}) : (i32, i64, i64, i64) -> i64
+    a[0] = 42;
+    // CHECK: call @__moore_array_new({{%.+}}, {{%.+}}) : (i32, i64) -> i64
+    a = new[2 * n](a);
+    // CHECK: call @__moore_array_get({{%.+}}, {{%.+}}, {{%.+}}) : (i32, i64, i64) -> i64
+    // CHECK: call @__moore_array_size({{%.+}}) : (i64) -> i32
+    return a[1] + a.size();
+endfunction
+// CHECK: func private @__moore_array_new(i32, i64) -> i64
+
+// CHECK-LABEL: func @Queue(
+function byte Queue(byte x);
+    byte q[$];
+    // CHECK: call @__moore_queue_push({{%.+}}, {{%.+}}, {{%.+}}) : (i32, i64, i64) -> i64
+    q.push_back(x);
+    // CHECK: call @__moore_queue_push({{%.+}}, {{%.+}}, {{%.+}}) : (i32, i64, i64) -> i64
+    q.push_front(x + 1);
+    // CHECK: call @__moore_queue_pop({{%.+}}, {{%.+}}) : (i32, i64) -> i64
+    // CHECK: call @__moore_queue_peek({{%.+}}, {{%.+}}) : (i32, i64) -> i64
+    x = q.pop_front();
+    // CHECK: hw.constant 0 : i64
+    q.delete();
+    return x;
+endfunction
+
+// CHECK-LABEL: func @Assoc(
+function int Assoc(string key);
+    int a[string];
+    string k;
+    int n;
+    // CHECK: [[KIND:%.+]] = hw.constant 3 : i32
+    // CHECK: call @__moore_array_set([[KIND]],
+    a[key] = 1;
+    // CHECK: call @__moore_array_exists({{%.+}}, {{%.+}}, {{%.+}}) : (i32, i64, i64) -> i1
+    if (a.exists("foo"))
+        // CHECK: call @__moore_array_delete(
+        a.delete("foo");
+    // CHECK: call @__moore_assoc_traverse({{%.+}}, {{%.+}}, {{%.+}}, {{%.+}}) : (i32, i32, i64, i64) -> i64
+    // CHECK: call @__moore_array_size(
+    if (a.first(k)) begin
+        do begin
+            n += a[k];
+            // CHECK: call @__moore_assoc_traverse(
+            // CHECK: comb.icmp ne
+        end while (a.next(k));
+    end
+    return n;
+endfunction
+
+// CHECK-LABEL: llhd.entity @foo
+module foo;
+    // CHECK: [[ZERO:%.+]] = hw.constant 0 : i64
+    // CHECK: llhd.sig "q" [[ZERO]] : i64
+    int q[$];
+    // CHECK: llhd.sig "a" {{%.+}} : i64
+    bit [7:0] a[int];
+endmodule
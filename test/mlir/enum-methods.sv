@@ -0,0 +1,68 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+typedef enum bit [1:0] {IDLE, RUN, DONE} state_t;
+
+// CHECK-LABEL: func @Next(
+// CHECK-SAME:    [[S:%.+]]: i2
+// CHECK-SAME:  ) -> i2 {
+function state_t Next(state_t s);
+    // CHECK: comb.icmp eq [[S]], {{%.+}} : i2
+    // CHECK: comb.mux
+    return s.next();
+endfunction
+
+// CHECK-LABEL: func @Prev(
+function state_t Prev(state_t s);
+    // CHECK: comb.icmp eq
+    // CHECK: comb.mux
+    return s.prev(2);
+endfunction
+
+// CHECK-LABEL: func @NextDynamic(
+// CHECK-SAME:    [[S:%.+]]: i2
+// CHECK-SAME:    [[N:%.+]]: i32
+function state_t NextDynamic(state_t s, int unsigned n);
+    // The result is read from a table of the member values, followed by the
+    // default value for non-members.
+    // CHECK: [[TABLE:%.+]] = hw.array_create {{.+}} : i2
+    // CHECK: comb.icmp eq [[S]], {{%.+}} : i2
+    // CHECK: [[STEP:%.+]] = comb.modu [[N]], {{%.+}} : i32
+    // CHECK: [[ADV:%.+]] = comb.add {{%.+}}, [[STEP]] : i32
+    // CHECK: comb.modu [[ADV]], {{%.+}} : i32
+    // CHECK: hw.array_get [[TABLE]]
+    return s.next(n);
+endfunction
+
+// CHECK-LABEL: func @PrevDynamic(
+// CHECK-SAME:    [[N:%.+]]: i32
+function state_t PrevDynamic(state_t s, int unsigned n);
+    // CHECK: [[TABLE:%.+]] = hw.array_create {{.+}} : i2
+    // CHECK: [[STEP:%.+]] = comb.modu [[N]], {{%.+}} : i32
+    // CHECK: comb.sub {{%.+}}, [[STEP]] : i32
+    // CHECK: hw.array_get [[TABLE]]
+    return s.prev(n);
+endfunction
+
+// CHECK-LABEL: func @Name(
+// CHECK-SAME:  ) -> i64 {
+function string Name(state_t s);
+    // CHECK: comb.icmp eq
+    // CHECK: comb.mux {{%.+}}, {{%.+}}, {{%.+}} : i64
+    return s.name();
+endfunction
+
+// CHECK-LABEL: llhd.entity @foo
+module foo #(parameter state_t S = DONE);
+    // CHECK: [[FIRST:%.+]] = hw.constant 0 : i2
+    // CHECK: llhd.sig "first" [[FIRST]] : i2
+    state_t first = S.first();
+    // CHECK: [[NUM:%.+]] = hw.constant 3 : i32
+    // CHECK: llhd.sig "num" [[NUM]] : i32
+    int num = S.num;
+    // CHECK: [[PREV:%.+]] = hw.constant 1 : i2
+    // CHECK: llhd.sig "prev" [[PREV]] : i2
+    state_t prev = S.prev();
+    // CHECK: [[NEXT:%.+]] = hw.constant 0 : i2
+    // CHECK: llhd.sig "next" [[NEXT]] : i2
+    state_t next = S.next();
+endmodule
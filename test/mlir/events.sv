@@ -0,0 +1,36 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @foo
+module foo;
+    // CHECK: [[ZERO:%.+]] = hw.constant 0 : i64
+    // CHECK: [[EV:%.+]] = llhd.sig "ev" [[ZERO]] : i64
+    event ev;
+    bit x;
+
+    // CHECK-LABEL: llhd.proc @foo.initial.
+    initial begin
+        // CHECK: [[T:%.+]] = llhd.current_time
+        // CHECK: llhd.time_to_int [[T]]
+        // CHECK: llhd.drv [[EV:%.+]], {{%.+}} after
+        -> ev;
+        // CHECK: [[T:%.+]] = llhd.current_time
+        // CHECK: llhd.time_to_int [[T]]
+        // CHECK: llhd.drv [[EV]], {{%.+}} after
+        ->> ev;
+    end
+
+    // CHECK-LABEL: llhd.proc @foo.initial.
+    initial begin
+        // CHECK: llhd.wait
+        // CHECK: comb.icmp ne
+        @(ev) x = 1;
+        // The event only counts as triggered if it carries the stamp of the
+        // current time step.
+        // CHECK: [[T:%.+]] = llhd.current_time
+        // CHECK: [[NOW:%.+]] = llhd.time_to_int [[T]]
+        // CHECK: [[NEXT:%.+]] = comb.add [[NOW]], {{%.+}} : i64
+        // CHECK: [[STAMP:%.+]] = comb.mul [[NEXT]], {{%.+}} : i64
+        // CHECK: comb.icmp eq {{%.+}}, [[STAMP]] : i64
+        x = ev.triggered;
+    end
+endmodule
@@ -0,0 +1,205 @@
+// RUN: moore -e foo --four-state --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @Identity(
+// CHECK-SAME:    [[X:%.+]]: !hw.struct<value: i8, unknown: i8>
+// CHECK-SAME:  ) -> !hw.struct<value: i8, unknown: i8> {
+function logic [7:0] Identity(logic [7:0] x);
+    return x;
+endfunction
+
+// CHECK-LABEL: func @TwoState(
+// CHECK-SAME:    {{%.+}}: i8
+// CHECK-SAME:  ) -> i8 {
+function bit [7:0] TwoState(bit [7:0] x);
+    return x;
+endfunction
+
+// CHECK-LABEL: func @CaseEq(
+// CHECK-SAME:    [[A:%.+]]: !hw.struct<value: i8, unknown: i8>
+// CHECK-SAME:    [[B:%.+]]: !hw.struct<value: i8, unknown: i8>
+function bit CaseEq(logic [7:0] a, logic [7:0] b);
+    // CHECK-DAG: hw.struct_extract [[A]]["value"]
+    // CHECK-DAG: hw.struct_extract [[A]]["unknown"]
+    // CHECK-DAG: hw.struct_extract [[B]]["value"]
+    // CHECK-DAG: hw.struct_extract [[B]]["unknown"]
+    // CHECK: comb.icmp eq
+    // CHECK: comb.icmp eq
+    // CHECK: comb.and
+    return a === b;
+endfunction
+
+// CHECK-LABEL: func @Unknown(
+function logic [3:0] Unknown();
+    // CHECK: [[V:%.+]] = hw.constant 0 : i4
+    // CHECK: [[U:%.+]] = hw.constant -1 : i4
+    // CHECK: hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i4, unknown: i4>
+    return 4'bx;
+endfunction
+
+// CHECK-LABEL: func @Casez(
+// CHECK-SAME:    [[X:%.+]]: !hw.struct<value: i4, unknown: i4>
+function int Casez(logic [3:0] x);
+    // CHECK-DAG: hw.struct_extract [[X]]["value"]
+    // CHECK-DAG: hw.struct_extract [[X]]["unknown"]
+    // CHECK: comb.icmp eq
+    // CHECK: comb.icmp eq
+    // CHECK: comb.and
+    // CHECK: cond_br
+    casez (x)
+        4'b1??0: return 1;
+        default: return 0;
+    endcase
+endfunction
+
+// CHECK-LABEL: func @PowNegative(
+function logic signed [7:0] PowNegative(logic signed [7:0] a, logic signed [7:0] b);
+    // Zero raised to a negative power is X.
+    // CHECK: [[NEG:%.+]] = comb.extract {{%.+}} from 7 : (i8) -> i1
+    // CHECK: [[ZERO:%.+]] = comb.icmp eq {{%.+}}, {{%.+}} : i8
+    // CHECK: [[UNDEF:%.+]] = comb.and [[NEG]], [[ZERO]] : i1
+    // CHECK: [[U:%.+]] = comb.or {{%.+}}, [[UNDEF]] : i1
+    // CHECK: comb.mux [[U]], {{%.+}}, {{%.+}} : i8
+    return a ** b;
+endfunction
+
+// CHECK-LABEL: func @LogicEq(
+// CHECK-SAME:    [[A:%.+]]: !hw.struct<value: i8, unknown: i8>
+// CHECK-SAME:    [[B:%.+]]: !hw.struct<value: i8, unknown: i8>
+// CHECK-SAME:  ) -> !hw.struct<value: i1, unknown: i1> {
+function logic LogicEq(logic [7:0] a, logic [7:0] b);
+    // Equal known bits and any X or Z bit produce X.
+    // CHECK-DAG: [[AV:%.+]] = hw.struct_extract [[A]]["value"]
+    // CHECK-DAG: [[AU:%.+]] = hw.struct_extract [[A]]["unknown"]
+    // CHECK-DAG: [[BV:%.+]] = hw.struct_extract [[B]]["value"]
+    // CHECK-DAG: [[BU:%.+]] = hw.struct_extract [[B]]["unknown"]
+    // CHECK: [[ANY_U:%.+]] = comb.or [[AU]], [[BU]] : i8
+    // CHECK: comb.icmp ne [[ANY_U]], {{%.+}} : i8
+    // CHECK: [[DIFF:%.+]] = comb.xor [[AV]], [[BV]] : i8
+    // CHECK: comb.and [[DIFF]], {{%.+}} : i8
+    // CHECK: comb.icmp ne
+    // CHECK: hw.struct_create ({{%.+}}, {{%.+}}) : !hw.struct<value: i1, unknown: i1>
+    return a == b;
+endfunction
+
+// CHECK-LABEL: func @WildcardEq(
+// CHECK-SAME:    [[A:%.+]]: !hw.struct<value: i4, unknown: i4>
+function logic WildcardEq(logic [3:0] a);
+    // The X bits of the right operand are ignored.
+    // CHECK-DAG: [[AV:%.+]] = hw.struct_extract [[A]]["value"]
+    // CHECK-DAG: [[AU:%.+]] = hw.struct_extract [[A]]["unknown"]
+    // CHECK-DAG: [[BV:%.+]] = hw.constant 10 : i4
+    // CHECK-DAG: [[BU:%.+]] = hw.constant 5 : i4
+    // CHECK: [[DIFF:%.+]] = comb.xor [[AV]], {{%.+}} : i4
+    // CHECK: hw.struct_create ({{%.+}}, {{%.+}}) : !hw.struct<value: i1, unknown: i1>
+    return a ==? 4'b1x1x;
+endfunction
+
+// CHECK-LABEL: func @AddUnknown(
+// CHECK-SAME:    [[A:%.+]]: !hw.struct<value: i8, unknown: i8>
+// CHECK-SAME:    [[B:%.+]]: !hw.struct<value: i8, unknown: i8>
+function logic [7:0] AddUnknown(logic [7:0] a, logic [7:0] b);
+    // Any X or Z operand bit makes the entire sum X.
+    // CHECK: [[SUM:%.+]] = comb.add {{%.+}}, {{%.+}} : i8
+    // CHECK: [[ALL_U:%.+]] = comb.mux {{%.+}}, {{%.+}}, {{%.+}} : i8
+    // CHECK: comb.and [[SUM]], {{%.+}} : i8
+    // CHECK: hw.struct_create ({{%.+}}, [[ALL_U]]) : !hw.struct<value: i8, unknown: i8>
+    return a + b;
+endfunction
+
+// CHECK-LABEL: func @Merge(
+// CHECK-SAME:    [[C:%.+]]: !hw.struct<value: i1, unknown: i1>
+// CHECK-SAME:    [[A:%.+]]: !hw.struct<value: i4, unknown: i4>
+// CHECK-SAME:    [[B:%.+]]: !hw.struct<value: i4, unknown: i4>
+function logic [3:0] Merge(logic c, logic [3:0] a, logic [3:0] b);
+    // An X condition keeps the bits on which both values agree.
+    // CHECK-DAG: [[AV:%.+]] = hw.struct_extract [[A]]["value"]
+    // CHECK-DAG: [[BV:%.+]] = hw.struct_extract [[B]]["value"]
+    // CHECK: [[DIFFER:%.+]] = comb.xor [[AV]], [[BV]] : i4
+    // CHECK: [[V:%.+]] = comb.mux {{%.+}}, [[AV]], [[BV]] : i4
+    // CHECK: [[U:%.+]] = comb.mux {{%.+}}, {{%.+}}, {{%.+}} : i4
+    // CHECK: comb.mux {{%.+}}, {{%.+}}, [[V]] : i4
+    // CHECK: comb.mux {{%.+}}, {{%.+}}, [[U]] : i4
+    return c ? a : b;
+endfunction
+
+// CHECK-LABEL: llhd.entity @foo
+// CHECK-LABEL: func @DisplayUnknown(
+// CHECK-SAME:    [[X:%.+]]: !hw.struct<value: i8, unknown: i8>
+function void DisplayUnknown(logic [7:0] x);
+    // The unknown plane is passed along, such that X and Z bits are printed.
+    // CHECK-DAG: hw.struct_extract [[X]]["value"]
+    // CHECK-DAG: hw.struct_extract [[X]]["unknown"]
+    // CHECK: call @__moore_fmt_unknown(
+    // CHECK: call @__moore_fmt_int(
+    // CHECK: call @__moore_print(
+    $display("%b", x);
+endfunction
+
+module foo;
+    localparam logic [3:0] Zero = 0;
+    localparam integer ZeroInt = 0;
+
+    // Constant operations propagate X as well.
+    // CHECK: [[V:%.+]] = hw.constant false
+    // CHECK: [[U:%.+]] = hw.constant true
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i1, unknown: i1>
+    // CHECK: llhd.sig "eq_x" [[TMP]]
+    logic eq_x = 4'b10x0 == 4'b1000;
+    // CHECK: [[V:%.+]] = hw.constant false
+    // CHECK: [[U:%.+]] = hw.constant false
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i1, unknown: i1>
+    // CHECK: llhd.sig "eq_known" [[TMP]]
+    logic eq_known = 4'b10x0 == 4'b0000;
+    // CHECK: [[V:%.+]] = hw.constant true
+    // CHECK: [[U:%.+]] = hw.constant false
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i1, unknown: i1>
+    // CHECK: llhd.sig "wild" [[TMP]]
+    logic wild = 4'b1010 ==? 4'b1x1x;
+    // CHECK: [[V:%.+]] = hw.constant 0 : i4
+    // CHECK: [[U:%.+]] = hw.constant -1 : i4
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i4, unknown: i4>
+    // CHECK: llhd.sig "sum_x" [[TMP]]
+    logic [3:0] sum_x = 4'b10x0 + 4'd1;
+    // CHECK: [[V:%.+]] = hw.constant 0 : i4
+    // CHECK: [[U:%.+]] = hw.constant -1 : i4
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i4, unknown: i4>
+    // CHECK: llhd.sig "div_x" [[TMP]]
+    logic [3:0] div_x = 4'd4 / Zero;
+    // CHECK: [[V:%.+]] = hw.constant 0 : i32
+    // CHECK: [[U:%.+]] = hw.constant -1 : i32
+    // CHECK: [[TMP:%.+]] = hw.struct_create ([[V]], [[U]]) : !hw.struct<value: i32, unknown: i32>
+    // CHECK: llhd.sig "pow_x" [[TMP]]
+    integer pow_x = ZeroInt ** -1;
+
+    logic clk;
+    bit seen;
+
+    // CHECK-LABEL: llhd.proc @foo.initial.
+    initial begin
+        // A transition from X to 1 counts as a rising edge.
+        // CHECK: llhd.wait
+        // CHECK: hw.struct_extract [[PREV:%.+]]["value"]
+        // CHECK: [[PREV_U:%.+]] = hw.struct_extract [[PREV]]["unknown"]
+        // CHECK: [[PREV_ANY_U:%.+]] = comb.icmp ne [[PREV_U]], {{%.+}} : i1
+        // CHECK: hw.struct_extract [[NOW:%.+]]["value"]
+        // CHECK: hw.struct_extract [[NOW]]["unknown"]
+        // CHECK: comb.and [[PREV_ANY_U]], {{%.+}} : i1
+        // CHECK: comb.or
+        // CHECK: cond_br
+        @(posedge clk) seen = 1;
+    end
+
+    logic [3:0] bus;
+
+    // CHECK-LABEL: llhd.proc @foo.initial.
+    initial begin
+        // Only the LSB of a vector counts for edges.
+        // CHECK: llhd.wait
+        // CHECK: hw.struct_extract [[PREV:%.+]]["value"]
+        // CHECK: comb.extract {{%.+}} from 0 : (i4) -> i1
+        // CHECK: hw.struct_extract [[PREV]]["unknown"]
+        // CHECK: comb.extract {{%.+}} from 0 : (i4) -> i1
+        // CHECK: cond_br
+        @(posedge bus) seen = 1;
+    end
+endmodule
@@ -0,0 +1,37 @@
+// RUN: moore -e Top --format=hw %s | FileCheck %s
+
+// CHECK-NOT: llhd.
+
+// CHECK-LABEL: hw.module @Top(
+module Top (input bit clk, input bit sel, input bit [7:0] a, output bit [7:0] z);
+    bit [7:0] count, sum;
+    // CHECK: [[COUNT:%.+]] = hw.instance "c" @Counter(clk: %clk: i1, en: %sel: i1) -> (count: i8)
+    Counter c (clk, sel, count);
+    // CHECK: [[SUM:%.+]] = hw.instance "u" @Adder(a: %a: i8, b: [[COUNT]]: i8) -> (z: i8)
+    Adder u (a, count, sum);
+    // CHECK: [[Z:%.+]] = comb.mux %sel, [[SUM]], %a : i8
+    // CHECK: hw.output [[Z]] : i8
+    always_comb begin
+        if (sel)
+            z = sum;
+        else
+            z = a;
+    end
+endmodule
+
+// CHECK-LABEL: hw.module @Counter(
+module Counter (input bit clk, input bit en, output bit [7:0] count);
+    // CHECK: [[CLK:%.+]] = seq.to_clock %clk
+    // CHECK: [[REG:%.+]] = seq.compreg.ce {{.+}}, [[CLK]], %en
+    // CHECK: hw.output [[REG]] : i8
+    always_ff @(posedge clk) if (en) count <= count + 1;
+endmodule
+
+// CHECK-LABEL: hw.module @Adder(
+module Adder (input bit [7:0] a, input bit [7:0] b, output bit [7:0] z);
+    // CHECK: [[Z:%.+]] = comb.add %a, %b : i8
+    // CHECK: hw.output [[Z]] : i8
+    assign z = a + b;
+endmodule
+
+// CHECK-NOT: llhd.
@@ -0,0 +1,61 @@
+// RUN: moore -e Bus -e WiredAnd -e Supply --four-state --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @Pad(
+// CHECK-SAME:    [[PAD:%[^:]+]]: !llhd.sig<!hw.struct<value: i1, unknown: i1>>,
+// CHECK-SAME:  ) -> (
+// CHECK-SAME:    [[DRV:%[^:]+]]: !llhd.sig<!hw.struct<value: i1, unknown: i1>>,
+module Pad (inout logic pad, input logic oe, input logic o, output logic i);
+    // A single driver of an inout port drives the port's contribution
+    // directly, while reading the port yields the resolved net.
+    // CHECK: llhd.drv [[DRV]]
+    assign pad = oe ? o : 1'bz;
+    // CHECK: llhd.prb [[PAD]]
+    assign i = pad;
+endmodule
+
+// CHECK-LABEL: llhd.entity @Bus
+module Bus;
+    logic oe, o, i;
+    logic en, data;
+
+    // CHECK: [[BUS:%.+]] = llhd.sig "bus"
+    wire bus;
+
+    // CHECK: [[A:%.+]] = llhd.sig "bus.drv"
+    // CHECK: llhd.drv [[A]]
+    assign bus = en ? data : 1'bz;
+
+    // CHECK: [[B:%.+]] = llhd.sig "bus.drv_0"
+    // CHECK: llhd.inst "pad" @Pad([[BUS]], {{.+}}) -> ([[B]], {{.+}})
+    Pad pad(bus, oe, o, i);
+
+    // The drivers are combined into the net.
+    // CHECK-DAG: llhd.prb [[A]]
+    // CHECK-DAG: llhd.prb [[B]]
+    // CHECK: comb.or
+    // CHECK: hw.struct_create
+    // CHECK: llhd.drv [[BUS]]
+endmodule
+
+// CHECK-LABEL: llhd.entity @WiredAnd
+module WiredAnd;
+    logic a, b;
+    // CHECK: [[NET:%.+]] = llhd.sig "sda"
+    // CHECK: [[A:%.+]] = llhd.sig "sda.drv"
+    // CHECK: [[B:%.+]] = llhd.sig "sda.drv_0"
+    wand sda;
+    assign sda = a;
+    assign sda = b;
+    // CHECK-DAG: llhd.prb [[A]]
+    // CHECK-DAG: llhd.prb [[B]]
+    // CHECK: llhd.drv [[NET]]
+endmodule
+
+// CHECK-LABEL: llhd.entity @Supply
+module Supply;
+    // CHECK: [[ONE:%.+]] = hw.constant true
+    // CHECK: [[ZERO:%.+]] = hw.constant false
+    // CHECK: [[INIT:%.+]] = hw.struct_create ([[ONE]], [[ZERO]])
+    // CHECK: llhd.sig "vdd" [[INIT]]
+    supply1 vdd;
+endmodule
@@ -0,0 +1,78 @@
+// RUN: moore -e OpenDrain -e Storage --four-state --format=mlir-native %s | FileCheck %s
+// RUN: moore -e PullUp --format=mlir-native %s | FileCheck %s --check-prefix=TWO
+
+// CHECK-LABEL: llhd.entity @OpenDrain
+module OpenDrain;
+    logic a, b;
+
+    // CHECK: [[NET:%.+]] = llhd.sig "line"
+    wire line;
+
+    // A driver which only pulls low floats when driving a 1, such that the
+    // pullup determines the value of the net.
+    // CHECK: [[A:%.+]] = llhd.sig "line.drv"
+    // CHECK: llhd.drv [[A]]
+    assign (strong0, highz1) line = a;
+    // CHECK: [[B:%.+]] = llhd.sig "line.drv_0"
+    // CHECK: llhd.drv [[B]]
+    assign (highz1, weak0) line = b;
+    // CHECK: [[P:%.+]] = llhd.sig "line.drv_1"
+    // CHECK: llhd.drv [[P]]
+    pullup (line);
+
+    // CHECK-DAG: llhd.prb [[A]]
+    // CHECK-DAG: llhd.prb [[B]]
+    // CHECK-DAG: llhd.prb [[P]]
+    // CHECK: llhd.drv [[NET]]
+endmodule
+
+// CHECK-LABEL: llhd.entity @Storage
+module Storage;
+    logic en, d;
+
+    // A charge storage net starts out as X and keeps its value while it is
+    // not driven.
+    // CHECK: [[NET:%.+]] = llhd.sig "cap"
+    trireg (small) cap;
+    // CHECK: [[A:%.+]] = llhd.sig "cap.drv"
+    assign cap = en ? d : 1'bz;
+
+    // The previous value is kept for the bits which are not driven.
+    // CHECK: llhd.prb [[A]]
+    // CHECK: [[OLD:%.+]] = llhd.prb [[NET]]
+    // CHECK: [[OLDV:%.+]] = hw.struct_extract [[OLD]]["value"]
+    // CHECK: [[OLDU:%.+]] = hw.struct_extract [[OLD]]["unknown"]
+    // CHECK: [[KEEPV:%.+]] = comb.and [[OLDV]], [[Z:%.+]] : i1
+    // CHECK: [[KEEPU:%.+]] = comb.and [[OLDU]], [[Z]] : i1
+    // CHECK: [[V:%.+]] = comb.or {{%.+}}, [[KEEPV]] : i1
+    // CHECK: [[U:%.+]] = comb.or {{%.+}}, [[KEEPU]] : i1
+    // CHECK: [[R:%.+]] = hw.struct_create ([[V]], [[U]])
+    // CHECK: llhd.drv [[NET]], [[R]] after
+endmodule
+
+// Strengths are resolved without `--four-state` as well.
+// TWO-LABEL: llhd.entity @PullUp
+module PullUp;
+    bit a;
+
+    // TWO: [[NET:%.+]] = llhd.sig "line"
+    wire line;
+    // TWO: [[A:%.+]] = llhd.sig "line.drv"
+    assign (strong0, highz1) line = a;
+    // TWO: [[P:%.+]] = llhd.sig "line.drv_0"
+    pullup (line);
+
+    // TWO: [[VA:%.+]] = llhd.prb [[A]]
+    // TWO: [[VP:%.+]] = llhd.prb [[P]]
+    // TWO: [[NOTA:%.+]] = comb.xor {{%.+}}, [[VA]] : i1
+    // TWO: [[A0:%.+]] = comb.and {{%.+}}, [[NOTA]] : i1
+    // TWO: [[P1:%.+]] = comb.and {{%.+}}, [[VP]] : i1
+
+    // The zeros driven by `a` at strong strength take precedence over the ones
+    // driven by the pullup at pull strength. Where `a` is 1 it floats, and the
+    // pullup determines the value.
+    // TWO: [[ANY0:%.+]] = comb.or {{%.+}}, [[A0]] : i1
+    // TWO: comb.and [[ANY0]], {{%.+}} : i1
+    // TWO: [[ANY1:%.+]] = comb.or {{%.+}}, [[P1]] : i1
+    // TWO: llhd.drv [[NET]], {{%.+}} after
+endmodule
@@ -0,0 +1,40 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @Unsigned(
+function bit [7:0] Unsigned(bit [7:0] a, bit [1:0] b);
+    // CHECK: comb.mul
+    // CHECK: comb.mux
+    // CHECK: comb.mul
+    // CHECK: comb.mul
+    // CHECK: comb.mux
+    // CHECK-NOT: comb.mux
+    // CHECK: return
+    return a ** b;
+endfunction
+
+// CHECK-LABEL: func @Signed(
+function byte Signed(byte a, bit signed [1:0] b);
+    // CHECK: comb.mux
+    // CHECK: comb.mux
+    // CHECK: comb.or
+    // CHECK: [[CLAMPED:%.+]] = comb.mux
+    // CHECK: comb.mux {{%.+}}, [[CLAMPED]], {{%.+}} : i8
+    return a ** b;
+endfunction
+
+// CHECK-LABEL: func @Zero(
+function int Zero(int a);
+    // CHECK: [[ONE:%.+]] = hw.constant 1 : i32
+    // CHECK: return [[ONE]]
+    return a ** 0;
+endfunction
+
+// CHECK-LABEL: func @Real(
+function real Real(real a, real b);
+    // CHECK: [[OP:%.+]] = hw.constant 4 : i32
+    // CHECK: call @__moore_real_arith([[OP]],
+    return a ** b;
+endfunction
+
+module foo;
+endmodule
@@ -0,0 +1,20 @@
+// RUN: moore -e foo --four-state --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @foo(
+module foo;
+    // X and Z digits are kept in the unknown plane. Z bits are set in both
+    // planes, and the address not loaded from the file stays X.
+    // CHECK: [[V0:%.+]] = hw.constant 16 : i8
+    // CHECK: [[U0:%.+]] = hw.constant 15 : i8
+    // CHECK: [[W0:%.+]] = hw.struct_create ([[V0]], [[U0]]) : !hw.struct<value: i8, unknown: i8>
+    // CHECK: [[V1:%.+]] = hw.constant -11 : i8
+    // CHECK: [[U1:%.+]] = hw.constant -16 : i8
+    // CHECK: [[W1:%.+]] = hw.struct_create ([[V1]], [[U1]]) : !hw.struct<value: i8, unknown: i8>
+    // CHECK: [[V2:%.+]] = hw.constant 0 : i8
+    // CHECK: [[U2:%.+]] = hw.constant -1 : i8
+    // CHECK: [[W2:%.+]] = hw.struct_create ([[V2]], [[U2]]) : !hw.struct<value: i8, unknown: i8>
+    // CHECK: [[INIT:%.+]] = hw.array_create [[W0]], [[W1]], [[W2]] : !hw.struct<value: i8, unknown: i8>
+    // CHECK: llhd.sig "mem" [[INIT]]
+    logic [7:0] mem [0:2];
+    initial $readmemh("Inputs/unknown.hex", mem);
+endmodule
@@ -0,0 +1,29 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: llhd.entity @foo(
+module foo;
+    // Addresses 0 to 2 are loaded in sequence, address 3 is skipped, and
+    // address 4 is loaded from the explicit `@4`.
+    // CHECK: [[A0:%.+]] = hw.constant 18 : i8
+    // CHECK: [[A1:%.+]] = hw.constant 52 : i8
+    // CHECK: [[A2:%.+]] = hw.constant 86 : i8
+    // CHECK: [[A3:%.+]] = hw.constant 0 : i8
+    // CHECK: [[A4:%.+]] = hw.constant 127 : i8
+    // CHECK: [[INIT:%.+]] = hw.array_create [[A0]], [[A1]], [[A2]], [[A3]], [[A4]] : i8
+    // CHECK: llhd.sig "rom" [[INIT]] : !hw.array<5xi8>
+    bit [7:0] rom [0:4];
+    initial $readmemh("Inputs/rom.hex", rom);
+
+    // Only addresses 1 and 2 are loaded, the others keep their default value.
+    // CHECK: [[L0:%.+]] = hw.constant 0 : i2
+    // CHECK: [[L1:%.+]] = hw.constant 1 : i2
+    // CHECK: [[L2:%.+]] = hw.constant -1 : i2
+    // CHECK: [[L3:%.+]] = hw.constant 0 : i2
+    // CHECK: [[LUT:%.+]] = hw.array_create [[L0]], [[L1]], [[L2]], [[L3]] : i2
+    // CHECK: llhd.sig "lut" [[LUT]] : !hw.array<4xi2>
+    bit [1:0] lut [4];
+    initial begin
+        // CHECK-NOT: readmem
+        $readmemb("Inputs/table.bin", lut, 1, 2);
+    end
+endmodule
@@ -0,0 +1,55 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @Arith(
+// CHECK-SAME:    [[A:%.+]]: i64, [[B:%.+]]: i64
+// CHECK-SAME:  ) -> i64 {
+function real Arith(real a, real b);
+    // CHECK: [[OP:%.+]] = hw.constant 0 : i32
+    // CHECK: call @__moore_real_arith([[OP]], [[A]], [[B]]) : (i32, i64, i64) -> i64
+    // CHECK: hw.constant 4 : i32
+    // CHECK: call @__moore_real_arith(
+    return (a + b) ** b;
+endfunction
+// CHECK: func private @__moore_real_arith(i32, i64, i64) -> i64
+
+// CHECK-LABEL: func @Compare(
+// CHECK-SAME:    [[A:%.+]]: i64, [[B:%.+]]: i64
+function bit Compare(real a, real b);
+    // CHECK: [[OP:%.+]] = hw.constant 2 : i32
+    // CHECK: call @__moore_real_comp([[OP]], [[A]], [[B]]) : (i32, i64, i64) -> i1
+    return a < b;
+endfunction
+// CHECK: func private @__moore_real_comp(i32, i64, i64) -> i1
+
+// CHECK-LABEL: func @Negate(
+// CHECK-SAME:    [[A:%.+]]: i64
+function real Negate(real a);
+    // CHECK: [[SIGN:%.+]] = hw.constant -9223372036854775808 : i64
+    // CHECK: comb.xor [[A]], [[SIGN]] : i64
+    return -a;
+endfunction
+
+// CHECK-LABEL: func @Convert(
+function int Convert(int x, real r);
+    real t;
+    // CHECK: call @__moore_real_from_int(
+    t = x;
+    // CHECK: call @__moore_real_to_int(
+    x = r;
+    // CHECK: call @__moore_real_to_int(
+    return $rtoi(r);
+endfunction
+// CHECK: func private @__moore_real_from_int(i32, i64) -> i64
+// CHECK: func private @__moore_real_to_int(i32, i64) -> i64
+
+// CHECK-LABEL: func @Short(
+// CHECK-SAME:    [[A:%.+]]: i32
+function shortreal Short(shortreal a);
+    // CHECK: call @__moore_real_from_shortreal([[A]]) : (i32) -> i64
+    // CHECK: call @__moore_real_arith(
+    // CHECK: call @__moore_real_to_shortreal(
+    return a * 2.5;
+endfunction
+
+module foo;
+endmodule
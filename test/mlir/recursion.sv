@@ -0,0 +1,41 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+module foo;
+    function automatic int fact(int n);
+        if (n <= 1)
+            return 1;
+        return n * fact(n - 1);
+    endfunction
+
+    function automatic int log2(int n);
+        int k = 0;
+        while (n > 1) begin
+            n = n / 2;
+            k++;
+        end
+        return k;
+    endfunction
+
+    function automatic int tree_sum(int lo, int hi);
+        if (lo == hi)
+            return lo;
+        return tree_sum(lo, (lo + hi) / 2) + tree_sum((lo + hi) / 2 + 1, hi);
+    endfunction
+
+    localparam int F = fact(5);
+    localparam int L = log2(256);
+    localparam int S = tree_sum(1, 10);
+
+    int x, y, z, w;
+    assign x = F;
+    assign y = L;
+    assign z = S;
+
+    // Recursive calls at runtime call the function itself.
+    always_comb w = fact(x);
+
+    // CHECK-DAG: hw.constant 120 : i32
+    // CHECK-DAG: hw.constant 8 : i32
+    // CHECK-DAG: hw.constant 55 : i32
+    // CHECK-DAG: call @fact(
+endmodule
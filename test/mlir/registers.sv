@@ -0,0 +1,89 @@
+// RUN: moore -e Dff -e DffEnable -e DffAsyncReset -e DffSyncReset -e Counter -e Fallback -e Impure --format=mlir-native %s | FileCheck %s
+// RUN: moore -e Counter --format=hw %s | FileCheck %s --check-prefix=HW
+
+// CHECK-LABEL: llhd.entity @Dff(
+module Dff (input bit clk, input bit [7:0] d, output bit [7:0] q);
+    // CHECK: [[CLK:%.+]] = seq.to_clock
+    // CHECK: [[REG:%.+]] = seq.compreg %{{.+}}, [[CLK]] : i8
+    // CHECK: llhd.drv %{{.+}}, [[REG]]
+    always_ff @(posedge clk) q <= d;
+endmodule
+
+// CHECK-LABEL: llhd.entity @DffEnable(
+module DffEnable (input bit clk, input bit en, input bit [7:0] d, output bit [7:0] q);
+    // A falling clock edge inverts the clock.
+    // CHECK: [[INV:%.+]] = comb.xor
+    // CHECK: [[CLK:%.+]] = seq.to_clock [[INV]]
+    // CHECK: [[REG:%.+]] = seq.compreg.ce %{{.+}}, [[CLK]], %{{.+}} : i8
+    // CHECK: llhd.drv %{{.+}}, [[REG]]
+    always_ff @(negedge clk) if (en) q <= d;
+endmodule
+
+// CHECK-LABEL: llhd.entity @DffAsyncReset(
+module DffAsyncReset (input bit clk, input bit rst_n, input bit [7:0] d, output bit [7:0] q);
+    // CHECK: [[CLK:%.+]] = seq.to_clock
+    // CHECK: [[REG:%.+]] = seq.firreg %{{.+}} clock [[CLK]] reset async
+    // CHECK: llhd.drv %{{.+}}, [[REG]]
+    always_ff @(posedge clk, negedge rst_n) begin
+        if (!rst_n)
+            q <= 0;
+        else
+            q <= d;
+    end
+endmodule
+
+// CHECK-LABEL: llhd.entity @DffSyncReset(
+module DffSyncReset (input bit clk, input bit rst, input bit [7:0] d, output bit [7:0] q);
+    // CHECK: [[CLK:%.+]] = seq.to_clock
+    // CHECK: [[REG:%.+]] = seq.compreg %{{.+}}, [[CLK]] reset %{{.+}}, %{{.+}} : i8
+    // CHECK: llhd.drv %{{.+}}, [[REG]]
+    always_ff @(posedge clk) begin
+        if (rst)
+            q <= 8'h42;
+        else
+            q <= d;
+    end
+endmodule
+
+// CHECK-LABEL: llhd.entity @Counter(
+module Counter (input bit clk, input bit rst_n, input bit [1:0] op, output bit [7:0] q);
+    // The enable and next value are determined from the `case` statement.
+    // Registers with an asynchronous reset have no enable, such that the
+    // current value is fed back.
+    // CHECK: [[CLK:%.+]] = seq.to_clock
+    // CHECK: comb.mux
+    // CHECK: [[HOLD:%.+]] = llhd.prb
+    // CHECK: [[NEXT:%.+]] = comb.mux %{{.+}}, %{{.+}}, [[HOLD]]
+    // CHECK: [[REG:%.+]] = seq.firreg [[NEXT]] clock [[CLK]] reset async
+    // CHECK: llhd.drv %{{.+}}, [[REG]]
+    // The register drives the output, which needs no default value.
+    // CHECK-NOT: llhd.drv
+    // HW-LABEL: hw.module @Counter(
+    // HW: [[REG:%.+]] = seq.firreg
+    // HW: hw.output [[REG]] : i8
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (rst_n == 0) begin
+            q <= 0;
+        end else begin
+            case (op)
+                2'd1: q <= q + 1;
+                2'd2: q <= q - 1;
+            endcase
+        end
+    end
+endmodule
+
+// Procedures which do not follow the register patterns remain processes.
+// CHECK: llhd.proc @Fallback.always_ff
+module Fallback (input bit clk, input bit [7:0] d, output bit [7:0] q);
+    always_ff @(posedge clk) q = d;
+endmodule
+
+// Function calls cannot be emitted as dataflow.
+// CHECK: llhd.proc @Impure.always_ff
+module Impure (input bit clk, input bit [7:0] d, output bit [7:0] q);
+    function bit [7:0] f(bit [7:0] x);
+        return x + 1;
+    endfunction
+    always_ff @(posedge clk) q <= f(d);
+endmodule
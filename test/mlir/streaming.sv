@@ -0,0 +1,87 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @SwapBytes(
+// CHECK-SAME:    [[X:%.+]]: i32
+// CHECK-SAME:  ) -> i32 {
+function bit [31:0] SwapBytes(bit [31:0] x);
+    // CHECK: comb.extract {{%.+}} from 0 : (i32) -> i8
+    // CHECK: comb.extract {{%.+}} from 0 : (i32) -> i8
+    // CHECK: comb.extract {{%.+}} from 0 : (i32) -> i8
+    // CHECK: comb.extract {{%.+}} from 0 : (i32) -> i8
+    // CHECK: moore.mir.concat
+    return {<<8{x}};
+endfunction
+
+// CHECK-LABEL: func @SwapBytesType(
+function bit [31:0] SwapBytesType(bit [31:0] x);
+    // CHECK: moore.mir.concat {{.+}} : (!moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 7:0>>)
+    return {<<byte{x}};
+endfunction
+
+// CHECK-LABEL: func @Reverse(
+function bit [3:0] Reverse(bit [3:0] x);
+    // CHECK: moore.mir.concat {{.+}} : (!moore.packed<range<bit, 0:0>>, !moore.packed<range<bit, 0:0>>, !moore.packed<range<bit, 0:0>>, !moore.packed<range<bit, 0:0>>)
+    return {<<{x}};
+endfunction
+
+// CHECK-LABEL: func @Uneven(
+function bit [11:0] Uneven(bit [7:0] a, bit [3:0] b);
+    // The slices are cut from the LSB, such that the least significant one
+    // spans both operands and the most significant one is short.
+    // CHECK: [[SLICE:%.+]] = moore.mir.concat {{.+}} : (!moore.packed<range<bit, 3:0>>, !moore.packed<range<bit, 3:0>>) -> !moore.packed<range<bit, 7:0>>
+    // CHECK: moore.mir.concat [[SLICE]], {{.+}} : (!moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 3:0>>) -> !moore.packed<range<bit, 11:0>>
+    return {<<8{a, b}};
+endfunction
+
+// CHECK-LABEL: func @Unpack(
+function void Unpack(bit [15:0] x, output bit [7:0] a, output bit [7:0] b);
+    // CHECK: llhd.store
+    // CHECK: llhd.store
+    {<<8{a, b}} = x;
+endfunction
+
+// CHECK-LABEL: func @UnpackUneven(
+function void UnpackUneven(bit [5:0] x, output bit [1:0] a, output bit [3:0] b);
+    // The least significant slice of the stream is `b`, which receives the
+    // upper bits of `x`.
+    // CHECK: comb.extract {{%.+}} from 0 : (i6) -> i2
+    // CHECK: llhd.store
+    // CHECK: hw.constant 2 : i32
+    // CHECK: comb.extract {{%.+}} from 0 : (i6) -> i4
+    // CHECK: llhd.store
+    {<<4{a, b}} = x;
+endfunction
+
+// CHECK-LABEL: func @PackWide(
+function bit [23:0] PackWide(bit [15:0] x);
+    // CHECK: hw.constant 0 : i8
+    // CHECK: moore.mir.concat {{.+}} : (!moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 7:0>>, !moore.packed<range<bit, 7:0>>) -> !moore.packed<range<bit, 23:0>>
+    return {<<8{x}};
+endfunction
+
+// CHECK-LABEL: func @UnpackWide(
+// CHECK-SAME:    [[X:%.+]]: i24
+function void UnpackWide(bit [23:0] x, output bit [7:0] a, output bit [7:0] b);
+    // CHECK: comb.shru [[X]]
+    // CHECK: comb.extract {{%.+}} from 0 : (i24) -> i16
+    // CHECK: llhd.store
+    // CHECK: llhd.store
+    {>>{a, b}} = x;
+endfunction
+
+module foo;
+    localparam bit [15:0] X = 16'h1234;
+    // CHECK: [[TMP:%.+]] = hw.constant 13330 : i16
+    // CHECK: llhd.sig "y" [[TMP]] : i16
+    bit [15:0] y = {<<8{X}};
+    // CHECK: [[TMP:%.+]] = hw.constant 4660 : i16
+    // CHECK: llhd.sig "z" [[TMP]] : i16
+    bit [15:0] z = {>>4{X}};
+    // CHECK: [[TMP:%.+]] = hw.constant 3412480 : i24
+    // CHECK: llhd.sig "w" [[TMP]] : i24
+    bit [23:0] w = {<<8{X}};
+    // See §11.4.14.2, which yields `6'b0101_11`.
+    // CHECK: [[TMP:%.+]] = hw.constant 23 : i6
+    // CHECK: llhd.sig "v" [[TMP]] : i6
+    bit [5:0] v = {<<4{6'b11_0101}};
+endmodule
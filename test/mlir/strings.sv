@@ -0,0 +1,72 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK-LABEL: func @Concat(
+// CHECK-SAME:    [[A:%.+]]: i64, [[B:%.+]]: i64
+// CHECK-SAME:  ) -> i64 {
+function string Concat(string a, string b);
+    // CHECK: call @__moore_string_concat(
+    // CHECK: call @__moore_string_concat(
+    return {a, b};
+endfunction
+// CHECK: func private @__moore_string_concat(i64, i64) -> i64
+
+// CHECK-LABEL: func @Compare(
+// CHECK-SAME:    [[A:%.+]]: i64, [[B:%.+]]: i64
+function bit Compare(string a, string b);
+    // CHECK: [[OP:%.+]] = hw.constant 2 : i32
+    // CHECK: call @__moore_string_comp([[OP]], [[A]], [[B]]) : (i32, i64, i64) -> i1
+    return a < b;
+endfunction
+// CHECK: func private @__moore_string_comp(i32, i64, i64) -> i1
+
+// CHECK-LABEL: func @Methods(
+// CHECK-SAME:    [[A:%.+]]: i64
+function int Methods(string a);
+    byte c;
+    // CHECK: call @__moore_string_len([[A]]) : (i64) -> i32
+    // CHECK: call @__moore_string_getc([[A]], {{%.+}}) : (i64, i32) -> i8
+    c = a.getc(a.len() - 1);
+    // CHECK: call @__moore_string_putc(
+    a.putc(0, c);
+    // CHECK: call @__moore_string_substr(
+    a = a.substr(1, 2);
+    // CHECK: call @__moore_string_itoa(
+    a.hextoa(c);
+    // CHECK: call @__moore_string_atoi(
+    return a.atoi();
+endfunction
+
+// CHECK-LABEL: func @Convert(
+function bit [23:0] Convert(bit [23:0] x);
+    string s;
+    // CHECK: call @__moore_string_unpack(
+    s = string'(x);
+    // CHECK: call @__moore_string_pack(
+    return 24'(s);
+endfunction
+
+// CHECK-LABEL: func @Format(
+function string Format(string s, int x);
+    // CHECK: call @__moore_fmt_string(
+    // CHECK: call @__moore_fmt_int(
+    // CHECK: call @__moore_string_format() : () -> i64
+    return $sformatf("%s = %0d", s, x);
+endfunction
+
+// The inner message is complete before the outer one starts.
+// CHECK-LABEL: func @NestedFormat(
+function string NestedFormat(int x);
+    // CHECK: call @__moore_fmt_int(
+    // CHECK: [[INNER:%.+]] = call @__moore_string_format() : () -> i64
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: call @__moore_fmt_string({{%.+}}, {{%.+}}, [[INNER]])
+    // CHECK: call @__moore_string_format() : () -> i64
+    return $sformatf("x = %s", $sformatf("%0d", x));
+endfunction
+
+// CHECK-LABEL: llhd.entity @foo
+module foo;
+    // CHECK: [[ZERO:%.+]] = hw.constant 0 : i64
+    // CHECK: llhd.sig "s" [[ZERO]] : i64
+    string s;
+endmodule
@@ -0,0 +1,57 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+// CHECK: moore.strings = ["x = ", "{{.*}}system-tasks.sv", "in ", "Hier", "boom", "|"]
+
+// CHECK-LABEL: func @Display(
+function void Display(bit [7:0] x);
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: call @__moore_fmt_int(
+    // CHECK: call @__moore_print(
+    $display("x = %0d", x);
+endfunction
+// CHECK: func private @__moore_fmt_literal(i32)
+// CHECK: func private @__moore_fmt_int(i32, i32, i32, i32, i64)
+// CHECK: func private @__moore_print(i32, i32, i32, i32)
+
+// CHECK-LABEL: func @WideWrite(
+function void WideWrite(bit [99:0] x);
+    // CHECK: call @__moore_fmt_word(
+    // CHECK: call @__moore_fmt_int(
+    // CHECK: call @__moore_print(
+    $write("%h", x);
+endfunction
+
+// CHECK-LABEL: func @Hier(
+function void Hier();
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: call @__moore_print(
+    $info("in %m");
+endfunction
+
+// CHECK-LABEL: func @Terminate(
+function void Terminate();
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: call @__moore_print(
+    // CHECK: [[DIAG:%.+]] = hw.constant 2 : i32
+    // CHECK: call @__moore_finish([[DIAG]])
+    $fatal(2, "boom");
+    // CHECK: call @__moore_stop(
+    $stop;
+endfunction
+
+// CHECK-LABEL: func @Real(
+function void Real(real r, int x);
+    // CHECK: [[SPEC:%.+]] = hw.constant 102 : i32
+    // CHECK: [[WIDTH:%.+]] = hw.constant 10 : i32
+    // CHECK: [[PREC:%.+]] = hw.constant 3 : i32
+    // CHECK: call @__moore_fmt_real([[SPEC]], {{%.+}}, [[WIDTH]], [[PREC]], {{%.+}})
+    // CHECK: call @__moore_fmt_literal(
+    // CHECK: [[X:%.+]] = call @__moore_real_from_int(
+    // CHECK: call @__moore_fmt_real({{%.+}}, {{%.+}}, {{%.+}}, {{%.+}}, [[X]])
+    $display("%10.3f|%e", r, x);
+endfunction
+// CHECK: func private @__moore_fmt_real(i32, i32, i32, i32, i64)
+
+module foo;
+endmodule
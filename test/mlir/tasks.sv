@@ -0,0 +1,70 @@
+// RUN: moore -e top --format=mlir-native %s | FileCheck %s
+
+module foo;
+    bit clk;
+    int data;
+
+    task automatic drive(input int value, output int old);
+        old = data;
+        @(posedge clk);
+        data = value;
+        #1ns;
+    endtask
+
+    task wait_cycles(int n);
+        if (n == 0)
+            return;
+        repeat (n) @(posedge clk);
+    endtask
+
+    // CHECK-LABEL: llhd.proc @foo.initial.
+    initial begin
+        int result;
+        // CHECK: [[VALUE:%.+]] = hw.constant 42 : i32
+        // CHECK: [[VALUE_VAR:%.+]] = llhd.var [[VALUE]] : i32
+        // CHECK: [[OLD:%.+]] = hw.constant 0 : i32
+        // CHECK: [[OLD_VAR:%.+]] = llhd.var [[OLD]] : i32
+        // CHECK: [[DATA:%.+]] = llhd.prb {{%.+}}
+        // CHECK: llhd.store [[OLD_VAR]], [[DATA]] : !llhd.ptr<i32>
+        // CHECK: llhd.wait
+        // CHECK: [[NEW:%.+]] = llhd.load [[VALUE_VAR]] : !llhd.ptr<i32>
+        // CHECK: llhd.drv {{%.+}}, [[NEW]]
+        // CHECK: llhd.wait for
+        // CHECK: br ^[[EXIT:.+]]
+        // CHECK: ^[[EXIT]]:
+        // CHECK: [[RESULT:%.+]] = llhd.load [[OLD_VAR]] : !llhd.ptr<i32>
+        // CHECK: llhd.store {{%.+}}, [[RESULT]] : !llhd.ptr<i32>
+        drive(42, result);
+        // CHECK: comb.icmp eq
+        // CHECK: cond_br
+        // CHECK: llhd.wait
+        wait_cycles(3);
+    end
+endmodule
+
+// Tasks are emitted in the parameter environment of the calling module.
+module bar #(parameter int Step = 1);
+    int count;
+
+    task automatic bump(inout int x);
+        x = x + Step;
+    endtask
+
+    // CHECK-LABEL: llhd.proc @bar
+    initial begin
+        // CHECK: [[COUNT:%.+]] = llhd.prb {{%.+}}
+        // CHECK: [[X:%.+]] = llhd.var [[COUNT]] : i32
+        // CHECK: [[XV:%.+]] = llhd.load [[X]] : !llhd.ptr<i32>
+        // CHECK: [[STEP:%.+]] = hw.constant 5 : i32
+        // CHECK: [[SUM:%.+]] = comb.add [[XV]], [[STEP]] : i32
+        // CHECK: llhd.store [[X]], [[SUM]] : !llhd.ptr<i32>
+        // CHECK: [[NEW:%.+]] = llhd.load [[X]] : !llhd.ptr<i32>
+        // CHECK: llhd.drv {{%.+}}, [[NEW]]
+        bump(count);
+    end
+endmodule
+
+module top;
+    foo f();
+    bar #(.Step(5)) b();
+endmodule
@@ -0,0 +1,66 @@
+// RUN: moore -e foo --format=mlir-native %s | FileCheck %s
+
+typedef union packed {
+    logic [31:0] word;
+    logic [3:0][7:0] bytes;
+} word_t;
+
+typedef union tagged {
+    void Invalid;
+    int Valid;
+} maybe_t;
+
+// CHECK-LABEL: func @LowByte(
+// CHECK-SAME:    [[W:%.+]]: i32
+// CHECK-SAME:  ) -> i8 {
+function logic [7:0] LowByte(word_t w);
+    // CHECK: comb.shru [[W]]
+    // CHECK: comb.extract {{%.+}} from 0 : (i32) -> i8
+    return w.bytes[0];
+endfunction
+
+// CHECK-LABEL: func @MakeValid(
+// CHECK-SAME:  ) -> !hw.struct<tag: i1, Invalid: !hw.struct<>, Valid: i32> {
+function maybe_t MakeValid(int x);
+    // CHECK: hw.constant true
+    // CHECK: hw.struct_create
+    return tagged Valid x;
+endfunction
+
+// CHECK-LABEL: func @Unwrap(
+// CHECK-SAME:    [[M:%.+]]: !hw.struct<tag: i1, Invalid: !hw.struct<>, Valid: i32>
+function int Unwrap(maybe_t m);
+    int result;
+    // CHECK: hw.struct_extract [[M]]["tag"]
+    // CHECK: comb.icmp eq
+    // CHECK: cond_br
+    case (m) matches
+        tagged Valid .v: result = v;
+        tagged Invalid: result = -1;
+    endcase
+    return result;
+endfunction
+
+typedef union tagged {
+    void None;
+    maybe_t Some;
+} nested_t;
+
+// CHECK-LABEL: func @UnwrapNested(
+// CHECK-SAME:    [[N:%.+]]: !hw.struct<tag: i1, None: !hw.struct<>, Some: !hw.struct<tag: i1, Invalid: !hw.struct<>, Valid: i32>>
+function int UnwrapNested(nested_t n);
+    int result;
+    // CHECK: hw.struct_extract [[N]]["tag"]
+    // CHECK: [[SOME:%.+]] = hw.struct_extract [[N]]["Some"]
+    // CHECK: hw.struct_extract [[SOME]]["tag"]
+    // CHECK: [[V:%.+]] = hw.struct_extract {{%.+}}["Valid"]
+    // CHECK: llhd.store {{%.+}}, [[V]]
+    case (n) matches
+        tagged Some (tagged Valid .v): result = v;
+        default: result = -1;
+    endcase
+    return result;
+endfunction
+
+module foo;
+endmodule
@@ -0,0 +1,105 @@
+// RUN: moore -e Top -e Generate -e Sink -e Structs -e StructInput -e StructOutput --format=verilog %s | FileCheck %s
+
+// CHECK-NOT: always_ff
+// CHECK-NOT: always_comb
+// CHECK-NOT: struct
+
+// CHECK-LABEL: module Top(
+module Top (input bit clk, input bit sel, input bit [7:0] a, output bit [7:0] z, output bit [15:0] w);
+    bit [7:0] count, sum;
+    // CHECK: Counter c (
+    Counter c (clk, sel, count);
+    // CHECK: Adder u (
+    Adder u (a, count, sum);
+    // CHECK: Params{{.*}} p (
+    Params #(.Width(16)) p ({a, a}, w);
+    // CHECK: assign z = sel ? {{.+}} : a;
+    always_comb begin
+        if (sel)
+            z = sum;
+        else
+            z = a;
+    end
+endmodule
+
+// CHECK-LABEL: module Counter(
+module Counter (input bit clk, input bit en, output bit [7:0] count);
+    // CHECK: always @(posedge clk)
+    always_ff @(posedge clk) if (en) count <= count + 1;
+endmodule
+
+// CHECK-LABEL: module Adder(
+module Adder (input bit [7:0] a, input bit [7:0] b, output bit [7:0] z);
+    // CHECK: assign z = a + b;
+    assign z = a + b;
+endmodule
+
+// Parameters are substituted into the port widths and the module body.
+// CHECK-LABEL: module Params{{.*}}(
+// CHECK: input {{ *}}[15:0] a
+// CHECK: output {{ *}}[15:0] z
+module Params #(parameter int Width = 4) (input bit [Width-1:0] a, output bit [Width-1:0] z);
+    // CHECK: assign z = ~a;
+    assign z = ~a;
+endmodule
+
+// Generate blocks are expanded.
+// CHECK-LABEL: module Generate(
+module Generate #(parameter int N = 3) (input bit [7:0] a, output bit [7:0] z);
+    // CHECK-NOT: a + 8'h1
+    // CHECK: assign z = {{.+}};
+    if (N > 2) begin : g
+        assign z = a << N;
+    end else begin : g
+        assign z = a + 1;
+    end
+endmodule
+
+// Interface ports are flattened into one port per signal.
+interface Bus;
+    bit [7:0] data;
+    bit valid;
+    bit ready;
+    modport in (input data, valid, output ready);
+endinterface
+
+// CHECK-LABEL: module Sink(
+// CHECK: input {{ *}}[7:0] bus{{.}}data
+// CHECK: input {{ *}}bus{{.}}valid
+// CHECK: output {{ *}}bus{{.}}ready
+module Sink (Bus.in bus, output bit [7:0] z);
+    // CHECK: assign bus{{.}}ready = 1'h1;
+    assign bus.ready = 1;
+    // CHECK: assign z = bus{{.}}valid ? bus{{.}}data : 8'h0;
+    assign z = bus.valid ? bus.data : 0;
+endmodule
+
+// Structs which are only taken apart again are folded into their fields.
+// CHECK-LABEL: module Structs(
+module Structs (input bit [7:0] a, input bit [7:0] b, output bit [7:0] z);
+    struct packed { bit [7:0] x; bit [7:0] y; } p;
+    assign p = '{a, b};
+    // CHECK: assign z = a - b;
+    assign z = p.x - p.y;
+endmodule
+
+// CHECK-NOT: always_ff
+// CHECK-NOT: always_comb
+// CHECK-NOT: struct
+
+// Struct ports are flattened into bit vectors of the same width.
+package types;
+    typedef struct packed { bit [7:0] x; bit [7:0] y; } pair_t;
+endpackage
+
+// CHECK-LABEL: module StructInput(
+// CHECK: input {{ *}}[15:0] p
+module StructInput (input types::pair_t p, output bit [7:0] z);
+    assign z = p.x + p.y;
+endmodule
+
+// CHECK-LABEL: module StructOutput(
+// CHECK: output {{ *}}[15:0] p
+module StructOutput (input bit [7:0] a, input bit [7:0] b, output types::pair_t p);
+    assign p = '{a, b};
+endmodule
@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: `new[]` cannot allocate a value of type `int $ [$]`
+// CHECK-ERR: note: Only dynamic arrays such as `int a[]` can be allocated
+
+module foo;
+    int q[$];
+    initial q = new[4];
+endmodule
@@ -0,0 +1,7 @@
+// RUN: moore %s -e foo
+
+// CHECK-ERR: warning: `$rtoi(1.0 / 0.0)` is inf and cannot be converted to an integer; using 0
+
+module foo;
+    int a [$rtoi(1.0 / 0.0) + 1];
+endmodule
@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: streaming concatenation of 16 bits does not fit into `bit [7:0]`
+
+module foo;
+    bit [7:0] a;
+    bit [15:0] b;
+    initial a = {<<8{b}};
+endmodule
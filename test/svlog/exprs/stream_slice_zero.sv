@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: slice size of streaming concatenation must be positive
+
+module foo;
+    bit [7:0] a, b;
+    initial a = {<<0{b}};
+endmodule
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: streaming concatenation needs 16 bits, but `c` only provides 8
+
+module foo;
+    bit [7:0] a, b, c;
+    initial {<<8{a, b}} = c;
+endmodule
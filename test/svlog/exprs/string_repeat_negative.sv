@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: replication count `-1` must be a non-negative integer
+
+module foo;
+    string s;
+    initial s = {-1{"ab"}};
+endmodule
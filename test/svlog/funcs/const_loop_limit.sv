@@ -0,0 +1,15 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    function automatic int spin(int n);
+        while (n >= 0)
+            n = n + 1;
+        return n;
+    endfunction
+
+    localparam int X = spin(0);
+    int y;
+    assign y = X;
+    // CHECK-ERR: error: loop exceeds the maximum of 1000000 iterations in constant function call
+endmodule
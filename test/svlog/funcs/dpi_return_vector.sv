@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: DPI import cannot return a value of type `bit [7:0]`
+
+import "DPI-C" function bit [7:0] c_byte();
+
+module foo;
+    bit [7:0] a;
+    initial a = c_byte();
+endmodule
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+import "DPI-C" function void c_model(real r);
+
+module foo;
+    initial c_model(1.0);
+    // CHECK-ERR: error: unsupported: type `real` in DPI import
+endmodule
@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    function automatic int forever_down(int n);
+        return forever_down(n - 1);
+    endfunction
+
+    localparam int X = forever_down(0);
+    int y;
+    assign y = X;
+    // CHECK-ERR: error: call to `forever_down` exceeds the maximum constant evaluation depth of 128
+endmodule
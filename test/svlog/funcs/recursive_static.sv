@@ -0,0 +1,14 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    function int countdown(int n);
+        if (n > 0)
+            return countdown(n - 1);
+        return 0;
+    endfunction
+
+    int x;
+    always_comb x = countdown(3);
+    // CHECK-ERR: error: static function `countdown` cannot be called recursively
+endmodule
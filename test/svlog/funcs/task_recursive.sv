@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo
+// FAIL
+
+module foo;
+    task automatic countdown(int n);
+        if (n > 0)
+            countdown(n - 1);
+    endtask
+
+    initial countdown(3);
+    // CHECK-ERR: error: recursive call to task `countdown` cannot be inlined
+endmodule
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: unsupported: action block of concurrent assertion
+
+module foo;
+    bit clk, a;
+    assert property (@(posedge clk) a) else $error("a is low");
+endmodule
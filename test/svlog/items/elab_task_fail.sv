@@ -0,0 +1,21 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: note: width 8 has 1 bytes
+// CHECK-ERR: error: unsupported width 12
+// CHECK-ERR: warning: bus wider than a byte
+
+module foo;
+    bar #(8) a();
+    bar #(12) b();
+endmodule
+
+module bar #(int W);
+    if (W % 8 != 0) begin
+        $error("unsupported width %0d", W);
+    end else begin
+        $info("width %0d has %0d bytes", W, W / 8);
+    end
+    if (W > 8)
+        $warning("bus wider than a byte");
+endmodule
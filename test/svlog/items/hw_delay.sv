@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: delay cannot be lowered to structural HW
+
+module foo (input bit a, output bit z);
+    always @(a) #1 z = a;
+endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: signal `a` is driven by itself and cannot be lowered to structural HW
+
+module foo (output bit z);
+    bit a, b;
+    assign a = b;
+    assign b = a;
+    assign z = a;
+endmodule
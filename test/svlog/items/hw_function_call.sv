@@ -0,0 +1,12 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: call to function `incr` cannot be lowered to structural HW
+
+function bit [7:0] incr(bit [7:0] x);
+    return x + 1;
+endfunction
+
+module foo (input bit [7:0] a, output bit [7:0] z);
+    assign z = incr(a);
+endmodule
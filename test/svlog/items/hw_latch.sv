@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: procedure cannot be lowered to structural HW; only registers and combinational logic without latches are supported
+
+module foo (input bit en, input bit [7:0] d, output bit [7:0] q);
+    always_comb if (en) q = d;
+endmodule
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: signal with multiple drivers cannot be lowered to structural HW
+
+module foo (input bit a, input bit b, output bit z);
+    always @* z = a;
+    always @* z = b;
+endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo --format=hw
+// FAIL
+
+// CHECK-ERR: error: procedure cannot be lowered to structural HW; only registers and combinational logic without latches are supported
+
+module foo (input bit clk, output bit [7:0] z);
+    initial begin
+        z = 0;
+        repeat (4) @(posedge clk) z = z + 1;
+    end
+endmodule
@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo
+
+// Drivers of disjoint parts of a variable are fine.
+module foo (input bit a, input bit b);
+    bit [1:0] z;
+    assign z[0] = a;
+    assign z[1] = b;
+endmodule
@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: variable `x` has 2 drivers
+
+module foo (input bit a, input bit b);
+    bit x;
+    assign x = a;
+    assign x = b;
+endmodule
@@ -0,0 +1,13 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: variable `y` has 2 drivers
+
+module bar (output bit z);
+endmodule
+
+module foo (input bit a);
+    bit y;
+    assign y = a;
+    bar b(y);
+endmodule
@@ -14,11 +14,11 @@ module B (
 );
 endmodule
 
-// CHECK: entity @B (i1$ %x) -> (i1$ %y, i1$ %z) {
+// CHECK: entity @B (i1$ %x, i1$ %z) -> (i1$ %y, i1$ %z.drv) {
 // CHECK:     %0 = const i1 0
 // CHECK:     drv i1$ %y, %0, %1
 // CHECK:     %2 = const i1 0
-// CHECK:     drv i1$ %z, %2, %3
+// CHECK:     drv i1$ %z.drv, %2, %3
 // CHECK: }
 
 
@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: unsupported: system task `$monitor`
+
+module foo;
+    int x;
+    initial $monitor("x = %0d", x);
+endmodule
@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: unsupported: `$readmemh` of memory `mem` declared outside of the enclosing block
+
+module foo;
+    bit [7:0] mem [0:4];
+    if (1) begin : g
+        initial $readmemh("Inputs/rom.hex", mem);
+    end
+endmodule
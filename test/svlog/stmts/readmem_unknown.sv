@@ -0,0 +1,8 @@
+// RUN: moore %s -e foo
+
+// CHECK-ERR: warning: memory file `Inputs/unknown.hex` contains X or Z digits; they are read as 0 since the memory is two-state
+
+module foo;
+    bit [7:0] mem [0:1];
+    initial $readmemh("Inputs/unknown.hex", mem);
+endmodule
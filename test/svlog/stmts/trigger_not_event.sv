@@ -0,0 +1,10 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: `x` is not an event
+// CHECK-ERR: note: `x` has type `bit`
+
+module foo;
+    bit x;
+    initial -> x;
+endmodule
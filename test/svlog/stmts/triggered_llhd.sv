@@ -0,0 +1,11 @@
+// RUN: moore %s -e foo --format=llhd
+// FAIL
+
+// CHECK-ERR: error: unsupported in LLHD output: `ev.triggered`
+// CHECK-ERR: note: LLHD cannot express the simulation time that `.triggered` depends on; use `--format=mlir` instead
+
+module foo;
+    event ev;
+    bit x;
+    initial x = ev.triggered;
+endmodule
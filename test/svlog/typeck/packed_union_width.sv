@@ -0,0 +1,9 @@
+// RUN: moore %s -e foo
+// FAIL
+
+// CHECK-ERR: error: members of a packed union must all have the same width
+// CHECK-ERR: note: `b` has 16 bits, but `a` has 8 bits
+
+module foo;
+    union packed { logic [7:0] a; logic [15:0] b; } u;
+endmodule